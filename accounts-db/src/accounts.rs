@@ -35,7 +35,7 @@ use {
             hash_map::{self},
             BinaryHeap, HashMap, HashSet,
         },
-        ops::RangeBounds,
+        ops::{Bound, RangeBounds},
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc, Mutex,
@@ -99,6 +99,26 @@ pub struct Accounts {
     pub(crate) account_locks: Mutex<AccountLocks>,
 }
 
+/// Keeps the max root at the time it was created registered as an ongoing scan root, so that
+/// scans as of that root see the same accounts until this is dropped; see
+/// `AccountsIndex::pin_scan_root()`
+pub struct ScanRootPin {
+    accounts_db: Arc<AccountsDb>,
+    root: Slot,
+}
+
+impl ScanRootPin {
+    pub fn root(&self) -> Slot {
+        self.root
+    }
+}
+
+impl Drop for ScanRootPin {
+    fn drop(&mut self) {
+        self.accounts_db.accounts_index.unpin_scan_root(self.root);
+    }
+}
+
 pub enum AccountAddressFilter {
     Exclude, // exclude all addresses matching the filter
     Include, // only include addresses matching the filter
@@ -365,6 +385,84 @@ impl Accounts {
             .map(|_| collector)
     }
 
+    /// Pins the current max root for scans; clean won't remove any of the state visible at that
+    /// root until the returned pin is dropped, so hold it no longer than necessary
+    pub fn pin_scan_root(&self) -> ScanRootPin {
+        ScanRootPin {
+            accounts_db: self.accounts_db.clone(),
+            root: self.accounts_db.accounts_index.pin_scan_root(),
+        }
+    }
+
+    /// Loads, in pubkey order, up to `limit` accounts owned by `program_id` that pass `filter`,
+    /// as of `scan_root`. The scan resumes after `start_after`, the last pubkey of the previous
+    /// page, if provided, so the pages loaded with the same `scan_root` are consistent.
+    #[allow(clippy::too_many_arguments)]
+    pub fn load_page_by_program_with_filter<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        program_id: &Pubkey,
+        filter: F,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+        scan_root: &ScanRootPin,
+        config: &ScanConfig,
+        byte_limit_for_scan: Option<usize>,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        let mut collector = Vec::with_capacity(limit);
+        if limit == 0 {
+            return Ok(collector);
+        }
+        // aborting is how the scan stops once the page is full, so use an abort flag of our own
+        let page_config = ScanConfig::new(false).recreate_with_abort();
+        let range = (
+            start_after.map_or(Bound::Unbounded, |pubkey| Bound::Excluded(*pubkey)),
+            Bound::Unbounded,
+        );
+        let sum = AtomicUsize::default();
+        self.accounts_db.checked_range_scan_accounts(
+            ancestors,
+            bank_id,
+            Some(IndexKey::ProgramId(*program_id)),
+            range,
+            Some(scan_root.root()),
+            |some_account_tuple| {
+                if config.is_aborted() {
+                    page_config.abort();
+                    return;
+                }
+                Self::load_while_filtering(&mut collector, some_account_tuple, |account| {
+                    let use_account = account.owner() == program_id && filter(account);
+                    if use_account
+                        && Self::accumulate_and_check_scan_result_size(
+                            &sum,
+                            account,
+                            &byte_limit_for_scan,
+                        )
+                    {
+                        // total size of results exceeds size limit, so abort scan
+                        page_config.abort();
+                    }
+                    use_account
+                });
+                if collector.len() >= limit {
+                    page_config.abort();
+                }
+            },
+            &page_config,
+        )?;
+        if config.is_aborted() {
+            return Err(ScanError::Aborted("The scan was aborted".to_string()));
+        }
+        if byte_limit_for_scan.is_some_and(|byte_limit| sum.into_inner() > byte_limit) {
+            return Err(ScanError::Aborted(
+                "The accumulated scan results exceeded the limit".to_string(),
+            ));
+        }
+        Ok(collector)
+    }
+
     fn calc_scan_result_size(account: &AccountSharedData) -> usize {
         account.data().len()
             + std::mem::size_of::<AccountSharedData>()
@@ -864,6 +962,7 @@ fn prepare_if_nonce_account(
 mod tests {
     use {
         super::*,
+        crate::accounts_index::{AccountIndex, AccountSecondaryIndexes},
         assert_matches::assert_matches,
         solana_program_runtime::loaded_programs::LoadedProgramsForTxBatch,
         solana_sdk::{
@@ -1119,6 +1218,156 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_page_by_program_with_filter() {
+        let program_id = Pubkey::new_unique();
+        let program_id_index_enabled = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::ProgramId]),
+        };
+        // pages are read from the program id index when it's enabled, else from the whole index
+        for account_indexes in [AccountSecondaryIndexes::default(), program_id_index_enabled] {
+            let accounts_db = AccountsDb {
+                account_indexes,
+                ..AccountsDb::new_single_for_tests()
+            };
+            let accounts = Accounts::new(Arc::new(accounts_db));
+            let ancestors = Ancestors::from(vec![0]);
+
+            let mut pubkeys: Vec<_> = (0..10)
+                .map(|lamports| {
+                    let pubkey = solana_sdk::pubkey::new_rand();
+                    let account = AccountSharedData::new(lamports + 1, 0, &program_id);
+                    accounts.store_slow_uncached(0, &pubkey, &account);
+                    pubkey
+                })
+                .collect();
+            pubkeys.sort();
+            accounts.store_slow_uncached(
+                0,
+                &solana_sdk::pubkey::new_rand(),
+                &AccountSharedData::new(1, 0, &Pubkey::new_unique()),
+            );
+
+            let scan_root = accounts.pin_scan_root();
+            let load_page = |start_after: Option<&Pubkey>, limit, byte_limit_for_scan| {
+                accounts
+                    .load_page_by_program_with_filter(
+                        &ancestors,
+                        0,
+                        &program_id,
+                        |account| account.lamports() % 2 == 0,
+                        start_after,
+                        limit,
+                        &scan_root,
+                        &ScanConfig::default(),
+                        byte_limit_for_scan,
+                    )
+                    .map(|accounts| {
+                        accounts
+                            .into_iter()
+                            .map(|(pubkey, _account)| pubkey)
+                            .collect::<Vec<_>>()
+                    })
+            };
+            let expected: Vec<_> = pubkeys
+                .iter()
+                .copied()
+                .filter(|pubkey| {
+                    accounts
+                        .load_without_fixed_root(&ancestors, pubkey)
+                        .unwrap()
+                        .0
+                        .lamports()
+                        % 2
+                        == 0
+                })
+                .collect();
+            assert_eq!(expected.len(), 5);
+
+            // pages are in pubkey order and pick up where the previous one left off
+            let first_page = load_page(None, 3, None).unwrap();
+            assert_eq!(first_page, expected[..3]);
+            let second_page = load_page(first_page.last(), 3, None).unwrap();
+            assert_eq!(second_page, expected[3..]);
+            assert_eq!(load_page(second_page.last(), 3, None).unwrap(), vec![]);
+            assert_eq!(load_page(None, 10, None).unwrap(), expected);
+            assert_eq!(load_page(None, 0, None).unwrap(), vec![]);
+
+            // the scan byte limit applies to the accounts loaded for the page
+            let account_size = Accounts::calc_scan_result_size(&AccountSharedData::default());
+            assert_eq!(
+                load_page(None, 3, Some(3 * account_size)).unwrap(),
+                expected[..3]
+            );
+            assert_matches!(
+                load_page(None, 3, Some(3 * account_size - 1)),
+                Err(ScanError::Aborted(_))
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_page_by_program_with_filter_pinned_root() {
+        let accounts = Accounts::new(Arc::new(AccountsDb::new_single_for_tests()));
+        let program_id = Pubkey::new_unique();
+        let pubkey0 = Pubkey::new_unique();
+        accounts.store_slow_uncached(0, &pubkey0, &AccountSharedData::new(1, 0, &program_id));
+        accounts.add_root(0);
+
+        let scan_root = accounts.pin_scan_root();
+        assert_eq!(scan_root.root(), 0);
+        let ancestors = Ancestors::from(vec![0]);
+        let load_page = |scan_root: &ScanRootPin| {
+            let mut page = accounts
+                .load_page_by_program_with_filter(
+                    &ancestors,
+                    0,
+                    &program_id,
+                    |_account| true,
+                    None,
+                    10,
+                    scan_root,
+                    &ScanConfig::default(),
+                    None,
+                )
+                .unwrap();
+            page.sort_by_key(|(pubkey, _account)| *pubkey);
+            page
+        };
+        let pinned_page = load_page(&scan_root);
+        assert_eq!(pinned_page.len(), 1);
+
+        // newer roots, and cleaning them, don't change what a scan as of the pinned root sees
+        let pubkey1 = Pubkey::new_unique();
+        accounts.store_slow_uncached(1, &pubkey0, &AccountSharedData::new(2, 0, &program_id));
+        accounts.store_slow_uncached(1, &pubkey1, &AccountSharedData::new(1, 0, &program_id));
+        accounts.add_root(1);
+        accounts.accounts_db.clean_accounts_for_tests();
+        assert_eq!(load_page(&scan_root), pinned_page);
+
+        let new_scan_root = accounts.pin_scan_root();
+        assert_eq!(new_scan_root.root(), 1);
+        let new_page = load_page(&new_scan_root);
+        assert_eq!(new_page.len(), 2);
+        assert!(new_page.contains(&(pubkey0, AccountSharedData::new(2, 0, &program_id))));
+
+        assert_eq!(
+            accounts.accounts_db.accounts_index.min_ongoing_scan_root(),
+            Some(0)
+        );
+        drop(scan_root);
+        assert_eq!(
+            accounts.accounts_db.accounts_index.min_ongoing_scan_root(),
+            Some(1)
+        );
+        drop(new_scan_root);
+        assert_eq!(
+            accounts.accounts_db.accounts_index.min_ongoing_scan_root(),
+            None
+        );
+    }
+
     #[test]
     fn test_accounts_empty_bank_hash_stats() {
         let accounts_db = AccountsDb::new_single_for_tests();
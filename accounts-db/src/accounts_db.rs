@@ -41,10 +41,10 @@ use {
             ZeroLamportAccounts,
         },
         accounts_index::{
-            AccountIndexGetResult, AccountMapEntry, AccountSecondaryIndexes, AccountsIndex,
-            AccountsIndexConfig, AccountsIndexRootsStats, AccountsIndexScanResult, DiskIndexValue,
-            IndexKey, IndexValue, IsCached, RefCount, ScanConfig, ScanResult, SlotList,
-            UpsertReclaim, ZeroLamport, ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS,
+            AccountIndex, AccountIndexGetResult, AccountMapEntry, AccountSecondaryIndexes,
+            AccountsIndex, AccountsIndexConfig, AccountsIndexRootsStats, AccountsIndexScanResult,
+            DiskIndexValue, IndexKey, IndexValue, IsCached, RefCount, ScanConfig, ScanResult,
+            SlotList, UpsertReclaim, ZeroLamport, ACCOUNTS_INDEX_CONFIG_FOR_BENCHMARKS,
             ACCOUNTS_INDEX_CONFIG_FOR_TESTING,
         },
         accounts_index_storage::Startup,
@@ -4851,6 +4851,43 @@ impl AccountsDb {
         );
     }
 
+    /// Scans the accounts in `range` in pubkey order, as of `pinned_root` if provided, see
+    /// `AccountsIndex::pin_scan_root()`. Only the accounts under `index_key` are scanned if it is
+    /// provided and indexed. Unlike `range_scan_accounts`, this is safe to call while the bank
+    /// being scanned may be removed, e.g. from RPC.
+    #[allow(clippy::too_many_arguments)]
+    pub fn checked_range_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: Option<IndexKey>,
+        range: R,
+        pinned_root: Option<Slot>,
+        mut scan_func: F,
+        config: &ScanConfig,
+    ) -> ScanResult<()>
+    where
+        F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        let index_key = index_key.filter(|index_key| self.is_indexed(index_key));
+        self.accounts_index.checked_range_scan_accounts(
+            ancestors,
+            bank_id,
+            index_key,
+            range,
+            pinned_root,
+            |pubkey, (account_info, slot)| {
+                let account_slot = self
+                    .get_account_accessor(slot, pubkey, &account_info.storage_location())
+                    .get_loaded_account()
+                    .map(|loaded_account| (pubkey, loaded_account.take_account(), slot));
+                scan_func(account_slot)
+            },
+            config,
+        )
+    }
+
     /// Only guaranteed to be safe when called from rent collection
     pub fn range_scan_accounts<F, R>(
         &self,
@@ -4889,6 +4926,16 @@ impl AccountsDb {
         );
     }
 
+    /// Whether the accounts under `index_key` are in its secondary index
+    fn is_indexed(&self, index_key: &IndexKey) -> bool {
+        let (index, key) = match index_key {
+            IndexKey::ProgramId(key) => (AccountIndex::ProgramId, key),
+            IndexKey::SplTokenMint(key) => (AccountIndex::SplTokenMint, key),
+            IndexKey::SplTokenOwner(key) => (AccountIndex::SplTokenOwner, key),
+        };
+        self.account_indexes.contains(&index) && self.account_indexes.include_key(key)
+    }
+
     pub fn index_scan_accounts<F>(
         &self,
         ancestors: &Ancestors,
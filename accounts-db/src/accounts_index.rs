@@ -158,7 +158,7 @@ pub enum ScanError {
 
 enum ScanTypes<R: RangeBounds<Pubkey>> {
     Unindexed(Option<R>),
-    Indexed(IndexKey),
+    Indexed(IndexKey, Option<R>),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -679,7 +679,7 @@ pub enum AccountsIndexScanResult {
 pub struct AccountsIndex<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> {
     pub account_maps: LockMapType<T, U>,
     pub bin_calculator: PubkeyBinCalculator24,
-    program_id_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
+    program_id_index: SecondaryIndex<RwLockBTreeSecondaryIndexEntry>,
     spl_token_mint_index: SecondaryIndex<DashMapSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
     pub roots_tracker: RwLock<RootsTracker>,
@@ -727,7 +727,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         Self {
             account_maps,
             bin_calculator,
-            program_id_index: SecondaryIndex::<DashMapSecondaryIndexEntry>::new(
+            program_id_index: SecondaryIndex::<RwLockBTreeSecondaryIndexEntry>::new(
                 "program_id_index_stats",
             ),
             spl_token_mint_index: SecondaryIndex::<DashMapSecondaryIndexEntry>::new(
@@ -786,11 +786,35 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         ongoing_scan_roots.keys().next().cloned()
     }
 
+    /// Registers the current max root as an ongoing scan root, and returns it. Until
+    /// `unpin_scan_root()` is called with that root, clean keeps the state that scans at the root
+    /// observe, so that scans passed it as their `pinned_root` see the same accounts however far
+    /// the max root advances.
+    pub fn pin_scan_root(&self) -> Slot {
+        let mut w_ongoing_scan_roots = self.ongoing_scan_roots.write().unwrap();
+        let max_root_inclusive = self.max_root_inclusive();
+        *w_ongoing_scan_roots.entry(max_root_inclusive).or_default() += 1;
+        max_root_inclusive
+    }
+
+    pub fn unpin_scan_root(&self, root: Slot) {
+        let mut ongoing_scan_roots = self.ongoing_scan_roots.write().unwrap();
+        let count = ongoing_scan_roots.get_mut(&root).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            ongoing_scan_roots.remove(&root);
+        }
+    }
+
+    /// Scans as of `pinned_root`, which must have been pinned with `pin_scan_root()`, or as of the
+    /// current max root if `None`
+    #[allow(clippy::too_many_arguments)]
     fn do_checked_scan_accounts<F, R>(
         &self,
         metric_name: &'static str,
         ancestors: &Ancestors,
         scan_bank_id: BankId,
+        pinned_root: Option<Slot>,
         func: F,
         scan_type: ScanTypes<R>,
         config: &ScanConfig,
@@ -823,7 +847,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             // the `ongoing_scan_roots` lock is held,
             // make sure inverse doesn't happen to avoid
             // deadlock
-            let max_root_inclusive = self.max_root_inclusive();
+            let max_root_inclusive = pinned_root.unwrap_or_else(|| self.max_root_inclusive());
             if let Some(min_ongoing_scan_root) =
                 Self::min_ongoing_scan_root_from_btree(&w_ongoing_scan_roots)
             {
@@ -958,47 +982,43 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 // Pass "" not to log metrics, so RPC doesn't get spammy
                 self.do_scan_accounts(metric_name, ancestors, func, range, Some(max_root), config);
             }
-            ScanTypes::Indexed(IndexKey::ProgramId(program_id)) => {
+            ScanTypes::Indexed(IndexKey::ProgramId(program_id), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.program_id_index,
                     &program_id,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_mint_index,
                     &mint_key,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_owner_index,
                     &owner_key,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
         }
 
-        {
-            self.active_scans.fetch_sub(1, Ordering::Relaxed);
-            let mut ongoing_scan_roots = self.ongoing_scan_roots.write().unwrap();
-            let count = ongoing_scan_roots.get_mut(&max_root).unwrap();
-            *count -= 1;
-            if *count == 0 {
-                ongoing_scan_roots.remove(&max_root);
-            }
-        }
+        self.active_scans.fetch_sub(1, Ordering::Relaxed);
+        self.unpin_scan_root(max_root);
 
         // If the fork with tip at bank `scan_bank_id` was removed during our scan, then the scan
         // may have been corrupted, so abort the results.
@@ -1095,8 +1115,13 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         }
     }
 
+    /// Scans the accounts under `index_key` in `index`. If a `range` is given, only the accounts
+    /// in it are scanned, in pubkey order, reading `ITER_BATCH_SIZE` keys from the index at a
+    /// time so a scan that stops early doesn't have to read every key.
+    #[allow(clippy::too_many_arguments)]
     fn do_scan_secondary_index<
         F,
+        R,
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
         &self,
@@ -1104,23 +1129,44 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         mut func: F,
         index: &SecondaryIndex<SecondaryIndexEntryType>,
         index_key: &Pubkey,
+        range: Option<R>,
         max_root: Option<Slot>,
         config: &ScanConfig,
     ) where
         F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey>,
     {
-        for pubkey in index.get(index_key) {
-            // Maybe these reads from the AccountsIndex can be batched every time it
-            // grabs the read lock as well...
-            if let AccountIndexGetResult::Found(list_r, index) =
-                self.get(&pubkey, Some(ancestors), max_root)
-            {
-                let entry = &list_r.slot_list()[index];
-                func(&pubkey, (&entry.1, entry.0));
+        let mut scan_pubkeys = |pubkeys: Vec<Pubkey>| {
+            for pubkey in pubkeys {
+                // Maybe these reads from the AccountsIndex can be batched every time it
+                // grabs the read lock as well...
+                if let AccountIndexGetResult::Found(list_r, index) =
+                    self.get(&pubkey, Some(ancestors), max_root)
+                {
+                    let entry = &list_r.slot_list()[index];
+                    func(&pubkey, (&entry.1, entry.0));
+                }
+                if config.is_aborted() {
+                    return false;
+                }
             }
-            if config.is_aborted() {
+            true
+        };
+        let Some(range) = range else {
+            scan_pubkeys(index.get(index_key));
+            return;
+        };
+        let mut start_bound = range.start_bound().cloned();
+        let end_bound = range.end_bound().cloned();
+        loop {
+            let pubkeys = index.get_range(index_key, (start_bound, end_bound), ITER_BATCH_SIZE);
+            let Some(last) = pubkeys.last().copied() else {
+                break;
+            };
+            if !scan_pubkeys(pubkeys) {
                 break;
             }
+            start_bound = Excluded(last);
         }
     }
 
@@ -1229,6 +1275,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             "",
             ancestors,
             scan_bank_id,
+            None,
             func,
             ScanTypes::Unindexed(None::<Range<Pubkey>>),
             config,
@@ -1253,6 +1300,43 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         );
     }
 
+    /// call func, in pubkey order, with every pubkey in range and index visible from a given set
+    /// of ancestors, optionally only those under `index_key` in its secondary index, as of
+    /// `pinned_root`, see `pin_scan_root()`
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn checked_range_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        index_key: Option<IndexKey>,
+        range: R,
+        pinned_root: Option<Slot>,
+        func: F,
+        config: &ScanConfig,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        let scan_type = match index_key {
+            Some(index_key) => ScanTypes::Indexed(index_key, Some(range)),
+            None => ScanTypes::Unindexed(Some(range)),
+        };
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts(
+            "",
+            ancestors,
+            scan_bank_id,
+            pinned_root,
+            func,
+            scan_type,
+            &ScanConfig {
+                abort: config.abort.clone(),
+                collect_all_unsorted: false,
+            },
+        )
+    }
+
     /// call func with every pubkey and index visible from a given set of ancestors with range
     /// Only guaranteed to be safe when called from rent collection
     pub(crate) fn range_scan_accounts<F, R>(
@@ -1287,8 +1371,9 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             "",
             ancestors,
             scan_bank_id,
+            None,
             func,
-            ScanTypes::<Range<Pubkey>>::Indexed(index_key),
+            ScanTypes::<Range<Pubkey>>::Indexed(index_key, None),
             config,
         )
     }
@@ -3531,6 +3616,37 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_secondary_index_get_range() {
+        let key = Pubkey::new_unique();
+        let mut inner_keys: Vec<_> = (0..10).map(|_| Pubkey::new_unique()).collect();
+        inner_keys.sort_unstable();
+        let btree_index = SecondaryIndex::<RwLockBTreeSecondaryIndexEntry>::new("btree_index");
+        let dashmap_index = SecondaryIndex::<DashMapSecondaryIndexEntry>::new("dashmap_index");
+        for inner_key in inner_keys.iter().rev() {
+            btree_index.insert(&key, inner_key);
+            dashmap_index.insert(&key, inner_key);
+        }
+
+        // both ordered and unordered entries return the first keys in the range, in order
+        let range = (Excluded(inner_keys[2]), Included(inner_keys[8]));
+        assert_eq!(
+            btree_index.get_range(&key, range, 4),
+            inner_keys[3..7].to_vec()
+        );
+        assert_eq!(
+            dashmap_index.get_range(&key, range, 4),
+            inner_keys[3..7].to_vec()
+        );
+        assert_eq!(
+            btree_index.get_range(&key, range, usize::MAX),
+            inner_keys[3..9].to_vec()
+        );
+        assert!(btree_index
+            .get_range(&Pubkey::new_unique(), (Unbounded, Unbounded), 4)
+            .is_empty());
+    }
+
     #[test]
     fn test_purge_older_root_entries() {
         // No roots, should be no reclaims
@@ -3,8 +3,9 @@ use {
     log::*,
     solana_sdk::{pubkey::Pubkey, timing::AtomicInterval},
     std::{
-        collections::HashSet,
+        collections::{BTreeSet, HashSet},
         fmt::Debug,
+        ops::{Bound, RangeBounds},
         sync::{
             atomic::{AtomicU64, Ordering},
             RwLock,
@@ -24,6 +25,14 @@ pub trait SecondaryIndexEntry: Debug {
     fn remove_inner_key(&self, key: &Pubkey) -> bool;
     fn is_empty(&self) -> bool;
     fn keys(&self) -> Vec<Pubkey>;
+    // Returns up to `limit` of the keys in `range`, in order.
+    fn keys_in_range(&self, range: (Bound<Pubkey>, Bound<Pubkey>), limit: usize) -> Vec<Pubkey> {
+        let mut keys = self.keys();
+        keys.retain(|key| range.contains(key));
+        keys.sort_unstable();
+        keys.truncate(limit);
+        keys
+    }
     fn len(&self) -> usize;
 }
 
@@ -99,6 +108,48 @@ impl SecondaryIndexEntry for RwLockSecondaryIndexEntry {
     }
 }
 
+/// Keeps its keys ordered, so that a range of them can be read without collecting and sorting
+/// the whole set, ex: to page through the accounts of a large program.
+#[derive(Debug, Default)]
+pub struct RwLockBTreeSecondaryIndexEntry {
+    account_keys: RwLock<BTreeSet<Pubkey>>,
+}
+
+impl SecondaryIndexEntry for RwLockBTreeSecondaryIndexEntry {
+    fn insert_if_not_exists(&self, key: &Pubkey, inner_keys_count: &AtomicU64) {
+        let exists = self.account_keys.read().unwrap().contains(key);
+        if !exists && self.account_keys.write().unwrap().insert(*key) {
+            inner_keys_count.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn remove_inner_key(&self, key: &Pubkey) -> bool {
+        self.account_keys.write().unwrap().remove(key)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.account_keys.read().unwrap().is_empty()
+    }
+
+    fn keys(&self) -> Vec<Pubkey> {
+        self.account_keys.read().unwrap().iter().cloned().collect()
+    }
+
+    fn keys_in_range(&self, range: (Bound<Pubkey>, Bound<Pubkey>), limit: usize) -> Vec<Pubkey> {
+        self.account_keys
+            .read()
+            .unwrap()
+            .range(range)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.account_keys.read().unwrap().len()
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct SecondaryIndex<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send> {
     metrics_name: &'static str,
@@ -223,6 +274,20 @@ impl<SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send>
         }
     }
 
+    /// Returns up to `limit` of the keys under `key` in `range`, in order
+    pub fn get_range(
+        &self,
+        key: &Pubkey,
+        range: (Bound<Pubkey>, Bound<Pubkey>),
+        limit: usize,
+    ) -> Vec<Pubkey> {
+        if let Some(inner_keys_map) = self.index.get(key) {
+            inner_keys_map.keys_in_range(range, limit)
+        } else {
+            vec![]
+        }
+    }
+
     /// log top 20 (owner, # accounts) in descending order of # accounts
     pub fn log_contents(&self) {
         let mut entries = self
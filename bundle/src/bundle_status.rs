@@ -0,0 +1,200 @@
+//! Keeps a bounded record of what happened to recently processed bundles so operators and
+//! searchers can see whether a bundle landed, failed, or was dropped by this leader.
+use {
+    crate::{bundle_execution::LoadAndExecuteBundleError, BundleExecutionError},
+    solana_sdk::{clock::Slot, signature::Signature},
+    std::{
+        collections::{HashMap, VecDeque},
+        sync::RwLock,
+    },
+};
+
+/// Number of bundle statuses retained before the oldest ones are evicted
+pub const DEFAULT_BUNDLE_STATUS_CACHE_CAPACITY: usize = 100_000;
+
+#[derive(Clone, Debug)]
+pub enum BundleOutcome {
+    /// All transactions in the bundle were recorded and committed
+    Landed,
+    /// The bundle was attempted but not committed
+    Failed(BundleExecutionError),
+    /// The bundle was cleared from the buffer before it could be executed, typically because the
+    /// leader slots ended
+    Dropped,
+}
+
+#[derive(Clone, Debug)]
+pub struct BundleStatus {
+    pub bundle_id: String,
+    pub slot: Slot,
+    pub outcome: BundleOutcome,
+    pub compute_units_consumed: u64,
+    pub tip_lamports: u64,
+}
+
+impl BundleStatus {
+    pub fn landed(
+        bundle_id: String,
+        slot: Slot,
+        compute_units_consumed: u64,
+        tip_lamports: u64,
+    ) -> Self {
+        Self {
+            bundle_id,
+            slot,
+            outcome: BundleOutcome::Landed,
+            compute_units_consumed,
+            tip_lamports,
+        }
+    }
+
+    pub fn failed(bundle_id: String, slot: Slot, error: BundleExecutionError) -> Self {
+        Self {
+            bundle_id,
+            slot,
+            outcome: BundleOutcome::Failed(error),
+            compute_units_consumed: 0,
+            tip_lamports: 0,
+        }
+    }
+
+    pub fn dropped(bundle_id: String, slot: Slot) -> Self {
+        Self {
+            bundle_id,
+            slot,
+            outcome: BundleOutcome::Dropped,
+            compute_units_consumed: 0,
+            tip_lamports: 0,
+        }
+    }
+
+    pub fn is_landed(&self) -> bool {
+        matches!(self.outcome, BundleOutcome::Landed)
+    }
+
+    /// Signature of the transaction that caused the bundle to fail, if any
+    pub fn failing_signature(&self) -> Option<Signature> {
+        match &self.outcome {
+            BundleOutcome::Failed(BundleExecutionError::TransactionFailure(
+                LoadAndExecuteBundleError::LockError { signature, .. }
+                | LoadAndExecuteBundleError::TransactionError { signature, .. },
+            )) => Some(*signature),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct BundleStatusCacheInner {
+    statuses: HashMap<String, BundleStatus>,
+    // insertion order of bundle ids, used for evicting the oldest entries
+    order: VecDeque<String>,
+}
+
+/// Bounded in-memory store of the most recent outcome for each bundle id.
+/// A bundle that is retried (ex: hit the end of the slot) has its status overwritten by the
+/// latest attempt but keeps its original position in the eviction order.
+pub struct BundleStatusCache {
+    inner: RwLock<BundleStatusCacheInner>,
+    capacity: usize,
+}
+
+impl Default for BundleStatusCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUNDLE_STATUS_CACHE_CAPACITY)
+    }
+}
+
+impl BundleStatusCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: RwLock::new(BundleStatusCacheInner::default()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn insert(&self, status: BundleStatus) {
+        let mut inner = self.inner.write().unwrap();
+        if !inner.statuses.contains_key(&status.bundle_id) {
+            while inner.order.len() >= self.capacity {
+                if let Some(evicted) = inner.order.pop_front() {
+                    inner.statuses.remove(&evicted);
+                }
+            }
+            inner.order.push_back(status.bundle_id.clone());
+        }
+        inner.statuses.insert(status.bundle_id.clone(), status);
+    }
+
+    pub fn get(&self, bundle_id: &str) -> Option<BundleStatus> {
+        self.inner.read().unwrap().statuses.get(bundle_id).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().statuses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::transaction::TransactionError};
+
+    #[test]
+    fn test_bundle_status_cache_evicts_oldest() {
+        let cache = BundleStatusCache::new(2);
+        cache.insert(BundleStatus::landed("a".to_string(), 1, 100, 10));
+        cache.insert(BundleStatus::dropped("b".to_string(), 1));
+        cache.insert(BundleStatus::dropped("c".to_string(), 2));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+    }
+
+    #[test]
+    fn test_bundle_status_cache_overwrites_retried_bundle() {
+        let cache = BundleStatusCache::new(2);
+        cache.insert(BundleStatus::failed(
+            "a".to_string(),
+            1,
+            BundleExecutionError::BankProcessingTimeLimitReached,
+        ));
+        cache.insert(BundleStatus::dropped("b".to_string(), 1));
+        cache.insert(BundleStatus::landed("a".to_string(), 2, 100, 10));
+
+        assert_eq!(cache.len(), 2);
+        let status = cache.get("a").unwrap();
+        assert!(status.is_landed());
+        assert_eq!(status.slot, 2);
+        assert_eq!(status.tip_lamports, 10);
+
+        // "a" keeps its original position, so it's evicted first
+        cache.insert(BundleStatus::dropped("c".to_string(), 3));
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+    }
+
+    #[test]
+    fn test_bundle_status_failing_signature() {
+        let signature = Signature::new_unique();
+        let status = BundleStatus::failed(
+            "a".to_string(),
+            1,
+            BundleExecutionError::TransactionFailure(LoadAndExecuteBundleError::LockError {
+                signature,
+                transaction_error: TransactionError::AccountInUse,
+            }),
+        );
+        assert_eq!(status.failing_signature(), Some(signature));
+        assert_eq!(
+            BundleStatus::failed("b".to_string(), 1, BundleExecutionError::LockError)
+                .failing_signature(),
+            None
+        );
+    }
+}
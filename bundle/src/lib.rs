@@ -8,6 +8,8 @@ use {
 };
 
 pub mod bundle_execution;
+pub mod bundle_status;
+pub mod packet_bundle;
 
 #[derive(Error, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TipError {
@@ -36,6 +38,9 @@ impl From<anchor_lang::error::Error> for TipError {
     }
 }
 
+/// Max number of transactions in a bundle
+pub const MAX_TRANSACTIONS_PER_BUNDLE: usize = 5;
+
 pub type BundleExecutionResult<T> = Result<T, BundleExecutionError>;
 
 #[derive(Error, Debug, Clone)]
@@ -57,4 +62,7 @@ pub enum BundleExecutionError {
 
     #[error("Tip payment error {0}")]
     TipError(#[from] TipError),
+
+    #[error("Bundle rejected by a runtime plugin: {0}")]
+    RejectedByPolicy(String),
 }
@@ -0,0 +1,10 @@
+use {
+    serde::{Deserialize, Serialize},
+    solana_perf::packet::PacketBatch,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PacketBundle {
+    pub batch: PacketBatch,
+    pub bundle_id: String,
+}
@@ -3,6 +3,7 @@ use {
         cluster_slots_service::cluster_slots::ClusterSlots,
         proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
         repair::{outstanding_requests::OutstandingRequests, serve_repair::ShredRepairType},
+        tip_manager::TipDistributionAccountConfigStore,
     },
     solana_gossip::cluster_info::ClusterInfo,
     solana_runtime::bank_forks::BankForks,
@@ -26,5 +27,8 @@ pub struct AdminRpcRequestMetadataPostInit {
     pub cluster_slots: Arc<ClusterSlots>,
     pub block_engine_config: Arc<Mutex<BlockEngineConfig>>,
     pub relayer_config: Arc<Mutex<RelayerConfig>>,
+    // the embedded relayer streams packets that already passed sigverify back into the TPU
+    pub embedded_relayer: bool,
     pub shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
+    pub tip_distribution_account_config: TipDistributionAccountConfigStore,
 }
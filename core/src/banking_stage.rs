@@ -610,7 +610,11 @@ impl BankingStage {
         bank_thread_hdls.push({
             let packet_deserializer =
                 PacketDeserializer::new(non_vote_receiver, bank_forks.clone());
-            let scheduler = PrioGraphScheduler::new(work_senders, finished_work_receiver);
+            let scheduler = PrioGraphScheduler::new(
+                work_senders,
+                finished_work_receiver,
+                bundle_account_locker,
+            );
             let scheduler_controller = SchedulerController::new(
                 decision_maker.clone(),
                 packet_deserializer,
@@ -13,8 +13,14 @@ pub struct ReadWriteAccountSet {
 }
 
 impl ReadWriteAccountSet {
+    pub fn new(read_set: HashSet<Pubkey>, write_set: HashSet<Pubkey>) -> Self {
+        Self {
+            read_set,
+            write_set,
+        }
+    }
+
     /// Returns true if all account locks were available and false otherwise.
-    #[allow(dead_code)]
     pub fn check_locks(&self, message: &SanitizedMessage) -> bool {
         message
             .account_keys()
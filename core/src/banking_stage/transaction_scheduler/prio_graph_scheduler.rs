@@ -6,11 +6,16 @@ use {
         transaction_state::SanitizedTransactionTTL,
         transaction_state_container::TransactionStateContainer,
     },
-    crate::banking_stage::{
-        consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
-        read_write_account_set::ReadWriteAccountSet,
-        scheduler_messages::{ConsumeWork, FinishedConsumeWork, TransactionBatchId, TransactionId},
-        transaction_scheduler::transaction_priority_id::TransactionPriorityId,
+    crate::{
+        banking_stage::{
+            consumer::TARGET_NUM_TRANSACTIONS_PER_BATCH,
+            read_write_account_set::ReadWriteAccountSet,
+            scheduler_messages::{
+                ConsumeWork, FinishedConsumeWork, TransactionBatchId, TransactionId,
+            },
+            transaction_scheduler::transaction_priority_id::TransactionPriorityId,
+        },
+        bundle_stage::bundle_account_locker::BundleAccountLocker,
     },
     crossbeam_channel::{Receiver, Sender, TryRecvError},
     itertools::izip,
@@ -28,12 +33,14 @@ pub(crate) struct PrioGraphScheduler {
     consume_work_senders: Vec<Sender<ConsumeWork>>,
     finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
     look_ahead_window_size: usize,
+    bundle_account_locker: BundleAccountLocker,
 }
 
 impl PrioGraphScheduler {
     pub(crate) fn new(
         consume_work_senders: Vec<Sender<ConsumeWork>>,
         finished_consume_work_receiver: Receiver<FinishedConsumeWork>,
+        bundle_account_locker: BundleAccountLocker,
     ) -> Self {
         let num_threads = consume_work_senders.len();
         Self {
@@ -42,6 +49,7 @@ impl PrioGraphScheduler {
             consume_work_senders,
             finished_consume_work_receiver,
             look_ahead_window_size: 2048,
+            bundle_account_locker,
         }
     }
 
@@ -61,6 +69,10 @@ impl PrioGraphScheduler {
     /// This, combined with internal tracking of threads' in-flight transactions, allows
     /// for load-balancing while prioritizing scheduling transactions onto threads that will
     /// not cause conflicts in the near future.
+    ///
+    /// Accounts locked by BundleStage for its buffered bundles are snapshotted at the start of
+    /// each pass. Transactions conflicting with them are deferred like unschedulable
+    /// transactions instead of being sent to workers that would fail to lock them.
     pub(crate) fn schedule(
         &mut self,
         container: &mut TransactionStateContainer,
@@ -75,6 +87,13 @@ impl PrioGraphScheduler {
         // these transactions to be scheduled before them.
         let mut unschedulable_ids = Vec::new();
         let mut blocking_locks = ReadWriteAccountSet::default();
+        let bundle_locks = {
+            let bundle_account_locks = self.bundle_account_locker.account_locks();
+            ReadWriteAccountSet::new(
+                bundle_account_locks.read_locks(),
+                bundle_account_locks.write_locks(),
+            )
+        };
         let mut prio_graph = PrioGraph::new(|id: &TransactionPriorityId, _graph_node| *id);
 
         // Track metrics on filter.
@@ -139,6 +158,7 @@ impl PrioGraphScheduler {
         let mut num_scheduled: usize = 0;
         let mut num_sent: usize = 0;
         let mut num_unschedulable: usize = 0;
+        let mut num_deferred_bundle_locks: usize = 0;
         while num_scheduled < MAX_TRANSACTIONS_PER_SCHEDULING_PASS {
             // If nothing is in the main-queue of the `PrioGraph` then there's nothing left to schedule.
             if prio_graph.is_empty() {
@@ -168,6 +188,14 @@ impl PrioGraphScheduler {
                     continue;
                 }
 
+                // Check if this transaction conflicts with accounts locked by BundleStage
+                if !bundle_locks.check_locks(transaction.message()) {
+                    blocking_locks.take_locks(transaction.message());
+                    unschedulable_ids.push(id);
+                    saturating_add_assign!(num_deferred_bundle_locks, 1);
+                    continue;
+                }
+
                 // Schedule the transaction if it can be.
                 let transaction_locks = transaction.get_account_locks_unchecked();
                 let Some(thread_id) = self.account_locks.try_lock_accounts(
@@ -247,6 +275,7 @@ impl PrioGraphScheduler {
         Ok(SchedulingSummary {
             num_scheduled,
             num_unschedulable,
+            num_deferred_bundle_locks,
             num_filtered_out,
             filter_time_us: total_filter_time_us,
         })
@@ -433,6 +462,8 @@ pub(crate) struct SchedulingSummary {
     pub num_scheduled: usize,
     /// Number of transactions that were not scheduled due to conflicts.
     pub num_unschedulable: usize,
+    /// Number of transactions that were not scheduled due to accounts locked by BundleStage.
+    pub num_deferred_bundle_locks: usize,
     /// Number of transactions that were dropped due to filter.
     pub num_filtered_out: usize,
     /// Time spent filtering transactions
@@ -494,7 +525,7 @@ mod tests {
             compute_budget::ComputeBudgetInstruction, hash::Hash, message::Message, pubkey::Pubkey,
             signature::Keypair, signer::Signer, system_instruction, transaction::Transaction,
         },
-        std::borrow::Borrow,
+        std::{borrow::Borrow, collections::HashMap},
     };
 
     macro_rules! txid {
@@ -519,8 +550,11 @@ mod tests {
         let (consume_work_senders, consume_work_receivers) =
             (0..num_threads).map(|_| unbounded()).unzip();
         let (finished_consume_work_sender, finished_consume_work_receiver) = unbounded();
-        let scheduler =
-            PrioGraphScheduler::new(consume_work_senders, finished_consume_work_receiver);
+        let scheduler = PrioGraphScheduler::new(
+            consume_work_senders,
+            finished_consume_work_receiver,
+            BundleAccountLocker::default(),
+        );
         (
             scheduler,
             consume_work_receivers,
@@ -788,4 +822,47 @@ mod tests {
             vec![txids!([2]), txids!([0])]
         );
     }
+
+    #[test]
+    fn test_schedule_defers_bundle_locked_accounts() {
+        let (mut scheduler, work_receivers, _finished_work_sender) = create_test_frame(1);
+        let bundle_write_locked = Pubkey::new_unique();
+        let bundle_read_locked = Pubkey::new_unique();
+        let mut container = create_container([
+            (&Keypair::new(), &[bundle_write_locked], 1, 4),
+            (&Keypair::new(), &[bundle_read_locked], 1, 3),
+            (&Keypair::new(), &[Pubkey::new_unique()], 1, 2),
+        ]);
+        scheduler
+            .bundle_account_locker
+            .account_locks()
+            .lock_accounts(
+                HashMap::from([(bundle_read_locked, 1)]),
+                HashMap::from([(bundle_write_locked, 1)]),
+            );
+
+        // Transfers write-lock their recipients, so only the 3rd transaction can be scheduled.
+        let scheduling_summary = scheduler
+            .schedule(&mut container, test_pre_graph_filter, test_pre_lock_filter)
+            .unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 1);
+        assert_eq!(scheduling_summary.num_unschedulable, 0);
+        assert_eq!(scheduling_summary.num_deferred_bundle_locks, 2);
+        assert_eq!(collect_work(&work_receivers[0]).1, vec![txids!([2])]);
+
+        // Once the bundle releases its locks, the deferred transactions are scheduled.
+        scheduler
+            .bundle_account_locker
+            .account_locks()
+            .unlock_accounts(
+                HashMap::from([(bundle_read_locked, 1)]),
+                HashMap::from([(bundle_write_locked, 1)]),
+            );
+        let scheduling_summary = scheduler
+            .schedule(&mut container, test_pre_graph_filter, test_pre_lock_filter)
+            .unwrap();
+        assert_eq!(scheduling_summary.num_scheduled, 2);
+        assert_eq!(scheduling_summary.num_deferred_bundle_locks, 0);
+        assert_eq!(collect_work(&work_receivers[0]).1, vec![txids!([0, 1])]);
+    }
 }
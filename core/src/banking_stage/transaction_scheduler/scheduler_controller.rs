@@ -157,6 +157,10 @@ impl SchedulerController {
                         count_metrics.num_unschedulable,
                         scheduling_summary.num_unschedulable
                     );
+                    saturating_add_assign!(
+                        count_metrics.num_deferred_bundle_locks,
+                        scheduling_summary.num_deferred_bundle_locks
+                    );
                     saturating_add_assign!(
                         count_metrics.num_schedule_filtered_out,
                         scheduling_summary.num_filtered_out
@@ -518,6 +522,7 @@ mod tests {
                 tests::create_slow_genesis_config,
             },
             banking_trace::BankingPacketBatch,
+            bundle_stage::bundle_account_locker::BundleAccountLocker,
             sigverify::SigverifyTracerPacketStats,
         },
         crossbeam_channel::{unbounded, Receiver, Sender},
@@ -605,7 +610,11 @@ mod tests {
             decision_maker,
             packet_deserializer,
             bank_forks,
-            PrioGraphScheduler::new(consume_work_senders, finished_consume_work_receiver),
+            PrioGraphScheduler::new(
+                consume_work_senders,
+                finished_consume_work_receiver,
+                BundleAccountLocker::default(),
+            ),
             vec![], // no actual workers with metrics to report, this can be empty
         );
 
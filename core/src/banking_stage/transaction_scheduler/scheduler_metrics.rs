@@ -51,6 +51,8 @@ pub struct SchedulerCountMetricsInner {
     pub num_scheduled: usize,
     /// Number of transactions that were unschedulable.
     pub num_unschedulable: usize,
+    /// Number of transactions that were deferred because BundleStage locked their accounts.
+    pub num_deferred_bundle_locks: usize,
     /// Number of transactions that were filtered out during scheduling.
     pub num_schedule_filtered_out: usize,
     /// Number of completed transactions received from workers.
@@ -113,6 +115,11 @@ impl SchedulerCountMetricsInner {
             ("num_buffered", self.num_buffered, i64),
             ("num_scheduled", self.num_scheduled, i64),
             ("num_unschedulable", self.num_unschedulable, i64),
+            (
+                "num_deferred_bundle_locks",
+                self.num_deferred_bundle_locks,
+                i64
+            ),
             (
                 "num_schedule_filtered_out",
                 self.num_schedule_filtered_out,
@@ -157,6 +164,7 @@ impl SchedulerCountMetricsInner {
             || self.num_buffered != 0
             || self.num_scheduled != 0
             || self.num_unschedulable != 0
+            || self.num_deferred_bundle_locks != 0
             || self.num_schedule_filtered_out != 0
             || self.num_finished != 0
             || self.num_retryable != 0
@@ -174,6 +182,7 @@ impl SchedulerCountMetricsInner {
         self.num_buffered = 0;
         self.num_scheduled = 0;
         self.num_unschedulable = 0;
+        self.num_deferred_bundle_locks = 0;
         self.num_schedule_filtered_out = 0;
         self.num_finished = 0;
         self.num_retryable = 0;
@@ -16,7 +16,10 @@ use {
         BankingStageStats, FilterForwardingResults, ForwardOption,
     },
     crate::{
-        bundle_stage::bundle_stage_leader_metrics::BundleStageLeaderMetrics,
+        bundle_stage::{
+            bundle_auction::{BundleAuction, BundleAuctionResult},
+            bundle_stage_leader_metrics::BundleStageLeaderMetrics,
+        },
         immutable_deserialized_bundle::ImmutableDeserializedBundle,
     },
     itertools::Itertools,
@@ -287,6 +290,11 @@ impl UnprocessedTransactionStorage {
     }
 
     pub fn new_bundle_storage() -> Self {
+        Self::new_bundle_storage_with_auction(None)
+    }
+
+    /// Bundle storage that orders bundles with the [BundleAuction] before executing them, if set
+    pub fn new_bundle_storage_with_auction(bundle_auction: Option<BundleAuction>) -> Self {
         Self::BundleStorage(BundleStorage {
             last_update_slot: Slot::default(),
             unprocessed_bundle_storage: VecDeque::with_capacity(
@@ -295,6 +303,7 @@ impl UnprocessedTransactionStorage {
             cost_model_buffered_bundle_storage: VecDeque::with_capacity(
                 BundleStorage::BUNDLE_STORAGE_CAPACITY,
             ),
+            bundle_auction,
         })
     }
 
@@ -1129,6 +1138,8 @@ pub struct BundleStorage {
     // Storage for bundles that exceeded the cost model for the slot they were last attempted
     // execution on
     cost_model_buffered_bundle_storage: VecDeque<ImmutableDeserializedBundle>,
+    // Orders bundles before execution instead of executing them in the order received
+    bundle_auction: Option<BundleAuction>,
 }
 
 impl BundleStorage {
@@ -1163,10 +1174,43 @@ impl BundleStorage {
         self.unprocessed_bundle_storage.capacity() - self.unprocessed_bundle_storage.len()
     }
 
+    /// Estimated cost of the buffered bundles that can still execute in `slot`, based on the
+    /// compute unit limits their transactions request. Bundles that exceeded the cost model are
+    /// only retried once a new slot starts.
+    pub(crate) fn pending_bundle_cost(&self, slot: Slot) -> u64 {
+        let num_cost_model_buffered = if slot != self.last_update_slot {
+            self.cost_model_buffered_bundle_storage.len()
+        } else {
+            0
+        };
+        self.unprocessed_bundle_storage
+            .iter()
+            .chain(
+                self.cost_model_buffered_bundle_storage
+                    .iter()
+                    .take(num_cost_model_buffered),
+            )
+            .map(|bundle| bundle.compute_unit_limit())
+            .fold(0, u64::saturating_add)
+    }
+
     fn forward_option(&self) -> ForwardOption {
         ForwardOption::NotForward
     }
 
+    /// Slot of the bank bundles were last attempted on
+    pub(crate) fn last_update_slot(&self) -> Slot {
+        self.last_update_slot
+    }
+
+    /// Ids of all buffered bundles, including those waiting on the cost model
+    pub(crate) fn bundle_ids(&self) -> impl Iterator<Item = &str> {
+        self.unprocessed_bundle_storage
+            .iter()
+            .chain(self.cost_model_buffered_bundle_storage.iter())
+            .map(|b| b.bundle_id())
+    }
+
     /// Returns the number of unprocessed bundles + cost model buffered cleared
     pub fn reset(&mut self) -> (usize, usize) {
         let num_unprocessed_bundles = self.unprocessed_bundle_storage.len();
@@ -1272,11 +1316,34 @@ impl BundleStorage {
         ) -> Vec<Result<(), BundleExecutionError>>,
     {
         let sanitized_bundles = self.drain_and_sanitize_bundles(
-            bank,
+            bank.clone(),
             bundle_stage_leader_metrics,
             blacklisted_accounts,
         );
 
+        let (sanitized_bundles, deferred_bundles) = match &mut self.bundle_auction {
+            Some(bundle_auction) => {
+                let (BundleAuctionResult { selected, deferred }, auction_us) =
+                    measure_us!(bundle_auction.run(&bank, sanitized_bundles));
+                debug!(
+                    "bundle auction selected {} bundles, deferred {}",
+                    selected.len(),
+                    deferred.len()
+                );
+                bundle_stage_leader_metrics
+                    .bundle_stage_metrics_tracker()
+                    .increment_bundle_auction_elapsed_us(auction_us);
+                bundle_stage_leader_metrics
+                    .bundle_stage_metrics_tracker()
+                    .increment_num_bundles_deferred_by_auction(deferred.len() as u64);
+                (selected, deferred)
+            }
+            None => (sanitized_bundles, vec![]),
+        };
+        // deferred bundles conflict with a higher paying bundle, retry them on the next pass
+        // against the updated state
+        self.insert_unprocessed_bundles(deferred_bundles, true);
+
         debug!("processing {} bundles", sanitized_bundles.len());
         let bundle_execution_results =
             processing_function(&sanitized_bundles, bundle_stage_leader_metrics);
@@ -1330,6 +1397,13 @@ impl BundleStorage {
                         // lock errors are irrecoverable due to malformed transactions
                         debug!("bundle={} lock error", sanitized_bundle.bundle_id);
                     }
+                    Err(BundleExecutionError::RejectedByPolicy(reason)) => {
+                        // the operator doesn't want this bundle, drop it
+                        debug!(
+                            "bundle={} rejected by policy: {}",
+                            sanitized_bundle.bundle_id, reason
+                        );
+                    }
                 },
             );
 
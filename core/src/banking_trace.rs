@@ -1,15 +1,16 @@
 use {
-    crate::sigverify::SigverifyTracerPacketStats,
+    crate::{packet_bundle::PacketBundle, sigverify::SigverifyTracerPacketStats},
     bincode::serialize_into,
     chrono::{DateTime, Local},
     crossbeam_channel::{unbounded, Receiver, SendError, Sender, TryRecvError},
     rolling_file::{RollingCondition, RollingConditionBasic, RollingFileAppender},
+    serde::de::DeserializeOwned,
     solana_perf::packet::PacketBatch,
     solana_sdk::{hash::Hash, slot_history::Slot},
     std::{
-        fs::{create_dir_all, remove_dir_all},
-        io::{self, Write},
-        path::PathBuf,
+        fs::{create_dir_all, remove_dir_all, File},
+        io::{self, BufReader, Write},
+        path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, Ordering},
             Arc,
@@ -43,6 +44,10 @@ pub enum TraceError {
 }
 
 const BASENAME: &str = "events";
+// bundles are traced to their own files so the format of `events` is unchanged
+const BUNDLE_BASENAME: &str = "bundle_events";
+// the dir byte limit is split evenly between the `events` and `bundle_events` files
+const TRACE_FILE_STREAM_COUNT: u64 = 2;
 const TRACE_FILE_ROTATE_COUNT: u64 = 14; // target 2 weeks retention under normal load
 const TRACE_FILE_WRITE_INTERVAL_MS: u64 = 100;
 const BUF_WRITER_CAPACITY: usize = 10 * 1024 * 1024;
@@ -53,10 +58,16 @@ pub const BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT: DirByteLimit =
 
 #[derive(Clone, Debug)]
 struct ActiveTracer {
-    trace_sender: Sender<TimedTracedEvent>,
+    trace_sender: Sender<TraceFileEvent>,
     exit: Arc<AtomicBool>,
 }
 
+#[derive(Debug)]
+enum TraceFileEvent {
+    Banking(TimedTracedEvent),
+    Bundle(TimedTracedBundleEvent),
+}
+
 #[derive(Debug)]
 pub struct BankingTracer {
     active_tracer: Option<ActiveTracer>,
@@ -80,6 +91,67 @@ pub enum ChannelLabel {
     Dummy,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TimedTracedBundleEvent(pub std::time::SystemTime, pub TracedBundleEvent);
+
+#[derive(Serialize, Deserialize, Debug)]
+pub enum TracedBundleEvent {
+    /// Bundles received by BundleStage along with the slot of the working bank at the time
+    Bundles(Slot, Vec<PacketBundle>),
+}
+
+/// Everything recorded in a banking trace directory, in the order it was traced
+#[derive(Debug, Default)]
+pub struct BankingTraceEvents {
+    pub events: Vec<TimedTracedEvent>,
+    pub bundle_events: Vec<TimedTracedBundleEvent>,
+}
+
+impl BankingTraceEvents {
+    /// Reads back the trace files in `path`, oldest first. A truncated event at the end of a
+    /// file (from an unclean shutdown) ends that file.
+    pub fn load(path: &Path) -> Result<Self, TraceError> {
+        Ok(Self {
+            events: Self::read_trace_files(path, BASENAME)?,
+            bundle_events: Self::read_trace_files(path, BUNDLE_BASENAME)?,
+        })
+    }
+
+    fn read_trace_files<T: DeserializeOwned>(
+        path: &Path,
+        basename: &str,
+    ) -> Result<Vec<T>, TraceError> {
+        let mut events = vec![];
+        // rotated files are suffixed with .1, .2, ... where higher suffixes are older
+        let file_names = (1..TRACE_FILE_ROTATE_COUNT)
+            .rev()
+            .map(|index| format!("{basename}.{index}"))
+            .chain(std::iter::once(basename.to_string()));
+        for file_name in file_names {
+            let file = match File::open(path.join(file_name)) {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let mut stream = BufReader::new(file);
+            loop {
+                match bincode::deserialize_from::<_, T>(&mut stream) {
+                    Ok(event) => events.push(event),
+                    Err(err) => match *err {
+                        bincode::ErrorKind::Io(ref err)
+                            if err.kind() == io::ErrorKind::UnexpectedEof =>
+                        {
+                            break
+                        }
+                        _ => return Err(err.into()),
+                    },
+                }
+            }
+        }
+        Ok(events)
+    }
+}
+
 struct RollingConditionGrouped {
     basic: RollingConditionBasic,
     tried_rollover_after_opened: bool,
@@ -179,20 +251,28 @@ impl BankingTracer {
         match maybe_config {
             None => Ok((Self::new_disabled(), None)),
             Some((path, exit, dir_byte_limit)) => {
-                let rotate_threshold_size = dir_byte_limit / TRACE_FILE_ROTATE_COUNT;
+                let rotate_threshold_size =
+                    dir_byte_limit / (TRACE_FILE_ROTATE_COUNT * TRACE_FILE_STREAM_COUNT);
                 if rotate_threshold_size == 0 {
                     return Err(TraceError::TooSmallDirByteLimit(
                         dir_byte_limit,
-                        TRACE_FILE_ROTATE_COUNT,
+                        TRACE_FILE_ROTATE_COUNT * TRACE_FILE_STREAM_COUNT,
                     ));
                 }
 
                 let (trace_sender, trace_receiver) = unbounded();
 
-                let file_appender = Self::create_file_appender(path, rotate_threshold_size)?;
+                let file_appender =
+                    Self::create_file_appender(path, BASENAME, rotate_threshold_size)?;
+                let bundle_file_appender =
+                    Self::create_file_appender(path, BUNDLE_BASENAME, rotate_threshold_size)?;
 
-                let tracer_thread =
-                    Self::spawn_background_thread(trace_receiver, file_appender, exit.clone())?;
+                let tracer_thread = Self::spawn_background_thread(
+                    trace_receiver,
+                    file_appender,
+                    bundle_file_appender,
+                    exit.clone(),
+                )?;
 
                 Ok((
                     Arc::new(Self {
@@ -232,14 +312,24 @@ impl BankingTracer {
 
     pub fn hash_event(&self, slot: Slot, blockhash: &Hash, bank_hash: &Hash) {
         self.trace_event(|| {
-            TimedTracedEvent(
+            TraceFileEvent::Banking(TimedTracedEvent(
                 SystemTime::now(),
                 TracedEvent::BlockAndBankHash(slot, *blockhash, *bank_hash),
-            )
+            ))
+        })
+    }
+
+    /// Traces bundles received by BundleStage while `slot` is the working bank
+    pub fn bundle_event(&self, slot: Slot, bundles: &[PacketBundle]) {
+        self.trace_event(|| {
+            TraceFileEvent::Bundle(TimedTracedBundleEvent(
+                SystemTime::now(),
+                TracedBundleEvent::Bundles(slot, bundles.to_vec()),
+            ))
         })
     }
 
-    fn trace_event(&self, on_trace: impl Fn() -> TimedTracedEvent) {
+    fn trace_event(&self, on_trace: impl Fn() -> TraceFileEvent) {
         if let Some(ActiveTracer { trace_sender, exit }) = &self.active_tracer {
             if !exit.load(Ordering::Relaxed) {
                 trace_sender
@@ -273,6 +363,7 @@ impl BankingTracer {
 
     fn create_file_appender(
         path: &PathBuf,
+        basename: &str,
         rotate_threshold_size: u64,
     ) -> Result<RollingFileAppender<RollingConditionGrouped>, TraceError> {
         create_dir_all(path)?;
@@ -282,7 +373,7 @@ impl BankingTracer {
                 .max_size(rotate_threshold_size),
         );
         let appender = RollingFileAppender::new_with_buffer_capacity(
-            path.join(BASENAME),
+            path.join(basename),
             grouped,
             (TRACE_FILE_ROTATE_COUNT - 1).try_into()?,
             BUF_WRITER_CAPACITY,
@@ -291,8 +382,9 @@ impl BankingTracer {
     }
 
     fn spawn_background_thread(
-        trace_receiver: Receiver<TimedTracedEvent>,
+        trace_receiver: Receiver<TraceFileEvent>,
         mut file_appender: RollingFileAppender<RollingConditionGrouped>,
+        mut bundle_file_appender: RollingFileAppender<RollingConditionGrouped>,
         exit: Arc<AtomicBool>,
     ) -> Result<JoinHandle<TracerThreadResult>, TraceError> {
         let thread = thread::Builder::new().name("solBanknTracer".into()).spawn(
@@ -301,12 +393,27 @@ impl BankingTracer {
                     exit,
                     trace_receiver,
                     |event| -> Result<(), TraceError> {
-                        file_appender.condition_mut().reset();
-                        serialize_into(&mut GroupedWriter::new(&mut file_appender), &event)?;
+                        match event {
+                            TraceFileEvent::Banking(event) => {
+                                file_appender.condition_mut().reset();
+                                serialize_into(
+                                    &mut GroupedWriter::new(&mut file_appender),
+                                    &event,
+                                )?;
+                            }
+                            TraceFileEvent::Bundle(event) => {
+                                bundle_file_appender.condition_mut().reset();
+                                serialize_into(
+                                    &mut GroupedWriter::new(&mut bundle_file_appender),
+                                    &event,
+                                )?;
+                            }
+                        }
                         Ok(())
                     },
                 )?;
                 file_appender.flush()?;
+                bundle_file_appender.flush()?;
                 Ok(())
             },
         )?;
@@ -339,10 +446,10 @@ impl TracedSender {
         if let Some(ActiveTracer { trace_sender, exit }) = &self.active_tracer {
             if !exit.load(Ordering::Relaxed) {
                 trace_sender
-                    .send(TimedTracedEvent(
+                    .send(TraceFileEvent::Banking(TimedTracedEvent(
                         SystemTime::now(),
                         TracedEvent::PacketBatch(self.label, BankingPacketBatch::clone(&batch)),
-                    ))
+                    )))
                     .map_err(|err| {
                         error!(
                             "unexpected error when tracing a banking event...: {:?}",
@@ -533,6 +640,68 @@ mod tests {
         for_test::drop_and_clean_temp_dir_unless_suppressed(temp_dir);
     }
 
+    #[test]
+    fn test_record_and_load_bundles() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("banking-trace");
+        let exit = Arc::<AtomicBool>::default();
+        let (tracer, tracer_thread) =
+            BankingTracer::new(Some((&path, exit.clone(), DirByteLimit::max_value()))).unwrap();
+        let (non_vote_sender, non_vote_receiver) = tracer.create_channel_non_vote();
+
+        let dummy_main_thread = thread::spawn(move || {
+            receiving_loop_with_minimized_sender_overhead::<_, TraceError, 0>(
+                exit,
+                non_vote_receiver,
+                |_packet_batch| Ok(()),
+            )
+        });
+
+        non_vote_sender
+            .send(for_test::sample_packet_batch())
+            .unwrap();
+        let sample_batch = for_test::sample_packet_batch();
+        let bundle = PacketBundle {
+            batch: sample_batch.0[0].clone(),
+            bundle_id: "bundle".to_string(),
+        };
+        tracer.bundle_event(5, &[bundle.clone()]);
+        let blockhash = Hash::from_str("B1ockhash1111111111111111111111111111111111").unwrap();
+        let bank_hash = Hash::from_str("BankHash11111111111111111111111111111111111").unwrap();
+        tracer.hash_event(5, &blockhash, &bank_hash);
+
+        for_test::terminate_tracer(
+            tracer,
+            tracer_thread,
+            dummy_main_thread,
+            non_vote_sender,
+            None,
+        );
+
+        let BankingTraceEvents {
+            events,
+            bundle_events,
+        } = BankingTraceEvents::load(&path).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_matches!(
+            events[0],
+            TimedTracedEvent(_, TracedEvent::PacketBatch(ChannelLabel::NonVote, _))
+        );
+        assert_matches!(
+            events[1],
+            TimedTracedEvent(_, TracedEvent::BlockAndBankHash(5, _, _))
+        );
+        assert_eq!(bundle_events.len(), 1);
+        let TimedTracedBundleEvent(_, TracedBundleEvent::Bundles(slot, bundles)) =
+            &bundle_events[0];
+        assert_eq!(*slot, 5);
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].bundle_id, bundle.bundle_id);
+        assert_eq!(bundles[0].batch.len(), bundle.batch.len());
+
+        for_test::drop_and_clean_temp_dir_unless_suppressed(temp_dir);
+    }
+
     #[test]
     fn test_spill_over_at_rotation() {
         let temp_dir = TempDir::new().unwrap();
@@ -540,7 +709,8 @@ mod tests {
         const REALLY_SMALL_ROTATION_THRESHOLD: u64 = 1;
 
         let mut file_appender =
-            BankingTracer::create_file_appender(&path, REALLY_SMALL_ROTATION_THRESHOLD).unwrap();
+            BankingTracer::create_file_appender(&path, BASENAME, REALLY_SMALL_ROTATION_THRESHOLD)
+                .unwrap();
         file_appender.write_all(b"foo").unwrap();
         file_appender.condition_mut().reset();
         file_appender.write_all(b"bar").unwrap();
@@ -565,17 +735,23 @@ mod tests {
 
         let path = temp_dir.path().join("banking-trace");
 
-        let mut file_appender =
-            BankingTracer::create_file_appender(&path, TRACE_FILE_DEFAULT_ROTATE_BYTE_THRESHOLD)
-                .unwrap();
+        let mut file_appender = BankingTracer::create_file_appender(
+            &path,
+            BASENAME,
+            TRACE_FILE_DEFAULT_ROTATE_BYTE_THRESHOLD,
+        )
+        .unwrap();
         // assume this is unclean write
         file_appender.write_all(b"f").unwrap();
         file_appender.flush().unwrap();
 
         // reopen while shadow-dropping the old tracer
-        let mut file_appender =
-            BankingTracer::create_file_appender(&path, TRACE_FILE_DEFAULT_ROTATE_BYTE_THRESHOLD)
-                .unwrap();
+        let mut file_appender = BankingTracer::create_file_appender(
+            &path,
+            BASENAME,
+            TRACE_FILE_DEFAULT_ROTATE_BYTE_THRESHOLD,
+        )
+        .unwrap();
         // new file won't be created as appender is lazy
         assert_eq!(
             [
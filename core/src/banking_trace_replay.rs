@@ -0,0 +1,512 @@
+//! Replays the packets and bundles recorded by the [BankingTracer] through BankingStage and
+//! BundleStage for a single leader slot on top of its parent bank. Events are sent with the same
+//! timing relative to the start of the slot as they were recorded with, so mis-packed blocks can
+//! be reproduced and changes to the schedulers measured offline. The replayed slot's entries and
+//! tips are written to a scratch blockstore, leaving the ledger being replayed from untouched.
+use {
+    crate::{
+        banking_stage::BankingStage,
+        banking_trace::{
+            BankingPacketBatch, BankingTraceEvents, BankingTracer, ChannelLabel,
+            TimedTracedBundleEvent, TimedTracedEvent, TraceError, TracedBundleEvent, TracedEvent,
+        },
+        bundle_stage::{
+            bundle_account_locker::BundleAccountLocker, bundle_auction::BundleOrderingPolicy,
+            bundle_reserved_space_manager::BundleReservedSpacePolicy,
+            bundle_status_reporter::BundleStatusReporter, BundleStage,
+        },
+        packet_bundle::PacketBundle,
+        proxy::block_engine_stage::BlockBuilderFeeInfo,
+        tip_manager::{TipManager, TipManagerConfig},
+        validator::BlockProductionMethod,
+    },
+    crossbeam_channel::unbounded,
+    solana_bundle::bundle_status::BundleStatusCache,
+    solana_client::connection_cache::ConnectionCache,
+    solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
+    solana_ledger::{
+        blockstore::{Blockstore, BlockstoreError},
+        leader_schedule_cache::LeaderScheduleCache,
+    },
+    solana_poh::{
+        poh_recorder::PohRecorder,
+        poh_service::{PohService, DEFAULT_HASHES_PER_BATCH, DEFAULT_PINNED_CPU_CORE},
+    },
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache,
+    },
+    solana_sdk::{
+        account::AccountSharedData, clock::Slot, hash::Hash, native_token::LAMPORTS_PER_SOL,
+        poh_config::PohConfig, pubkey::Pubkey, signature::Keypair, signer::Signer, system_program,
+        timing::timestamp,
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    std::{
+        collections::HashSet,
+        io,
+        path::Path,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex, RwLock,
+        },
+        thread::sleep,
+        time::{Duration, Instant, SystemTime},
+    },
+    tempfile::TempDir,
+    thiserror::Error,
+};
+
+/// Events which arrived up to this long before the slot started are sent before the slot's bank
+/// is set, standing in for the backlog a leader has buffered by the time its slot starts.
+const WARMUP_DURATION: Duration = Duration::from_millis(400);
+
+/// How long to wait for PoH to complete the slot once all events were sent
+const SLOT_COMPLETION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The replay runs with a throwaway identity, which needs funds to pay for the tip program
+/// initialization transactions sent by BundleStage.
+const IDENTITY_LAMPORTS: u64 = 1_000 * LAMPORTS_PER_SOL;
+
+#[derive(Error, Debug)]
+pub enum BankingTraceReplayError {
+    #[error("Failed to load banking trace: {0}")]
+    Trace(#[from] TraceError),
+
+    #[error("Slot {0} must be greater than the parent slot {1}")]
+    InvalidSlot(Slot, Slot),
+
+    #[error("Slot {0} already exists in bank forks")]
+    SlotAlreadyExists(Slot),
+
+    #[error("Banking trace doesn't record when parent slot {0} was frozen")]
+    MissingParentHashEvent(Slot),
+
+    #[error("Timed out waiting for slot {0} to complete")]
+    SlotTimeout(Slot),
+
+    #[error("Failed to create the scratch ledger directory: {0}")]
+    ScratchLedgerDir(#[from] io::Error),
+
+    #[error("Failed to open the scratch blockstore: {0}")]
+    ScratchBlockstore(#[from] BlockstoreError),
+}
+
+pub struct BankingTraceReplayConfig {
+    pub block_production_method: BlockProductionMethod,
+    pub bundle_ordering_policy: BundleOrderingPolicy,
+    pub tip_manager_config: TipManagerConfig,
+    pub preallocated_bundle_cost: u64,
+    pub bundle_reserved_space_policy: BundleReservedSpacePolicy,
+}
+
+#[derive(Debug)]
+pub struct BankingTraceReplaySummary {
+    pub slot: Slot,
+    pub parent_slot: Slot,
+    /// Number of packet batches sent to BankingStage
+    pub num_packet_batches: usize,
+    /// Number of bundles sent to BundleStage
+    pub num_bundles: usize,
+    /// Number of non-tick entries recorded into the replayed bank
+    pub num_entries: usize,
+    /// Number of transactions recorded into the replayed bank
+    pub num_transactions: usize,
+    pub bank_hash: Hash,
+    /// Bank hash of the slot when it was traced, if the trace recorded it
+    pub recorded_bank_hash: Option<Hash>,
+}
+
+enum ReplayEvent {
+    PacketBatch(ChannelLabel, BankingPacketBatch),
+    Bundles(Vec<PacketBundle>),
+}
+
+type TimedReplayEvents = Vec<(SystemTime, ReplayEvent)>;
+
+/// Returns the vote account of the leader scheduled for `slot`, so that tips can be sent to the
+/// same tip distribution account as when the trace was recorded.
+pub fn slot_leader_vote_account(parent: &Bank, slot: Slot) -> Option<Pubkey> {
+    let leader = LeaderScheduleCache::new_from_bank(parent).slot_leader_at(slot, Some(parent))?;
+    parent
+        .vote_accounts()
+        .iter()
+        .find_map(|(vote_pubkey, (_, vote_account))| {
+            (vote_account.node_pubkey() == Some(leader)).then_some(*vote_pubkey)
+        })
+}
+
+pub struct BankingTraceReplayer {
+    events: BankingTraceEvents,
+}
+
+impl BankingTraceReplayer {
+    pub fn new(events: BankingTraceEvents) -> Self {
+        Self { events }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BankingTraceReplayError> {
+        Ok(Self::new(BankingTraceEvents::load(path)?))
+    }
+
+    /// Replays the trace for `slot` as a child of the working bank of `bank_forks`, which is
+    /// expected to be the parent the slot was built on when the trace was recorded.
+    pub fn replay(
+        &self,
+        config: BankingTraceReplayConfig,
+        slot: Slot,
+        bank_forks: &Arc<RwLock<BankForks>>,
+        poh_config: &PohConfig,
+    ) -> Result<BankingTraceReplaySummary, BankingTraceReplayError> {
+        let parent = bank_forks.read().unwrap().working_bank();
+        if slot <= parent.slot() {
+            return Err(BankingTraceReplayError::InvalidSlot(slot, parent.slot()));
+        }
+        if bank_forks.read().unwrap().get(slot).is_some() {
+            return Err(BankingTraceReplayError::SlotAlreadyExists(slot));
+        }
+
+        // the tracer records the parent being frozen right before the leader's bank is created
+        let slot_start = self.hash_event(parent.slot()).map(|(time, _)| time).ok_or(
+            BankingTraceReplayError::MissingParentHashEvent(parent.slot()),
+        )?;
+        let recorded_bank_hash = self.hash_event(slot).map(|(_, bank_hash)| bank_hash);
+        let slot_duration = Duration::from_nanos(
+            u64::try_from(parent.ns_per_slot)
+                .unwrap_or(u64::MAX)
+                .saturating_mul(slot - parent.slot()),
+        );
+        let (warmup_events, slot_events) =
+            self.events_in_window(slot_start, slot_duration + WARMUP_DURATION);
+        info!(
+            "replaying slot {} on parent {}: {} warmup events, {} events during the slot",
+            slot,
+            parent.slot(),
+            warmup_events.len(),
+            slot_events.len()
+        );
+
+        // BundleStage persists the tips paid in the slot, which must not overwrite the ones the
+        // ledger recorded for it
+        let scratch_ledger_dir = TempDir::new()?;
+        let blockstore = Arc::new(Blockstore::open(scratch_ledger_dir.path())?);
+
+        let identity = Arc::new(Keypair::new());
+        let bank = Bank::new_from_parent(parent.clone(), &identity.pubkey(), slot);
+        bank.store_account(
+            &identity.pubkey(),
+            &AccountSharedData::new(IDENTITY_LAMPORTS, 0, &system_program::id()),
+        );
+
+        let exit = Arc::new(AtomicBool::default());
+        let leader_schedule_cache = Arc::new(LeaderScheduleCache::new_from_bank(&parent));
+        let (poh_recorder, entry_receiver, record_receiver) = PohRecorder::new(
+            parent.tick_height(),
+            parent.last_blockhash(),
+            parent.clone(),
+            Some((slot, slot)),
+            parent.ticks_per_slot(),
+            &identity.pubkey(),
+            blockstore.clone(),
+            &leader_schedule_cache,
+            poh_config,
+            exit.clone(),
+        );
+        let poh_recorder = Arc::new(RwLock::new(poh_recorder));
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new_localhost(&identity.pubkey(), timestamp()),
+            identity,
+            SocketAddrSpace::Unspecified,
+        ));
+
+        let (non_vote_sender, non_vote_receiver) = unbounded();
+        let (tpu_vote_sender, tpu_vote_receiver) = unbounded();
+        let (gossip_vote_sender, gossip_vote_receiver) = unbounded();
+        let (bundle_sender, bundle_receiver) = unbounded();
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let prioritization_fee_cache = Arc::new(PrioritizationFeeCache::new(0u64));
+        let bundle_account_locker = BundleAccountLocker::default();
+
+        let tip_manager = TipManager::new(config.tip_manager_config);
+        let mut blacklisted_accounts = HashSet::new();
+        blacklisted_accounts.insert(tip_manager.tip_payment_config_pubkey());
+        blacklisted_accounts.extend(tip_manager.get_tip_accounts());
+
+        let banking_stage = BankingStage::new(
+            config.block_production_method,
+            &cluster_info,
+            &poh_recorder,
+            non_vote_receiver,
+            tpu_vote_receiver,
+            gossip_vote_receiver,
+            None,
+            replay_vote_sender.clone(),
+            None,
+            Arc::new(ConnectionCache::new(
+                "connection_cache_banking_trace_replay",
+            )),
+            bank_forks.clone(),
+            &prioritization_fee_cache,
+            blacklisted_accounts,
+            bundle_account_locker.clone(),
+        );
+        let bundle_stage = BundleStage::new(
+            &cluster_info,
+            &poh_recorder,
+            bundle_receiver,
+            None,
+            replay_vote_sender,
+            None,
+            exit.clone(),
+            tip_manager,
+            bundle_account_locker,
+            &Arc::new(Mutex::new(BlockBuilderFeeInfo {
+                block_builder: cluster_info.id(),
+                block_builder_commission: 0,
+            })),
+            config.preallocated_bundle_cost,
+            bank_forks.clone(),
+            &prioritization_fee_cache,
+            BundleStatusReporter::new(Arc::new(BundleStatusCache::default()), None, None),
+            config.bundle_ordering_policy,
+            config.bundle_reserved_space_policy,
+            blockstore,
+            None,
+            BankingTracer::new_disabled(),
+        );
+
+        let mut num_packet_batches = 0;
+        let mut num_bundles = 0;
+        let mut send_event = |event: &ReplayEvent| match event {
+            ReplayEvent::PacketBatch(label, batch) => {
+                num_packet_batches += 1;
+                let sender = match label {
+                    ChannelLabel::NonVote => &non_vote_sender,
+                    ChannelLabel::TpuVote => &tpu_vote_sender,
+                    ChannelLabel::GossipVote => &gossip_vote_sender,
+                    ChannelLabel::Dummy => return,
+                };
+                // the stages only hang up once the replay is over
+                let _ = sender.send(batch.clone());
+            }
+            ReplayEvent::Bundles(bundles) => {
+                num_bundles += bundles.len();
+                let _ = bundle_sender.send(bundles.clone());
+            }
+        };
+
+        for (_, event) in &warmup_events {
+            send_event(event);
+        }
+
+        let bank = bank_forks.write().unwrap().insert(bank);
+        let replayed_bank = bank.clone_without_scheduler();
+        poh_recorder.write().unwrap().set_bank(bank, false);
+        let poh_service = PohService::new(
+            poh_recorder.clone(),
+            poh_config,
+            exit.clone(),
+            parent.ticks_per_slot(),
+            DEFAULT_PINNED_CPU_CORE,
+            DEFAULT_HASHES_PER_BATCH,
+            record_receiver,
+        );
+        let replay_start = Instant::now();
+
+        for (time, event) in &slot_events {
+            let offset = time.duration_since(slot_start).unwrap_or_default();
+            if let Some(delay) = offset.checked_sub(replay_start.elapsed()) {
+                sleep(delay);
+            }
+            if !poh_recorder.read().unwrap().has_bank() {
+                break;
+            }
+            send_event(event);
+        }
+
+        let completed = loop {
+            if !poh_recorder.read().unwrap().has_bank() {
+                break true;
+            }
+            if replay_start.elapsed() > slot_duration + SLOT_COMPLETION_TIMEOUT {
+                break false;
+            }
+            sleep(Duration::from_millis(10));
+        };
+
+        exit.store(true, Ordering::Relaxed);
+        drop((
+            non_vote_sender,
+            tpu_vote_sender,
+            gossip_vote_sender,
+            bundle_sender,
+        ));
+        banking_stage.join().unwrap();
+        bundle_stage.join().unwrap();
+        poh_service.join().unwrap();
+
+        if !completed {
+            return Err(BankingTraceReplayError::SlotTimeout(slot));
+        }
+
+        let (num_entries, num_transactions) = entry_receiver
+            .try_iter()
+            .filter(|(_, (entry, _))| !entry.is_tick())
+            .fold(
+                (0, 0),
+                |(num_entries, num_transactions), (_, (entry, _))| {
+                    (num_entries + 1, num_transactions + entry.transactions.len())
+                },
+            );
+
+        replayed_bank.freeze();
+        Ok(BankingTraceReplaySummary {
+            slot,
+            parent_slot: parent.slot(),
+            num_packet_batches,
+            num_bundles,
+            num_entries,
+            num_transactions,
+            bank_hash: replayed_bank.hash(),
+            recorded_bank_hash,
+        })
+    }
+
+    /// Returns when the bank for `slot` was traced as frozen along with its bank hash
+    fn hash_event(&self, slot: Slot) -> Option<(SystemTime, Hash)> {
+        self.events
+            .events
+            .iter()
+            .rev()
+            .find_map(|TimedTracedEvent(time, event)| match event {
+                TracedEvent::BlockAndBankHash(event_slot, _, bank_hash) if *event_slot == slot => {
+                    Some((*time, *bank_hash))
+                }
+                _ => None,
+            })
+    }
+
+    /// Splits the events traced within `duration` after `slot_start - WARMUP_DURATION` into the
+    /// ones which arrived before the slot started and the ones during it, each sorted by arrival.
+    fn events_in_window(
+        &self,
+        slot_start: SystemTime,
+        duration: Duration,
+    ) -> (TimedReplayEvents, TimedReplayEvents) {
+        let window_start = slot_start
+            .checked_sub(WARMUP_DURATION)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let window_end = window_start + duration;
+
+        let packet_events =
+            self.events
+                .events
+                .iter()
+                .filter_map(|TimedTracedEvent(time, event)| match event {
+                    TracedEvent::PacketBatch(label, batch) => {
+                        Some((*time, ReplayEvent::PacketBatch(*label, batch.clone())))
+                    }
+                    TracedEvent::BlockAndBankHash(..) => None,
+                });
+        let bundle_events = self.events.bundle_events.iter().map(
+            |TimedTracedBundleEvent(time, event)| match event {
+                TracedBundleEvent::Bundles(_, bundles) => {
+                    (*time, ReplayEvent::Bundles(bundles.clone()))
+                }
+            },
+        );
+        let mut events: Vec<_> = packet_events
+            .chain(bundle_events)
+            .filter(|(time, _)| *time >= window_start && *time < window_end)
+            .collect();
+        events.sort_by_key(|(time, _)| *time);
+
+        events.into_iter().partition(|(time, _)| *time < slot_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::banking_trace::BankingTraceEvents,
+        solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_perf::packet::PacketBatch,
+        solana_sdk::{packet::Packet, system_transaction},
+    };
+
+    #[test]
+    fn test_replay_recorded_trace() {
+        solana_logger::setup();
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(10 * LAMPORTS_PER_SOL);
+        let (parent, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        parent.fill_bank_with_ticks_for_tests();
+        parent.freeze();
+
+        let recipient = Pubkey::new_unique();
+        let transfer = system_transaction::transfer(
+            &mint_keypair,
+            &recipient,
+            LAMPORTS_PER_SOL,
+            genesis_config.hash(),
+        );
+        let batch = PacketBatch::new(vec![Packet::from_data(None, &transfer).unwrap()]);
+        let recorded_bank_hash = Hash::new_unique();
+        let slot_start = SystemTime::now();
+        let replayer = BankingTraceReplayer::new(BankingTraceEvents {
+            events: vec![
+                TimedTracedEvent(
+                    slot_start,
+                    TracedEvent::BlockAndBankHash(
+                        parent.slot(),
+                        parent.last_blockhash(),
+                        parent.hash(),
+                    ),
+                ),
+                TimedTracedEvent(
+                    slot_start + Duration::from_millis(10),
+                    TracedEvent::PacketBatch(
+                        ChannelLabel::NonVote,
+                        BankingPacketBatch::new((vec![batch], None)),
+                    ),
+                ),
+                TimedTracedEvent(
+                    slot_start + Duration::from_millis(400),
+                    TracedEvent::BlockAndBankHash(1, Hash::new_unique(), recorded_bank_hash),
+                ),
+            ],
+            bundle_events: vec![],
+        });
+
+        let summary = replayer
+            .replay(
+                BankingTraceReplayConfig {
+                    block_production_method: BlockProductionMethod::default(),
+                    bundle_ordering_policy: BundleOrderingPolicy::default(),
+                    tip_manager_config: TipManagerConfig::default(),
+                    preallocated_bundle_cost: 0,
+                    bundle_reserved_space_policy: BundleReservedSpacePolicy::default(),
+                },
+                1,
+                &bank_forks,
+                &genesis_config.poh_config,
+            )
+            .unwrap();
+
+        assert_eq!(summary.slot, 1);
+        assert_eq!(summary.parent_slot, parent.slot());
+        assert_eq!(summary.num_packet_batches, 1);
+        assert_eq!(summary.num_transactions, 1);
+        assert_eq!(summary.recorded_bank_hash, Some(recorded_bank_hash));
+
+        // the reported hash is the one of the replayed bank, which includes the traced transfer
+        let replayed_bank = bank_forks.read().unwrap().get(1).unwrap();
+        assert!(replayed_bank.is_frozen());
+        assert_eq!(summary.bank_hash, replayed_bank.hash());
+        assert_ne!(summary.bank_hash, parent.hash());
+        assert_eq!(replayed_bank.get_balance(&recipient), LAMPORTS_PER_SOL);
+    }
+}
@@ -7,23 +7,33 @@ use {
             qos_service::QosService,
             unprocessed_transaction_storage::UnprocessedTransactionStorage,
         },
+        banking_trace::BankingTracer,
         bundle_stage::{
-            bundle_account_locker::BundleAccountLocker, bundle_consumer::BundleConsumer,
+            bundle_account_locker::BundleAccountLocker,
+            bundle_auction::{BundleAuction, BundleOrderingPolicy},
+            bundle_consumer::BundleConsumer,
             bundle_packet_receiver::BundleReceiver,
-            bundle_reserved_space_manager::BundleReservedSpaceManager,
-            bundle_stage_leader_metrics::BundleStageLeaderMetrics, committer::Committer,
+            bundle_reserved_space_manager::{
+                BundleReservedSpaceManager, BundleReservedSpacePolicy,
+            },
+            bundle_stage_leader_metrics::BundleStageLeaderMetrics,
+            bundle_status_reporter::BundleStatusReporter,
+            committer::Committer,
+            tip_ledger::TipLedger,
         },
         packet_bundle::PacketBundle,
         proxy::block_engine_stage::BlockBuilderFeeInfo,
         tip_manager::TipManager,
     },
     crossbeam_channel::{Receiver, RecvTimeoutError},
+    solana_bundle::{bundle_status::BundleStatus, MAX_TRANSACTIONS_PER_BUNDLE},
     solana_cost_model::block_cost_limits::MAX_BLOCK_UNITS,
     solana_gossip::cluster_info::ClusterInfo,
-    solana_ledger::blockstore_processor::TransactionStatusSender,
+    solana_ledger::{blockstore::Blockstore, blockstore_processor::TransactionStatusSender},
     solana_measure::measure,
     solana_poh::poh_recorder::PohRecorder,
     solana_runtime::{bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache},
+    solana_runtime_plugin::runtime_plugin_manager::RuntimePluginManager,
     solana_sdk::timing::AtomicInterval,
     solana_vote::vote_sender_types::ReplayVoteSender,
     std::{
@@ -37,12 +47,15 @@ use {
 };
 
 pub mod bundle_account_locker;
+pub mod bundle_auction;
 mod bundle_consumer;
 mod bundle_packet_deserializer;
 mod bundle_packet_receiver;
-mod bundle_reserved_space_manager;
+pub mod bundle_reserved_space_manager;
 pub(crate) mod bundle_stage_leader_metrics;
+pub mod bundle_status_reporter;
 mod committer;
+mod tip_ledger;
 
 const MAX_BUNDLE_RETRY_DURATION: Duration = Duration::from_millis(10);
 const SLOT_BOUNDARY_CHECK_PERIOD: Duration = Duration::from_millis(10);
@@ -210,6 +223,12 @@ impl BundleStage {
         preallocated_bundle_cost: u64,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        bundle_status_reporter: BundleStatusReporter,
+        bundle_ordering_policy: BundleOrderingPolicy,
+        bundle_reserved_space_policy: BundleReservedSpacePolicy,
+        blockstore: Arc<Blockstore>,
+        runtime_plugin_manager: Option<Arc<RwLock<RuntimePluginManager>>>,
+        banking_tracer: Arc<BankingTracer>,
     ) -> Self {
         Self::start_bundle_thread(
             cluster_info,
@@ -226,6 +245,12 @@ impl BundleStage {
             preallocated_bundle_cost,
             bank_forks,
             prioritization_fee_cache,
+            bundle_status_reporter,
+            bundle_ordering_policy,
+            bundle_reserved_space_policy,
+            blockstore,
+            runtime_plugin_manager,
+            banking_tracer,
         )
     }
 
@@ -249,13 +274,24 @@ impl BundleStage {
         preallocated_bundle_cost: u64,
         bank_forks: Arc<RwLock<BankForks>>,
         prioritization_fee_cache: &Arc<PrioritizationFeeCache>,
+        bundle_status_reporter: BundleStatusReporter,
+        bundle_ordering_policy: BundleOrderingPolicy,
+        bundle_reserved_space_policy: BundleReservedSpacePolicy,
+        blockstore: Arc<Blockstore>,
+        runtime_plugin_manager: Option<Arc<RwLock<RuntimePluginManager>>>,
+        banking_tracer: Arc<BankingTracer>,
     ) -> Self {
         const BUNDLE_STAGE_ID: u32 = 10_000;
         let poh_recorder = poh_recorder.clone();
         let cluster_info = cluster_info.clone();
 
-        let mut bundle_receiver =
-            BundleReceiver::new(BUNDLE_STAGE_ID, bundle_receiver, bank_forks, Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            BUNDLE_STAGE_ID,
+            bundle_receiver,
+            bank_forks,
+            Some(MAX_TRANSACTIONS_PER_BUNDLE),
+            banking_tracer,
+        );
 
         let committer = Committer::new(
             transaction_status_sender,
@@ -264,7 +300,14 @@ impl BundleStage {
         );
         let decision_maker = DecisionMaker::new(cluster_info.id(), poh_recorder.clone());
 
-        let unprocessed_bundle_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let bundle_auction = match bundle_ordering_policy {
+            BundleOrderingPolicy::Fifo => None,
+            BundleOrderingPolicy::TipPerComputeUnit => {
+                Some(BundleAuction::new(tip_manager.get_tip_accounts()))
+            }
+        };
+        let unprocessed_bundle_storage =
+            UnprocessedTransactionStorage::new_bundle_storage_with_auction(bundle_auction);
 
         let reserved_ticks = poh_recorder
             .read()
@@ -273,12 +316,13 @@ impl BundleStage {
             .saturating_mul(8)
             .saturating_div(10);
 
-        // The first 80% of the block, based on poh ticks, has `preallocated_bundle_cost` less compute units.
+        // The first 80% of the block, based on poh ticks, has up to `preallocated_bundle_cost` less compute units.
         // The last 20% has has full compute so blockspace is maximized if BundleStage is idle.
-        let reserved_space = BundleReservedSpaceManager::new(
+        let reserved_space = BundleReservedSpaceManager::new_with_policy(
             MAX_BLOCK_UNITS,
             preallocated_bundle_cost,
             reserved_ticks,
+            bundle_reserved_space_policy,
         );
 
         let consumer = BundleConsumer::new(
@@ -292,6 +336,9 @@ impl BundleStage {
             max_bundle_retry_duration,
             cluster_info,
             reserved_space,
+            bundle_status_reporter,
+            TipLedger::new(Some(blockstore)),
+            runtime_plugin_manager,
         );
 
         let bundle_thread = Builder::new()
@@ -368,6 +415,7 @@ impl BundleStage {
             );
             bundle_stage_metrics.maybe_report(1_000);
         }
+        consumer.maybe_flush_tip_ledger(None);
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -379,6 +427,7 @@ impl BundleStage {
     ) {
         let (decision, make_decision_time) =
             measure!(decision_maker.make_consume_or_forward_decision());
+        consumer.maybe_flush_tip_ledger(decision.bank_start());
 
         let (metrics_action, banking_stage_metrics_action) = bundle_stage_leader_metrics
             .check_leader_slot_boundary(decision.bank_start(), Some(unprocessed_bundle_storage));
@@ -412,8 +461,15 @@ impl BundleStage {
             // BufferedPacketsDecision::Forward means the leader is slot is far away.
             // Bundles aren't forwarded because it breaks atomicity guarantees, so just drop them.
             BufferedPacketsDecision::Forward => {
+                let bundle_storage = unprocessed_bundle_storage.bundle_storage().unwrap();
+                let slot = bundle_storage.last_update_slot();
+                bundle_storage.bundle_ids().for_each(|bundle_id| {
+                    consumer
+                        .bundle_status_reporter()
+                        .report(BundleStatus::dropped(bundle_id.to_string(), slot));
+                });
                 let (_num_bundles_cleared, _num_cost_model_buffered_bundles) =
-                    unprocessed_bundle_storage.bundle_storage().unwrap().reset();
+                    bundle_storage.reset();
 
                 // TODO (LB): add metrics here for how many bundles were cleared
 
@@ -0,0 +1,449 @@
+//! Optional local ordering of buffered bundles.
+//!
+//! By default BundleStage executes bundles in the order they arrive and relies on the block engine
+//! to have ordered them. When bundles arrive from several sources that order is meaningless, so
+//! the [BundleAuction] can instead rank bundles by the tip they pay per compute unit, found by
+//! simulating each bundle against the working bank. Bundles are then picked greedily in that order,
+//! skipping any that conflict on write locks with an already picked bundle. Skipped bundles are
+//! retried on the next pass against the updated state.
+//!
+//! Simulation results are cached for the lifetime of the working bank, and each pass stops
+//! simulating once it has used up its time budget. Bundles left unsimulated are ranked after the
+//! simulated ones, in arrival order, and simulated on a later pass.
+use {
+    crate::immutable_deserialized_bundle::ImmutableDeserializedBundle,
+    lazy_static::lazy_static,
+    solana_bundle::bundle_execution::load_and_execute_bundle,
+    solana_runtime::bank::{Bank, BankId},
+    solana_sdk::{
+        bundle::SanitizedBundle, clock::MAX_PROCESSING_AGE, pubkey::Pubkey,
+        transaction::SanitizedTransaction,
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        time::{Duration, Instant},
+    },
+    strum::{Display, EnumString, EnumVariantNames, IntoStaticStr, VariantNames},
+};
+
+/// Upper bound on the time spent simulating a single bundle
+const MAX_SIMULATION_DURATION: Duration = Duration::from_millis(20);
+
+/// Upper bound on the time spent simulating bundles in a single auction pass
+const MAX_PASS_SIMULATION_DURATION: Duration = Duration::from_millis(50);
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumString, EnumVariantNames, Default, IntoStaticStr, Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BundleOrderingPolicy {
+    /// Execute bundles in the order they were received
+    #[default]
+    Fifo,
+    /// Execute bundles in order of tip paid per compute unit, resolving write-lock conflicts greedily
+    TipPerComputeUnit,
+}
+
+impl BundleOrderingPolicy {
+    pub const fn cli_names() -> &'static [&'static str] {
+        Self::VARIANTS
+    }
+
+    pub fn cli_message() -> &'static str {
+        lazy_static! {
+            static ref MESSAGE: String = format!(
+                "Switch the order buffered bundles are executed in [default: {}]",
+                BundleOrderingPolicy::default()
+            );
+        };
+
+        &MESSAGE
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BundleAuctionResult {
+    /// Bundles to execute this pass, highest ranked first
+    pub selected: Vec<(ImmutableDeserializedBundle, SanitizedBundle)>,
+    /// Bundles that conflict with a higher ranked bundle and should be retried on the next pass
+    pub deferred: Vec<ImmutableDeserializedBundle>,
+}
+
+#[derive(Debug)]
+pub struct BundleAuction {
+    tip_accounts: HashSet<Pubkey>,
+    /// Bank the cached scores were simulated against
+    cached_bank_id: Option<BankId>,
+    /// Tip per compute unit of the bundles already simulated against the cached bank, by bundle id
+    cached_scores: HashMap<String, u64>,
+}
+
+impl BundleAuction {
+    pub fn new(tip_accounts: HashSet<Pubkey>) -> Self {
+        Self {
+            tip_accounts,
+            cached_bank_id: None,
+            cached_scores: HashMap::new(),
+        }
+    }
+
+    /// Ranks the bundles by simulated tip per compute unit and selects the highest ranked set of
+    /// bundles that don't conflict on write locks
+    pub fn run(
+        &mut self,
+        bank: &Bank,
+        bundles: Vec<(ImmutableDeserializedBundle, SanitizedBundle)>,
+    ) -> BundleAuctionResult {
+        self.run_with_budget(bank, bundles, MAX_PASS_SIMULATION_DURATION)
+    }
+
+    fn run_with_budget(
+        &mut self,
+        bank: &Bank,
+        bundles: Vec<(ImmutableDeserializedBundle, SanitizedBundle)>,
+        simulation_budget: Duration,
+    ) -> BundleAuctionResult {
+        if self.cached_bank_id != Some(bank.bank_id()) {
+            self.cached_bank_id = Some(bank.bank_id());
+            self.cached_scores.clear();
+        }
+
+        let deadline = Instant::now() + simulation_budget;
+        let mut ranked: Vec<_> = bundles
+            .into_iter()
+            .map(|(deserialized_bundle, sanitized_bundle)| {
+                let score = self.score(bank, &sanitized_bundle, deadline);
+                (score, deserialized_bundle, sanitized_bundle)
+            })
+            .collect();
+        // stable sort so equally ranked bundles keep their arrival order, unsimulated bundles
+        // rank last
+        ranked.sort_by(|(a, _, _), (b, _, _)| b.cmp(a));
+
+        self.select_non_conflicting(ranked.into_iter().map(
+            |(_, deserialized_bundle, sanitized_bundle)| (deserialized_bundle, sanitized_bundle),
+        ))
+    }
+
+    /// Returns the cached score of the bundle, simulating it if there's time left before
+    /// `deadline`, or None if it couldn't be simulated in time
+    fn score(
+        &mut self,
+        bank: &Bank,
+        sanitized_bundle: &SanitizedBundle,
+        deadline: Instant,
+    ) -> Option<u64> {
+        if let Some(score) = self.cached_scores.get(&sanitized_bundle.bundle_id) {
+            return Some(*score);
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return None;
+        }
+        let score = self.tip_per_compute_unit(
+            bank,
+            sanitized_bundle,
+            &remaining.min(MAX_SIMULATION_DURATION),
+        );
+        self.cached_scores
+            .insert(sanitized_bundle.bundle_id.clone(), score);
+        Some(score)
+    }
+
+    /// Simulates the bundle and returns the lamports paid to the tip accounts per million compute
+    /// units consumed. Bundles that fail simulation are ranked last so they still run through
+    /// BundleStage and have their failure reported.
+    fn tip_per_compute_unit(
+        &self,
+        bank: &Bank,
+        sanitized_bundle: &SanitizedBundle,
+        max_simulation_duration: &Duration,
+    ) -> u64 {
+        let tip_accounts_touched: Vec<Option<Vec<Pubkey>>> = sanitized_bundle
+            .transactions
+            .iter()
+            .map(|tx| {
+                let touched: Vec<Pubkey> = tx
+                    .message()
+                    .account_keys()
+                    .iter()
+                    .filter(|a| self.tip_accounts.contains(a))
+                    .cloned()
+                    .collect();
+                (!touched.is_empty()).then_some(touched)
+            })
+            .collect();
+        if tip_accounts_touched.iter().all(Option::is_none) {
+            return 0;
+        }
+
+        let output = load_and_execute_bundle(
+            bank,
+            sanitized_bundle,
+            MAX_PROCESSING_AGE,
+            max_simulation_duration,
+            false,
+            false,
+            false,
+            false,
+            &None,
+            true,
+            None,
+            &tip_accounts_touched,
+            &tip_accounts_touched,
+        );
+        if !output.executed_ok() {
+            return 0;
+        }
+
+        let mut tip_lamports: u64 = 0;
+        let mut compute_units: u64 = 0;
+        for batch in output.bundle_transaction_results() {
+            tip_lamports = batch
+                .pre_tx_execution_accounts()
+                .iter()
+                .zip(batch.post_tx_execution_accounts())
+                .filter_map(|(pre, post)| Some((pre.as_ref()?, post.as_ref()?)))
+                .flat_map(|(pre, post)| pre.iter().zip(post.iter()))
+                .map(|((_, pre), (_, post))| post.lamports().saturating_sub(pre.lamports()))
+                .fold(tip_lamports, u64::saturating_add);
+            compute_units = batch
+                .execution_results()
+                .iter()
+                .filter_map(|r| r.details())
+                .map(|d| d.executed_units)
+                .fold(compute_units, u64::saturating_add);
+        }
+
+        u128::from(tip_lamports)
+            .saturating_mul(1_000_000)
+            .checked_div(u128::from(compute_units.max(1)))
+            .and_then(|score| u64::try_from(score).ok())
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Walks the bundles in order, selecting each bundle whose locks don't conflict with the
+    /// bundles already selected. Tip accounts are written by nearly every bundle and are excluded
+    /// from the conflict check.
+    fn select_non_conflicting(
+        &self,
+        bundles: impl Iterator<Item = (ImmutableDeserializedBundle, SanitizedBundle)>,
+    ) -> BundleAuctionResult {
+        let mut write_locks: HashSet<Pubkey> = HashSet::new();
+        let mut read_locks: HashSet<Pubkey> = HashSet::new();
+        let mut result = BundleAuctionResult::default();
+
+        for (deserialized_bundle, sanitized_bundle) in bundles {
+            let (writable, readonly) =
+                self.bundle_account_locks(sanitized_bundle.transactions.iter());
+            let conflicts = writable
+                .iter()
+                .any(|a| write_locks.contains(a) || read_locks.contains(a))
+                || readonly.iter().any(|a| write_locks.contains(a));
+            if conflicts {
+                result.deferred.push(deserialized_bundle);
+            } else {
+                write_locks.extend(writable);
+                read_locks.extend(readonly);
+                result
+                    .selected
+                    .push((deserialized_bundle, sanitized_bundle));
+            }
+        }
+        result
+    }
+
+    /// Returns the (writable, readonly) accounts locked by the transactions, minus the tip
+    /// accounts. An account that's writable in one transaction is only reported as writable.
+    fn bundle_account_locks<'a>(
+        &self,
+        transactions: impl Iterator<Item = &'a SanitizedTransaction>,
+    ) -> (HashSet<Pubkey>, HashSet<Pubkey>) {
+        let mut writable = HashSet::new();
+        let mut readonly = HashSet::new();
+        for tx in transactions {
+            let locks = tx.get_account_locks_unchecked();
+            writable.extend(
+                locks
+                    .writable
+                    .into_iter()
+                    .filter(|a| !self.tip_accounts.contains(a))
+                    .cloned(),
+            );
+            readonly.extend(locks.readonly.into_iter().cloned());
+        }
+        readonly.retain(|a| !writable.contains(a));
+        (writable, readonly)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::packet_bundle::PacketBundle,
+        solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_perf::packet::PacketBatch,
+        solana_sdk::{
+            hash::Hash,
+            packet::Packet,
+            signature::{Keypair, Signer},
+            system_transaction::transfer,
+        },
+        solana_svm::transaction_error_metrics::TransactionErrorMetrics,
+    };
+
+    // rent exempt amount for the transferred to accounts
+    const TRANSFER_LAMPORTS: u64 = 1_000_000;
+
+    fn make_bundle(
+        bank: &Bank,
+        bundle_id: &str,
+        transfers: &[(&Keypair, &Pubkey)],
+        recent_blockhash: Hash,
+    ) -> (ImmutableDeserializedBundle, SanitizedBundle) {
+        let packets = transfers
+            .iter()
+            .map(|(from, to)| {
+                Packet::from_data(
+                    None,
+                    transfer(from, to, TRANSFER_LAMPORTS, recent_blockhash),
+                )
+                .unwrap()
+            })
+            .collect();
+        let mut packet_bundle = PacketBundle {
+            batch: PacketBatch::new(packets),
+            bundle_id: bundle_id.to_string(),
+        };
+        let deserialized_bundle =
+            ImmutableDeserializedBundle::new(&mut packet_bundle, None).unwrap();
+        let sanitized_bundle = deserialized_bundle
+            .build_sanitized_bundle(
+                bank,
+                &HashSet::default(),
+                &mut TransactionErrorMetrics::default(),
+            )
+            .unwrap();
+        (deserialized_bundle, sanitized_bundle)
+    }
+
+    #[test]
+    fn test_select_non_conflicting() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(1_000_000_000);
+        let bank = Bank::new_no_wallclock_throttle_for_tests(&genesis_config).0;
+        let blockhash = genesis_config.hash();
+
+        let tip_account = Pubkey::new_unique();
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        let carol = Pubkey::new_unique();
+
+        let auction = BundleAuction::new(HashSet::from([tip_account]));
+        let bundles = vec![
+            make_bundle(&bank, "a", &[(&mint_keypair, &carol)], blockhash),
+            // conflicts with "a" on the mint and carol
+            make_bundle(&bank, "b", &[(&mint_keypair, &bob.pubkey())], blockhash),
+            // only shares the tip account with "a", which isn't a conflict
+            make_bundle(&bank, "c", &[(&alice, &tip_account)], blockhash),
+            make_bundle(&bank, "d", &[(&bob, &carol)], blockhash),
+        ];
+
+        let result = auction.select_non_conflicting(bundles.into_iter());
+        assert_eq!(
+            result
+                .selected
+                .iter()
+                .map(|(_, b)| b.bundle_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "c"]
+        );
+        assert_eq!(
+            result
+                .deferred
+                .iter()
+                .map(|b| b.bundle_id())
+                .collect::<Vec<_>>(),
+            vec!["b", "d"]
+        );
+    }
+
+    #[test]
+    fn test_bundles_without_tips_rank_zero() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(1_000_000_000);
+        let bank = Bank::new_no_wallclock_throttle_for_tests(&genesis_config).0;
+
+        let tip_account = Pubkey::new_unique();
+        let auction = BundleAuction::new(HashSet::from([tip_account]));
+
+        let (_, no_tip) = make_bundle(
+            &bank,
+            "no-tip",
+            &[(&mint_keypair, &Pubkey::new_unique())],
+            genesis_config.hash(),
+        );
+        assert_eq!(
+            auction.tip_per_compute_unit(&bank, &no_tip, &MAX_SIMULATION_DURATION),
+            0
+        );
+
+        let (_, tip) = make_bundle(
+            &bank,
+            "tip",
+            &[(&mint_keypair, &tip_account)],
+            genesis_config.hash(),
+        );
+        assert!(auction.tip_per_compute_unit(&bank, &tip, &MAX_SIMULATION_DURATION) > 0);
+    }
+
+    fn bundle_ids(bundles: &[(ImmutableDeserializedBundle, SanitizedBundle)]) -> Vec<&str> {
+        bundles.iter().map(|(_, b)| b.bundle_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_simulation_budget_and_cache() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(1_000_000_000);
+        let bank = Bank::new_no_wallclock_throttle_for_tests(&genesis_config).0;
+        let blockhash = genesis_config.hash();
+
+        let tip_account = Pubkey::new_unique();
+        let mut auction = BundleAuction::new(HashSet::from([tip_account]));
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        let bundles = || {
+            vec![
+                make_bundle(&bank, "no-tip", &[(&alice, &bob.pubkey())], blockhash),
+                make_bundle(&bank, "tip", &[(&mint_keypair, &tip_account)], blockhash),
+            ]
+        };
+
+        // without a budget nothing is simulated and arrival order is kept
+        let result = auction.run_with_budget(&bank, bundles(), Duration::ZERO);
+        assert_eq!(bundle_ids(&result.selected), vec!["no-tip", "tip"]);
+        assert!(auction.cached_scores.is_empty());
+
+        let result = auction.run(&bank, bundles());
+        assert_eq!(bundle_ids(&result.selected), vec!["tip", "no-tip"]);
+        assert_eq!(auction.cached_scores.len(), 2);
+
+        // cached scores are used even when out of budget
+        let result = auction.run_with_budget(&bank, bundles(), Duration::ZERO);
+        assert_eq!(bundle_ids(&result.selected), vec!["tip", "no-tip"]);
+
+        // and dropped once the bank changes
+        let child = Bank::new_from_parent(bank.clone(), &Pubkey::new_unique(), bank.slot() + 1);
+        let result = auction.run_with_budget(&child, bundles(), Duration::ZERO);
+        assert_eq!(bundle_ids(&result.selected), vec!["no-tip", "tip"]);
+        assert!(auction.cached_scores.is_empty());
+    }
+}
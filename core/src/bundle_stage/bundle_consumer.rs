@@ -9,7 +9,9 @@ use {
             bundle_account_locker::{BundleAccountLocker, LockedBundle},
             bundle_reserved_space_manager::BundleReservedSpaceManager,
             bundle_stage_leader_metrics::BundleStageLeaderMetrics,
+            bundle_status_reporter::BundleStatusReporter,
             committer::Committer,
+            tip_ledger::TipLedger,
         },
         consensus_cache_updater::ConsensusCacheUpdater,
         immutable_deserialized_bundle::ImmutableDeserializedBundle,
@@ -18,6 +20,7 @@ use {
     },
     solana_bundle::{
         bundle_execution::{load_and_execute_bundle, BundleExecutionMetrics},
+        bundle_status::BundleStatus,
         BundleExecutionError, BundleExecutionResult, TipError,
     },
     solana_cost_model::transaction_cost::TransactionCost,
@@ -25,7 +28,11 @@ use {
     solana_measure::{measure, measure_us},
     solana_poh::poh_recorder::{BankStart, RecordTransactionsSummary, TransactionRecorder},
     solana_runtime::bank::Bank,
+    solana_runtime_plugin::{
+        runtime_plugin::BundleAcceptance, runtime_plugin_manager::RuntimePluginManager,
+    },
     solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
         bundle::SanitizedBundle,
         clock::{Slot, MAX_PROCESSING_AGE},
         feature_set,
@@ -35,7 +42,7 @@ use {
     solana_svm::transaction_error_metrics::TransactionErrorMetrics,
     std::{
         collections::HashSet,
-        sync::{Arc, Mutex},
+        sync::{Arc, Mutex, RwLock},
         time::{Duration, Instant},
     },
 };
@@ -46,6 +53,16 @@ pub struct ExecuteRecordCommitResult {
     execution_metrics: BundleExecutionMetrics,
     execute_and_commit_timings: LeaderExecuteAndCommitTimings,
     transaction_error_counter: TransactionErrorMetrics,
+    // lamports paid into each tip payment account by the bundle
+    tip_accounts: Vec<(Pubkey, u64)>,
+}
+
+/// Summary of a bundle that was recorded and committed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CommittedBundleSummary {
+    pub compute_units_consumed: u64,
+    pub tip_lamports: u64,
+    pub tip_accounts: Vec<(Pubkey, u64)>,
 }
 
 pub struct BundleConsumer {
@@ -72,6 +89,13 @@ pub struct BundleConsumer {
     cluster_info: Arc<ClusterInfo>,
 
     reserved_space: BundleReservedSpaceManager,
+
+    bundle_status_reporter: BundleStatusReporter,
+
+    tip_ledger: TipLedger,
+
+    // Runtime plugins that get to accept, reject or deprioritize bundles before they're locked
+    runtime_plugin_manager: Option<Arc<RwLock<RuntimePluginManager>>>,
 }
 
 impl BundleConsumer {
@@ -87,6 +111,9 @@ impl BundleConsumer {
         max_bundle_retry_duration: Duration,
         cluster_info: Arc<ClusterInfo>,
         reserved_space: BundleReservedSpaceManager,
+        bundle_status_reporter: BundleStatusReporter,
+        tip_ledger: TipLedger,
+        runtime_plugin_manager: Option<Arc<RwLock<RuntimePluginManager>>>,
     ) -> Self {
         Self {
             committer,
@@ -103,9 +130,22 @@ impl BundleConsumer {
             max_bundle_retry_duration,
             cluster_info,
             reserved_space,
+            bundle_status_reporter,
+            tip_ledger,
+            runtime_plugin_manager,
         }
     }
 
+    pub fn bundle_status_reporter(&self) -> &BundleStatusReporter {
+        &self.bundle_status_reporter
+    }
+
+    /// Writes out the tips paid during the last leader slot once the working bank has moved past it
+    pub fn maybe_flush_tip_ledger(&mut self, bank_start: Option<&BankStart>) {
+        self.tip_ledger
+            .maybe_flush(bank_start.map(|bank_start| bank_start.working_bank.slot()));
+    }
+
     // A bundle is a series of transactions to be executed sequentially, atomically, and all-or-nothing.
     // Sequentially:
     //  - Transactions are executed in order
@@ -130,7 +170,20 @@ impl BundleConsumer {
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
     ) {
         self.maybe_update_blacklist(bank_start);
-        self.reserved_space.tick(&bank_start.working_bank);
+        let pending_bundle_cost = unprocessed_transaction_storage
+            .bundle_storage()
+            .map(|bundle_storage| {
+                bundle_storage.pending_bundle_cost(bank_start.working_bank.slot())
+            })
+            .unwrap_or_default();
+        self.reserved_space
+            .tick(&bank_start.working_bank, pending_bundle_cost);
+        bundle_stage_leader_metrics
+            .bundle_stage_metrics_tracker()
+            .set_reserved_space(
+                self.reserved_space.slot_reserved_cost(),
+                self.reserved_space.released_early(),
+            );
 
         let reached_end_of_slot = unprocessed_transaction_storage.process_bundles(
             bank_start.working_bank.clone(),
@@ -148,7 +201,10 @@ impl BundleConsumer {
                     &self.qos_service,
                     &self.log_messages_bytes_limit,
                     self.max_bundle_retry_duration,
-                    &self.reserved_space,
+                    &mut self.reserved_space,
+                    &self.bundle_status_reporter,
+                    &mut self.tip_ledger,
+                    &self.runtime_plugin_manager,
                     bundles,
                     bank_start,
                     bundle_stage_leader_metrics,
@@ -199,22 +255,44 @@ impl BundleConsumer {
         qos_service: &QosService,
         log_messages_bytes_limit: &Option<usize>,
         max_bundle_retry_duration: Duration,
-        reserved_space: &BundleReservedSpaceManager,
+        reserved_space: &mut BundleReservedSpaceManager,
+        bundle_status_reporter: &BundleStatusReporter,
+        tip_ledger: &mut TipLedger,
+        runtime_plugin_manager: &Option<Arc<RwLock<RuntimePluginManager>>>,
         bundles: &[(ImmutableDeserializedBundle, SanitizedBundle)],
         bank_start: &BankStart,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
     ) -> Vec<Result<(), BundleExecutionError>> {
+        // Runtime plugins get the first say on each bundle. Rejected bundles aren't locked or
+        // executed and deprioritized bundles are executed after the accepted ones.
+        let (processing_order, policy_elapsed) = measure!(
+            Self::bundle_processing_order(
+                runtime_plugin_manager,
+                bundles,
+                &bank_start.working_bank,
+                bundle_stage_leader_metrics,
+            ),
+            "policy_elapsed"
+        );
+        bundle_stage_leader_metrics
+            .bundle_stage_metrics_tracker()
+            .increment_bundle_policy_elapsed_us(policy_elapsed.as_us());
+
         // BundleAccountLocker holds RW locks for ALL accounts in ALL transactions within a single bundle.
         // By pre-locking bundles before they're ready to be processed, it will prevent BankingStage from
         // grabbing those locks so BundleStage can process as fast as possible.
         // A LockedBundle is similar to TransactionBatch; once its dropped the locks are released.
         #[allow(clippy::needless_collect)]
         let (locked_bundle_results, locked_bundles_elapsed) = measure!(
-            bundles
+            processing_order
                 .iter()
-                .map(|(_, sanitized_bundle)| {
-                    bundle_account_locker
-                        .prepare_locked_bundle(sanitized_bundle, &bank_start.working_bank)
+                .filter_map(|(idx, acceptance)| match acceptance {
+                    Ok(()) => Some((
+                        *idx,
+                        bundle_account_locker
+                            .prepare_locked_bundle(&bundles[*idx].1, &bank_start.working_bank),
+                    )),
+                    Err(_) => None,
                 })
                 .collect::<Vec<_>>(),
             "locked_bundles_elapsed"
@@ -223,47 +301,119 @@ impl BundleConsumer {
             .bundle_stage_metrics_tracker()
             .increment_locked_bundle_elapsed_us(locked_bundles_elapsed.as_us());
 
-        let (execution_results, execute_locked_bundles_elapsed) = measure!(locked_bundle_results
-            .into_iter()
-            .map(|r| match r {
-                Ok(locked_bundle) => {
-                    let (r, measure) = measure_us!(Self::process_bundle(
-                        bundle_account_locker,
-                        tip_manager,
-                        last_tip_updated_slot,
-                        cluster_info,
-                        block_builder_fee_info,
-                        committer,
-                        recorder,
-                        qos_service,
-                        log_messages_bytes_limit,
-                        max_bundle_retry_duration,
-                        reserved_space,
-                        &locked_bundle,
-                        bank_start,
-                        bundle_stage_leader_metrics,
-                    ));
-                    bundle_stage_leader_metrics
-                        .leader_slot_metrics_tracker()
-                        .increment_process_packets_transactions_us(measure);
-                    r
-                }
-                Err(_) => {
-                    Err(BundleExecutionError::LockError)
-                }
-            })
-            .collect::<Vec<_>>());
+        // results are stored by the bundle's index so they're returned in the original order
+        let mut execution_results: Vec<Option<BundleExecutionResult<CommittedBundleSummary>>> =
+            vec![None; bundles.len()];
+        for (idx, acceptance) in processing_order {
+            if let Err(e) = acceptance {
+                execution_results[idx] = Some(Err(e));
+            }
+        }
+
+        let (_, execute_locked_bundles_elapsed) = measure!(
+            for (idx, locked_bundle_result) in locked_bundle_results {
+                let result = match locked_bundle_result {
+                    Ok(locked_bundle) => {
+                        let (r, measure) = measure_us!(Self::process_bundle(
+                            bundle_account_locker,
+                            tip_manager,
+                            last_tip_updated_slot,
+                            cluster_info,
+                            block_builder_fee_info,
+                            committer,
+                            recorder,
+                            qos_service,
+                            log_messages_bytes_limit,
+                            max_bundle_retry_duration,
+                            reserved_space,
+                            &locked_bundle,
+                            bank_start,
+                            bundle_stage_leader_metrics,
+                        ));
+                        bundle_stage_leader_metrics
+                            .leader_slot_metrics_tracker()
+                            .increment_process_packets_transactions_us(measure);
+                        r
+                    }
+                    Err(_) => Err(BundleExecutionError::LockError),
+                };
+                execution_results[idx] = Some(result);
+            },
+            "execute_locked_bundles_elapsed"
+        );
 
         bundle_stage_leader_metrics
             .bundle_stage_metrics_tracker()
             .increment_execute_locked_bundles_elapsed_us(execute_locked_bundles_elapsed.as_us());
-        execution_results.iter().for_each(|result| {
-            bundle_stage_leader_metrics
-                .bundle_stage_metrics_tracker()
-                .increment_bundle_execution_result(result);
-        });
+        let slot = bank_start.working_bank.slot();
+        bundles
+            .iter()
+            .zip(execution_results)
+            .map(|((_, sanitized_bundle), result)| {
+                let result = result.expect("every bundle has a result");
+                let bundle_id = sanitized_bundle.bundle_id.clone();
+                let (bundle_status, result) = match result {
+                    Ok(summary) => {
+                        reserved_space
+                            .record_bundle_compute_units(slot, summary.compute_units_consumed);
+                        tip_ledger.record_bundle(
+                            slot,
+                            &bundle_id,
+                            &summary.tip_accounts,
+                            &block_builder_fee_info.lock().unwrap(),
+                        );
+                        (
+                            BundleStatus::landed(
+                                bundle_id,
+                                slot,
+                                summary.compute_units_consumed,
+                                summary.tip_lamports,
+                            ),
+                            Ok(()),
+                        )
+                    }
+                    Err(e) => (BundleStatus::failed(bundle_id, slot, e.clone()), Err(e)),
+                };
+                bundle_stage_leader_metrics
+                    .bundle_stage_metrics_tracker()
+                    .increment_bundle_execution_result(&result);
+                bundle_status_reporter.report(bundle_status);
+                result
+            })
+            .collect()
+    }
 
-        execution_results
+    /// Returns the index of each bundle in the order it should be processed in, along with the
+    /// error for bundles that were rejected by a runtime plugin.
+    fn bundle_processing_order(
+        runtime_plugin_manager: &Option<Arc<RwLock<RuntimePluginManager>>>,
+        bundles: &[(ImmutableDeserializedBundle, SanitizedBundle)],
+        bank: &Bank,
+        bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
+    ) -> Vec<(usize, BundleExecutionResult<()>)> {
+        let Some(runtime_plugin_manager) = runtime_plugin_manager else {
+            return (0..bundles.len()).map(|idx| (idx, Ok(()))).collect();
+        };
+
+        let mut processing_order = Vec::with_capacity(bundles.len());
+        let mut deprioritized = Vec::new();
+        {
+            let runtime_plugin_manager = runtime_plugin_manager.read().unwrap();
+            for (idx, (_, sanitized_bundle)) in bundles.iter().enumerate() {
+                match runtime_plugin_manager.check_bundle(bank, sanitized_bundle) {
+                    BundleAcceptance::Accept => processing_order.push((idx, Ok(()))),
+                    BundleAcceptance::Reject(reason) => processing_order
+                        .push((idx, Err(BundleExecutionError::RejectedByPolicy(reason)))),
+                    BundleAcceptance::Deprioritize => deprioritized.push((idx, Ok(()))),
+                }
+            }
+        }
+        bundle_stage_leader_metrics
+            .bundle_stage_metrics_tracker()
+            .increment_num_bundles_deprioritized_by_policy(deprioritized.len() as u64);
+
+        processing_order.extend(deprioritized);
+        processing_order
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -282,7 +432,7 @@ impl BundleConsumer {
         locked_bundle: &LockedBundle,
         bank_start: &BankStart,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
-    ) -> Result<(), BundleExecutionError> {
+    ) -> Result<CommittedBundleSummary, BundleExecutionError> {
         if !Bank::should_bank_still_be_processing_txs(
             &bank_start.bank_creation_time,
             bank_start.working_bank.ns_per_slot,
@@ -290,11 +440,9 @@ impl BundleConsumer {
             return Err(BundleExecutionError::BankProcessingTimeLimitReached);
         }
 
+        let tip_accounts = tip_manager.get_tip_accounts();
         if bank_start.working_bank.slot() != *last_tip_updated_slot
-            && Self::bundle_touches_tip_pdas(
-                locked_bundle.sanitized_bundle(),
-                &tip_manager.get_tip_accounts(),
-            )
+            && Self::bundle_touches_tip_pdas(locked_bundle.sanitized_bundle(), &tip_accounts)
         {
             let start = Instant::now();
             let result = Self::handle_tip_programs(
@@ -328,12 +476,11 @@ impl BundleConsumer {
             log_messages_bytes_limit,
             max_bundle_retry_duration,
             reserved_space,
+            &tip_accounts,
             locked_bundle.sanitized_bundle(),
             bank_start,
             bundle_stage_leader_metrics,
-        )?;
-
-        Ok(())
+        )
     }
 
     /// The validator needs to manage state on two programs related to tips
@@ -378,6 +525,7 @@ impl BundleConsumer {
                 log_messages_bytes_limit,
                 max_bundle_retry_duration,
                 reserved_space,
+                &tip_manager.get_tip_accounts(),
                 locked_init_tip_programs_bundle.sanitized_bundle(),
                 bank_start,
                 bundle_stage_leader_metrics,
@@ -431,6 +579,7 @@ impl BundleConsumer {
                 log_messages_bytes_limit,
                 max_bundle_retry_duration,
                 reserved_space,
+                &tip_manager.get_tip_accounts(),
                 locked_tip_crank_bundle.sanitized_bundle(),
                 bank_start,
                 bundle_stage_leader_metrics,
@@ -499,10 +648,11 @@ impl BundleConsumer {
         log_messages_bytes_limit: &Option<usize>,
         max_bundle_retry_duration: Duration,
         reserved_space: &BundleReservedSpaceManager,
+        tip_accounts: &HashSet<Pubkey>,
         sanitized_bundle: &SanitizedBundle,
         bank_start: &BankStart,
         bundle_stage_leader_metrics: &mut BundleStageLeaderMetrics,
-    ) -> BundleExecutionResult<()> {
+    ) -> BundleExecutionResult<CommittedBundleSummary> {
         debug!(
             "bundle: {} reserving blockspace for {} transactions",
             sanitized_bundle.bundle_id,
@@ -529,6 +679,7 @@ impl BundleConsumer {
             recorder,
             log_messages_bytes_limit,
             max_bundle_retry_duration,
+            tip_accounts,
             sanitized_bundle,
             bank_start,
         ));
@@ -593,7 +744,15 @@ impl BundleConsumer {
                 }
 
                 qos_service.report_metrics(bank_start.working_bank.slot());
-                Ok(())
+                Ok(CommittedBundleSummary {
+                    compute_units_consumed: cu,
+                    tip_lamports: result
+                        .tip_accounts
+                        .iter()
+                        .map(|(_, lamports)| *lamports)
+                        .fold(0, u64::saturating_add),
+                    tip_accounts: result.tip_accounts,
+                })
             }
             Err(e) => {
                 // on bundle failure, none of the transactions are committed, so need to revert
@@ -615,6 +774,7 @@ impl BundleConsumer {
         recorder: &TransactionRecorder,
         log_messages_bytes_limit: &Option<usize>,
         max_bundle_retry_duration: Duration,
+        tip_accounts: &HashSet<Pubkey>,
         sanitized_bundle: &SanitizedBundle,
         bank_start: &BankStart,
     ) -> ExecuteRecordCommitResult {
@@ -623,7 +783,22 @@ impl BundleConsumer {
         let mut execute_and_commit_timings = LeaderExecuteAndCommitTimings::default();
 
         debug!("bundle: {} executing", sanitized_bundle.bundle_id);
-        let default_accounts = vec![None; sanitized_bundle.transactions.len()];
+        // load the tip accounts each transaction touches before and after execution so the tip
+        // paid by the bundle can be reported
+        let tip_accounts_touched: Vec<Option<Vec<Pubkey>>> = sanitized_bundle
+            .transactions
+            .iter()
+            .map(|tx| {
+                let touched: Vec<Pubkey> = tx
+                    .message()
+                    .account_keys()
+                    .iter()
+                    .filter(|a| tip_accounts.contains(a))
+                    .cloned()
+                    .collect();
+                (!touched.is_empty()).then_some(touched)
+            })
+            .collect();
         let mut bundle_execution_results = load_and_execute_bundle(
             &bank_start.working_bank,
             sanitized_bundle,
@@ -636,8 +811,8 @@ impl BundleConsumer {
             log_messages_bytes_limit,
             false,
             None,
-            &default_accounts,
-            &default_accounts,
+            &tip_accounts_touched,
+            &tip_accounts_touched,
         );
 
         let execution_metrics = bundle_execution_results.metrics();
@@ -671,9 +846,21 @@ impl BundleConsumer {
                 execution_metrics,
                 execute_and_commit_timings,
                 transaction_error_counter,
+                tip_accounts: vec![],
             };
         }
 
+        let tip_accounts = Self::tip_lamports_paid(
+            bundle_execution_results
+                .bundle_transaction_results()
+                .iter()
+                .flat_map(|r| {
+                    r.pre_tx_execution_accounts()
+                        .iter()
+                        .zip(r.post_tx_execution_accounts().iter())
+                }),
+        );
+
         let (executed_batches, execution_results_to_transactions_us) =
             measure_us!(bundle_execution_results.executed_transaction_batches());
 
@@ -725,6 +912,7 @@ impl BundleConsumer {
                 execution_metrics,
                 execute_and_commit_timings,
                 transaction_error_counter,
+                tip_accounts: vec![],
             };
         }
 
@@ -765,9 +953,37 @@ impl BundleConsumer {
             execution_metrics,
             execute_and_commit_timings,
             transaction_error_counter,
+            tip_accounts,
         }
     }
 
+    /// Sums the lamports each transaction deposited into each tip account, given the tip accounts
+    /// loaded before and after each transaction executed. Accounts that weren't paid are omitted.
+    fn tip_lamports_paid<'a>(
+        pre_post_accounts: impl Iterator<
+            Item = (
+                &'a Option<Vec<(Pubkey, AccountSharedData)>>,
+                &'a Option<Vec<(Pubkey, AccountSharedData)>>,
+            ),
+        >,
+    ) -> Vec<(Pubkey, u64)> {
+        let mut tip_accounts: Vec<(Pubkey, u64)> = vec![];
+        for ((tip_account, pre), (_, post)) in pre_post_accounts
+            .filter_map(|(pre, post)| Some((pre.as_ref()?, post.as_ref()?)))
+            .flat_map(|(pre, post)| pre.iter().zip(post.iter()))
+        {
+            let lamports = post.lamports().saturating_sub(pre.lamports());
+            if lamports == 0 {
+                continue;
+            }
+            match tip_accounts.iter_mut().find(|(a, _)| a == tip_account) {
+                Some((_, total)) => *total = total.saturating_add(lamports),
+                None => tip_accounts.push((*tip_account, lamports)),
+            }
+        }
+        tip_accounts
+    }
+
     /// Returns true if any of the transactions in a bundle mention one of the tip PDAs
     fn bundle_touches_tip_pdas(bundle: &SanitizedBundle, tip_pdas: &HashSet<Pubkey>) -> bool {
         bundle.transactions.iter().any(|tx| {
@@ -787,9 +1003,11 @@ mod tests {
                 bundle_account_locker::BundleAccountLocker, bundle_consumer::BundleConsumer,
                 bundle_packet_deserializer::BundlePacketDeserializer,
                 bundle_reserved_space_manager::BundleReservedSpaceManager,
-                bundle_stage_leader_metrics::BundleStageLeaderMetrics, committer::Committer,
-                QosService, UnprocessedTransactionStorage,
+                bundle_stage_leader_metrics::BundleStageLeaderMetrics,
+                bundle_status_reporter::BundleStatusReporter, committer::Committer,
+                tip_ledger::TipLedger, QosService, UnprocessedTransactionStorage,
             },
+            immutable_deserialized_bundle::ImmutableDeserializedBundle,
             packet_bundle::PacketBundle,
             proxy::block_engine_stage::BlockBuilderFeeInfo,
             tip_manager::{TipDistributionAccountConfig, TipManager, TipManagerConfig},
@@ -797,6 +1015,10 @@ mod tests {
         crossbeam_channel::{unbounded, Receiver},
         jito_tip_distribution::sdk::derive_tip_distribution_account_address,
         rand::{thread_rng, RngCore},
+        solana_bundle::{
+            bundle_status::{BundleOutcome, BundleStatusCache},
+            BundleExecutionError,
+        },
         solana_cost_model::{block_cost_limits::MAX_BLOCK_UNITS, cost_model::CostModel},
         solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
         solana_ledger::{
@@ -811,10 +1033,17 @@ mod tests {
         solana_program_test::programs::spl_programs,
         solana_runtime::{
             bank::Bank,
+            bank_forks::BankForks,
             genesis_utils::{create_genesis_config_with_leader_ex, GenesisConfigInfo},
             installed_scheduler_pool::BankWithScheduler,
             prioritization_fee_cache::PrioritizationFeeCache,
         },
+        solana_runtime_plugin::{
+            runtime_plugin::{
+                BundleAcceptance, PluginDependencies, Result as PluginResult, RuntimePlugin,
+            },
+            runtime_plugin_manager::RuntimePluginManager,
+        },
         solana_sdk::{
             bundle::{derive_bundle_id, SanitizedBundle},
             clock::MAX_PROCESSING_AGE,
@@ -837,7 +1066,7 @@ mod tests {
             transaction_error_metrics::TransactionErrorMetrics,
         },
         std::{
-            collections::HashSet,
+            collections::{HashMap, HashSet},
             str::FromStr,
             sync::{
                 atomic::{AtomicBool, Ordering},
@@ -1087,6 +1316,9 @@ mod tests {
                     .saturating_mul(8)
                     .saturating_div(10),
             ),
+            BundleStatusReporter::default(),
+            TipLedger::default(),
+            None,
         );
 
         let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
@@ -1217,6 +1449,7 @@ mod tests {
             SocketAddrSpace::new(true),
         ));
 
+        let bundle_status_cache = Arc::new(BundleStatusCache::default());
         let mut consumer = BundleConsumer::new(
             committer,
             recorder,
@@ -1237,6 +1470,9 @@ mod tests {
                     .saturating_mul(8)
                     .saturating_div(10),
             ),
+            BundleStatusReporter::new(bundle_status_cache.clone(), None, None),
+            TipLedger::default(),
+            None,
         );
 
         let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
@@ -1355,6 +1591,16 @@ mod tests {
             sanitized_bundle.transactions[0].to_versioned_transaction()
         );
 
+        // the tip crank bundles aren't reported, only the bundle that was received
+        assert_eq!(bundle_status_cache.len(), 1);
+        let bundle_status = bundle_status_cache
+            .get(&sanitized_bundle.bundle_id)
+            .unwrap();
+        assert!(bundle_status.is_landed());
+        assert_eq!(bundle_status.slot, bank.slot());
+        assert_eq!(bundle_status.tip_lamports, 1);
+        assert!(bundle_status.compute_units_consumed > 0);
+
         poh_recorder
             .write()
             .unwrap()
@@ -1594,4 +1840,262 @@ mod tests {
                 .saturating_sub(50)
         );
     }
+
+    /// Returns a preset verdict for some bundles and accepts all the others
+    #[derive(Debug)]
+    struct BundleIdPlugin {
+        verdicts: HashMap<String, BundleAcceptance>,
+    }
+
+    impl RuntimePlugin for BundleIdPlugin {
+        fn name(&self) -> &'static str {
+            "bundle-id-plugin"
+        }
+
+        fn on_load(
+            &mut self,
+            _config_file: &str,
+            _dependencies: PluginDependencies,
+        ) -> PluginResult<()> {
+            Ok(())
+        }
+
+        fn on_unload(&mut self) {}
+
+        fn check_bundle(&self, _bank: &Bank, bundle: &SanitizedBundle) -> BundleAcceptance {
+            self.verdicts
+                .get(&bundle.bundle_id)
+                .cloned()
+                .unwrap_or(BundleAcceptance::Accept)
+        }
+    }
+
+    fn new_runtime_plugin_manager(
+        verdicts: HashMap<String, BundleAcceptance>,
+    ) -> Option<Arc<RwLock<RuntimePluginManager>>> {
+        let mut runtime_plugin_manager = RuntimePluginManager::new(
+            BankForks::new_rw_arc(Bank::default_for_tests()),
+            Arc::default(),
+            Arc::default(),
+        );
+        runtime_plugin_manager.add_plugin_for_tests(Box::new(BundleIdPlugin { verdicts }));
+        Some(Arc::new(RwLock::new(runtime_plugin_manager)))
+    }
+
+    fn sanitize_bundles(
+        bank: &Bank,
+        packet_bundles: &mut [PacketBundle],
+    ) -> Vec<(ImmutableDeserializedBundle, SanitizedBundle)> {
+        packet_bundles
+            .iter_mut()
+            .map(|packet_bundle| {
+                let deserialized_bundle =
+                    BundlePacketDeserializer::deserialize_bundle(packet_bundle, false, None)
+                        .unwrap();
+                let sanitized_bundle = deserialized_bundle
+                    .build_sanitized_bundle(
+                        bank,
+                        &HashSet::default(),
+                        &mut TransactionErrorMetrics::default(),
+                    )
+                    .unwrap();
+                (deserialized_bundle, sanitized_bundle)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bundle_processing_order() {
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config(1_000_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let mut packet_bundles =
+            make_random_overlapping_bundles(&mint_keypair, 5, 1, genesis_config.hash(), 10_000);
+        let bundles = sanitize_bundles(&bank, &mut packet_bundles);
+        let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(1);
+
+        // the order is unchanged without plugins
+        let processing_order = BundleConsumer::bundle_processing_order(
+            &None,
+            &bundles,
+            &bank,
+            &mut bundle_stage_leader_metrics,
+        );
+        assert_eq!(
+            processing_order
+                .iter()
+                .map(|(idx, acceptance)| (*idx, acceptance.is_ok()))
+                .collect::<Vec<_>>(),
+            vec![(0, true), (1, true), (2, true), (3, true), (4, true)]
+        );
+
+        let bundle_id = |idx: usize| bundles[idx].1.bundle_id.clone();
+        let runtime_plugin_manager = new_runtime_plugin_manager(HashMap::from([
+            (bundle_id(1), BundleAcceptance::Reject("no".to_string())),
+            (bundle_id(2), BundleAcceptance::Deprioritize),
+            (bundle_id(4), BundleAcceptance::Deprioritize),
+        ]));
+        let processing_order = BundleConsumer::bundle_processing_order(
+            &runtime_plugin_manager,
+            &bundles,
+            &bank,
+            &mut bundle_stage_leader_metrics,
+        );
+
+        // accepted and rejected bundles keep their relative order, deprioritized bundles go last
+        // in their relative order
+        let processing_order: Vec<_> = processing_order
+            .into_iter()
+            .map(|(idx, acceptance)| (idx, acceptance.map_err(|e| e.to_string())))
+            .collect();
+        assert_eq!(
+            processing_order,
+            vec![
+                (0, Ok(())),
+                (
+                    1,
+                    Err(
+                        BundleExecutionError::RejectedByPolicy("bundle-id-plugin: no".to_string())
+                            .to_string()
+                    )
+                ),
+                (3, Ok(())),
+                (2, Ok(())),
+                (4, Ok(())),
+            ]
+        );
+
+        // results are stored back by index, so every bundle must appear exactly once
+        let mut indexes: Vec<_> = processing_order.iter().map(|(idx, _)| *idx).collect();
+        indexes.sort_unstable();
+        assert_eq!(indexes, (0..bundles.len()).collect::<Vec<_>>());
+    }
+
+    /// Bundles deprioritized by a runtime plugin run after the accepted ones and rejected bundles
+    /// don't run, while each bundle's status is still reported against the right bundle
+    #[test]
+    fn test_bundle_runtime_plugin_ordering() {
+        solana_logger::setup();
+        let TestFixture {
+            genesis_config_info,
+            leader_keypair,
+            bank,
+            exit,
+            poh_recorder,
+            poh_simulator,
+            entry_receiver,
+        } = create_test_fixture(1_000_000);
+        let recorder = poh_recorder.read().unwrap().new_recorder();
+
+        let (replay_vote_sender, _replay_vote_receiver) = unbounded();
+        let committer = Committer::new(
+            None,
+            replay_vote_sender,
+            Arc::new(PrioritizationFeeCache::new(0u64)),
+        );
+        let tip_manager = get_tip_manager(&genesis_config_info.voting_keypair.pubkey());
+        let block_builder_info = Arc::new(Mutex::new(BlockBuilderFeeInfo {
+            block_builder: Pubkey::new_unique(),
+            block_builder_commission: 10,
+        }));
+        let cluster_info = Arc::new(ClusterInfo::new(
+            ContactInfo::new(leader_keypair.pubkey(), 0, 0),
+            Arc::new(leader_keypair),
+            SocketAddrSpace::new(true),
+        ));
+        let bank_start = poh_recorder.read().unwrap().bank_start().unwrap();
+
+        let mut packet_bundles = make_random_overlapping_bundles(
+            &genesis_config_info.mint_keypair,
+            3,
+            1,
+            genesis_config_info.genesis_config.hash(),
+            10_000,
+        );
+        let bundles = sanitize_bundles(&bank_start.working_bank, &mut packet_bundles);
+        let bundle_id = |idx: usize| bundles[idx].1.bundle_id.clone();
+        let runtime_plugin_manager = new_runtime_plugin_manager(HashMap::from([
+            (bundle_id(0), BundleAcceptance::Deprioritize),
+            (bundle_id(1), BundleAcceptance::Reject("no".to_string())),
+        ]));
+
+        let bundle_status_cache = Arc::new(BundleStatusCache::default());
+        let mut consumer = BundleConsumer::new(
+            committer,
+            recorder,
+            QosService::new(1),
+            None,
+            tip_manager,
+            BundleAccountLocker::default(),
+            block_builder_info,
+            Duration::from_secs(10),
+            cluster_info,
+            BundleReservedSpaceManager::new(
+                MAX_BLOCK_UNITS,
+                3_000_000,
+                poh_recorder
+                    .read()
+                    .unwrap()
+                    .ticks_per_slot()
+                    .saturating_mul(8)
+                    .saturating_div(10),
+            ),
+            BundleStatusReporter::new(bundle_status_cache.clone(), None, None),
+            TipLedger::default(),
+            runtime_plugin_manager,
+        );
+
+        let mut bundle_storage = UnprocessedTransactionStorage::new_bundle_storage();
+        let mut bundle_stage_leader_metrics = BundleStageLeaderMetrics::new(1);
+        let summary = bundle_storage.insert_bundles(
+            packet_bundles
+                .iter_mut()
+                .map(|packet_bundle| {
+                    BundlePacketDeserializer::deserialize_bundle(packet_bundle, false, None)
+                        .unwrap()
+                })
+                .collect(),
+        );
+        assert_eq!(summary.num_bundles_inserted, 3);
+
+        consumer.consume_buffered_bundles(
+            &bank_start,
+            &mut bundle_storage,
+            &mut bundle_stage_leader_metrics,
+        );
+
+        let mut transactions = Vec::new();
+        while let Ok(WorkingBankEntry { entries_ticks, .. }) = entry_receiver.recv() {
+            for (entry, _) in entries_ticks {
+                transactions.extend(entry.transactions);
+            }
+            if transactions.len() == 2 {
+                break;
+            }
+        }
+        let bundle_transaction =
+            |idx: usize| bundles[idx].1.transactions[0].to_versioned_transaction();
+        assert_eq!(
+            transactions,
+            vec![bundle_transaction(2), bundle_transaction(0)]
+        );
+
+        assert!(bundle_status_cache.get(&bundle_id(0)).unwrap().is_landed());
+        assert!(matches!(
+            bundle_status_cache.get(&bundle_id(1)).unwrap().outcome,
+            BundleOutcome::Failed(BundleExecutionError::RejectedByPolicy(_))
+        ));
+        assert!(bundle_status_cache.get(&bundle_id(2)).unwrap().is_landed());
+
+        poh_recorder
+            .write()
+            .unwrap()
+            .is_exited
+            .store(true, Ordering::Relaxed);
+        exit.store(true, Ordering::Relaxed);
+        poh_simulator.join().unwrap();
+    }
 }
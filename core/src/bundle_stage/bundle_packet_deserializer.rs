@@ -1,6 +1,7 @@
 //! Deserializes PacketBundles
 use {
     crate::{
+        banking_trace::BankingTracer,
         immutable_deserialized_bundle::{DeserializedBundleError, ImmutableDeserializedBundle},
         packet_bundle::PacketBundle,
     },
@@ -31,6 +32,8 @@ pub struct BundlePacketDeserializer {
     bank_forks: Arc<RwLock<BankForks>>,
     /// Max packets per bundle
     max_packets_per_bundle: Option<usize>,
+    /// Records received bundles so they can be replayed later
+    banking_tracer: Arc<BankingTracer>,
 }
 
 impl BundlePacketDeserializer {
@@ -38,11 +41,13 @@ impl BundlePacketDeserializer {
         bundle_packet_receiver: Receiver<Vec<PacketBundle>>,
         bank_forks: Arc<RwLock<BankForks>>,
         max_packets_per_bundle: Option<usize>,
+        banking_tracer: Arc<BankingTracer>,
     ) -> Self {
         Self {
             bundle_packet_receiver,
             bank_forks,
             max_packets_per_bundle,
+            banking_tracer,
         }
     }
 
@@ -57,7 +62,9 @@ impl BundlePacketDeserializer {
 
         // Note: this can be removed after feature `round_compute_unit_price` is activated in
         // mainnet-beta
-        let _working_bank = self.bank_forks.read().unwrap().working_bank();
+        let working_bank = self.bank_forks.read().unwrap().working_bank();
+        self.banking_tracer
+            .bundle_event(working_bank.slot(), &bundles);
         let round_compute_unit_price_enabled = false; // TODO get from working_bank.feature_set
 
         Ok(Self::deserialize_and_collect_bundles(
@@ -191,7 +198,12 @@ mod tests {
 
         let (sender, receiver) = unbounded();
 
-        let deserializer = BundlePacketDeserializer::new(receiver, bank_forks, Some(10));
+        let deserializer = BundlePacketDeserializer::new(
+            receiver,
+            bank_forks,
+            Some(10),
+            BankingTracer::new_disabled(),
+        );
 
         let packet_bundles: Vec<_> = (0..10)
             .map(|_| PacketBundle {
@@ -261,7 +273,12 @@ mod tests {
 
         let (sender, receiver) = unbounded();
 
-        let deserializer = BundlePacketDeserializer::new(receiver, bank_forks, Some(10));
+        let deserializer = BundlePacketDeserializer::new(
+            receiver,
+            bank_forks,
+            Some(10),
+            BankingTracer::new_disabled(),
+        );
 
         let packet_bundles: Vec<_> = (0..10)
             .map(|_| PacketBundle {
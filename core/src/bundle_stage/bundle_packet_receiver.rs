@@ -2,6 +2,7 @@ use {
     super::BundleStageLoopMetrics,
     crate::{
         banking_stage::unprocessed_transaction_storage::UnprocessedTransactionStorage,
+        banking_trace::BankingTracer,
         bundle_stage::{
             bundle_packet_deserializer::{BundlePacketDeserializer, ReceiveBundleResults},
             bundle_stage_leader_metrics::BundleStageLeaderMetrics,
@@ -30,6 +31,7 @@ impl BundleReceiver {
         bundle_packet_receiver: Receiver<Vec<PacketBundle>>,
         bank_forks: Arc<RwLock<BankForks>>,
         max_packets_per_bundle: Option<usize>,
+        banking_tracer: Arc<BankingTracer>,
     ) -> Self {
         Self {
             id,
@@ -37,6 +39,7 @@ impl BundleReceiver {
                 bundle_packet_receiver,
                 bank_forks,
                 max_packets_per_bundle,
+                banking_tracer,
             ),
         }
     }
@@ -253,7 +256,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         let bundles = make_random_bundles(&mint_keypair, 10, 2, genesis_config.hash());
         sender.send(bundles.clone()).unwrap();
@@ -304,7 +313,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         // send 5 more than capacity
         let bundles = make_random_bundles(
@@ -363,7 +378,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -430,7 +451,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -495,7 +522,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -543,7 +576,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -589,7 +628,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -631,7 +676,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         // send 5 bundles across the queue
         let bundles = make_random_bundles(&mint_keypair, 5, 2, genesis_config.hash());
@@ -708,7 +759,13 @@ mod tests {
         let mut unprocessed_storage = UnprocessedTransactionStorage::new_bundle_storage();
 
         let (sender, receiver) = unbounded();
-        let mut bundle_receiver = BundleReceiver::new(0, receiver, bank_forks.clone(), Some(5));
+        let mut bundle_receiver = BundleReceiver::new(
+            0,
+            receiver,
+            bank_forks.clone(),
+            Some(5),
+            BankingTracer::new_disabled(),
+        );
 
         // send 500 bundles across the queue
         let bundles0 = make_random_bundles(
@@ -1,30 +1,102 @@
-use {solana_runtime::bank::Bank, solana_sdk::clock::Slot, std::sync::Arc};
+use {
+    lazy_static::lazy_static,
+    solana_runtime::bank::Bank,
+    solana_sdk::clock::Slot,
+    std::{collections::VecDeque, sync::Arc},
+    strum::{Display, EnumString, EnumVariantNames, IntoStaticStr, VariantNames},
+};
+
+/// Number of recent leader slots whose bundle compute usage informs the adaptive reservation
+const RECENT_LEADER_SLOTS: usize = 4;
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumString, EnumVariantNames, Default, IntoStaticStr, Display,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum BundleReservedSpacePolicy {
+    /// Reserve the full `bundle_reserved_cost` for the reserved ticks of every leader slot
+    #[default]
+    Fixed,
+    /// Size the reservation from buffered bundles and recent bundle usage, up to
+    /// `bundle_reserved_cost`, and release it early once no bundles are pending
+    Adaptive,
+}
+
+impl BundleReservedSpacePolicy {
+    pub const fn cli_names() -> &'static [&'static str] {
+        Self::VARIANTS
+    }
+
+    pub fn cli_message() -> &'static str {
+        lazy_static! {
+            static ref MESSAGE: String = format!(
+                "Switch how blockspace is reserved for bundles at the start of leader slots [default: {}]",
+                BundleReservedSpacePolicy::default()
+            );
+        };
+
+        &MESSAGE
+    }
+}
 
 /// Manager responsible for reserving `bundle_reserved_cost` during the first `reserved_ticks` of a bank
 /// and resetting the block cost limit to `block_cost_limit` after the reserved tick period is over
 pub struct BundleReservedSpaceManager {
     // the bank's cost limit
     block_cost_limit: u64,
-    // bundles get this much reserved space for the first reserved_ticks
+    // bundles get up to this much reserved space for the first reserved_ticks
     bundle_reserved_cost: u64,
     // a reduced block_compute_limit is reserved for this many ticks, afterwards it goes back to full cost
     reserved_ticks: u64,
+    policy: BundleReservedSpacePolicy,
     last_slot_updated: Slot,
+    // space reserved for bundles in last_slot_updated
+    slot_reserved_cost: u64,
+    // set when the reservation was released before the end of the reserved tick period
+    released_early: bool,
+    // compute units used by bundles in the slot currently being recorded
+    usage_slot: Slot,
+    usage_slot_compute_units: u64,
+    // compute units used by bundles in recent leader slots, oldest first
+    recent_compute_units: VecDeque<u64>,
 }
 
 impl BundleReservedSpaceManager {
     pub fn new(block_cost_limit: u64, bundle_reserved_cost: u64, reserved_ticks: u64) -> Self {
+        Self::new_with_policy(
+            block_cost_limit,
+            bundle_reserved_cost,
+            reserved_ticks,
+            BundleReservedSpacePolicy::Fixed,
+        )
+    }
+
+    pub fn new_with_policy(
+        block_cost_limit: u64,
+        bundle_reserved_cost: u64,
+        reserved_ticks: u64,
+        policy: BundleReservedSpacePolicy,
+    ) -> Self {
         Self {
             block_cost_limit,
             bundle_reserved_cost,
             reserved_ticks,
+            policy,
             last_slot_updated: u64::MAX,
+            slot_reserved_cost: bundle_reserved_cost,
+            released_early: false,
+            usage_slot: u64::MAX,
+            usage_slot_compute_units: 0,
+            recent_compute_units: VecDeque::with_capacity(RECENT_LEADER_SLOTS),
         }
     }
 
     /// Call this on creation of new bank and periodically while bundle processing
-    /// to manage the block_cost_limits
-    pub fn tick(&mut self, bank: &Arc<Bank>) {
+    /// to manage the block_cost_limits. `buffered_bundle_cost` is the estimated cost of the
+    /// bundles waiting to be executed.
+    pub fn tick(&mut self, bank: &Arc<Bank>, buffered_bundle_cost: u64) {
+        // the previous leader slot is over, including when no bundles ran in it
+        self.start_usage_slot(bank.slot());
         if self.last_slot_updated == bank.slot() && !self.is_in_reserved_tick_period(bank) {
             // new slot logic already ran, need to revert the block cost limit to original if
             // ticks are past the reserved tick mark
@@ -37,9 +109,25 @@ impl BundleReservedSpaceManager {
             bank.write_cost_tracker()
                 .unwrap()
                 .set_block_cost_limit(self.block_cost_limit);
+        } else if self.last_slot_updated == bank.slot()
+            && self.should_release_early(bank, buffered_bundle_cost)
+        {
+            // no bundles are waiting for the reserved space, so hand it to BankingStage
+            debug!(
+                "slot: {} ticks: {}, no bundles pending, releasing reserved space of {}",
+                bank.slot(),
+                bank.tick_height(),
+                self.slot_reserved_cost
+            );
+            self.released_early = true;
+            bank.write_cost_tracker()
+                .unwrap()
+                .set_block_cost_limit(self.block_cost_limit);
         } else if self.last_slot_updated != bank.slot() && self.is_in_reserved_tick_period(bank) {
             // new slot, if in the first max_tick - tick_height slots reserve space
             // otherwise can leave the current block limit as is
+            self.slot_reserved_cost = self.reservation_for_new_slot(buffered_bundle_cost);
+            self.released_early = false;
             let new_block_cost_limit = self.reduced_block_cost_limit();
             debug!(
                 "slot: {} ticks: {}, reserving block_cost_limit with block_cost_limit of {}",
@@ -54,6 +142,56 @@ impl BundleReservedSpaceManager {
         }
     }
 
+    /// Records compute units used by a bundle executed in `slot`
+    pub fn record_bundle_compute_units(&mut self, slot: Slot, compute_units: u64) {
+        self.start_usage_slot(slot);
+        self.usage_slot_compute_units = self.usage_slot_compute_units.saturating_add(compute_units);
+    }
+
+    /// Moves the bundle usage of the slot being recorded into the recent history once `slot`
+    /// replaces it
+    fn start_usage_slot(&mut self, slot: Slot) {
+        if self.usage_slot == slot {
+            return;
+        }
+        if self.usage_slot != u64::MAX {
+            if self.recent_compute_units.len() == RECENT_LEADER_SLOTS {
+                self.recent_compute_units.pop_front();
+            }
+            self.recent_compute_units
+                .push_back(self.usage_slot_compute_units);
+        }
+        self.usage_slot = slot;
+        self.usage_slot_compute_units = 0;
+    }
+
+    fn reservation_for_new_slot(&self, buffered_bundle_cost: u64) -> u64 {
+        match self.policy {
+            BundleReservedSpacePolicy::Fixed => self.bundle_reserved_cost,
+            BundleReservedSpacePolicy::Adaptive => {
+                // without history, err on the side of reserving for bundles
+                let recent_compute_units = if self.recent_compute_units.is_empty() {
+                    self.bundle_reserved_cost
+                } else {
+                    self.recent_compute_units.iter().sum::<u64>()
+                        / self.recent_compute_units.len() as u64
+                };
+                buffered_bundle_cost
+                    .max(recent_compute_units)
+                    .min(self.bundle_reserved_cost)
+            }
+        }
+    }
+
+    /// In adaptive mode the reservation is held for at least the first tick of the slot to give
+    /// bundles streamed at the slot boundary a chance to arrive
+    fn should_release_early(&self, bank: &Bank, buffered_bundle_cost: u64) -> bool {
+        self.policy == BundleReservedSpacePolicy::Adaptive
+            && !self.released_early
+            && buffered_bundle_cost == 0
+            && bank.tick_height() % bank.ticks_per_slot() > 0
+    }
+
     /// return true if the bank is still in the period where block_cost_limits is reduced
     pub fn is_in_reserved_tick_period(&self, bank: &Bank) -> bool {
         bank.tick_height() % bank.ticks_per_slot() < self.reserved_ticks
@@ -61,7 +199,7 @@ impl BundleReservedSpaceManager {
 
     /// return the block_cost_limits as determined by the tick height of the bank
     pub fn expected_block_cost_limits(&self, bank: &Bank) -> u64 {
-        if self.is_in_reserved_tick_period(bank) {
+        if self.is_in_reserved_tick_period(bank) && !self.released_early {
             self.reduced_block_cost_limit()
         } else {
             self.block_cost_limit()
@@ -70,20 +208,33 @@ impl BundleReservedSpaceManager {
 
     pub fn reduced_block_cost_limit(&self) -> u64 {
         self.block_cost_limit
-            .saturating_sub(self.bundle_reserved_cost)
+            .saturating_sub(self.slot_reserved_cost)
     }
 
     pub fn block_cost_limit(&self) -> u64 {
         self.block_cost_limit
     }
+
+    /// Space reserved for bundles in the current leader slot
+    pub fn slot_reserved_cost(&self) -> u64 {
+        self.slot_reserved_cost
+    }
+
+    pub fn released_early(&self) -> bool {
+        self.released_early
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use {
-        crate::bundle_stage::bundle_reserved_space_manager::BundleReservedSpaceManager,
-        solana_ledger::genesis_utils::create_genesis_config, solana_runtime::bank::Bank,
-        solana_sdk::pubkey::Pubkey, std::sync::Arc,
+        crate::bundle_stage::bundle_reserved_space_manager::{
+            BundleReservedSpaceManager, BundleReservedSpacePolicy,
+        },
+        solana_ledger::genesis_utils::create_genesis_config,
+        solana_runtime::bank::Bank,
+        solana_sdk::pubkey::Pubkey,
+        std::sync::Arc,
     };
 
     #[test]
@@ -100,7 +251,7 @@ mod tests {
             BUNDLE_BLOCK_COST_LIMITS_RESERVATION,
             5,
         );
-        reserved_space.tick(&bank);
+        reserved_space.tick(&bank, 0);
 
         assert_eq!(
             bank.read_cost_tracker().unwrap().block_cost_limit(),
@@ -126,7 +277,7 @@ mod tests {
             BUNDLE_BLOCK_COST_LIMITS_RESERVATION,
             5,
         );
-        reserved_space.tick(&bank);
+        reserved_space.tick(&bank, 0);
 
         assert_eq!(
             bank.read_cost_tracker().unwrap().block_cost_limit(),
@@ -149,9 +300,9 @@ mod tests {
             5,
         );
 
-        reserved_space.tick(&bank);
+        reserved_space.tick(&bank, 0);
         bank.register_default_tick_for_test();
-        reserved_space.tick(&bank);
+        reserved_space.tick(&bank, 0);
 
         assert_eq!(
             bank.read_cost_tracker().unwrap().block_cost_limit(),
@@ -174,12 +325,12 @@ mod tests {
             5,
         );
 
-        reserved_space.tick(&bank);
+        reserved_space.tick(&bank, 0);
 
         for _ in 0..5 {
             bank.register_default_tick_for_test();
         }
-        reserved_space.tick(&bank);
+        reserved_space.tick(&bank, 0);
 
         assert_eq!(
             bank.read_cost_tracker().unwrap().block_cost_limit(),
@@ -216,7 +367,7 @@ mod tests {
             BUNDLE_BLOCK_COST_LIMITS_RESERVATION,
             RESERVED_TICKS,
         );
-        reserved_space.tick(&bank1);
+        reserved_space.tick(&bank1, 0);
 
         // wait for reservation to be over
         (0..RESERVED_TICKS).for_each(|_| {
@@ -226,7 +377,7 @@ mod tests {
                 block_cost_limits - BUNDLE_BLOCK_COST_LIMITS_RESERVATION
             );
         });
-        reserved_space.tick(&bank1);
+        reserved_space.tick(&bank1, 0);
 
         // after reservation, revert back to normal limit
         assert_eq!(
@@ -234,4 +385,101 @@ mod tests {
             solana_cost_model::block_cost_limits::MAX_BLOCK_UNITS,
         );
     }
+
+    #[test]
+    fn test_adaptive_reservation_follows_demand() {
+        const MAX_BUNDLE_RESERVATION: u64 = 1_000;
+        const RESERVED_TICKS: u64 = 5;
+        let genesis_config_info = create_genesis_config(100);
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config_info.genesis_config));
+        let block_cost_limits = bank.read_cost_tracker().unwrap().block_cost_limit();
+
+        let mut reserved_space = BundleReservedSpaceManager::new_with_policy(
+            block_cost_limits,
+            MAX_BUNDLE_RESERVATION,
+            RESERVED_TICKS,
+            BundleReservedSpacePolicy::Adaptive,
+        );
+
+        // no history yet, reserve the max
+        reserved_space.tick(&bank, 0);
+        assert_eq!(reserved_space.slot_reserved_cost(), MAX_BUNDLE_RESERVATION);
+        reserved_space.record_bundle_compute_units(bank.slot(), 100);
+        reserved_space.record_bundle_compute_units(bank.slot(), 100);
+        for _ in 0..genesis_config_info.genesis_config.ticks_per_slot {
+            bank.register_default_tick_for_test();
+        }
+        bank.freeze();
+
+        // reserve what bundles recently used, or what's buffered if that's more
+        let bank1 = Arc::new(Bank::new_from_parent(bank, &Pubkey::default(), 1));
+        reserved_space.tick(&bank1, 50);
+        assert_eq!(reserved_space.slot_reserved_cost(), 200);
+        assert_eq!(
+            bank1.read_cost_tracker().unwrap().block_cost_limit(),
+            block_cost_limits - 200
+        );
+        for _ in 0..genesis_config_info.genesis_config.ticks_per_slot {
+            bank1.register_default_tick_for_test();
+        }
+        bank1.freeze();
+
+        let bank2 = Arc::new(Bank::new_from_parent(bank1, &Pubkey::default(), 2));
+        reserved_space.tick(&bank2, 10_000);
+        assert_eq!(reserved_space.slot_reserved_cost(), MAX_BUNDLE_RESERVATION);
+        for _ in 0..genesis_config_info.genesis_config.ticks_per_slot {
+            bank2.register_default_tick_for_test();
+        }
+        bank2.freeze();
+
+        // slots without bundles count towards the history: (200 + 0 + 0) / 3
+        let bank3 = Arc::new(Bank::new_from_parent(bank2, &Pubkey::default(), 3));
+        reserved_space.tick(&bank3, 0);
+        assert_eq!(reserved_space.slot_reserved_cost(), 66);
+    }
+
+    #[test]
+    fn test_adaptive_reservation_released_when_no_bundles_pending() {
+        const MAX_BUNDLE_RESERVATION: u64 = 1_000;
+        const RESERVED_TICKS: u64 = 5;
+        let genesis_config_info = create_genesis_config(100);
+        let bank = Arc::new(Bank::new_for_tests(&genesis_config_info.genesis_config));
+        let block_cost_limits = bank.read_cost_tracker().unwrap().block_cost_limit();
+
+        let mut reserved_space = BundleReservedSpaceManager::new_with_policy(
+            block_cost_limits,
+            MAX_BUNDLE_RESERVATION,
+            RESERVED_TICKS,
+            BundleReservedSpacePolicy::Adaptive,
+        );
+        reserved_space.tick(&bank, 0);
+
+        // held through the first tick even though nothing is buffered
+        reserved_space.tick(&bank, 0);
+        assert_eq!(
+            bank.read_cost_tracker().unwrap().block_cost_limit(),
+            block_cost_limits - MAX_BUNDLE_RESERVATION
+        );
+
+        // held while bundles are pending
+        bank.register_default_tick_for_test();
+        reserved_space.tick(&bank, 100);
+        assert!(!reserved_space.released_early());
+        assert_eq!(
+            reserved_space.expected_block_cost_limits(&bank),
+            block_cost_limits - MAX_BUNDLE_RESERVATION
+        );
+
+        // released once the buffer drains
+        reserved_space.tick(&bank, 0);
+        assert!(reserved_space.released_early());
+        assert_eq!(
+            bank.read_cost_tracker().unwrap().block_cost_limit(),
+            block_cost_limits
+        );
+        assert_eq!(
+            reserved_space.expected_block_cost_limits(&bank),
+            block_cost_limits
+        );
+    }
 }
@@ -252,6 +252,9 @@ impl BundleStageStatsMetricsTracker {
                 Err(BundleExecutionError::TipError(_)) => {
                     saturating_add_assign!(bundle_stage_metrics.execution_results_tip_errors, 1);
                 }
+                Err(BundleExecutionError::RejectedByPolicy(_)) => {
+                    saturating_add_assign!(bundle_stage_metrics.num_rejected_by_policy, 1);
+                }
                 Err(BundleExecutionError::TransactionFailure(
                     LoadAndExecuteBundleError::InvalidPreOrPostAccounts,
                 )) => {
@@ -267,6 +270,33 @@ impl BundleStageStatsMetricsTracker {
         }
     }
 
+    pub(crate) fn increment_bundle_auction_elapsed_us(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.bundle_auction_elapsed_us, count);
+        }
+    }
+
+    pub(crate) fn increment_num_bundles_deferred_by_auction(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.num_bundles_deferred_by_auction, count);
+        }
+    }
+
+    pub(crate) fn increment_num_bundles_deprioritized_by_policy(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(
+                bundle_stage_metrics.num_bundles_deprioritized_by_policy,
+                count
+            );
+        }
+    }
+
+    pub(crate) fn increment_bundle_policy_elapsed_us(&mut self, count: u64) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            saturating_add_assign!(bundle_stage_metrics.bundle_policy_elapsed_us, count);
+        }
+    }
+
     pub(crate) fn increment_locked_bundle_elapsed_us(&mut self, count: u64) {
         if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
             saturating_add_assign!(bundle_stage_metrics.locked_bundle_elapsed_us, count);
@@ -309,6 +339,13 @@ impl BundleStageStatsMetricsTracker {
         }
     }
 
+    pub(crate) fn set_reserved_space(&mut self, reserved_cost: u64, released_early: bool) {
+        if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
+            bundle_stage_metrics.reserved_bundle_cost = reserved_cost;
+            bundle_stage_metrics.reserved_space_released_early = released_early;
+        }
+    }
+
     pub(crate) fn increment_execute_locked_bundles_elapsed_us(&mut self, count: u64) {
         if let Some(bundle_stage_metrics) = &mut self.bundle_stage_metrics {
             saturating_add_assign!(
@@ -337,6 +374,13 @@ pub struct BundleStageStats {
     sanitize_transaction_failed_marked_discard: u64,
     sanitize_transaction_failed_sig_verify_failed: u64,
 
+    bundle_auction_elapsed_us: u64,
+    num_bundles_deferred_by_auction: u64,
+
+    bundle_policy_elapsed_us: u64,
+    num_bundles_deprioritized_by_policy: u64,
+    num_rejected_by_policy: u64,
+
     locked_bundle_elapsed_us: u64,
 
     num_lock_errors: u64,
@@ -353,6 +397,9 @@ pub struct BundleStageStats {
 
     execute_locked_bundles_elapsed_us: u64,
 
+    reserved_bundle_cost: u64,
+    reserved_space_released_early: bool,
+
     execution_results_ok: u64,
     execution_results_poh_max_height: u64,
     execution_results_transaction_failures: u64,
@@ -440,6 +487,27 @@ impl BundleStageStats {
                 self.sanitize_transaction_failed_sig_verify_failed,
                 i64
             ),
+            (
+                "bundle_auction_elapsed_us",
+                self.bundle_auction_elapsed_us,
+                i64
+            ),
+            (
+                "num_bundles_deferred_by_auction",
+                self.num_bundles_deferred_by_auction,
+                i64
+            ),
+            (
+                "bundle_policy_elapsed_us",
+                self.bundle_policy_elapsed_us,
+                i64
+            ),
+            (
+                "num_bundles_deprioritized_by_policy",
+                self.num_bundles_deprioritized_by_policy,
+                i64
+            ),
+            ("num_rejected_by_policy", self.num_rejected_by_policy, i64),
             (
                 "locked_bundle_elapsed_us",
                 self.locked_bundle_elapsed_us,
@@ -474,6 +542,12 @@ impl BundleStageStats {
                 self.execute_locked_bundles_elapsed_us,
                 i64
             ),
+            ("reserved_bundle_cost", self.reserved_bundle_cost, i64),
+            (
+                "reserved_space_released_early",
+                self.reserved_space_released_early,
+                bool
+            ),
             ("execution_results_ok", self.execution_results_ok, i64),
             (
                 "execution_results_poh_max_height",
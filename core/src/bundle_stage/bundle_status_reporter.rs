@@ -0,0 +1,41 @@
+use {
+    crossbeam_channel::Sender,
+    solana_bundle::bundle_status::{BundleStatus, BundleStatusCache},
+    solana_rpc::rpc_subscriptions::RpcSubscriptions,
+    std::sync::Arc,
+};
+
+/// Publishes the outcome of each bundle BundleStage attempts to the [BundleStatusCache] backing
+/// getBundleStatuses, to bundleStatusSubscribe subscribers, and to the block engine connection
+#[derive(Default)]
+pub struct BundleStatusReporter {
+    bundle_status_cache: Arc<BundleStatusCache>,
+    rpc_subscriptions: Option<Arc<RpcSubscriptions>>,
+    block_engine_sender: Option<Sender<BundleStatus>>,
+}
+
+impl BundleStatusReporter {
+    pub fn new(
+        bundle_status_cache: Arc<BundleStatusCache>,
+        rpc_subscriptions: Option<Arc<RpcSubscriptions>>,
+        block_engine_sender: Option<Sender<BundleStatus>>,
+    ) -> Self {
+        Self {
+            bundle_status_cache,
+            rpc_subscriptions,
+            block_engine_sender,
+        }
+    }
+
+    pub fn report(&self, bundle_status: BundleStatus) {
+        if let Some(rpc_subscriptions) = &self.rpc_subscriptions {
+            rpc_subscriptions.notify_bundle_status(bundle_status.clone());
+        }
+        if let Some(block_engine_sender) = &self.block_engine_sender {
+            // never block BundleStage on the block engine connection, the result is dropped if
+            // the channel is full
+            let _ = block_engine_sender.try_send(bundle_status.clone());
+        }
+        self.bundle_status_cache.insert(bundle_status);
+    }
+}
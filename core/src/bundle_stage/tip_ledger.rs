@@ -0,0 +1,164 @@
+//! Accumulates the tips paid by bundles committed during a leader slot and writes a summary of
+//! them to the blockstore once the slot is over, where it can be served over RPC with
+//! getTipsForSlot.
+use {
+    crate::proxy::block_engine_stage::BlockBuilderFeeInfo,
+    log::*,
+    solana_ledger::{
+        blockstore::Blockstore,
+        blockstore_meta::{BundleTips, SlotTips},
+    },
+    solana_sdk::{clock::Slot, pubkey::Pubkey},
+    std::sync::Arc,
+};
+
+#[derive(Default)]
+pub struct TipLedger {
+    // summaries are dropped when there's no blockstore to write them to
+    blockstore: Option<Arc<Blockstore>>,
+    // the slot being accumulated and its tips so far
+    current: Option<(Slot, SlotTips)>,
+}
+
+impl TipLedger {
+    pub fn new(blockstore: Option<Arc<Blockstore>>) -> Self {
+        Self {
+            blockstore,
+            current: None,
+        }
+    }
+
+    /// Adds the tips paid by a committed bundle to the summary for `slot`. Bundles that didn't pay
+    /// any tips aren't recorded.
+    pub fn record_bundle(
+        &mut self,
+        slot: Slot,
+        bundle_id: &str,
+        tip_accounts: &[(Pubkey, u64)],
+        block_builder_fee_info: &BlockBuilderFeeInfo,
+    ) {
+        let tip_lamports = tip_accounts
+            .iter()
+            .map(|(_, lamports)| *lamports)
+            .fold(0, u64::saturating_add);
+        if tip_lamports == 0 {
+            return;
+        }
+
+        self.maybe_flush(Some(slot));
+        let (_, slot_tips) = self
+            .current
+            .get_or_insert_with(|| (slot, SlotTips::default()));
+
+        slot_tips.total_tip_lamports = slot_tips.total_tip_lamports.saturating_add(tip_lamports);
+        for (tip_account, lamports) in tip_accounts {
+            match slot_tips
+                .tip_accounts
+                .iter_mut()
+                .find(|(account, _)| account == tip_account)
+            {
+                Some((_, total)) => *total = total.saturating_add(*lamports),
+                None => slot_tips.tip_accounts.push((*tip_account, *lamports)),
+            }
+        }
+        slot_tips.bundles.push(BundleTips {
+            bundle_id: bundle_id.to_string(),
+            tip_lamports,
+            tip_accounts: tip_accounts.to_vec(),
+        });
+
+        // block_builder_commission is a percentage
+        slot_tips.block_builder = block_builder_fee_info.block_builder;
+        slot_tips.block_builder_commission = block_builder_fee_info.block_builder_commission;
+        slot_tips.block_builder_commission_lamports = slot_tips
+            .total_tip_lamports
+            .saturating_mul(slot_tips.block_builder_commission)
+            / 100;
+    }
+
+    /// Writes the summary of the slot being accumulated to the blockstore unless it's still the
+    /// `current_slot`
+    pub fn maybe_flush(&mut self, current_slot: Option<Slot>) {
+        let Some((slot, _)) = &self.current else {
+            return;
+        };
+        if Some(*slot) == current_slot {
+            return;
+        }
+
+        let (slot, slot_tips) = self.current.take().unwrap();
+        if let Some(blockstore) = &self.blockstore {
+            if let Err(e) = blockstore.write_slot_tips(slot, &slot_tips) {
+                error!("failed to write tips for slot {slot}: {e:?}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_ledger::get_tmp_ledger_path_auto_delete};
+
+    #[test]
+    fn test_record_and_flush() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let mut tip_ledger = TipLedger::new(Some(blockstore.clone()));
+
+        let (tip_account_0, tip_account_1) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let block_builder_fee_info = BlockBuilderFeeInfo {
+            block_builder: Pubkey::new_unique(),
+            block_builder_commission: 10,
+        };
+
+        tip_ledger.record_bundle(
+            1,
+            "a",
+            &[(tip_account_0, 100), (tip_account_1, 200)],
+            &block_builder_fee_info,
+        );
+        tip_ledger.record_bundle(1, "no-tip", &[], &block_builder_fee_info);
+        tip_ledger.record_bundle(1, "b", &[(tip_account_0, 700)], &block_builder_fee_info);
+
+        // still accumulating slot 1
+        tip_ledger.maybe_flush(Some(1));
+        assert_eq!(blockstore.get_slot_tips(1).unwrap(), None);
+
+        // moving on to another slot writes out slot 1
+        tip_ledger.record_bundle(2, "c", &[(tip_account_1, 5)], &block_builder_fee_info);
+        assert_eq!(
+            blockstore.get_slot_tips(1).unwrap(),
+            Some(SlotTips {
+                total_tip_lamports: 1_000,
+                block_builder: block_builder_fee_info.block_builder,
+                block_builder_commission: 10,
+                block_builder_commission_lamports: 100,
+                tip_accounts: vec![(tip_account_0, 800), (tip_account_1, 200)],
+                bundles: vec![
+                    BundleTips {
+                        bundle_id: "a".to_string(),
+                        tip_lamports: 300,
+                        tip_accounts: vec![(tip_account_0, 100), (tip_account_1, 200)],
+                    },
+                    BundleTips {
+                        bundle_id: "b".to_string(),
+                        tip_lamports: 700,
+                        tip_accounts: vec![(tip_account_0, 700)],
+                    },
+                ],
+            })
+        );
+        assert_eq!(blockstore.get_slot_tips(2).unwrap(), None);
+
+        // no longer leader
+        tip_ledger.maybe_flush(None);
+        assert_eq!(
+            blockstore
+                .get_slot_tips(2)
+                .unwrap()
+                .unwrap()
+                .total_tip_lamports,
+            5
+        );
+    }
+}
@@ -104,6 +104,14 @@ impl ImmutableDeserializedBundle {
         &self.bundle_id
     }
 
+    /// Sum of the compute unit limits requested by the bundle's transactions
+    pub fn compute_unit_limit(&self) -> u64 {
+        self.packets
+            .iter()
+            .map(|packet| packet.compute_unit_limit())
+            .fold(0, u64::saturating_add)
+    }
+
     /// A bundle has the following requirements:
     /// - all transactions must be sanitiz-able
     /// - no duplicate signatures
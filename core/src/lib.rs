@@ -12,6 +12,7 @@ pub mod accounts_hash_verifier;
 pub mod admin_rpc_post_init;
 pub mod banking_stage;
 pub mod banking_trace;
+pub mod banking_trace_replay;
 pub mod bundle_stage;
 pub mod cache_block_meta_service;
 pub mod cluster_info_vote_listener;
@@ -112,3 +113,28 @@ pub fn proto_packet_to_packet(p: jito_protos::proto::packet::Packet) -> Packet {
     }
     packet
 }
+
+/// The inverse of [proto_packet_to_packet], tagging the packet with the stake of its sender.
+/// Returns None for packets without data.
+pub fn packet_to_proto_packet(
+    p: &Packet,
+    sender_stake: u64,
+) -> Option<jito_protos::proto::packet::Packet> {
+    let meta = p.meta();
+    Some(jito_protos::proto::packet::Packet {
+        data: p.data(..)?.to_vec(),
+        meta: Some(jito_protos::proto::packet::Meta {
+            size: meta.size as u64,
+            addr: meta.addr.to_string(),
+            port: meta.port as u32,
+            flags: Some(jito_protos::proto::packet::PacketFlags {
+                discard: meta.discard(),
+                forwarded: meta.forwarded(),
+                repair: meta.repair(),
+                simple_vote_tx: meta.is_simple_vote_tx(),
+                tracer_packet: meta.is_tracer_packet(),
+            }),
+            sender_stake,
+        }),
+    })
+}
@@ -1,7 +1 @@
-use solana_perf::packet::PacketBatch;
-
-#[derive(Clone, Debug)]
-pub struct PacketBundle {
-    pub batch: PacketBatch,
-    pub bundle_id: String,
-}
+pub use solana_bundle::packet_bundle::PacketBundle;
@@ -0,0 +1,294 @@
+//! Health tracking for multiple Block Engine endpoints and de-duplication of bundles and
+//! packets raced across several Block Engine streams.
+use {
+    solana_sdk::{
+        short_vec::decode_shortu16_len,
+        signature::{Signature, SIGNATURE_BYTES},
+    },
+    std::{
+        borrow::Borrow,
+        collections::{HashSet, VecDeque},
+        hash::Hash,
+        time::{Duration, Instant},
+    },
+};
+
+/// A connection that stays up for at least this long is considered healthy, even if it
+/// eventually ends with an error.
+const HEALTHY_SESSION_DURATION: Duration = Duration::from_secs(60);
+
+/// Base and max backoff applied to an endpoint after consecutive failed connections
+const BASE_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug)]
+pub struct EndpointHealth {
+    url: String,
+    consecutive_failures: u32,
+    // endpoint shouldn't be connected to again until this time
+    retry_at: Option<Instant>,
+}
+
+impl EndpointHealth {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            consecutive_failures: 0,
+            retry_at: None,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    pub fn is_available(&self, now: Instant) -> bool {
+        self.retry_at.map(|t| now >= t).unwrap_or(true)
+    }
+
+    /// Time left until the endpoint can be retried
+    pub fn backoff(&self, now: Instant) -> Duration {
+        self.retry_at
+            .map(|t| t.saturating_duration_since(now))
+            .unwrap_or_default()
+    }
+
+    /// Records the end of a connection to this endpoint. Long running sessions reset the failure
+    /// count while short ones back off exponentially.
+    pub fn record_session(&mut self, session_duration: Duration, now: Instant) {
+        if session_duration >= HEALTHY_SESSION_DURATION {
+            self.consecutive_failures = 0;
+            self.retry_at = None;
+        } else {
+            self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+            let backoff = BASE_BACKOFF
+                .saturating_mul(
+                    1u32.checked_shl(self.consecutive_failures.saturating_sub(1))
+                        .unwrap_or(u32::MAX),
+                )
+                .min(MAX_BACKOFF);
+            self.retry_at = Some(now + backoff);
+        }
+    }
+}
+
+/// Block Engine endpoints in order of preference along with their health
+#[derive(Debug, Default)]
+pub struct BlockEngineEndpoints {
+    endpoints: Vec<EndpointHealth>,
+}
+
+impl BlockEngineEndpoints {
+    pub fn new(urls: &[String]) -> Self {
+        Self {
+            endpoints: urls.iter().cloned().map(EndpointHealth::new).collect(),
+        }
+    }
+
+    /// Resets health if the configured endpoints changed
+    pub fn update_urls(&mut self, urls: &[String]) {
+        if !self.endpoints.iter().map(|e| &e.url).eq(urls.iter()) {
+            *self = Self::new(urls);
+        }
+    }
+
+    /// Returns the index of the healthiest endpoint that isn't backing off. Ties are broken by
+    /// the configured order.
+    pub fn next_endpoint(&self, now: Instant) -> Option<usize> {
+        self.endpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.is_available(now))
+            .min_by_key(|(idx, e)| (e.consecutive_failures, *idx))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Time until the next endpoint becomes available
+    pub fn min_backoff(&self, now: Instant) -> Duration {
+        self.endpoints
+            .iter()
+            .map(|e| e.backoff(now))
+            .min()
+            .unwrap_or(BASE_BACKOFF)
+    }
+
+    pub fn get(&self, idx: usize) -> &EndpointHealth {
+        &self.endpoints[idx]
+    }
+
+    pub fn get_mut(&mut self, idx: usize) -> &mut EndpointHealth {
+        &mut self.endpoints[idx]
+    }
+}
+
+/// Filters out ids, bundle ids or packet signatures, that were already received from another
+/// Block Engine stream within `window`. Ids re-sent after the window are let through so the
+/// Block Engine can retry them on a later slot.
+pub struct StreamDeduper<K> {
+    window: Duration,
+    capacity: usize,
+    seen: HashSet<K>,
+    order: VecDeque<(Instant, K)>,
+}
+
+pub type BundleIdDeduper = StreamDeduper<String>;
+pub type PacketSignatureDeduper = StreamDeduper<Signature>;
+
+impl<K: Clone + Eq + Hash> StreamDeduper<K> {
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity,
+            seen: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns true if the id hasn't been seen within the window
+    pub fn insert<Q>(&mut self, id: &Q, now: Instant) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = K> + ?Sized,
+    {
+        while let Some((received, _)) = self.order.front() {
+            if now.saturating_duration_since(*received) < self.window
+                && self.order.len() < self.capacity
+            {
+                break;
+            }
+            let (_, evicted) = self.order.pop_front().unwrap();
+            self.seen.remove(&evicted);
+        }
+
+        if self.seen.contains(id) {
+            return false;
+        }
+        self.seen.insert(id.to_owned());
+        self.order.push_back((now, id.to_owned()));
+        true
+    }
+}
+
+/// Returns the first signature of the serialized transaction in `data`, which identifies the
+/// transaction
+pub fn first_signature(data: &[u8]) -> Option<Signature> {
+    let (num_signatures, offset) = decode_shortu16_len(data).ok()?;
+    if num_signatures == 0 {
+        return None;
+    }
+    let signature = data.get(offset..offset.checked_add(SIGNATURE_BYTES)?)?;
+    Signature::try_from(signature).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Keypair, system_transaction},
+    };
+
+    #[test]
+    fn test_failover_to_next_healthy_endpoint() {
+        let urls = vec!["http://a".to_string(), "http://b".to_string()];
+        let mut endpoints = BlockEngineEndpoints::new(&urls);
+        let now = Instant::now();
+        assert_eq!(endpoints.next_endpoint(now), Some(0));
+
+        // short lived connection to the primary backs it off
+        endpoints
+            .get_mut(0)
+            .record_session(Duration::from_secs(1), now);
+        assert_eq!(endpoints.next_endpoint(now), Some(1));
+
+        endpoints
+            .get_mut(1)
+            .record_session(Duration::from_secs(1), now);
+        assert_eq!(endpoints.next_endpoint(now), None);
+        assert_eq!(endpoints.min_backoff(now), BASE_BACKOFF);
+
+        // both recovered with the same health, ties go to the configured order
+        let later = now + BASE_BACKOFF;
+        assert_eq!(endpoints.next_endpoint(later), Some(0));
+
+        // a long running session resets health
+        endpoints
+            .get_mut(1)
+            .record_session(HEALTHY_SESSION_DURATION, later);
+        assert_eq!(endpoints.get(1).consecutive_failures(), 0);
+        assert_eq!(endpoints.next_endpoint(later), Some(1));
+    }
+
+    #[test]
+    fn test_endpoint_backoff_is_exponential_and_capped() {
+        let mut endpoint = EndpointHealth::new("http://a".to_string());
+        let now = Instant::now();
+        endpoint.record_session(Duration::ZERO, now);
+        assert_eq!(endpoint.backoff(now), BASE_BACKOFF);
+        endpoint.record_session(Duration::ZERO, now);
+        assert_eq!(endpoint.backoff(now), BASE_BACKOFF * 2);
+        for _ in 0..40 {
+            endpoint.record_session(Duration::ZERO, now);
+        }
+        assert_eq!(endpoint.backoff(now), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_update_urls_resets_health() {
+        let urls = vec!["http://a".to_string()];
+        let mut endpoints = BlockEngineEndpoints::new(&urls);
+        let now = Instant::now();
+        endpoints.get_mut(0).record_session(Duration::ZERO, now);
+
+        endpoints.update_urls(&urls);
+        assert_eq!(endpoints.get(0).consecutive_failures(), 1);
+
+        endpoints.update_urls(&["http://b".to_string()]);
+        assert_eq!(endpoints.get(0).url(), "http://b");
+        assert_eq!(endpoints.get(0).consecutive_failures(), 0);
+    }
+
+    #[test]
+    fn test_bundle_id_deduper() {
+        let mut deduper = BundleIdDeduper::new(Duration::from_secs(1), 2);
+        let now = Instant::now();
+        assert!(deduper.insert("a", now));
+        assert!(!deduper.insert("a", now));
+        assert!(deduper.insert("b", now));
+
+        // capacity reached, oldest is evicted
+        assert!(deduper.insert("c", now));
+        assert!(deduper.insert("a", now));
+
+        // window elapsed
+        let later = now + Duration::from_secs(1);
+        assert!(deduper.insert("c", later));
+    }
+
+    #[test]
+    fn test_first_signature() {
+        let keypair = Keypair::new();
+        let transaction =
+            system_transaction::transfer(&keypair, &Pubkey::new_unique(), 1, Hash::default());
+        let data = bincode::serialize(&transaction).unwrap();
+        assert_eq!(first_signature(&data), Some(transaction.signatures[0]));
+
+        assert_eq!(first_signature(&data[..SIGNATURE_BYTES]), None);
+        assert_eq!(first_signature(&[0]), None);
+        assert_eq!(first_signature(&[]), None);
+    }
+
+    #[test]
+    fn test_packet_signature_deduper() {
+        let mut deduper = PacketSignatureDeduper::new(Duration::from_secs(1), 10);
+        let now = Instant::now();
+        let signature = Signature::new_unique();
+        assert!(deduper.insert(&signature, now));
+        assert!(!deduper.insert(&signature, now));
+        assert!(deduper.insert(&Signature::new_unique(), now));
+        assert!(deduper.insert(&signature, now + Duration::from_secs(1)));
+    }
+}
@@ -10,17 +10,27 @@ use {
         proto_packet_to_packet,
         proxy::{
             auth::{generate_auth_tokens, maybe_refresh_auth_tokens, AuthInterceptor},
+            block_engine_endpoints::{
+                first_signature, BlockEngineEndpoints, BundleIdDeduper, EndpointHealth,
+                PacketSignatureDeduper,
+            },
+            bundle_result_reporter::{
+                BundleResultReporter, BundleResultReporterStats, BundleResultRouter,
+                BUNDLE_RESULTS_STREAM_BUFFER,
+            },
             ProxyError,
         },
     },
-    crossbeam_channel::Sender,
+    crossbeam_channel::{Receiver, Sender},
+    futures::{channel::mpsc, future::join_all},
     jito_protos::proto::{
         auth::{auth_service_client::AuthServiceClient, Token},
         block_engine::{
-            self, block_engine_validator_client::BlockEngineValidatorClient,
-            BlockBuilderFeeInfoRequest,
+            self, block_engine_bundle_results_client::BlockEngineBundleResultsClient,
+            block_engine_validator_client::BlockEngineValidatorClient, BlockBuilderFeeInfoRequest,
         },
     },
+    solana_bundle::bundle_status::BundleStatus,
     solana_gossip::cluster_info::ClusterInfo,
     solana_perf::packet::PacketBatch,
     solana_sdk::{
@@ -33,7 +43,7 @@ use {
             Arc, Mutex,
         },
         thread::{self, Builder, JoinHandle},
-        time::Duration,
+        time::{Duration, Instant},
     },
     tokio::{
         task,
@@ -42,19 +52,35 @@ use {
     tonic::{
         codegen::InterceptedService,
         transport::{Channel, Endpoint},
-        Status, Streaming,
+        Code, Status, Streaming,
     },
 };
 
 const CONNECTION_TIMEOUT_S: u64 = 10;
 const CONNECTION_BACKOFF_S: u64 = 5;
 
+// Bundles and packets raced across multiple block engines arrive within milliseconds of each other
+const BUNDLE_DEDUP_WINDOW: Duration = Duration::from_secs(2);
+const BUNDLE_DEDUP_CAPACITY: usize = 100_000;
+const PACKET_DEDUP_WINDOW: Duration = Duration::from_secs(2);
+const PACKET_DEDUP_CAPACITY: usize = 200_000;
+
+/// Filters out the bundles and packets already received on another stream when streaming from
+/// several block engines at once
+struct StreamDedupers {
+    bundles: Mutex<BundleIdDeduper>,
+    packets: Mutex<PacketSignatureDeduper>,
+}
+
 #[derive(Default)]
 struct BlockEngineStageStats {
     num_bundles: u64,
     num_bundle_packets: u64,
     num_packets: u64,
     num_empty_packets: u64,
+    num_duplicate_bundles: u64,
+    num_duplicate_packets: u64,
+    bundle_result_stats: BundleResultReporterStats,
 }
 
 impl BlockEngineStageStats {
@@ -64,7 +90,19 @@ impl BlockEngineStageStats {
             ("num_bundles", self.num_bundles, i64),
             ("num_bundle_packets", self.num_bundle_packets, i64),
             ("num_packets", self.num_packets, i64),
-            ("num_empty_packets", self.num_empty_packets, i64)
+            ("num_empty_packets", self.num_empty_packets, i64),
+            ("num_duplicate_bundles", self.num_duplicate_bundles, i64),
+            ("num_duplicate_packets", self.num_duplicate_packets, i64),
+            (
+                "num_bundle_results_sent",
+                self.bundle_result_stats.num_bundle_results_sent,
+                i64
+            ),
+            (
+                "num_bundle_results_dropped",
+                self.bundle_result_stats.num_bundle_results_dropped,
+                i64
+            )
         );
     }
 }
@@ -76,11 +114,15 @@ pub struct BlockBuilderFeeInfo {
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct BlockEngineConfig {
-    /// Block Engine URL
-    pub block_engine_url: String,
+    /// Block Engine URLs, in order of preference
+    pub block_engine_urls: Vec<String>,
 
     /// If set then it will be assumed the backend verified packets so signature verification will be bypassed in the validator.
     pub trust_packets: bool,
+
+    /// If set then streams are kept open to all block engines at once and bundles are de-duplicated by id.
+    /// Otherwise only the healthiest block engine is connected to, failing over to the others on error.
+    pub multi_stream: bool,
 }
 
 pub struct BlockEngineStage {
@@ -100,8 +142,11 @@ impl BlockEngineStage {
         banking_packet_sender: BankingPacketSender,
         exit: Arc<AtomicBool>,
         block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+        // Outcomes of bundles published by BundleStage, reported back to the block engine.
+        bundle_status_receiver: Receiver<BundleStatus>,
     ) -> Self {
         let block_builder_fee_info = block_builder_fee_info.clone();
+        let bundle_result_router = BundleResultRouter::new(bundle_status_receiver);
 
         let thread = Builder::new()
             .name("block-engine-stage".to_string())
@@ -118,6 +163,7 @@ impl BlockEngineStage {
                     banking_packet_sender,
                     exit,
                     block_builder_fee_info,
+                    bundle_result_router,
                 ));
             })
             .unwrap();
@@ -143,56 +189,182 @@ impl BlockEngineStage {
         banking_packet_sender: BankingPacketSender,
         exit: Arc<AtomicBool>,
         block_builder_fee_info: Arc<Mutex<BlockBuilderFeeInfo>>,
+        bundle_result_router: BundleResultRouter,
     ) {
         const CONNECTION_TIMEOUT: Duration = Duration::from_secs(CONNECTION_TIMEOUT_S);
         const CONNECTION_BACKOFF: Duration = Duration::from_secs(CONNECTION_BACKOFF_S);
         let mut error_count: u64 = 0;
+        let mut endpoints = BlockEngineEndpoints::default();
 
         while !exit.load(Ordering::Relaxed) {
             // Wait until a valid config is supplied (either initially or by admin rpc)
-            // Use if!/else here to avoid extra CONNECTION_BACKOFF wait on successful termination
-            let local_block_engine_config = {
-                let block_engine_config = block_engine_config.clone();
-                task::spawn_blocking(move || block_engine_config.lock().unwrap().clone())
-                    .await
-                    .unwrap()
-            };
+            let local_block_engine_config =
+                Self::get_block_engine_config(&block_engine_config).await;
             if !Self::is_valid_block_engine_config(&local_block_engine_config) {
                 sleep(CONNECTION_BACKOFF).await;
-            } else if let Err(e) = Self::connect_auth_and_stream(
-                &local_block_engine_config,
-                &block_engine_config,
-                &cluster_info,
-                &bundle_tx,
-                &packet_tx,
-                &banking_packet_sender,
-                &exit,
-                &block_builder_fee_info,
-                &CONNECTION_TIMEOUT,
-            )
-            .await
-            {
-                match e {
-                    // This error is frequent on hot spares, and the parsed string does not work
-                    // with datapoints (incorrect escaping).
-                    ProxyError::AuthenticationPermissionDenied => {
-                        warn!("block engine permission denied. not on leader schedule. ignore if hot-spare.")
+            } else if local_block_engine_config.multi_stream {
+                Self::connect_auth_and_stream_all(
+                    &local_block_engine_config,
+                    &block_engine_config,
+                    &cluster_info,
+                    &bundle_tx,
+                    &packet_tx,
+                    &banking_packet_sender,
+                    &exit,
+                    &block_builder_fee_info,
+                    &bundle_result_router,
+                    &CONNECTION_TIMEOUT,
+                )
+                .await;
+            } else {
+                // Connect to the healthiest block engine, failing over to the next one on error
+                endpoints.update_urls(&local_block_engine_config.block_engine_urls);
+                let Some(endpoint_idx) = endpoints.next_endpoint(Instant::now()) else {
+                    sleep(endpoints.min_backoff(Instant::now())).await;
+                    continue;
+                };
+                let block_engine_url = endpoints.get(endpoint_idx).url().to_string();
+
+                let session_start = Instant::now();
+                let result = Self::connect_auth_and_stream(
+                    &block_engine_url,
+                    &local_block_engine_config,
+                    &block_engine_config,
+                    &cluster_info,
+                    &bundle_tx,
+                    &packet_tx,
+                    &banking_packet_sender,
+                    &exit,
+                    &block_builder_fee_info,
+                    &bundle_result_router,
+                    None,
+                    &CONNECTION_TIMEOUT,
+                )
+                .await;
+
+                // Reconnecting because of a config change doesn't reflect on the endpoint's health
+                if local_block_engine_config
+                    == Self::get_block_engine_config(&block_engine_config).await
+                {
+                    let endpoint = endpoints.get_mut(endpoint_idx);
+                    endpoint.record_session(session_start.elapsed(), Instant::now());
+                    datapoint_info!(
+                        "block_engine_stage-endpoint_health",
+                        ("url", block_engine_url, String),
+                        ("consecutive_failures", endpoint.consecutive_failures(), i64),
+                    );
+                }
+                if let Err(e) = result {
+                    Self::report_proxy_error(e, &mut error_count);
+                }
+            }
+        }
+    }
+
+    /// Keeps a stream open to every configured block engine at once. The same bundle or packet
+    /// is usually sent by several block engines, so bundles are de-duplicated by id and packets by
+    /// signature before being handed off. Returns once the config changes or the validator exits.
+    #[allow(clippy::too_many_arguments)]
+    async fn connect_auth_and_stream_all(
+        local_block_engine_config: &BlockEngineConfig,
+        global_block_engine_config: &Arc<Mutex<BlockEngineConfig>>,
+        cluster_info: &Arc<ClusterInfo>,
+        bundle_tx: &Sender<Vec<PacketBundle>>,
+        packet_tx: &Sender<PacketBatch>,
+        banking_packet_sender: &BankingPacketSender,
+        exit: &Arc<AtomicBool>,
+        block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+        bundle_result_router: &BundleResultRouter,
+        connection_timeout: &Duration,
+    ) {
+        let dedupers = StreamDedupers {
+            bundles: Mutex::new(BundleIdDeduper::new(
+                BUNDLE_DEDUP_WINDOW,
+                BUNDLE_DEDUP_CAPACITY,
+            )),
+            packets: Mutex::new(PacketSignatureDeduper::new(
+                PACKET_DEDUP_WINDOW,
+                PACKET_DEDUP_CAPACITY,
+            )),
+        };
+        let dedupers = &dedupers;
+
+        join_all(local_block_engine_config.block_engine_urls.iter().map(
+            |block_engine_url| async move {
+                let mut endpoint = EndpointHealth::new(block_engine_url.clone());
+                let mut error_count: u64 = 0;
+                loop {
+                    sleep(endpoint.backoff(Instant::now())).await;
+                    if exit.load(Ordering::Relaxed)
+                        || *local_block_engine_config
+                            != Self::get_block_engine_config(global_block_engine_config).await
+                    {
+                        return;
                     }
-                    e => {
-                        error_count += 1;
-                        datapoint_warn!(
-                            "block_engine_stage-proxy_error",
-                            ("count", error_count, i64),
-                            ("error", e.to_string(), String),
-                        );
+
+                    let session_start = Instant::now();
+                    if let Err(e) = Self::connect_auth_and_stream(
+                        block_engine_url,
+                        local_block_engine_config,
+                        global_block_engine_config,
+                        cluster_info,
+                        bundle_tx,
+                        packet_tx,
+                        banking_packet_sender,
+                        exit,
+                        block_builder_fee_info,
+                        bundle_result_router,
+                        Some(dedupers),
+                        connection_timeout,
+                    )
+                    .await
+                    {
+                        Self::report_proxy_error(e, &mut error_count);
                     }
+                    endpoint.record_session(session_start.elapsed(), Instant::now());
+                    datapoint_info!(
+                        "block_engine_stage-endpoint_health",
+                        ("url", block_engine_url, String),
+                        ("consecutive_failures", endpoint.consecutive_failures(), i64),
+                    );
                 }
-                sleep(CONNECTION_BACKOFF).await;
+            },
+        ))
+        .await;
+    }
+
+    async fn get_block_engine_config(
+        block_engine_config: &Arc<Mutex<BlockEngineConfig>>,
+    ) -> BlockEngineConfig {
+        let block_engine_config = block_engine_config.clone();
+        task::spawn_blocking(move || block_engine_config.lock().unwrap().clone())
+            .await
+            .unwrap()
+    }
+
+    fn report_proxy_error(e: ProxyError, error_count: &mut u64) {
+        match e {
+            // This error is frequent on hot spares, and the parsed string does not work
+            // with datapoints (incorrect escaping).
+            ProxyError::AuthenticationPermissionDenied => {
+                warn!(
+                    "block engine permission denied. not on leader schedule. ignore if hot-spare."
+                )
+            }
+            e => {
+                *error_count += 1;
+                datapoint_warn!(
+                    "block_engine_stage-proxy_error",
+                    ("count", *error_count, i64),
+                    ("error", e.to_string(), String),
+                );
             }
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn connect_auth_and_stream(
+        block_engine_url: &str,
         local_block_engine_config: &BlockEngineConfig,
         global_block_engine_config: &Arc<Mutex<BlockEngineConfig>>,
         cluster_info: &Arc<ClusterInfo>,
@@ -201,24 +373,21 @@ impl BlockEngineStage {
         banking_packet_sender: &BankingPacketSender,
         exit: &Arc<AtomicBool>,
         block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+        bundle_result_router: &BundleResultRouter,
+        dedupers: Option<&StreamDedupers>,
         connection_timeout: &Duration,
     ) -> crate::proxy::Result<()> {
         // Get a copy of configs here in case they have changed at runtime
         let keypair = cluster_info.keypair().clone();
 
-        let mut backend_endpoint =
-            Endpoint::from_shared(local_block_engine_config.block_engine_url.clone())
-                .map_err(|_| {
-                    ProxyError::BlockEngineConnectionError(format!(
-                        "invalid block engine url value: {}",
-                        local_block_engine_config.block_engine_url
-                    ))
-                })?
-                .tcp_keepalive(Some(Duration::from_secs(60)));
-        if local_block_engine_config
-            .block_engine_url
-            .starts_with("https")
-        {
+        let mut backend_endpoint = Endpoint::from_shared(block_engine_url.to_string())
+            .map_err(|_| {
+                ProxyError::BlockEngineConnectionError(format!(
+                    "invalid block engine url value: {block_engine_url}",
+                ))
+            })?
+            .tcp_keepalive(Some(Duration::from_secs(60)));
+        if block_engine_url.starts_with("https") {
             backend_endpoint = backend_endpoint
                 .tls_config(tonic::transport::ClientTlsConfig::new())
                 .map_err(|_| {
@@ -228,10 +397,7 @@ impl BlockEngineStage {
                 })?;
         }
 
-        debug!(
-            "connecting to auth: {}",
-            local_block_engine_config.block_engine_url
-        );
+        debug!("connecting to auth: {block_engine_url}");
         let auth_channel = timeout(*connection_timeout, backend_endpoint.connect())
             .await
             .map_err(|_| ProxyError::AuthenticationConnectionTimeout)?
@@ -249,14 +415,11 @@ impl BlockEngineStage {
 
         datapoint_info!(
             "block_engine_stage-tokens_generated",
-            ("url", local_block_engine_config.block_engine_url, String),
+            ("url", block_engine_url, String),
             ("count", 1, i64),
         );
 
-        debug!(
-            "connecting to block engine: {}",
-            local_block_engine_config.block_engine_url
-        );
+        debug!("connecting to block engine: {block_engine_url}");
         let block_engine_channel = timeout(*connection_timeout, backend_endpoint.connect())
             .await
             .map_err(|_| ProxyError::BlockEngineConnectionTimeout)?
@@ -264,19 +427,27 @@ impl BlockEngineStage {
 
         let access_token = Arc::new(Mutex::new(access_token));
         let block_engine_client = BlockEngineValidatorClient::with_interceptor(
+            block_engine_channel.clone(),
+            AuthInterceptor::new(access_token.clone()),
+        );
+        let bundle_results_client = BlockEngineBundleResultsClient::with_interceptor(
             block_engine_channel,
             AuthInterceptor::new(access_token.clone()),
         );
 
         Self::start_consuming_block_engine_bundles_and_packets(
+            block_engine_url,
             bundle_tx,
             block_engine_client,
+            bundle_results_client,
             packet_tx,
             local_block_engine_config,
             global_block_engine_config,
             banking_packet_sender,
             exit,
             block_builder_fee_info,
+            bundle_result_router,
+            dedupers,
             auth_client,
             access_token,
             refresh_token,
@@ -289,14 +460,20 @@ impl BlockEngineStage {
 
     #[allow(clippy::too_many_arguments)]
     async fn start_consuming_block_engine_bundles_and_packets(
+        block_engine_url: &str,
         bundle_tx: &Sender<Vec<PacketBundle>>,
         mut client: BlockEngineValidatorClient<InterceptedService<Channel, AuthInterceptor>>,
+        bundle_results_client: BlockEngineBundleResultsClient<
+            InterceptedService<Channel, AuthInterceptor>,
+        >,
         packet_tx: &Sender<PacketBatch>,
         local_config: &BlockEngineConfig, // local copy of config with current connections
         global_config: &Arc<Mutex<BlockEngineConfig>>, // guarded reference for detecting run-time updates
         banking_packet_sender: &BankingPacketSender,
         exit: &Arc<AtomicBool>,
         block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+        bundle_result_router: &BundleResultRouter,
+        dedupers: Option<&StreamDedupers>,
         auth_client: AuthServiceClient<Channel>,
         access_token: Arc<Mutex<Token>>,
         refresh_token: Token,
@@ -345,7 +522,9 @@ impl BlockEngineStage {
         }
 
         Self::consume_bundle_and_packet_stream(
+            block_engine_url,
             client,
+            bundle_results_client,
             (subscribe_bundles_stream, subscribe_packets_stream),
             bundle_tx,
             packet_tx,
@@ -354,6 +533,8 @@ impl BlockEngineStage {
             banking_packet_sender,
             exit,
             block_builder_fee_info,
+            bundle_result_router,
+            dedupers,
             auth_client,
             access_token,
             refresh_token,
@@ -366,7 +547,11 @@ impl BlockEngineStage {
 
     #[allow(clippy::too_many_arguments)]
     async fn consume_bundle_and_packet_stream(
+        block_engine_url: &str,
         mut client: BlockEngineValidatorClient<InterceptedService<Channel, AuthInterceptor>>,
+        mut bundle_results_client: BlockEngineBundleResultsClient<
+            InterceptedService<Channel, AuthInterceptor>,
+        >,
         (mut bundle_stream, mut packet_stream): (
             Streaming<block_engine::SubscribeBundlesResponse>,
             Streaming<block_engine::SubscribePacketsResponse>,
@@ -378,6 +563,8 @@ impl BlockEngineStage {
         banking_packet_sender: &BankingPacketSender,
         exit: &Arc<AtomicBool>,
         block_builder_fee_info: &Arc<Mutex<BlockBuilderFeeInfo>>,
+        bundle_result_router: &BundleResultRouter,
+        dedupers: Option<&StreamDedupers>,
         mut auth_client: AuthServiceClient<Channel>,
         access_token: Arc<Mutex<Token>>,
         mut refresh_token: Token,
@@ -387,6 +574,7 @@ impl BlockEngineStage {
     ) -> crate::proxy::Result<()> {
         const METRICS_TICK: Duration = Duration::from_secs(1);
         const MAINTENANCE_TICK: Duration = Duration::from_secs(10 * 60);
+        const BUNDLE_RESULTS_TICK: Duration = Duration::from_millis(100);
         let refresh_within_s: u64 = METRICS_TICK.as_secs().saturating_mul(3).saturating_div(2);
 
         let mut num_full_refreshes: u64 = 1;
@@ -394,6 +582,21 @@ impl BlockEngineStage {
         let mut block_engine_stats = BlockEngineStageStats::default();
         let mut metrics_and_auth_tick = interval(METRICS_TICK);
         let mut maintenance_tick = interval(MAINTENANCE_TICK);
+        let mut bundle_results_tick = interval(BUNDLE_RESULTS_TICK);
+
+        // only results of bundles received on this connection are reported back on it
+        let bundle_result_reporter = bundle_result_router.reporter();
+
+        // the stream is closed when bundle_results_sender is dropped on return
+        let (mut bundle_results_sender, bundle_results_receiver) =
+            mpsc::channel(BUNDLE_RESULTS_STREAM_BUFFER);
+        let mut submit_bundle_results_task = task::spawn(async move {
+            bundle_results_client
+                .submit_bundle_results(bundle_results_receiver)
+                .await
+        });
+        // set once the block engine turns out not to serve SubmitBundleResults
+        let mut bundle_results_unsupported = false;
 
         info!("connected to packet and bundle stream");
 
@@ -401,10 +604,29 @@ impl BlockEngineStage {
             tokio::select! {
                 maybe_msg = packet_stream.message() => {
                     let resp = maybe_msg?.ok_or(ProxyError::GrpcStreamDisconnected)?;
-                    Self::handle_block_engine_packets(resp, packet_tx, banking_packet_sender, local_config.trust_packets, &mut block_engine_stats)?;
+                    Self::handle_block_engine_packets(resp, packet_tx, banking_packet_sender, local_config.trust_packets, dedupers.map(|dedupers| &dedupers.packets), &mut block_engine_stats)?;
                 }
                 maybe_bundles = bundle_stream.message() => {
-                    Self::handle_block_engine_maybe_bundles(maybe_bundles, bundle_tx, &mut block_engine_stats)?;
+                    Self::handle_block_engine_maybe_bundles(maybe_bundles, bundle_tx, dedupers.map(|dedupers| &dedupers.bundles), &bundle_result_reporter, &mut block_engine_stats)?;
+                }
+                _ = bundle_results_tick.tick() => {
+                    if bundle_results_unsupported {
+                        bundle_result_reporter.clear();
+                    } else {
+                        // a closed stream is handled once the task's result is polled
+                        bundle_result_reporter.forward_bundle_results(&mut bundle_results_sender, &mut block_engine_stats.bundle_result_stats);
+                    }
+                }
+                submit_bundle_results_result = &mut submit_bundle_results_task, if !bundle_results_unsupported => {
+                    match submit_bundle_results_result.map_err(|_| ProxyError::GrpcStreamDisconnected)? {
+                        Err(status) if status.code() == Code::Unimplemented => {
+                            warn!("block engine {block_engine_url} doesn't accept bundle results, not reporting them");
+                            bundle_results_unsupported = true;
+                        }
+                        Err(status) => return Err(ProxyError::GrpcError(status)),
+                        // the block engine should keep the stream open for the lifetime of the connection
+                        Ok(_) => return Err(ProxyError::GrpcStreamDisconnected),
+                    }
                 }
                 _ = metrics_and_auth_tick.tick() => {
                     block_engine_stats.report();
@@ -433,7 +655,7 @@ impl BlockEngineStage {
                         num_refresh_access_token += 1;
                         datapoint_info!(
                             "block_engine_stage-refresh_access_token",
-                            ("url", &local_config.block_engine_url, String),
+                            ("url", block_engine_url, String),
                             ("count", num_refresh_access_token, i64),
                         );
 
@@ -446,7 +668,7 @@ impl BlockEngineStage {
                         num_full_refreshes += 1;
                         datapoint_info!(
                             "block_engine_stage-tokens_generated",
-                            ("url", &local_config.block_engine_url, String),
+                            ("url", block_engine_url, String),
                             ("count", num_full_refreshes, i64),
                         );
                         refresh_token = new_token;
@@ -482,6 +704,8 @@ impl BlockEngineStage {
     fn handle_block_engine_maybe_bundles(
         maybe_bundles_response: Result<Option<block_engine::SubscribeBundlesResponse>, Status>,
         bundle_sender: &Sender<Vec<PacketBundle>>,
+        bundle_deduper: Option<&Mutex<BundleIdDeduper>>,
+        bundle_result_reporter: &BundleResultReporter,
         block_engine_stats: &mut BlockEngineStageStats,
     ) -> crate::proxy::Result<()> {
         let bundles_response = maybe_bundles_response?.ok_or(ProxyError::GrpcStreamDisconnected)?;
@@ -502,6 +726,27 @@ impl BlockEngineStage {
                 })
             })
             .collect();
+        let num_bundles_received = bundles.len();
+
+        // another block engine stream may have already delivered the same bundle
+        let bundles: Vec<PacketBundle> = match bundle_deduper {
+            Some(bundle_deduper) => {
+                let now = Instant::now();
+                let mut bundle_deduper = bundle_deduper.lock().unwrap();
+                bundles
+                    .into_iter()
+                    .filter(|bundle| bundle_deduper.insert(&bundle.bundle_id, now))
+                    .collect()
+            }
+            None => bundles,
+        };
+        saturating_add_assign!(
+            block_engine_stats.num_duplicate_bundles,
+            num_bundles_received.saturating_sub(bundles.len()) as u64
+        );
+
+        bundle_result_reporter
+            .record_bundles(bundles.iter().map(|bundle| bundle.bundle_id.as_str()));
 
         saturating_add_assign!(block_engine_stats.num_bundles, bundles.len() as u64);
         saturating_add_assign!(
@@ -520,6 +765,7 @@ impl BlockEngineStage {
         packet_tx: &Sender<PacketBatch>,
         banking_packet_sender: &BankingPacketSender,
         trust_packets: bool,
+        packet_deduper: Option<&Mutex<PacketSignatureDeduper>>,
         block_engine_stats: &mut BlockEngineStageStats,
     ) -> crate::proxy::Result<()> {
         if let Some(batch) = resp.batch {
@@ -528,13 +774,34 @@ impl BlockEngineStage {
                 return Ok(());
             }
 
-            let packet_batch = PacketBatch::new(
-                batch
-                    .packets
-                    .into_iter()
-                    .map(proto_packet_to_packet)
-                    .collect(),
+            // another block engine stream may have already delivered the same transaction
+            let num_packets_received = batch.packets.len();
+            let packets = match packet_deduper {
+                Some(packet_deduper) => {
+                    let now = Instant::now();
+                    let mut packet_deduper = packet_deduper.lock().unwrap();
+                    batch
+                        .packets
+                        .into_iter()
+                        .filter(|packet| {
+                            // packets without a signature are left for sigverify to discard
+                            first_signature(&packet.data)
+                                .map_or(true, |signature| packet_deduper.insert(&signature, now))
+                        })
+                        .collect()
+                }
+                None => batch.packets,
+            };
+            saturating_add_assign!(
+                block_engine_stats.num_duplicate_packets,
+                num_packets_received.saturating_sub(packets.len()) as u64
             );
+            if packets.is_empty() {
+                return Ok(());
+            }
+
+            let packet_batch =
+                PacketBatch::new(packets.into_iter().map(proto_packet_to_packet).collect());
 
             saturating_add_assign!(block_engine_stats.num_packets, packet_batch.len() as u64);
 
@@ -555,17 +822,20 @@ impl BlockEngineStage {
     }
 
     pub fn is_valid_block_engine_config(config: &BlockEngineConfig) -> bool {
-        if config.block_engine_url.is_empty() {
+        if config.block_engine_urls.is_empty() {
             warn!("can't connect to block_engine. missing block_engine_url.");
             return false;
         }
-        if let Err(e) = Endpoint::from_str(&config.block_engine_url) {
-            error!(
-                "can't connect to block engine. error creating block engine endpoint - {}",
-                e.to_string()
-            );
-            return false;
-        }
-        true
+        config.block_engine_urls.iter().all(|block_engine_url| {
+            if let Err(e) = Endpoint::from_str(block_engine_url) {
+                error!(
+                    "can't connect to block engine {}. error creating block engine endpoint - {}",
+                    block_engine_url,
+                    e.to_string()
+                );
+                return false;
+            }
+            true
+        })
     }
 }
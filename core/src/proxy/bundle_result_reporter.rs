@@ -0,0 +1,439 @@
+//! Forwards the outcome of bundles received from the Block Engine back to it.
+//!
+//! BundleStage publishes a [BundleStatus] for every bundle it attempts or drops. Each status is
+//! routed to the Block Engine the bundle was received from, then batched and written to that
+//! Block Engine's `SubmitBundleResults` client stream so it can stop re-sending bundles that will
+//! never land on this leader.
+use {
+    crossbeam_channel::Receiver,
+    futures::channel::mpsc,
+    jito_protos::proto::block_engine::{
+        bundle_execution_result, BundleExecutionResult, BundleExpired, BundleLanded,
+        BundleRejected, SubmitBundleResultsRequest,
+    },
+    solana_bundle::bundle_status::{BundleOutcome, BundleStatus},
+    solana_sdk::saturating_add_assign,
+    std::{
+        collections::{HashMap, VecDeque},
+        sync::Mutex,
+    },
+};
+
+/// Number of bundle results buffered between BundleStage and the Block Engine connections.
+/// Results are dropped if the buffer is full, for instance while the Block Engine is unreachable.
+pub const BUNDLE_RESULT_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Max number of bundle results sent in a single message on the stream
+const MAX_BUNDLE_RESULTS_PER_REQUEST: usize = 1_000;
+
+/// Max number of requests queued on the stream before results are dropped
+pub(crate) const BUNDLE_RESULTS_STREAM_BUFFER: usize = 100;
+
+/// Number of received bundles whose source Block Engine is remembered. Results of older bundles
+/// can't be routed and are dropped.
+const MAX_TRACKED_BUNDLE_SOURCES: usize = 100_000;
+
+#[derive(Default)]
+pub(crate) struct BundleResultReporterStats {
+    pub(crate) num_bundle_results_sent: u64,
+    pub(crate) num_bundle_results_dropped: u64,
+}
+
+/// Identifies one connection to a Block Engine
+type ReporterId = u64;
+
+#[derive(Default)]
+struct BundleResultRouterState {
+    next_reporter_id: ReporterId,
+    /// Connection each recently received bundle came from
+    bundle_sources: HashMap<String, ReporterId>,
+    bundle_sources_order: VecDeque<(String, ReporterId)>,
+    /// Results waiting to be sent on each open connection
+    pending_results: HashMap<ReporterId, Vec<BundleStatus>>,
+    /// Results that couldn't be routed since the last time they were counted
+    num_unroutable: u64,
+}
+
+impl BundleResultRouterState {
+    fn record_bundle_source(&mut self, bundle_id: &str, reporter_id: ReporterId) {
+        if self.bundle_sources_order.len() >= MAX_TRACKED_BUNDLE_SOURCES {
+            let (evicted, evicted_reporter_id) = self.bundle_sources_order.pop_front().unwrap();
+            // the bundle may have been received again since
+            if self.bundle_sources.get(&evicted) == Some(&evicted_reporter_id) {
+                self.bundle_sources.remove(&evicted);
+            }
+        }
+        self.bundle_sources
+            .insert(bundle_id.to_string(), reporter_id);
+        self.bundle_sources_order
+            .push_back((bundle_id.to_string(), reporter_id));
+    }
+
+    fn route(&mut self, bundle_status_receiver: &Receiver<BundleStatus>) {
+        for bundle_status in bundle_status_receiver.try_iter() {
+            let pending_results = self
+                .bundle_sources
+                .get(&bundle_status.bundle_id)
+                .and_then(|reporter_id| self.pending_results.get_mut(reporter_id))
+                .filter(|pending_results| pending_results.len() < BUNDLE_RESULT_CHANNEL_CAPACITY);
+            match pending_results {
+                Some(pending_results) => pending_results.push(bundle_status),
+                // from a connection that has since closed, or stuck behind a full buffer
+                None => saturating_add_assign!(self.num_unroutable, 1),
+            }
+        }
+    }
+}
+
+/// Receives the bundle results published by BundleStage and routes each one to the Block Engine
+/// connection the bundle was received on. Results of bundles whose connection has closed are
+/// dropped, as they're stale by the time the connection is back up.
+pub(crate) struct BundleResultRouter {
+    bundle_status_receiver: Receiver<BundleStatus>,
+    state: Mutex<BundleResultRouterState>,
+}
+
+impl BundleResultRouter {
+    pub(crate) fn new(bundle_status_receiver: Receiver<BundleStatus>) -> Self {
+        Self {
+            bundle_status_receiver,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Registers a new Block Engine connection. Results of the bundles recorded through the
+    /// returned reporter are buffered for it until it's dropped.
+    pub(crate) fn reporter(&self) -> BundleResultReporter<'_> {
+        let mut state = self.state.lock().unwrap();
+        // route what was published before the connection opened so none of it reaches it
+        state.route(&self.bundle_status_receiver);
+        let reporter_id = state.next_reporter_id;
+        state.next_reporter_id = reporter_id.wrapping_add(1);
+        state.pending_results.insert(reporter_id, vec![]);
+        BundleResultReporter {
+            router: self,
+            reporter_id,
+        }
+    }
+}
+
+/// Reports the outcome of bundles back to the Block Engine connection they were received on
+pub(crate) struct BundleResultReporter<'a> {
+    router: &'a BundleResultRouter,
+    reporter_id: ReporterId,
+}
+
+impl Drop for BundleResultReporter<'_> {
+    fn drop(&mut self) {
+        let mut state = self.router.state.lock().unwrap();
+        state.pending_results.remove(&self.reporter_id);
+    }
+}
+
+impl BundleResultReporter<'_> {
+    /// Records bundles received on this connection so their results are reported back to it
+    pub(crate) fn record_bundles<'b>(&self, bundle_ids: impl IntoIterator<Item = &'b str>) {
+        let mut state = self.router.state.lock().unwrap();
+        for bundle_id in bundle_ids {
+            state.record_bundle_source(bundle_id, self.reporter_id);
+        }
+    }
+
+    /// Discards the results buffered for this connection, returning how many there were
+    pub(crate) fn clear(&self) -> usize {
+        let mut state = self.router.state.lock().unwrap();
+        state.route(&self.router.bundle_status_receiver);
+        state
+            .pending_results
+            .get_mut(&self.reporter_id)
+            .map(|pending_results| pending_results.drain(..).count())
+            .unwrap_or_default()
+    }
+
+    /// Pushes the results buffered for this connection onto its stream. Once the stream is closed
+    /// the results are left buffered, since why it closed is only known from the response to the
+    /// stream, ex: the block engine doesn't accept bundle results.
+    pub(crate) fn forward_bundle_results(
+        &self,
+        stream_sender: &mut mpsc::Sender<SubmitBundleResultsRequest>,
+        stats: &mut BundleResultReporterStats,
+    ) {
+        if stream_sender.is_closed() {
+            return;
+        }
+        let pending_results = {
+            let mut state = self.router.state.lock().unwrap();
+            state.route(&self.router.bundle_status_receiver);
+            // unroutable results are counted by whichever connection notices them
+            saturating_add_assign!(
+                stats.num_bundle_results_dropped,
+                std::mem::take(&mut state.num_unroutable)
+            );
+            state
+                .pending_results
+                .get_mut(&self.reporter_id)
+                .map(std::mem::take)
+                .unwrap_or_default()
+        };
+
+        let mut pending_results = pending_results.into_iter().peekable();
+        while pending_results.peek().is_some() {
+            let results: Vec<BundleExecutionResult> = pending_results
+                .by_ref()
+                .take(MAX_BUNDLE_RESULTS_PER_REQUEST)
+                .map(bundle_status_to_proto)
+                .collect();
+            let num_results = results.len() as u64;
+            match stream_sender.try_send(SubmitBundleResultsRequest { results }) {
+                Ok(()) => {
+                    saturating_add_assign!(stats.num_bundle_results_sent, num_results);
+                }
+                // full, or closed since it was checked
+                Err(_) => {
+                    saturating_add_assign!(stats.num_bundle_results_dropped, num_results);
+                }
+            }
+        }
+    }
+}
+
+fn bundle_status_to_proto(bundle_status: BundleStatus) -> BundleExecutionResult {
+    let result = match bundle_status.outcome {
+        BundleOutcome::Landed => bundle_execution_result::Result::Landed(BundleLanded {
+            tip_lamports: bundle_status.tip_lamports,
+            compute_units_consumed: bundle_status.compute_units_consumed,
+        }),
+        BundleOutcome::Failed(e) => bundle_execution_result::Result::Rejected(BundleRejected {
+            reason: e.to_string(),
+        }),
+        BundleOutcome::Dropped => bundle_execution_result::Result::Expired(BundleExpired {}),
+    };
+    BundleExecutionResult {
+        bundle_id: bundle_status.bundle_id,
+        slot: bundle_status.slot,
+        result: Some(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::{unbounded, Sender},
+        solana_bundle::BundleExecutionError,
+    };
+
+    fn bundle_ids(prefix: &str, count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("{prefix}{i}")).collect()
+    }
+
+    fn record_bundles(reporter: &BundleResultReporter, bundle_ids: &[String]) {
+        reporter.record_bundles(bundle_ids.iter().map(String::as_str));
+    }
+
+    fn send_dropped(sender: &Sender<BundleStatus>, bundle_ids: &[String]) {
+        for bundle_id in bundle_ids {
+            sender
+                .send(BundleStatus::dropped(bundle_id.clone(), 1))
+                .unwrap();
+        }
+    }
+
+    fn forward(reporter: &BundleResultReporter) -> (Vec<String>, BundleResultReporterStats) {
+        let (mut sender, mut receiver) = mpsc::channel(BUNDLE_RESULTS_STREAM_BUFFER);
+        let mut stats = BundleResultReporterStats::default();
+        reporter.forward_bundle_results(&mut sender, &mut stats);
+        let mut forwarded = vec![];
+        while let Ok(Some(request)) = receiver.try_next() {
+            forwarded.extend(request.results.into_iter().map(|result| result.bundle_id));
+        }
+        (forwarded, stats)
+    }
+
+    #[test]
+    fn test_bundle_status_to_proto() {
+        let landed = bundle_status_to_proto(BundleStatus::landed("a".to_string(), 1, 100, 10));
+        assert_eq!(landed.bundle_id, "a");
+        assert_eq!(landed.slot, 1);
+        assert_eq!(
+            landed.result,
+            Some(bundle_execution_result::Result::Landed(BundleLanded {
+                tip_lamports: 10,
+                compute_units_consumed: 100,
+            }))
+        );
+
+        let rejected = bundle_status_to_proto(BundleStatus::failed(
+            "b".to_string(),
+            2,
+            BundleExecutionError::LockError,
+        ));
+        assert_eq!(rejected.bundle_id, "b");
+        assert_eq!(rejected.slot, 2);
+        assert_eq!(
+            rejected.result,
+            Some(bundle_execution_result::Result::Rejected(BundleRejected {
+                reason: BundleExecutionError::LockError.to_string(),
+            }))
+        );
+
+        let expired = bundle_status_to_proto(BundleStatus::dropped("c".to_string(), 3));
+        assert_eq!(expired.bundle_id, "c");
+        assert_eq!(expired.slot, 3);
+        assert_eq!(
+            expired.result,
+            Some(bundle_execution_result::Result::Expired(BundleExpired {}))
+        );
+    }
+
+    #[test]
+    fn test_forward_bundle_results_batches() {
+        let (sender, receiver) = unbounded();
+        let router = BundleResultRouter::new(receiver);
+        let reporter = router.reporter();
+        let ids = bundle_ids("bundle", MAX_BUNDLE_RESULTS_PER_REQUEST + 1);
+        record_bundles(&reporter, &ids);
+        send_dropped(&sender, &ids);
+
+        let (mut stream_sender, mut stream_receiver) = mpsc::channel(BUNDLE_RESULTS_STREAM_BUFFER);
+        let mut stats = BundleResultReporterStats::default();
+        reporter.forward_bundle_results(&mut stream_sender, &mut stats);
+        assert_eq!(
+            stream_receiver.try_next().unwrap().unwrap().results.len(),
+            MAX_BUNDLE_RESULTS_PER_REQUEST
+        );
+        assert_eq!(
+            stream_receiver.try_next().unwrap().unwrap().results.len(),
+            1
+        );
+        assert!(stream_receiver.try_next().is_err());
+        assert_eq!(
+            stats.num_bundle_results_sent,
+            MAX_BUNDLE_RESULTS_PER_REQUEST as u64 + 1
+        );
+        assert_eq!(stats.num_bundle_results_dropped, 0);
+
+        // nothing buffered, nothing sent
+        reporter.forward_bundle_results(&mut stream_sender, &mut stats);
+        assert!(stream_receiver.try_next().is_err());
+    }
+
+    #[test]
+    fn test_forward_bundle_results_drops_when_stream_full() {
+        let (sender, receiver) = unbounded();
+        let router = BundleResultRouter::new(receiver);
+        let reporter = router.reporter();
+        let ids = bundle_ids("bundle", 2 * MAX_BUNDLE_RESULTS_PER_REQUEST);
+        record_bundles(&reporter, &ids);
+        send_dropped(&sender, &ids);
+
+        // a buffer of 0 still holds one request per sender
+        let (mut stream_sender, mut stream_receiver) = mpsc::channel(0);
+        let mut stats = BundleResultReporterStats::default();
+        reporter.forward_bundle_results(&mut stream_sender, &mut stats);
+        assert_eq!(
+            stats.num_bundle_results_sent,
+            MAX_BUNDLE_RESULTS_PER_REQUEST as u64
+        );
+        assert_eq!(
+            stats.num_bundle_results_dropped,
+            MAX_BUNDLE_RESULTS_PER_REQUEST as u64
+        );
+        assert!(stream_receiver.try_next().unwrap().is_some());
+        assert!(stream_receiver.try_next().is_err());
+    }
+
+    #[test]
+    fn test_forward_bundle_results_disconnected() {
+        let (sender, receiver) = unbounded();
+        let router = BundleResultRouter::new(receiver);
+        let reporter = router.reporter();
+        let ids = bundle_ids("bundle", 1);
+        record_bundles(&reporter, &ids);
+        send_dropped(&sender, &ids);
+
+        // the results stay buffered until the connection learns why the stream closed
+        let (mut stream_sender, stream_receiver) = mpsc::channel(BUNDLE_RESULTS_STREAM_BUFFER);
+        drop(stream_receiver);
+        let mut stats = BundleResultReporterStats::default();
+        reporter.forward_bundle_results(&mut stream_sender, &mut stats);
+        assert_eq!(stats.num_bundle_results_sent, 0);
+        assert_eq!(stats.num_bundle_results_dropped, 0);
+        assert_eq!(reporter.clear(), 1);
+    }
+
+    #[test]
+    fn test_results_routed_to_source_block_engine() {
+        let (sender, receiver) = unbounded();
+        let router = BundleResultRouter::new(receiver);
+        let reporter_a = router.reporter();
+        let reporter_b = router.reporter();
+        let ids_a = bundle_ids("a", 2);
+        let ids_b = bundle_ids("b", 1);
+        record_bundles(&reporter_a, &ids_a);
+        record_bundles(&reporter_b, &ids_b);
+
+        send_dropped(&sender, &ids_b);
+        send_dropped(&sender, &ids_a);
+        send_dropped(&sender, &bundle_ids("unknown", 1));
+
+        let (forwarded, stats) = forward(&reporter_a);
+        assert_eq!(forwarded, ids_a);
+        assert_eq!(stats.num_bundle_results_sent, 2);
+        assert_eq!(stats.num_bundle_results_dropped, 1);
+
+        let (forwarded, stats) = forward(&reporter_b);
+        assert_eq!(forwarded, ids_b);
+        assert_eq!(stats.num_bundle_results_sent, 1);
+        assert_eq!(stats.num_bundle_results_dropped, 0);
+
+        // results of bundles from a closed connection aren't sent on the others
+        drop(reporter_b);
+        send_dropped(&sender, &ids_b);
+        let (forwarded, stats) = forward(&reporter_a);
+        assert!(forwarded.is_empty());
+        assert_eq!(stats.num_bundle_results_dropped, 1);
+    }
+
+    #[test]
+    fn test_clear() {
+        let (sender, receiver) = unbounded();
+        let router = BundleResultRouter::new(receiver);
+        let reporter_a = router.reporter();
+        let reporter_b = router.reporter();
+        let ids_a = bundle_ids("a", 3);
+        let ids_b = bundle_ids("b", 1);
+        record_bundles(&reporter_a, &ids_a);
+        record_bundles(&reporter_b, &ids_b);
+        send_dropped(&sender, &ids_a);
+        send_dropped(&sender, &ids_b);
+
+        assert_eq!(reporter_a.clear(), 3);
+        assert_eq!(reporter_a.clear(), 0);
+        let (forwarded, stats) = forward(&reporter_a);
+        assert!(forwarded.is_empty());
+        assert_eq!(stats.num_bundle_results_sent, 0);
+
+        // clearing one connection leaves the others' results alone
+        let (forwarded, _stats) = forward(&reporter_b);
+        assert_eq!(forwarded, ids_b);
+    }
+
+    #[test]
+    fn test_new_reporter_skips_earlier_results() {
+        let (sender, receiver) = unbounded();
+        let router = BundleResultRouter::new(receiver);
+        let ids = bundle_ids("bundle", 1);
+        {
+            let reporter = router.reporter();
+            record_bundles(&reporter, &ids);
+        }
+        send_dropped(&sender, &ids);
+
+        // reconnecting to the same block engine doesn't pick up the previous connection's results
+        let reporter = router.reporter();
+        let (forwarded, stats) = forward(&reporter);
+        assert!(forwarded.is_empty());
+        assert_eq!(stats.num_bundle_results_dropped, 1);
+    }
+}
@@ -0,0 +1,672 @@
+//! Runs the relayer and block engine roles inside the validator.
+//!
+//! The embedded relayer serves the same gRPC services the [RelayerStage](super::relayer_stage)
+//! and [BlockEngineStage](super::block_engine_stage) connect to, so the full proxy path can be
+//! exercised without any external services:
+//! - Packets verified by the TPU's sigverify stage are streamed to the relayer subscriber along
+//!   with heartbeats, tagged with the stake of the node at their source IP. While nothing is
+//!   subscribed they're passed straight on to banking.
+//! - Bundles submitted over the sendBundle RPC are streamed to the block engine subscriber.
+//! - Only the validator's own identity can authenticate.
+
+use {
+    crate::{
+        banking_trace::{BankingPacketBatch, BankingPacketReceiver, BankingPacketSender},
+        packet_to_proto_packet,
+        proxy::proxy_server::{
+            header, subscribe_block_engine_packets, subscribe_bundles, subscribe_packets,
+            to_socket, AuthPolicy, ProxyAuthConfig, ProxyAuthService,
+            SubscribeBlockEnginePacketsStream, SubscribeBundlesStream, SubscribePacketsStream,
+        },
+    },
+    crossbeam_channel::{never, select, tick, Receiver},
+    jito_protos::proto::{
+        auth::auth_service_server::AuthServiceServer,
+        block_engine::{
+            self,
+            block_engine_validator_server::{BlockEngineValidator, BlockEngineValidatorServer},
+            BlockBuilderFeeInfoRequest, BlockBuilderFeeInfoResponse, SubscribeBundlesRequest,
+        },
+        bundle::{Bundle, BundleUuid},
+        packet::PacketBatch as ProtoPacketBatch,
+        relayer::{
+            self,
+            relayer_server::{Relayer, RelayerServer},
+            GetTpuConfigsRequest, GetTpuConfigsResponse,
+        },
+    },
+    solana_bundle::packet_bundle::PacketBundle,
+    solana_client::connection_cache::Protocol,
+    solana_gossip::cluster_info::ClusterInfo,
+    solana_sdk::{pubkey::Pubkey, saturating_add_assign},
+    solana_streamer::streamer::StakedNodes,
+    std::{
+        collections::HashMap,
+        net::{IpAddr, SocketAddr},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, RwLock,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+    tokio::{sync::broadcast, time::sleep},
+    tonic::{transport::Server, Request, Response, Status},
+};
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_millis(500);
+const BROADCAST_CHANNEL_CAPACITY: usize = 1_000;
+const METRICS_CADENCE: Duration = Duration::from_secs(1);
+const SENDER_STAKES_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmbeddedRelayerConfig {
+    /// Address the gRPC server listens on.
+    pub bind_address: SocketAddr,
+}
+
+impl EmbeddedRelayerConfig {
+    /// The url the relayer and block engine stages connect to.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.bind_address)
+    }
+}
+
+/// Stake of the nodes at each IP address advertised in gossip, which stands in for the stake of
+/// the connection a packet arrived on since packets don't carry it.
+#[derive(Default)]
+struct SenderStakes(HashMap<IpAddr, u64>);
+
+impl SenderStakes {
+    fn new(cluster_info: &ClusterInfo, staked_nodes: &RwLock<StakedNodes>) -> Self {
+        let staked_nodes = staked_nodes.read().unwrap();
+        let mut stakes = HashMap::new();
+        for (contact_info, _) in cluster_info.all_peers() {
+            let (Ok(gossip), Some(stake)) = (
+                contact_info.gossip(),
+                staked_nodes.get_node_stake(contact_info.pubkey()),
+            ) else {
+                continue;
+            };
+            let ip_stake: &mut u64 = stakes.entry(gossip.ip()).or_default();
+            *ip_stake = ip_stake.saturating_add(stake);
+        }
+        Self(stakes)
+    }
+
+    fn get(&self, ip: &IpAddr) -> u64 {
+        self.0.get(ip).copied().unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+struct EmbeddedRelayerStats {
+    num_packets: u64,
+    /// Packets passed on to banking because nothing was subscribed
+    num_packets_without_subscriber: u64,
+    num_bundles: u64,
+    num_bundles_without_subscriber: u64,
+}
+
+impl EmbeddedRelayerStats {
+    fn report(&self) {
+        datapoint_info!(
+            "embedded_relayer-stats",
+            ("num_packets", self.num_packets, i64),
+            (
+                "num_packets_without_subscriber",
+                self.num_packets_without_subscriber,
+                i64
+            ),
+            ("num_bundles", self.num_bundles, i64),
+            (
+                "num_bundles_without_subscriber",
+                self.num_bundles_without_subscriber,
+                i64
+            ),
+        );
+    }
+}
+
+pub struct EmbeddedRelayer {
+    t_hdls: Vec<JoinHandle<()>>,
+}
+
+impl EmbeddedRelayer {
+    pub fn new(
+        config: EmbeddedRelayerConfig,
+        // Used to advertise the validator's own TPU and to authenticate the validator.
+        cluster_info: Arc<ClusterInfo>,
+        // Used to tag packets with the stake of their sender.
+        staked_nodes: Arc<RwLock<StakedNodes>>,
+        // Packets verified by the TPU's sigverify stage.
+        verified_receiver: BankingPacketReceiver,
+        // Verified packets are passed on through here while nothing is subscribed.
+        banking_packet_sender: BankingPacketSender,
+        // Bundles to stream to the block engine subscriber.
+        bundle_receiver: Receiver<Vec<PacketBundle>>,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
+        let (packet_broadcast, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (bundle_broadcast, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+
+        let contact_info = cluster_info.my_contact_info();
+        let tpu_configs = GetTpuConfigsResponse {
+            tpu: contact_info.tpu(Protocol::QUIC).ok().map(to_socket),
+            tpu_forward: contact_info
+                .tpu_forwards(Protocol::QUIC)
+                .ok()
+                .map(to_socket),
+        };
+
+        let forward_hdl = {
+            let cluster_info = cluster_info.clone();
+            let packet_broadcast = packet_broadcast.clone();
+            let bundle_broadcast = bundle_broadcast.clone();
+            let exit = exit.clone();
+            Builder::new()
+                .name("embedded-relayer-forwarder".to_string())
+                .spawn(move || {
+                    Self::forward_packets_and_bundles(
+                        &cluster_info,
+                        &staked_nodes,
+                        verified_receiver,
+                        banking_packet_sender,
+                        bundle_receiver,
+                        packet_broadcast,
+                        bundle_broadcast,
+                        exit,
+                    )
+                })
+                .unwrap()
+        };
+
+        let server_hdl = Builder::new()
+            .name("embedded-relayer-server".to_string())
+            .spawn(move || {
+                let rt = tokio::runtime::Builder::new_multi_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap();
+                rt.block_on(Self::serve(
+                    config,
+                    cluster_info,
+                    tpu_configs,
+                    packet_broadcast,
+                    bundle_broadcast,
+                    exit,
+                ));
+            })
+            .unwrap();
+
+        Self {
+            t_hdls: vec![forward_hdl, server_hdl],
+        }
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        for t in self.t_hdls {
+            t.join()?;
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn forward_packets_and_bundles(
+        cluster_info: &ClusterInfo,
+        staked_nodes: &RwLock<StakedNodes>,
+        verified_receiver: BankingPacketReceiver,
+        banking_packet_sender: BankingPacketSender,
+        mut bundle_receiver: Receiver<Vec<PacketBundle>>,
+        packet_broadcast: broadcast::Sender<ProtoPacketBatch>,
+        bundle_broadcast: broadcast::Sender<Vec<BundleUuid>>,
+        exit: Arc<AtomicBool>,
+    ) {
+        let metrics_tick = tick(METRICS_CADENCE);
+        let sender_stakes_tick = tick(SENDER_STAKES_REFRESH_INTERVAL);
+        let mut sender_stakes = SenderStakes::new(cluster_info, staked_nodes);
+        let mut stats = EmbeddedRelayerStats::default();
+        while !exit.load(Ordering::Relaxed) {
+            select! {
+                recv(verified_receiver) -> maybe_batches => {
+                    let Ok(batches) = maybe_batches else {
+                        return;
+                    };
+                    let (num_packets, num_packets_without_subscriber) =
+                        Self::broadcast_packets(&batches, &sender_stakes, &packet_broadcast);
+                    saturating_add_assign!(stats.num_packets, num_packets);
+                    if num_packets_without_subscriber > 0 {
+                        saturating_add_assign!(
+                            stats.num_packets_without_subscriber,
+                            num_packets_without_subscriber
+                        );
+                        if banking_packet_sender.send(batches).is_err() {
+                            warn!("embedded relayer banking receiver disconnected, shutting down");
+                            return;
+                        }
+                    }
+                }
+                recv(bundle_receiver) -> maybe_bundles => {
+                    if let Ok(bundles) = maybe_bundles {
+                        let bundles: Vec<BundleUuid> = bundles.iter().map(to_bundle_uuid).collect();
+                        let num_bundles = bundles.len() as u64;
+                        saturating_add_assign!(stats.num_bundles, num_bundles);
+                        if bundle_broadcast.send(bundles).is_err() {
+                            saturating_add_assign!(stats.num_bundles_without_subscriber, num_bundles);
+                        }
+                    } else {
+                        // sendBundle is disabled, keep streaming packets
+                        bundle_receiver = never();
+                    }
+                }
+                recv(sender_stakes_tick) -> _ => {
+                    sender_stakes = SenderStakes::new(cluster_info, staked_nodes);
+                }
+                recv(metrics_tick) -> _ => {
+                    stats.report();
+                    stats = EmbeddedRelayerStats::default();
+                }
+            }
+        }
+    }
+
+    /// Returns the number of packets sent and the number of those without a subscriber.
+    fn broadcast_packets(
+        batches: &BankingPacketBatch,
+        sender_stakes: &SenderStakes,
+        packet_broadcast: &broadcast::Sender<ProtoPacketBatch>,
+    ) -> (u64, u64) {
+        let packets: Vec<_> = batches
+            .0
+            .iter()
+            .flat_map(|batch| batch.iter())
+            .filter(|packet| !packet.meta().discard())
+            .filter_map(|packet| {
+                packet_to_proto_packet(packet, sender_stakes.get(&packet.meta().addr))
+            })
+            .collect();
+        if packets.is_empty() {
+            return (0, 0);
+        }
+        let num_packets = packets.len() as u64;
+        match packet_broadcast.send(ProtoPacketBatch { packets }) {
+            Ok(_) => (num_packets, 0),
+            Err(_) => (num_packets, num_packets),
+        }
+    }
+
+    async fn serve(
+        config: EmbeddedRelayerConfig,
+        cluster_info: Arc<ClusterInfo>,
+        tpu_configs: GetTpuConfigsResponse,
+        packet_broadcast: broadcast::Sender<ProtoPacketBatch>,
+        bundle_broadcast: broadcast::Sender<Vec<BundleUuid>>,
+        exit: Arc<AtomicBool>,
+    ) {
+        let auth_service = new_auth_service(cluster_info.clone());
+        let relayer_service = EmbeddedRelayerService {
+            tpu_configs,
+            packet_broadcast,
+        };
+        let block_engine_service = EmbeddedBlockEngineService {
+            cluster_info,
+            bundle_broadcast,
+        };
+
+        let relayer_interceptor = auth_service.interceptor();
+        let block_engine_interceptor = auth_service.interceptor();
+        info!("embedded relayer listening on {}", config.bind_address);
+        if let Err(e) = Server::builder()
+            .add_service(AuthServiceServer::new(auth_service))
+            .add_service(RelayerServer::with_interceptor(
+                relayer_service,
+                relayer_interceptor,
+            ))
+            .add_service(BlockEngineValidatorServer::with_interceptor(
+                block_engine_service,
+                block_engine_interceptor,
+            ))
+            .serve_with_shutdown(config.bind_address, async {
+                while !exit.load(Ordering::Relaxed) {
+                    sleep(Duration::from_millis(100)).await;
+                }
+            })
+            .await
+        {
+            error!("embedded relayer server error: {e:?}");
+        }
+    }
+}
+
+fn to_bundle_uuid(bundle: &PacketBundle) -> BundleUuid {
+    BundleUuid {
+        bundle: Some(Bundle {
+            header: Some(header()),
+            // bundles aren't subject to the relayer stage's packet QoS
+            packets: bundle
+                .batch
+                .iter()
+                .filter_map(|packet| packet_to_proto_packet(packet, 0))
+                .collect(),
+        }),
+        uuid: bundle.bundle_id.clone(),
+    }
+}
+
+/// Only admits the validator's current identity.
+struct IdentityAuthPolicy(Arc<ClusterInfo>);
+
+impl AuthPolicy for IdentityAuthPolicy {
+    fn is_authorized(&self, pubkey: &Pubkey) -> bool {
+        *pubkey == self.0.id()
+    }
+}
+
+fn new_auth_service(cluster_info: Arc<ClusterInfo>) -> ProxyAuthService {
+    ProxyAuthService::new(
+        Arc::new(IdentityAuthPolicy(cluster_info)),
+        ProxyAuthConfig::default(),
+        Arc::default(),
+    )
+}
+
+struct EmbeddedRelayerService {
+    tpu_configs: GetTpuConfigsResponse,
+    packet_broadcast: broadcast::Sender<ProtoPacketBatch>,
+}
+
+#[tonic::async_trait]
+impl Relayer for EmbeddedRelayerService {
+    type SubscribePacketsStream = SubscribePacketsStream;
+
+    /// The validator keeps advertising its own TPU since the embedded relayer reads from it.
+    async fn get_tpu_configs(
+        &self,
+        _request: Request<GetTpuConfigsRequest>,
+    ) -> Result<Response<GetTpuConfigsResponse>, Status> {
+        Ok(Response::new(self.tpu_configs.clone()))
+    }
+
+    async fn subscribe_packets(
+        &self,
+        _request: Request<relayer::SubscribePacketsRequest>,
+    ) -> Result<Response<Self::SubscribePacketsStream>, Status> {
+        Ok(Response::new(subscribe_packets(
+            &self.packet_broadcast,
+            HEARTBEAT_INTERVAL,
+            || true,
+        )))
+    }
+}
+
+struct EmbeddedBlockEngineService {
+    cluster_info: Arc<ClusterInfo>,
+    bundle_broadcast: broadcast::Sender<Vec<BundleUuid>>,
+}
+
+#[tonic::async_trait]
+impl BlockEngineValidator for EmbeddedBlockEngineService {
+    type SubscribePacketsStream = SubscribeBlockEnginePacketsStream;
+    type SubscribeBundlesStream = SubscribeBundlesStream;
+
+    /// Packets are streamed by the relayer service, so this stream stays open without sending.
+    async fn subscribe_packets(
+        &self,
+        _request: Request<block_engine::SubscribePacketsRequest>,
+    ) -> Result<Response<Self::SubscribePacketsStream>, Status> {
+        Ok(Response::new(subscribe_block_engine_packets()))
+    }
+
+    async fn subscribe_bundles(
+        &self,
+        _request: Request<SubscribeBundlesRequest>,
+    ) -> Result<Response<Self::SubscribeBundlesStream>, Status> {
+        Ok(Response::new(subscribe_bundles(&self.bundle_broadcast)))
+    }
+
+    /// The validator builds its own blocks, so there is no block builder to pay.
+    async fn get_block_builder_fee_info(
+        &self,
+        _request: Request<BlockBuilderFeeInfoRequest>,
+    ) -> Result<Response<BlockBuilderFeeInfoResponse>, Status> {
+        Ok(Response::new(BlockBuilderFeeInfoResponse {
+            pubkey: self.cluster_info.id().to_string(),
+            commission: 0,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            banking_trace::BankingTracer,
+            proxy::auth::{generate_auth_tokens, AuthInterceptor},
+        },
+        jito_protos::proto::{
+            auth::{
+                auth_service_client::AuthServiceClient, auth_service_server::AuthService,
+                GenerateAuthChallengeRequest, GenerateAuthTokensRequest,
+                GenerateAuthTokensResponse, RefreshAccessTokenRequest,
+            },
+            relayer::{relayer_client::RelayerClient, subscribe_packets_response::Msg},
+        },
+        solana_gossip::contact_info::ContactInfo,
+        solana_perf::packet::PacketBatch,
+        solana_sdk::{
+            packet::Packet,
+            signature::{Keypair, Signer},
+            timing::timestamp,
+        },
+        solana_streamer::socket::SocketAddrSpace,
+        std::{net::TcpListener, sync::Mutex},
+        tonic::transport::Endpoint,
+    };
+
+    fn new_cluster_info(keypair: Keypair) -> ClusterInfo {
+        let contact_info = ContactInfo::new_localhost(&keypair.pubkey(), timestamp());
+        ClusterInfo::new(
+            contact_info,
+            Arc::new(keypair),
+            SocketAddrSpace::Unspecified,
+        )
+    }
+
+    fn auth_service(keypair: Keypair) -> ProxyAuthService {
+        new_auth_service(Arc::new(new_cluster_info(keypair)))
+    }
+
+    async fn authenticate(
+        auth_service: &ProxyAuthService,
+        keypair: &Keypair,
+    ) -> Result<GenerateAuthTokensResponse, Status> {
+        let challenge = auth_service
+            .generate_auth_challenge(Request::new(GenerateAuthChallengeRequest {
+                role: 0,
+                pubkey: keypair.pubkey().to_bytes().to_vec(),
+            }))
+            .await?
+            .into_inner()
+            .challenge;
+        let challenge = format!("{}-{}", keypair.pubkey(), challenge);
+        auth_service
+            .generate_auth_tokens(Request::new(GenerateAuthTokensRequest {
+                signed_challenge: keypair.sign_message(challenge.as_bytes()).as_ref().to_vec(),
+                challenge,
+                client_pubkey: keypair.pubkey().to_bytes().to_vec(),
+            }))
+            .await
+            .map(Response::into_inner)
+    }
+
+    fn authorized_request(access_token: &str) -> Request<()> {
+        let mut request = Request::new(());
+        request.metadata_mut().insert(
+            "authorization",
+            format!("Bearer {access_token}").parse().unwrap(),
+        );
+        request
+    }
+
+    #[tokio::test]
+    async fn test_auth_only_admits_identity() {
+        let keypair = Keypair::new();
+        let identity = keypair.insecure_clone();
+        let auth_service = auth_service(keypair);
+        let mut interceptor = auth_service.interceptor();
+
+        let status = authenticate(&auth_service, &Keypair::new())
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+        assert_eq!(
+            interceptor(Request::new(())).unwrap_err().code(),
+            tonic::Code::Unauthenticated
+        );
+
+        let tokens = authenticate(&auth_service, &identity).await.unwrap();
+        let access_token = tokens.access_token.unwrap().value;
+        assert!(interceptor(authorized_request(&access_token)).is_ok());
+        assert!(interceptor(authorized_request("bogus")).is_err());
+
+        let refreshed = auth_service
+            .refresh_access_token(Request::new(RefreshAccessTokenRequest {
+                refresh_token: tokens.refresh_token.unwrap().value,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .access_token
+            .unwrap();
+        assert!(interceptor(authorized_request(&refreshed.value)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_challenge_must_be_signed() {
+        let keypair = Keypair::new();
+        let identity = keypair.insecure_clone();
+        let auth_service = auth_service(keypair);
+
+        let challenge = auth_service
+            .generate_auth_challenge(Request::new(GenerateAuthChallengeRequest {
+                role: 0,
+                pubkey: identity.pubkey().to_bytes().to_vec(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .challenge;
+        let challenge = format!("{}-{}", identity.pubkey(), challenge);
+        let status = auth_service
+            .generate_auth_tokens(Request::new(GenerateAuthTokensRequest {
+                signed_challenge: Keypair::new()
+                    .sign_message(challenge.as_bytes())
+                    .as_ref()
+                    .to_vec(),
+                challenge,
+                client_pubkey: identity.pubkey().to_bytes().to_vec(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_verified_packets_streamed_to_subscriber() {
+        let keypair = Keypair::new();
+        let identity = keypair.insecure_clone();
+        let cluster_info = Arc::new(new_cluster_info(keypair));
+        // a staked node at the address the packets come from
+        let staked_node = Pubkey::new_unique();
+        cluster_info.insert_info(ContactInfo::new_localhost(&staked_node, timestamp()));
+        let staked_nodes = Arc::new(RwLock::new(StakedNodes::new(
+            Arc::new(HashMap::from([(staked_node, 1_000)])),
+            HashMap::default(),
+        )));
+
+        let bind_address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let config = EmbeddedRelayerConfig { bind_address };
+        let (verified_sender, verified_receiver) =
+            BankingTracer::new_disabled().create_channel_non_vote();
+        let (banking_packet_sender, banking_packet_receiver) =
+            BankingTracer::new_disabled().create_channel_non_vote();
+        let (_bundle_sender, bundle_receiver) = crossbeam_channel::unbounded();
+        let exit = Arc::new(AtomicBool::default());
+        let embedded_relayer = EmbeddedRelayer::new(
+            config.clone(),
+            cluster_info,
+            staked_nodes,
+            verified_receiver,
+            banking_packet_sender,
+            bundle_receiver,
+            exit.clone(),
+        );
+
+        let mut packet = Packet::from_data(None, [7u8; 32]).unwrap();
+        packet
+            .meta_mut()
+            .set_socket_addr(&"127.0.0.1:8000".parse().unwrap());
+        let packet_batch = || Arc::new((vec![PacketBatch::new(vec![packet.clone()])], None));
+
+        // nothing is subscribed, so verified packets go straight to banking
+        verified_sender.send(packet_batch()).unwrap();
+        let passed_on = banking_packet_receiver
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(passed_on.0[0][0], packet);
+
+        let endpoint = Endpoint::from_shared(config.url()).unwrap();
+        let mut channel = None;
+        for _ in 0..100 {
+            if let Ok(connected) = endpoint.connect().await {
+                channel = Some(connected);
+                break;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        let channel = channel.expect("embedded relayer server didn't start");
+        let (access_token, _refresh_token) =
+            generate_auth_tokens(&mut AuthServiceClient::new(channel.clone()), &identity)
+                .await
+                .unwrap();
+        let mut relayer_client = RelayerClient::with_interceptor(
+            channel,
+            AuthInterceptor::new(Arc::new(Mutex::new(access_token))),
+        );
+        let mut stream = relayer_client
+            .subscribe_packets(relayer::SubscribePacketsRequest {})
+            .await
+            .unwrap()
+            .into_inner();
+
+        verified_sender.send(packet_batch()).unwrap();
+        let proto_batch = loop {
+            match stream.message().await.unwrap().unwrap().msg {
+                Some(Msg::Batch(proto_batch)) => break proto_batch,
+                Some(Msg::Heartbeat(_)) | None => continue,
+            }
+        };
+        assert_eq!(proto_batch.packets.len(), 1);
+        let proto_packet = &proto_batch.packets[0];
+        assert_eq!(proto_packet.data, packet.data(..).unwrap());
+        let meta = proto_packet.meta.as_ref().unwrap();
+        assert_eq!(meta.addr, "127.0.0.1");
+        assert_eq!(meta.port, 8000);
+        assert_eq!(meta.sender_stake, 1_000);
+        // streamed packets reach banking through the relayer stage
+        assert!(banking_packet_receiver.is_empty());
+
+        drop(stream);
+        drop(relayer_client);
+        drop(verified_sender);
+        exit.store(true, Ordering::Relaxed);
+        tokio::task::spawn_blocking(move || embedded_relayer.join())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -11,8 +11,13 @@
 //!           to accept bundles while maintaining some level of privacy for in-flight transactions.
 
 mod auth;
+mod block_engine_endpoints;
 pub mod block_engine_stage;
+pub mod bundle_result_reporter;
+pub mod embedded_relayer;
 pub mod fetch_stage_manager;
+pub mod proxy_server;
+pub mod relayer_packet_qos;
 pub mod relayer_stage;
 
 use {
@@ -0,0 +1,337 @@
+//! Server side of the auth, relayer and block engine gRPC services the proxy stages connect to.
+//!
+//! Shared by the [EmbeddedRelayer](super::embedded_relayer::EmbeddedRelayer) and the mock proxy
+//! used in tests, which differ only in which clients may authenticate and what they stream.
+
+use {
+    chrono::Utc,
+    jito_protos::proto::{
+        auth::{
+            auth_service_server::AuthService, GenerateAuthChallengeRequest,
+            GenerateAuthChallengeResponse, GenerateAuthTokensRequest, GenerateAuthTokensResponse,
+            RefreshAccessTokenRequest, RefreshAccessTokenResponse, Token,
+        },
+        block_engine::{self, SubscribeBundlesResponse},
+        bundle::BundleUuid,
+        packet::PacketBatch as ProtoPacketBatch,
+        relayer::{self, subscribe_packets_response::Msg},
+        shared::{Header, Heartbeat, Socket},
+    },
+    rand::{distributions::Alphanumeric, thread_rng, Rng},
+    solana_sdk::{pubkey::Pubkey, signature::Signature},
+    std::{
+        collections::HashMap,
+        net::SocketAddr,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        time::{Duration, SystemTime},
+    },
+    tokio::{
+        sync::{broadcast, mpsc},
+        time::interval,
+    },
+    tokio_stream::wrappers::ReceiverStream,
+    tonic::{Request, Response, Status},
+};
+
+const SUBSCRIBER_CHANNEL_CAPACITY: usize = 1_000;
+
+pub type SubscribePacketsStream = ReceiverStream<Result<relayer::SubscribePacketsResponse, Status>>;
+pub type SubscribeBlockEnginePacketsStream =
+    ReceiverStream<Result<block_engine::SubscribePacketsResponse, Status>>;
+pub type SubscribeBundlesStream = ReceiverStream<Result<SubscribeBundlesResponse, Status>>;
+
+/// Decides which clients may authenticate.
+pub trait AuthPolicy: Send + Sync {
+    fn is_authorized(&self, pubkey: &Pubkey) -> bool;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProxyAuthConfig {
+    /// Lifetime of issued access tokens.
+    pub access_token_ttl: Duration,
+
+    /// Lifetime of issued refresh tokens.
+    pub refresh_token_ttl: Duration,
+}
+
+impl Default for ProxyAuthConfig {
+    fn default() -> Self {
+        Self {
+            access_token_ttl: Duration::from_secs(30 * 60),
+            refresh_token_ttl: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+/// Counters for the auth requests served since the service started.
+#[derive(Default, Debug)]
+pub struct ProxyAuthStats {
+    pub auth_challenges: AtomicU64,
+    pub tokens_generated: AtomicU64,
+    pub access_tokens_refreshed: AtomicU64,
+    /// Requests rejected for a missing or expired access token.
+    pub rejected_requests: AtomicU64,
+}
+
+#[derive(Default)]
+struct AuthState {
+    // outstanding challenge per client
+    challenges: HashMap<Pubkey, String>,
+    // token value to expiry in seconds since the epoch
+    access_tokens: HashMap<String, i64>,
+    refresh_tokens: HashMap<String, i64>,
+}
+
+impl AuthState {
+    fn issue(tokens: &mut HashMap<String, i64>, ttl: Duration) -> Token {
+        let now = Utc::now().timestamp();
+        tokens.retain(|_, expires_at| *expires_at > now);
+        let value = random_string(32);
+        let expires_at = now.saturating_add(ttl.as_secs() as i64);
+        tokens.insert(value.clone(), expires_at);
+        Token {
+            value,
+            expires_at_utc: Some(prost_types::Timestamp {
+                seconds: expires_at,
+                nanos: 0,
+            }),
+        }
+    }
+
+    fn is_valid(tokens: &HashMap<String, i64>, value: &str) -> bool {
+        tokens
+            .get(value)
+            .map(|expires_at| *expires_at > Utc::now().timestamp())
+            .unwrap_or_default()
+    }
+}
+
+/// Implements the challenge-response auth flow the proxy stages use.
+#[derive(Clone)]
+pub struct ProxyAuthService {
+    policy: Arc<dyn AuthPolicy>,
+    config: ProxyAuthConfig,
+    state: Arc<Mutex<AuthState>>,
+    stats: Arc<ProxyAuthStats>,
+}
+
+impl ProxyAuthService {
+    pub fn new(
+        policy: Arc<dyn AuthPolicy>,
+        config: ProxyAuthConfig,
+        stats: Arc<ProxyAuthStats>,
+    ) -> Self {
+        Self {
+            policy,
+            config,
+            state: Arc::default(),
+            stats,
+        }
+    }
+
+    /// Rejects requests without a valid access token.
+    pub fn interceptor(
+        &self,
+    ) -> impl FnMut(Request<()>) -> Result<Request<()>, Status> + Clone + Send + Sync + 'static
+    {
+        let state = self.state.clone();
+        let stats = self.stats.clone();
+        move |request: Request<()>| {
+            let is_authorized = request
+                .metadata()
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(|access_token| {
+                    AuthState::is_valid(&state.lock().unwrap().access_tokens, access_token)
+                })
+                .unwrap_or_default();
+            if is_authorized {
+                Ok(request)
+            } else {
+                stats.rejected_requests.fetch_add(1, Ordering::Relaxed);
+                Err(Status::unauthenticated("missing or invalid access token"))
+            }
+        }
+    }
+
+    fn authorized_pubkey(&self, pubkey: &[u8]) -> Result<Pubkey, Status> {
+        let pubkey =
+            Pubkey::try_from(pubkey).map_err(|_| Status::invalid_argument("invalid pubkey"))?;
+        if !self.policy.is_authorized(&pubkey) {
+            return Err(Status::permission_denied("pubkey is not authorized"));
+        }
+        Ok(pubkey)
+    }
+}
+
+#[tonic::async_trait]
+impl AuthService for ProxyAuthService {
+    async fn generate_auth_challenge(
+        &self,
+        request: Request<GenerateAuthChallengeRequest>,
+    ) -> Result<Response<GenerateAuthChallengeResponse>, Status> {
+        let pubkey = self.authorized_pubkey(&request.into_inner().pubkey)?;
+        let challenge = random_string(9);
+        self.state
+            .lock()
+            .unwrap()
+            .challenges
+            .insert(pubkey, challenge.clone());
+        self.stats.auth_challenges.fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(GenerateAuthChallengeResponse { challenge }))
+    }
+
+    async fn generate_auth_tokens(
+        &self,
+        request: Request<GenerateAuthTokensRequest>,
+    ) -> Result<Response<GenerateAuthTokensResponse>, Status> {
+        let request = request.into_inner();
+        let pubkey = self.authorized_pubkey(&request.client_pubkey)?;
+        let signature = Signature::try_from(request.signed_challenge.as_slice())
+            .map_err(|_| Status::invalid_argument("invalid signature"))?;
+
+        let mut state = self.state.lock().unwrap();
+        let challenge = state
+            .challenges
+            .remove(&pubkey)
+            .ok_or_else(|| Status::failed_precondition("no outstanding challenge"))?;
+        if request.challenge != format!("{pubkey}-{challenge}")
+            || !signature.verify(pubkey.as_ref(), request.challenge.as_bytes())
+        {
+            return Err(Status::permission_denied("challenge verification failed"));
+        }
+
+        let response = GenerateAuthTokensResponse {
+            access_token: Some(AuthState::issue(
+                &mut state.access_tokens,
+                self.config.access_token_ttl,
+            )),
+            refresh_token: Some(AuthState::issue(
+                &mut state.refresh_tokens,
+                self.config.refresh_token_ttl,
+            )),
+        };
+        self.stats.tokens_generated.fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(response))
+    }
+
+    async fn refresh_access_token(
+        &self,
+        request: Request<RefreshAccessTokenRequest>,
+    ) -> Result<Response<RefreshAccessTokenResponse>, Status> {
+        let mut state = self.state.lock().unwrap();
+        if !AuthState::is_valid(&state.refresh_tokens, &request.into_inner().refresh_token) {
+            return Err(Status::unauthenticated("invalid refresh token"));
+        }
+        let access_token = AuthState::issue(&mut state.access_tokens, self.config.access_token_ttl);
+        self.stats
+            .access_tokens_refreshed
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(RefreshAccessTokenResponse {
+            access_token: Some(access_token),
+        }))
+    }
+}
+
+/// Streams the packet batches sent on `packet_broadcast` to a relayer subscriber, with a
+/// heartbeat every `heartbeat_interval` for which `should_send_heartbeat` returns true.
+pub fn subscribe_packets(
+    packet_broadcast: &broadcast::Sender<ProtoPacketBatch>,
+    heartbeat_interval: Duration,
+    should_send_heartbeat: impl Fn() -> bool + Send + 'static,
+) -> SubscribePacketsStream {
+    let (sender, receiver) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    let mut packet_receiver = packet_broadcast.subscribe();
+    tokio::spawn(async move {
+        let mut heartbeat_interval = interval(heartbeat_interval);
+        let mut heartbeat_count: u64 = 0;
+        loop {
+            let msg = tokio::select! {
+                _ = heartbeat_interval.tick() => {
+                    if !should_send_heartbeat() {
+                        continue;
+                    }
+                    heartbeat_count = heartbeat_count.saturating_add(1);
+                    Msg::Heartbeat(Heartbeat { count: heartbeat_count })
+                }
+                maybe_batch = packet_receiver.recv() => match maybe_batch {
+                    Ok(batch) => Msg::Batch(batch),
+                    Err(broadcast::error::RecvError::Lagged(num_skipped)) => {
+                        warn!("packet subscriber lagged, skipped {num_skipped} batches");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+            let response = relayer::SubscribePacketsResponse {
+                header: Some(header()),
+                msg: Some(msg),
+            };
+            if sender.send(Ok(response)).await.is_err() {
+                break;
+            }
+        }
+    });
+    ReceiverStream::new(receiver)
+}
+
+/// Streams the bundles sent on `bundle_broadcast` to a block engine subscriber.
+pub fn subscribe_bundles(
+    bundle_broadcast: &broadcast::Sender<Vec<BundleUuid>>,
+) -> SubscribeBundlesStream {
+    let (sender, receiver) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    let mut bundle_receiver = bundle_broadcast.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let bundles = match bundle_receiver.recv().await {
+                Ok(bundles) => bundles,
+                Err(broadcast::error::RecvError::Lagged(num_skipped)) => {
+                    warn!("bundle subscriber lagged, skipped {num_skipped} bundle batches");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            if sender
+                .send(Ok(SubscribeBundlesResponse { bundles }))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+    ReceiverStream::new(receiver)
+}
+
+/// A block engine packet stream that stays open without sending, for servers that stream
+/// packets over the relayer service instead.
+pub fn subscribe_block_engine_packets() -> SubscribeBlockEnginePacketsStream {
+    let (sender, receiver) = mpsc::channel(1);
+    tokio::spawn(async move { sender.closed().await });
+    ReceiverStream::new(receiver)
+}
+
+pub fn header() -> Header {
+    Header {
+        ts: Some(prost_types::Timestamp::from(SystemTime::now())),
+    }
+}
+
+pub fn to_socket(addr: SocketAddr) -> Socket {
+    Socket {
+        ip: addr.ip().to_string(),
+        port: addr.port() as i64,
+    }
+}
+
+fn random_string(len: usize) -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,380 @@
+//! Admission control for packets streamed from the relayer.
+//!
+//! The relayer multiplexes packets from every sender onto a single stream, so without per-sender
+//! accounting one spammer behind the relayer can crowd everyone else out of banking. Packets are
+//! attributed to the source IP carried in their metadata and admitted per second as follows:
+//! - Staked senders may send a share of `staked_packets_per_second` proportional to the stake the
+//!   relayer attached to their packets, but never less than an unstaked sender.
+//! - Unstaked senders may each send `unstaked_packets_per_second_per_ip`, and all unstaked senders
+//!   together may send at most `unstaked_packets_per_second`.
+//!
+//! A limit of 0 means unlimited, and every limit is 0 by default, so admission control is opt-in.
+//! Packets whose source address is missing or can't be parsed can't be attributed to a sender, so
+//! they're only subject to the combined unstaked limit.
+
+use {
+    jito_protos::proto::packet::Packet as ProtoPacket,
+    solana_sdk::saturating_add_assign,
+    solana_streamer::streamer::StakedNodes,
+    std::{
+        collections::HashMap,
+        net::{IpAddr, SocketAddr},
+        sync::{Arc, RwLock},
+    },
+};
+
+/// Limits of 0 are unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RelayerPacketQosConfig {
+    /// Packets admitted per second from all staked senders combined, split by stake.
+    pub staked_packets_per_second: u64,
+
+    /// Packets admitted per second from a single unstaked source IP.
+    pub unstaked_packets_per_second_per_ip: u64,
+
+    /// Packets admitted per second from all unstaked senders combined.
+    pub unstaked_packets_per_second: u64,
+}
+
+impl RelayerPacketQosConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.staked_packets_per_second > 0
+            || self.unstaked_packets_per_second_per_ip > 0
+            || self.unstaked_packets_per_second > 0
+    }
+}
+
+/// Whether `count` packets already reached `limit`, where a limit of 0 is unlimited
+fn exceeds_limit(count: u64, limit: u64) -> bool {
+    limit > 0 && count >= limit
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PacketDropReason {
+    /// A staked sender exceeded its stake-weighted share.
+    StakedSenderLimit,
+    /// An unstaked sender exceeded the per-IP limit.
+    UnstakedSenderLimit,
+    /// Unstaked senders combined exceeded their limit.
+    UnstakedLimit,
+}
+
+#[derive(Default)]
+struct SenderState {
+    stake: u64,
+    num_admitted: u64,
+    num_dropped: u64,
+}
+
+#[derive(Default)]
+struct RelayerPacketQosStats {
+    num_admitted_staked: u64,
+    num_admitted_unstaked: u64,
+    /// Packets admitted without a source address to attribute them to
+    num_admitted_unattributed: u64,
+    num_dropped_staked_sender_limit: u64,
+    num_dropped_unstaked_sender_limit: u64,
+    num_dropped_unstaked_limit: u64,
+}
+
+pub struct RelayerPacketQos {
+    config: RelayerPacketQosConfig,
+    staked_nodes: Arc<RwLock<StakedNodes>>,
+    total_stake: u64,
+    senders: HashMap<IpAddr, SenderState>,
+    num_unstaked_admitted: u64,
+    stats: RelayerPacketQosStats,
+}
+
+impl RelayerPacketQos {
+    pub fn new(config: RelayerPacketQosConfig, staked_nodes: Arc<RwLock<StakedNodes>>) -> Self {
+        let total_stake = staked_nodes.read().unwrap().total_stake();
+        Self {
+            config,
+            staked_nodes,
+            total_stake,
+            senders: HashMap::default(),
+            num_unstaked_admitted: 0,
+            stats: RelayerPacketQosStats::default(),
+        }
+    }
+
+    /// Drops the packets that exceed their sender's allowance for the current second.
+    pub fn filter_packets(&mut self, packets: Vec<ProtoPacket>) -> Vec<ProtoPacket> {
+        if !self.config.is_enabled() {
+            return packets;
+        }
+        packets
+            .into_iter()
+            .filter(|packet| self.admit(packet).is_ok())
+            .collect()
+    }
+
+    pub fn admit(&mut self, packet: &ProtoPacket) -> Result<(), PacketDropReason> {
+        let Some((ip, stake)) = packet
+            .meta
+            .as_ref()
+            .and_then(|meta| Some((parse_ip(&meta.addr)?, meta.sender_stake)))
+        else {
+            return self.admit_unattributed();
+        };
+        let sender = self.senders.entry(ip).or_default();
+        sender.stake = sender.stake.max(stake);
+
+        let result = if sender.stake == 0 || self.total_stake == 0 {
+            if exceeds_limit(
+                sender.num_admitted,
+                self.config.unstaked_packets_per_second_per_ip,
+            ) {
+                saturating_add_assign!(self.stats.num_dropped_unstaked_sender_limit, 1);
+                Err(PacketDropReason::UnstakedSenderLimit)
+            } else if exceeds_limit(
+                self.num_unstaked_admitted,
+                self.config.unstaked_packets_per_second,
+            ) {
+                saturating_add_assign!(self.stats.num_dropped_unstaked_limit, 1);
+                Err(PacketDropReason::UnstakedLimit)
+            } else {
+                saturating_add_assign!(self.num_unstaked_admitted, 1);
+                saturating_add_assign!(self.stats.num_admitted_unstaked, 1);
+                Ok(())
+            }
+        } else {
+            let stake_weighted_limit = (self.config.staked_packets_per_second as u128)
+                .saturating_mul(sender.stake.min(self.total_stake) as u128)
+                .checked_div(self.total_stake as u128)
+                .unwrap_or_default() as u64;
+            // a staked sender is never held to less than an unstaked one, so it's unlimited when
+            // unstaked senders are
+            let limit = match (
+                self.config.staked_packets_per_second,
+                self.config.unstaked_packets_per_second_per_ip,
+            ) {
+                (0, _) | (_, 0) => 0,
+                (_, unstaked_limit) => stake_weighted_limit.max(unstaked_limit),
+            };
+            if exceeds_limit(sender.num_admitted, limit) {
+                saturating_add_assign!(self.stats.num_dropped_staked_sender_limit, 1);
+                Err(PacketDropReason::StakedSenderLimit)
+            } else {
+                saturating_add_assign!(self.stats.num_admitted_staked, 1);
+                Ok(())
+            }
+        };
+
+        match result {
+            Ok(()) => saturating_add_assign!(sender.num_admitted, 1),
+            Err(_) => saturating_add_assign!(sender.num_dropped, 1),
+        }
+        result
+    }
+
+    /// Admits a packet that can't be attributed to a sender, which only counts towards the
+    /// combined unstaked limit.
+    fn admit_unattributed(&mut self) -> Result<(), PacketDropReason> {
+        if exceeds_limit(
+            self.num_unstaked_admitted,
+            self.config.unstaked_packets_per_second,
+        ) {
+            saturating_add_assign!(self.stats.num_dropped_unstaked_limit, 1);
+            return Err(PacketDropReason::UnstakedLimit);
+        }
+        saturating_add_assign!(self.num_unstaked_admitted, 1);
+        saturating_add_assign!(self.stats.num_admitted_unstaked, 1);
+        saturating_add_assign!(self.stats.num_admitted_unattributed, 1);
+        Ok(())
+    }
+
+    /// Reports the metrics for the last second and resets the allowances. Called once a second.
+    pub fn report_and_reset(&mut self) {
+        let num_staked_senders = self.senders.values().filter(|s| s.stake > 0).count();
+        let (max_dropped_sender, max_dropped) = self
+            .senders
+            .iter()
+            .map(|(ip, sender)| (ip.to_string(), sender.num_dropped))
+            .max_by_key(|(_, num_dropped)| *num_dropped)
+            .unwrap_or_default();
+        datapoint_info!(
+            "relayer_stage-packet_qos",
+            ("num_senders", self.senders.len(), i64),
+            ("num_staked_senders", num_staked_senders, i64),
+            ("num_admitted_staked", self.stats.num_admitted_staked, i64),
+            (
+                "num_admitted_unstaked",
+                self.stats.num_admitted_unstaked,
+                i64
+            ),
+            (
+                "num_admitted_unattributed",
+                self.stats.num_admitted_unattributed,
+                i64
+            ),
+            (
+                "num_dropped_staked_sender_limit",
+                self.stats.num_dropped_staked_sender_limit,
+                i64
+            ),
+            (
+                "num_dropped_unstaked_sender_limit",
+                self.stats.num_dropped_unstaked_sender_limit,
+                i64
+            ),
+            (
+                "num_dropped_unstaked_limit",
+                self.stats.num_dropped_unstaked_limit,
+                i64
+            ),
+            ("max_dropped_sender", max_dropped_sender, String),
+            ("max_dropped_sender_count", max_dropped, i64),
+        );
+
+        self.senders.clear();
+        self.num_unstaked_admitted = 0;
+        self.stats = RelayerPacketQosStats::default();
+        self.total_stake = self.staked_nodes.read().unwrap().total_stake();
+    }
+}
+
+/// The relayer sends the source address either as a bare IP or as a socket address.
+fn parse_ip(addr: &str) -> Option<IpAddr> {
+    addr.parse::<IpAddr>()
+        .or_else(|_| addr.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .ok()
+        .filter(|ip| !ip.is_unspecified())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, jito_protos::proto::packet::Meta as ProtoMeta, solana_sdk::pubkey::Pubkey};
+
+    fn packet(addr: &str, sender_stake: u64) -> ProtoPacket {
+        ProtoPacket {
+            data: vec![],
+            meta: Some(ProtoMeta {
+                addr: addr.to_string(),
+                sender_stake,
+                ..ProtoMeta::default()
+            }),
+        }
+    }
+
+    fn packet_qos(config: RelayerPacketQosConfig, total_stake: u64) -> RelayerPacketQos {
+        let stakes = HashMap::from([(Pubkey::new_unique(), total_stake)]);
+        let staked_nodes = StakedNodes::new(Arc::new(stakes), HashMap::default());
+        RelayerPacketQos::new(config, Arc::new(RwLock::new(staked_nodes)))
+    }
+
+    #[test]
+    fn test_unstaked_limits() {
+        let mut qos = packet_qos(
+            RelayerPacketQosConfig {
+                staked_packets_per_second: 100,
+                unstaked_packets_per_second_per_ip: 2,
+                unstaked_packets_per_second: 3,
+            },
+            1_000,
+        );
+
+        assert_eq!(qos.admit(&packet("1.1.1.1", 0)), Ok(()));
+        assert_eq!(qos.admit(&packet("1.1.1.1", 0)), Ok(()));
+        assert_eq!(
+            qos.admit(&packet("1.1.1.1", 0)),
+            Err(PacketDropReason::UnstakedSenderLimit)
+        );
+        assert_eq!(qos.admit(&packet("2.2.2.2:8001", 0)), Ok(()));
+        assert_eq!(
+            qos.admit(&packet("3.3.3.3", 0)),
+            Err(PacketDropReason::UnstakedLimit)
+        );
+
+        // a spammer doesn't affect staked senders
+        assert_eq!(qos.admit(&packet("4.4.4.4", 100)), Ok(()));
+
+        qos.report_and_reset();
+        assert_eq!(qos.admit(&packet("1.1.1.1", 0)), Ok(()));
+    }
+
+    #[test]
+    fn test_staked_limit_is_stake_weighted() {
+        let mut qos = packet_qos(
+            RelayerPacketQosConfig {
+                staked_packets_per_second: 100,
+                unstaked_packets_per_second_per_ip: 2,
+                unstaked_packets_per_second: 3,
+            },
+            1_000,
+        );
+
+        // 10% of stake gets 10 packets
+        let admitted = (0..20)
+            .filter(|_| qos.admit(&packet("1.1.1.1", 100)).is_ok())
+            .count();
+        assert_eq!(admitted, 10);
+        assert_eq!(
+            qos.admit(&packet("1.1.1.1", 100)),
+            Err(PacketDropReason::StakedSenderLimit)
+        );
+
+        // tiny stake still gets the unstaked allowance
+        let admitted = (0..20)
+            .filter(|_| qos.admit(&packet("2.2.2.2", 1)).is_ok())
+            .count();
+        assert_eq!(admitted, 2);
+    }
+
+    #[test]
+    fn test_unknown_addr_skips_per_ip_limit() {
+        let mut qos = packet_qos(
+            RelayerPacketQosConfig {
+                staked_packets_per_second: 100,
+                unstaked_packets_per_second_per_ip: 2,
+                unstaked_packets_per_second: 5,
+            },
+            1_000,
+        );
+
+        // packets without a usable source address don't share a per-IP allowance, but still
+        // count towards the combined unstaked limit
+        let mut packets = vec![ProtoPacket::default(); 2];
+        packets.extend(["", "not an address", "0.0.0.0"].map(|addr| packet(addr, 0)));
+        assert_eq!(qos.filter_packets(packets).len(), 5);
+        assert!(qos.senders.is_empty());
+        assert_eq!(
+            qos.admit(&ProtoPacket::default()),
+            Err(PacketDropReason::UnstakedLimit)
+        );
+        assert_eq!(
+            qos.admit(&packet("1.1.1.1", 0)),
+            Err(PacketDropReason::UnstakedLimit)
+        );
+    }
+
+    #[test]
+    fn test_zero_limits_are_unlimited() {
+        // disabled by default
+        let mut qos = packet_qos(RelayerPacketQosConfig::default(), 1_000);
+        let packets = vec![packet("1.1.1.1", 0); 1_000];
+        assert_eq!(qos.filter_packets(packets).len(), 1_000);
+
+        // only the combined unstaked limit is set
+        let mut qos = packet_qos(
+            RelayerPacketQosConfig {
+                unstaked_packets_per_second: 3,
+                ..RelayerPacketQosConfig::default()
+            },
+            1_000,
+        );
+        assert_eq!(
+            (0..10)
+                .filter(|_| qos.admit(&packet("1.1.1.1", 0)).is_ok())
+                .count(),
+            3
+        );
+        assert_eq!(
+            (0..10)
+                .filter(|_| qos.admit(&packet("2.2.2.2", 100)).is_ok())
+                .count(),
+            10
+        );
+    }
+}
@@ -14,6 +14,7 @@ use {
         proto_packet_to_packet,
         proxy::{
             auth::{generate_auth_tokens, maybe_refresh_auth_tokens, AuthInterceptor},
+            relayer_packet_qos::{RelayerPacketQos, RelayerPacketQosConfig},
             HeartbeatEvent, ProxyError,
         },
     },
@@ -28,12 +29,13 @@ use {
         saturating_add_assign,
         signature::{Keypair, Signer},
     },
+    solana_streamer::streamer::StakedNodes,
     std::{
         net::{IpAddr, Ipv4Addr, SocketAddr},
         str::FromStr,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc, Mutex,
+            Arc, Mutex, RwLock,
         },
         thread::{self, Builder, JoinHandle},
         time::{Duration, Instant},
@@ -56,6 +58,7 @@ const CONNECTION_BACKOFF_S: u64 = 5;
 struct RelayerStageStats {
     num_empty_messages: u64,
     num_packets: u64,
+    num_packets_dropped: u64,
     num_heartbeats: u64,
 }
 
@@ -65,6 +68,7 @@ impl RelayerStageStats {
             "relayer_stage-stats",
             ("num_empty_messages", self.num_empty_messages, i64),
             ("num_packets", self.num_packets, i64),
+            ("num_packets_dropped", self.num_packets_dropped, i64),
             ("num_heartbeats", self.num_heartbeats, i64),
         );
     }
@@ -83,6 +87,9 @@ pub struct RelayerConfig {
 
     /// If set then it will be assumed the backend verified packets so signature verification will be bypassed in the validator.
     pub trust_packets: bool,
+
+    /// Per-sender admission limits for streamed packets.
+    pub packet_qos: RelayerPacketQosConfig,
 }
 
 pub struct RelayerStage {
@@ -100,6 +107,8 @@ impl RelayerStage {
         packet_tx: Sender<PacketBatch>,
         // Channel that trusted streamed packets are piped through.
         banking_packet_sender: BankingPacketSender,
+        // Used to weigh the stake the relayer attaches to packets against the cluster's.
+        staked_nodes: Arc<RwLock<StakedNodes>>,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let thread = Builder::new()
@@ -116,6 +125,7 @@ impl RelayerStage {
                     heartbeat_tx,
                     packet_tx,
                     banking_packet_sender,
+                    staked_nodes,
                     exit,
                 ));
             })
@@ -140,6 +150,7 @@ impl RelayerStage {
         heartbeat_tx: Sender<HeartbeatEvent>,
         packet_tx: Sender<PacketBatch>,
         banking_packet_sender: BankingPacketSender,
+        staked_nodes: Arc<RwLock<StakedNodes>>,
         exit: Arc<AtomicBool>,
     ) {
         const CONNECTION_TIMEOUT: Duration = Duration::from_secs(CONNECTION_TIMEOUT_S);
@@ -165,6 +176,7 @@ impl RelayerStage {
                 &heartbeat_tx,
                 &packet_tx,
                 &banking_packet_sender,
+                &staked_nodes,
                 &exit,
                 &CONNECTION_TIMEOUT,
             )
@@ -190,6 +202,7 @@ impl RelayerStage {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn connect_auth_and_stream(
         local_relayer_config: &RelayerConfig,
         global_relayer_config: &Arc<Mutex<RelayerConfig>>,
@@ -197,6 +210,7 @@ impl RelayerStage {
         heartbeat_tx: &Sender<HeartbeatEvent>,
         packet_tx: &Sender<PacketBatch>,
         banking_packet_sender: &BankingPacketSender,
+        staked_nodes: &Arc<RwLock<StakedNodes>>,
         exit: &Arc<AtomicBool>,
         connection_timeout: &Duration,
     ) -> crate::proxy::Result<()> {
@@ -263,6 +277,7 @@ impl RelayerStage {
             heartbeat_tx,
             packet_tx,
             banking_packet_sender,
+            staked_nodes,
             local_relayer_config,
             global_relayer_config,
             exit,
@@ -282,6 +297,7 @@ impl RelayerStage {
         heartbeat_tx: &Sender<HeartbeatEvent>,
         packet_tx: &Sender<PacketBatch>,
         banking_packet_sender: &BankingPacketSender,
+        staked_nodes: &Arc<RwLock<StakedNodes>>,
         local_config: &RelayerConfig, // local copy of config with current connections
         global_config: &Arc<Mutex<RelayerConfig>>, // guarded reference for detecting run-time updates
         exit: &Arc<AtomicBool>,
@@ -334,6 +350,7 @@ impl RelayerStage {
             local_config,
             global_config,
             banking_packet_sender,
+            staked_nodes,
             exit,
             auth_client,
             access_token,
@@ -354,6 +371,7 @@ impl RelayerStage {
         local_config: &RelayerConfig, // local copy of config with current connections
         global_config: &Arc<Mutex<RelayerConfig>>, // guarded reference for detecting run-time updates
         banking_packet_sender: &BankingPacketSender,
+        staked_nodes: &Arc<RwLock<StakedNodes>>,
         exit: &Arc<AtomicBool>,
         mut auth_client: AuthServiceClient<Channel>,
         access_token: Arc<Mutex<Token>>,
@@ -366,6 +384,7 @@ impl RelayerStage {
         let refresh_within_s: u64 = METRICS_TICK.as_secs().saturating_mul(3).saturating_div(2);
 
         let mut relayer_stats = RelayerStageStats::default();
+        let mut packet_qos = RelayerPacketQos::new(local_config.packet_qos, staked_nodes.clone());
         let mut metrics_and_auth_tick = interval(METRICS_TICK);
 
         let mut num_full_refreshes: u64 = 1;
@@ -380,7 +399,7 @@ impl RelayerStage {
             tokio::select! {
                 maybe_msg = packet_stream.message() => {
                     let resp = maybe_msg?.ok_or(ProxyError::GrpcStreamDisconnected)?;
-                    Self::handle_relayer_packets(resp, heartbeat_event, heartbeat_tx, &mut last_heartbeat_ts, packet_tx, local_config.trust_packets, banking_packet_sender, &mut relayer_stats)?;
+                    Self::handle_relayer_packets(resp, heartbeat_event, heartbeat_tx, &mut last_heartbeat_ts, packet_tx, local_config.trust_packets, banking_packet_sender, &mut packet_qos, &mut relayer_stats)?;
                 }
                 _ = heartbeat_check_interval.tick() => {
                     if last_heartbeat_ts.elapsed() > local_config.oldest_allowed_heartbeat {
@@ -390,6 +409,7 @@ impl RelayerStage {
                 _ = metrics_and_auth_tick.tick() => {
                     relayer_stats.report();
                     relayer_stats = RelayerStageStats::default();
+                    packet_qos.report_and_reset();
 
                     if cluster_info.id() != keypair.pubkey() {
                         return Err(ProxyError::AuthenticationConnectionError("validator identity changed".to_string()));
@@ -438,6 +458,7 @@ impl RelayerStage {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn handle_relayer_packets(
         subscribe_packets_resp: relayer::SubscribePacketsResponse,
         heartbeat_event: HeartbeatEvent,
@@ -446,6 +467,7 @@ impl RelayerStage {
         packet_tx: &Sender<PacketBatch>,
         trust_packets: bool,
         banking_packet_sender: &BankingPacketSender,
+        packet_qos: &mut RelayerPacketQos,
         relayer_stats: &mut RelayerStageStats,
     ) -> crate::proxy::Result<()> {
         match subscribe_packets_resp.msg {
@@ -458,15 +480,19 @@ impl RelayerStage {
                     return Ok(());
                 }
 
-                let packet_batch = PacketBatch::new(
-                    proto_batch
-                        .packets
-                        .into_iter()
-                        .map(proto_packet_to_packet)
-                        .collect(),
+                let num_packets = proto_batch.packets.len();
+                let packets = packet_qos.filter_packets(proto_batch.packets);
+                saturating_add_assign!(relayer_stats.num_packets, num_packets as u64);
+                saturating_add_assign!(
+                    relayer_stats.num_packets_dropped,
+                    num_packets.saturating_sub(packets.len()) as u64
                 );
+                if packets.is_empty() {
+                    return Ok(());
+                }
 
-                saturating_add_assign!(relayer_stats.num_packets, packet_batch.len() as u64);
+                let packet_batch =
+                    PacketBatch::new(packets.into_iter().map(proto_packet_to_packet).collect());
 
                 if trust_packets {
                     banking_packet_sender
@@ -10,13 +10,14 @@ use {
             InitializeArgs, InitializeTipDistributionAccountAccounts,
             InitializeTipDistributionAccountArgs,
         },
+        state::TipDistributionAccount,
     },
     jito_tip_payment::{
         Config, InitBumps, TipPaymentAccount, CONFIG_ACCOUNT_SEED, TIP_ACCOUNT_SEED_0,
         TIP_ACCOUNT_SEED_1, TIP_ACCOUNT_SEED_2, TIP_ACCOUNT_SEED_3, TIP_ACCOUNT_SEED_4,
         TIP_ACCOUNT_SEED_5, TIP_ACCOUNT_SEED_6, TIP_ACCOUNT_SEED_7,
     },
-    log::warn,
+    log::{info, warn},
     solana_bundle::TipError,
     solana_runtime::bank::Bank,
     solana_sdk::{
@@ -30,11 +31,24 @@ use {
         system_program,
         transaction::{SanitizedTransaction, Transaction},
     },
-    std::{collections::HashSet, sync::Arc},
+    std::{
+        collections::HashSet,
+        fs, io,
+        path::{Path, PathBuf},
+        sync::{Arc, RwLock},
+    },
+    thiserror::Error,
 };
 
 pub type Result<T> = std::result::Result<T, TipError>;
 
+/// Name of the file in the ledger directory that runtime changes to the
+/// [TipDistributionAccountConfig] are persisted to.
+pub const TIP_DISTRIBUTION_ACCOUNT_CONFIG_FILE: &str = "tip_distribution_account_config.bin";
+
+/// The max commission accepted by the tip-distribution program.
+pub const MAX_COMMISSION_BPS: u16 = 10_000;
+
 #[derive(Debug, Clone)]
 struct TipPaymentProgramInfo {
     program_id: Pubkey,
@@ -62,7 +76,7 @@ struct TipDistributionProgramInfo {
 }
 
 /// This config is used on each invocation to the `initialize_tip_distribution_account` instruction.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TipDistributionAccountConfig {
     /// The account with authority to upload merkle-roots to this validator's [TipDistributionAccount].
     pub merkle_root_upload_authority: Pubkey,
@@ -84,11 +98,176 @@ impl Default for TipDistributionAccountConfig {
     }
 }
 
+#[derive(Error, Debug)]
+pub enum TipDistributionAccountConfigError {
+    #[error("commission of {0} bps exceeds the max of {MAX_COMMISSION_BPS} bps")]
+    InvalidCommission(u16),
+
+    #[error("failed to persist tip distribution account config: {0}")]
+    Persist(String),
+}
+
+/// Runtime changes to the [TipDistributionAccountConfig] as written to disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedTipDistributionAccountConfig {
+    /// Config the validator was started with when the change was made.
+    startup_config: TipDistributionAccountConfig,
+
+    /// Config after the change.
+    config: TipDistributionAccountConfig,
+}
+
+#[derive(Debug)]
+struct TipDistributionAccountConfigStoreInner {
+    /// Config the next [TipDistributionAccount] is initialized with.
+    config: TipDistributionAccountConfig,
+
+    /// Epoch and config of the last [TipDistributionAccount] found initialized on-chain.
+    applied: Option<(Epoch, TipDistributionAccountConfig)>,
+}
+
+/// Holds the [TipDistributionAccountConfig] so the commission and merkle-root upload authority can be
+/// changed while the validator is running. [TipDistributionAccount]s are initialized once per epoch,
+/// so a change takes effect on the first epoch whose account hasn't been initialized yet.
+#[derive(Debug, Clone)]
+pub struct TipDistributionAccountConfigStore {
+    inner: Arc<RwLock<TipDistributionAccountConfigStoreInner>>,
+
+    /// Config the validator was started with, from the command line.
+    startup_config: TipDistributionAccountConfig,
+
+    /// Where changes are persisted so they survive a restart.
+    path: Option<PathBuf>,
+}
+
+impl TipDistributionAccountConfigStore {
+    /// Creates a store starting from `config`. A commission and merkle-root upload authority
+    /// previously changed at runtime and persisted to `path` take precedence, unless `config` has
+    /// been changed since that change was made, in which case `config` wins.
+    pub fn new(startup_config: TipDistributionAccountConfig, path: Option<PathBuf>) -> Self {
+        let mut config = startup_config.clone();
+        if let Some(persisted) = path.as_ref().and_then(|path| Self::load(path)) {
+            let startup_config_changed = persisted.startup_config.commission_bps
+                != startup_config.commission_bps
+                || persisted.startup_config.merkle_root_upload_authority
+                    != startup_config.merkle_root_upload_authority;
+            if startup_config_changed {
+                warn!(
+                    "ignoring tip distribution account config changed at runtime \
+                     (commission_bps: {}, merkle_root_upload_authority: {}) since the command \
+                     line config changed after it was made. using commission_bps: {}, \
+                     merkle_root_upload_authority: {}",
+                    persisted.config.commission_bps,
+                    persisted.config.merkle_root_upload_authority,
+                    startup_config.commission_bps,
+                    startup_config.merkle_root_upload_authority
+                );
+            } else {
+                if persisted.config != persisted.startup_config {
+                    warn!(
+                        "overriding command line tip distribution account config \
+                         (commission_bps: {}, merkle_root_upload_authority: {}) with the config \
+                         changed at runtime (commission_bps: {}, merkle_root_upload_authority: {})",
+                        startup_config.commission_bps,
+                        startup_config.merkle_root_upload_authority,
+                        persisted.config.commission_bps,
+                        persisted.config.merkle_root_upload_authority
+                    );
+                }
+                config.commission_bps = persisted.config.commission_bps;
+                config.merkle_root_upload_authority = persisted.config.merkle_root_upload_authority;
+            }
+        }
+
+        Self {
+            inner: Arc::new(RwLock::new(TipDistributionAccountConfigStoreInner {
+                config,
+                applied: None,
+            })),
+            startup_config,
+            path,
+        }
+    }
+
+    fn load(path: &Path) -> Option<PersistedTipDistributionAccountConfig> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("failed to read tip distribution account config from {path:?}: {e}");
+                return None;
+            }
+        };
+        bincode::deserialize(&bytes)
+            .map_err(|e| {
+                warn!("failed to deserialize tip distribution account config from {path:?}: {e}")
+            })
+            .ok()
+    }
+
+    /// Returns the config the next [TipDistributionAccount] will be initialized with.
+    pub fn config(&self) -> TipDistributionAccountConfig {
+        self.inner.read().unwrap().config.clone()
+    }
+
+    /// Returns the epoch and config of the last [TipDistributionAccount] found initialized on-chain.
+    pub fn applied(&self) -> Option<(Epoch, TipDistributionAccountConfig)> {
+        self.inner.read().unwrap().applied.clone()
+    }
+
+    pub fn vote_account(&self) -> Pubkey {
+        self.inner.read().unwrap().config.vote_account
+    }
+
+    /// Updates the commission and/or merkle-root upload authority used for future
+    /// [TipDistributionAccount]s and persists the result.
+    pub fn update(
+        &self,
+        commission_bps: Option<u16>,
+        merkle_root_upload_authority: Option<Pubkey>,
+    ) -> std::result::Result<TipDistributionAccountConfig, TipDistributionAccountConfigError> {
+        if let Some(commission_bps) = commission_bps {
+            if commission_bps > MAX_COMMISSION_BPS {
+                return Err(TipDistributionAccountConfigError::InvalidCommission(
+                    commission_bps,
+                ));
+            }
+        }
+
+        let mut inner = self.inner.write().unwrap();
+        let mut config = inner.config.clone();
+        if let Some(commission_bps) = commission_bps {
+            config.commission_bps = commission_bps;
+        }
+        if let Some(merkle_root_upload_authority) = merkle_root_upload_authority {
+            config.merkle_root_upload_authority = merkle_root_upload_authority;
+        }
+
+        if let Some(path) = &self.path {
+            let bytes = bincode::serialize(&PersistedTipDistributionAccountConfig {
+                startup_config: self.startup_config.clone(),
+                config: config.clone(),
+            })
+            .map_err(|e| TipDistributionAccountConfigError::Persist(e.to_string()))?;
+            fs::write(path, bytes)
+                .map_err(|e| TipDistributionAccountConfigError::Persist(e.to_string()))?;
+        }
+
+        info!("updated tip distribution account config: {config:?}");
+        inner.config = config.clone();
+        Ok(config)
+    }
+
+    fn mark_applied(&self, epoch: Epoch, config: TipDistributionAccountConfig) {
+        self.inner.write().unwrap().applied = Some((epoch, config));
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TipManager {
     tip_payment_program_info: TipPaymentProgramInfo,
     tip_distribution_program_info: TipDistributionProgramInfo,
-    tip_distribution_account_config: TipDistributionAccountConfig,
+    tip_distribution_account_config: TipDistributionAccountConfigStore,
 }
 
 #[derive(Clone)]
@@ -110,12 +289,22 @@ impl Default for TipManagerConfig {
 
 impl TipManager {
     pub fn new(config: TipManagerConfig) -> TipManager {
-        let TipManagerConfig {
-            tip_payment_program_id,
-            tip_distribution_program_id,
+        let tip_distribution_account_config =
+            TipDistributionAccountConfigStore::new(config.tip_distribution_account_config, None);
+        Self::new_with_tip_distribution_account_config(
+            config.tip_payment_program_id,
+            config.tip_distribution_program_id,
             tip_distribution_account_config,
-        } = config;
+        )
+    }
 
+    /// Creates a [TipManager] whose [TipDistributionAccountConfig] is read from a store that can be
+    /// updated at runtime.
+    pub fn new_with_tip_distribution_account_config(
+        tip_payment_program_id: Pubkey,
+        tip_distribution_program_id: Pubkey,
+        tip_distribution_account_config: TipDistributionAccountConfigStore,
+    ) -> TipManager {
         let config_pda_bump =
             Pubkey::find_program_address(&[CONFIG_ACCOUNT_SEED], &tip_payment_program_id);
 
@@ -255,7 +444,7 @@ impl TipManager {
     pub fn get_my_tip_distribution_pda(&self, epoch: Epoch) -> Pubkey {
         derive_tip_distribution_account_address(
             &self.tip_distribution_program_info.program_id,
-            &self.tip_distribution_account_config.vote_account,
+            &self.tip_distribution_account_config.vote_account(),
             epoch,
         )
         .0
@@ -277,11 +466,43 @@ impl TipManager {
         }
     }
 
+    /// Records the config of this epoch's [TipDistributionAccount] once it's initialized on-chain, so
+    /// the applied config reflects what the account was actually created with.
+    pub fn maybe_mark_tip_distribution_account_applied(&self, bank: &Bank) {
+        let epoch = bank.epoch();
+        if matches!(
+            self.tip_distribution_account_config.applied(),
+            Some((applied_epoch, _)) if applied_epoch >= epoch
+        ) {
+            return;
+        }
+        let Some(account) = bank.get_account(&self.get_my_tip_distribution_pda(epoch)) else {
+            return;
+        };
+        if account.owner() != &self.tip_distribution_program_info.program_id {
+            return;
+        }
+        match TipDistributionAccount::try_deserialize(&mut account.data()) {
+            Ok(tip_distribution_account) => {
+                self.tip_distribution_account_config.mark_applied(
+                    epoch,
+                    TipDistributionAccountConfig {
+                        merkle_root_upload_authority: tip_distribution_account
+                            .merkle_root_upload_authority,
+                        vote_account: tip_distribution_account.validator_vote_account,
+                        commission_bps: tip_distribution_account.validator_commission_bps,
+                    },
+                );
+            }
+            Err(e) => warn!("failed to deserialize tip distribution account: {e}"),
+        }
+    }
+
     /// Returns whether or not the current [TipDistributionAccount] PDA should be initialized for this epoch.
     pub fn should_init_tip_distribution_account(&self, bank: &Bank) -> bool {
         let pda = derive_tip_distribution_account_address(
             &self.tip_distribution_program_info.program_id,
-            &self.tip_distribution_account_config.vote_account,
+            &self.tip_distribution_account_config.vote_account(),
             bank.epoch(),
         )
         .0;
@@ -330,19 +551,18 @@ impl TipManager {
         epoch: Epoch,
         keypair: &Keypair,
     ) -> SanitizedTransaction {
+        let config = self.tip_distribution_account_config.config();
         let (tip_distribution_account, bump) = derive_tip_distribution_account_address(
             &self.tip_distribution_program_info.program_id,
-            &self.tip_distribution_account_config.vote_account,
+            &config.vote_account,
             epoch,
         );
 
         let ix = initialize_tip_distribution_account_ix(
             self.tip_distribution_program_info.program_id,
             InitializeTipDistributionAccountArgs {
-                merkle_root_upload_authority: self
-                    .tip_distribution_account_config
-                    .merkle_root_upload_authority,
-                validator_commission_bps: self.tip_distribution_account_config.commission_bps,
+                merkle_root_upload_authority: config.merkle_root_upload_authority,
+                validator_commission_bps: config.commission_bps,
                 bump,
             },
             InitializeTipDistributionAccountAccounts {
@@ -350,10 +570,9 @@ impl TipManager {
                 tip_distribution_account,
                 system_program: system_program::id(),
                 signer: keypair.pubkey(),
-                validator_vote_account: self.tip_distribution_account_config.vote_account,
+                validator_vote_account: config.vote_account,
             },
         );
-
         SanitizedTransaction::try_from_legacy_transaction(Transaction::new_signed_with_payer(
             &[ix],
             Some(&keypair.pubkey()),
@@ -540,6 +759,7 @@ impl TipManager {
                 keypair,
             ))
         } else {
+            self.maybe_mark_tip_distribution_account_applied(bank);
             None
         };
 
@@ -581,3 +801,138 @@ impl TipManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        anchor_lang::AccountSerialize,
+        solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_sdk::account::AccountSharedData,
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_tip_distribution_account_config_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(TIP_DISTRIBUTION_ACCOUNT_CONFIG_FILE);
+        let config = TipDistributionAccountConfig {
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            vote_account: Pubkey::new_unique(),
+            commission_bps: 800,
+        };
+
+        let store = TipDistributionAccountConfigStore::new(config.clone(), Some(path.clone()));
+        assert_eq!(store.config(), config);
+        assert_eq!(store.applied(), None);
+
+        assert!(matches!(
+            store.update(Some(MAX_COMMISSION_BPS + 1), None),
+            Err(TipDistributionAccountConfigError::InvalidCommission(_))
+        ));
+        assert_eq!(store.config(), config);
+
+        let new_authority = Pubkey::new_unique();
+        let updated = store.update(Some(500), Some(new_authority)).unwrap();
+        assert_eq!(updated.commission_bps, 500);
+        assert_eq!(updated.merkle_root_upload_authority, new_authority);
+        assert_eq!(updated.vote_account, config.vote_account);
+        assert_eq!(store.config(), updated);
+
+        // the update survives a restart, but the vote account comes from the startup config
+        let new_vote_account = Pubkey::new_unique();
+        let restarted = TipDistributionAccountConfigStore::new(
+            TipDistributionAccountConfig {
+                vote_account: new_vote_account,
+                ..config.clone()
+            },
+            Some(path.clone()),
+        );
+        assert_eq!(
+            restarted.config(),
+            TipDistributionAccountConfig {
+                merkle_root_upload_authority: new_authority,
+                vote_account: new_vote_account,
+                commission_bps: 500,
+            }
+        );
+
+        // the command line config wins if it was changed since the update
+        let changed_startup_config = TipDistributionAccountConfig {
+            commission_bps: 700,
+            ..config
+        };
+        let restarted =
+            TipDistributionAccountConfigStore::new(changed_startup_config.clone(), Some(path));
+        assert_eq!(restarted.config(), changed_startup_config);
+    }
+
+    #[test]
+    fn test_initialize_tip_distribution_account_uses_latest_config() {
+        let tip_manager = TipManager::new(TipManagerConfig::default());
+        let keypair = Keypair::new();
+
+        let tx = tip_manager.initialize_tip_distribution_account_tx(Hash::default(), 1, &keypair);
+        // building the transaction doesn't mean the account was initialized with it
+        assert_eq!(tip_manager.tip_distribution_account_config.applied(), None);
+
+        tip_manager
+            .tip_distribution_account_config
+            .update(Some(1_000), None)
+            .unwrap();
+        let updated_tx =
+            tip_manager.initialize_tip_distribution_account_tx(Hash::default(), 1, &keypair);
+        assert_ne!(
+            tx.message().instructions()[0].data,
+            updated_tx.message().instructions()[0].data
+        );
+    }
+
+    #[test]
+    fn test_mark_tip_distribution_account_applied() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(1_000_000_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let tip_manager = TipManager::new(TipManagerConfig::default());
+
+        tip_manager.maybe_mark_tip_distribution_account_applied(&bank);
+        assert_eq!(tip_manager.tip_distribution_account_config.applied(), None);
+
+        // the account on-chain was initialized with a different config than the current one
+        let config = tip_manager.tip_distribution_account_config.config();
+        let (tip_distribution_account, bump) = derive_tip_distribution_account_address(
+            &tip_manager.tip_distribution_program_id(),
+            &config.vote_account,
+            bank.epoch(),
+        );
+        let on_chain_config = TipDistributionAccountConfig {
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            commission_bps: 42,
+            ..config
+        };
+        let mut data = vec![0; TipDistributionAccount::SIZE];
+        TipDistributionAccount {
+            validator_vote_account: on_chain_config.vote_account,
+            merkle_root_upload_authority: on_chain_config.merkle_root_upload_authority,
+            merkle_root: None,
+            epoch_created_at: bank.epoch(),
+            validator_commission_bps: on_chain_config.commission_bps,
+            expires_at: bank.epoch() + 10,
+            bump,
+        }
+        .try_serialize(&mut data.as_mut_slice())
+        .unwrap();
+        let mut account = AccountSharedData::new(
+            1_000_000,
+            TipDistributionAccount::SIZE,
+            &tip_manager.tip_distribution_program_id(),
+        );
+        account.set_data_from_slice(&data);
+        bank.store_account(&tip_distribution_account, &account);
+
+        tip_manager.maybe_mark_tip_distribution_account_applied(&bank);
+        assert_eq!(
+            tip_manager.tip_distribution_account_config.applied(),
+            Some((bank.epoch(), on_chain_config))
+        );
+    }
+}
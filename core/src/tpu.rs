@@ -6,26 +6,34 @@ use {
     crate::{
         banking_stage::BankingStage,
         banking_trace::{BankingTracer, TracerThread},
-        bundle_stage::{bundle_account_locker::BundleAccountLocker, BundleStage},
+        bundle_stage::{
+            bundle_account_locker::BundleAccountLocker, bundle_auction::BundleOrderingPolicy,
+            bundle_reserved_space_manager::BundleReservedSpacePolicy,
+            bundle_status_reporter::BundleStatusReporter, BundleStage,
+        },
         cluster_info_vote_listener::{
             ClusterInfoVoteListener, DuplicateConfirmedSlotsSender, GossipVerifiedVoteHashSender,
             VerifiedVoteSender, VoteTracker,
         },
         fetch_stage::FetchStage,
+        packet_bundle::PacketBundle,
         proxy::{
             block_engine_stage::{BlockBuilderFeeInfo, BlockEngineConfig, BlockEngineStage},
+            bundle_result_reporter::BUNDLE_RESULT_CHANNEL_CAPACITY,
+            embedded_relayer::{EmbeddedRelayer, EmbeddedRelayerConfig},
             fetch_stage_manager::FetchStageManager,
             relayer_stage::{RelayerConfig, RelayerStage},
         },
         sigverify::TransactionSigVerifier,
         sigverify_stage::SigVerifyStage,
         staked_nodes_updater_service::StakedNodesUpdaterService,
-        tip_manager::{TipManager, TipManagerConfig},
+        tip_manager::{TipDistributionAccountConfigStore, TipManager, TipManagerConfig},
         tpu_entry_notifier::TpuEntryNotifier,
         validator::{BlockProductionMethod, GeneratorConfig},
     },
     bytes::Bytes,
-    crossbeam_channel::{unbounded, Receiver},
+    crossbeam_channel::{bounded, never, unbounded, Receiver, Sender},
+    solana_bundle::bundle_status::BundleStatusCache,
     solana_client::connection_cache::ConnectionCache,
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
@@ -38,6 +46,7 @@ use {
         rpc_subscriptions::RpcSubscriptions,
     },
     solana_runtime::{bank_forks::BankForks, prioritization_fee_cache::PrioritizationFeeCache},
+    solana_runtime_plugin::runtime_plugin_manager::RuntimePluginManager,
     solana_sdk::{
         clock::Slot, pubkey::Pubkey, quic::NotifyKeyUpdate, signature::Keypair, signer::Signer,
     },
@@ -85,6 +94,7 @@ pub struct Tpu {
     relayer_stage: RelayerStage,
     block_engine_stage: BlockEngineStage,
     fetch_stage_manager: FetchStageManager,
+    embedded_relayer: Option<EmbeddedRelayer>,
     bundle_stage: BundleStage,
 }
 
@@ -127,8 +137,17 @@ impl Tpu {
         block_engine_config: Arc<Mutex<BlockEngineConfig>>,
         relayer_config: Arc<Mutex<RelayerConfig>>,
         tip_manager_config: TipManagerConfig,
+        tip_distribution_account_config: TipDistributionAccountConfigStore,
         shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
         preallocated_bundle_cost: u64,
+        bundle_status_cache: Arc<BundleStatusCache>,
+        bundle_ordering_policy: BundleOrderingPolicy,
+        bundle_reserved_space_policy: BundleReservedSpacePolicy,
+        bundle_sender: Sender<Vec<PacketBundle>>,
+        bundle_receiver: Receiver<Vec<PacketBundle>>,
+        runtime_plugin_manager: Option<Arc<RwLock<RuntimePluginManager>>>,
+        // Config and bundle source for serving the relayer and block engine roles in-process.
+        embedded_relayer: Option<(EmbeddedRelayerConfig, Receiver<Vec<PacketBundle>>)>,
     ) -> (Self, Vec<Arc<dyn NotifyKeyUpdate + Sync + Send>>) {
         let TpuSockets {
             transactions: transactions_sockets,
@@ -139,9 +158,17 @@ impl Tpu {
             transactions_forwards_quic: transactions_forwards_quic_sockets,
         } = sockets;
 
+        let (packet_sender, packet_receiver) = unbounded();
+
         // Packets from fetch stage and quic server are intercepted and sent through fetch_stage_manager
         // If relayer is connected, packets are dropped. If not, packets are forwarded on to packet_sender
-        let (packet_intercept_sender, packet_intercept_receiver) = unbounded();
+        // The embedded relayer streams the packets the TPU verified, so with it packets always go
+        // straight to sigverify.
+        let (packet_intercept_sender, packet_intercept_receiver) = if embedded_relayer.is_some() {
+            (packet_sender.clone(), never())
+        } else {
+            unbounded()
+        };
 
         let (vote_packet_sender, vote_packet_receiver) = unbounded();
         let (forwarded_packet_sender, forwarded_packet_receiver) = unbounded();
@@ -207,10 +234,28 @@ impl Tpu {
         )
         .unwrap();
 
-        let (packet_sender, packet_receiver) = unbounded();
+        // With the embedded relayer, verified packets reach banking through the relayer stage,
+        // which traces them
+        let (verified_sender, embedded_relayer) = match embedded_relayer {
+            Some((embedded_relayer_config, embedded_bundle_receiver)) => {
+                let (verified_sender, verified_receiver) =
+                    BankingTracer::new_disabled().create_channel_non_vote();
+                let embedded_relayer = EmbeddedRelayer::new(
+                    embedded_relayer_config,
+                    cluster_info.clone(),
+                    staked_nodes.clone(),
+                    verified_receiver,
+                    non_vote_sender.clone(),
+                    embedded_bundle_receiver,
+                    exit.clone(),
+                );
+                (verified_sender, Some(embedded_relayer))
+            }
+            None => (non_vote_sender.clone(), None),
+        };
 
         let sigverify_stage = {
-            let verifier = TransactionSigVerifier::new(non_vote_sender.clone());
+            let verifier = TransactionSigVerifier::new(verified_sender);
             SigVerifyStage::new(packet_receiver, verifier, "tpu-verifier")
         };
 
@@ -229,7 +274,8 @@ impl Tpu {
             block_builder_commission: 0,
         }));
 
-        let (bundle_sender, bundle_receiver) = unbounded();
+        let (bundle_status_sender, bundle_status_receiver) =
+            bounded(BUNDLE_RESULT_CHANNEL_CAPACITY);
         let block_engine_stage = BlockEngineStage::new(
             block_engine_config,
             bundle_sender,
@@ -238,6 +284,7 @@ impl Tpu {
             non_vote_sender.clone(),
             exit.clone(),
             &block_builder_fee_info,
+            bundle_status_receiver,
         );
 
         let (heartbeat_tx, heartbeat_rx) = unbounded();
@@ -255,6 +302,7 @@ impl Tpu {
             heartbeat_tx,
             packet_sender,
             non_vote_sender,
+            staked_nodes.clone(),
             exit.clone(),
         );
 
@@ -274,7 +322,11 @@ impl Tpu {
             duplicate_confirmed_slot_sender,
         );
 
-        let tip_manager = TipManager::new(tip_manager_config);
+        let tip_manager = TipManager::new_with_tip_distribution_account_config(
+            tip_manager_config.tip_payment_program_id,
+            tip_manager_config.tip_distribution_program_id,
+            tip_distribution_account_config,
+        );
 
         let bundle_account_locker = BundleAccountLocker::default();
 
@@ -314,6 +366,16 @@ impl Tpu {
             preallocated_bundle_cost,
             bank_forks.clone(),
             prioritization_fee_cache,
+            BundleStatusReporter::new(
+                bundle_status_cache,
+                Some(subscriptions.clone()),
+                Some(bundle_status_sender),
+            ),
+            bundle_ordering_policy,
+            bundle_reserved_space_policy,
+            blockstore.clone(),
+            runtime_plugin_manager,
+            banking_tracer,
         );
 
         let (entry_receiver, tpu_entry_notifier) =
@@ -359,6 +421,7 @@ impl Tpu {
                 block_engine_stage,
                 relayer_stage,
                 fetch_stage_manager,
+                embedded_relayer,
                 bundle_stage,
             },
             vec![key_updater, forwards_key_updater],
@@ -380,6 +443,9 @@ impl Tpu {
             self.block_engine_stage.join(),
             self.fetch_stage_manager.join(),
         ];
+        if let Some(embedded_relayer) = self.embedded_relayer {
+            embedded_relayer.join()?;
+        }
         let broadcast_result = self.broadcast_stage.join();
         for result in results {
             result?;
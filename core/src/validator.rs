@@ -6,6 +6,10 @@ use {
         accounts_hash_verifier::AccountsHashVerifier,
         admin_rpc_post_init::AdminRpcRequestMetadataPostInit,
         banking_trace::{self, BankingTracer},
+        bundle_stage::{
+            bundle_auction::BundleOrderingPolicy,
+            bundle_reserved_space_manager::BundleReservedSpacePolicy,
+        },
         cache_block_meta_service::{CacheBlockMetaSender, CacheBlockMetaService},
         cluster_info_vote_listener::VoteTracker,
         completed_data_sets_service::CompletedDataSetsService,
@@ -15,7 +19,10 @@ use {
             ExternalRootSource, Tower,
         },
         poh_timing_report_service::PohTimingReportService,
-        proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
+        proxy::{
+            block_engine_stage::BlockEngineConfig, embedded_relayer::EmbeddedRelayerConfig,
+            relayer_stage::RelayerConfig,
+        },
         repair::{self, serve_repair::ServeRepair, serve_repair_service::ServeRepairService},
         rewards_recorder_service::{RewardsRecorderSender, RewardsRecorderService},
         sample_performance_service::SamplePerformanceService,
@@ -25,7 +32,10 @@ use {
         system_monitor_service::{
             verify_net_stats_access, SystemMonitorService, SystemMonitorStatsReportConfig,
         },
-        tip_manager::TipManagerConfig,
+        tip_manager::{
+            TipDistributionAccountConfigStore, TipManagerConfig,
+            TIP_DISTRIBUTION_ACCOUNT_CONFIG_FILE,
+        },
         tpu::{Tpu, TpuSockets, DEFAULT_TPU_COALESCE},
         tvu::{Tvu, TvuConfig, TvuSockets},
     },
@@ -39,6 +49,7 @@ use {
         hardened_unpack::{open_genesis_config, MAX_GENESIS_ARCHIVE_UNPACKED_SIZE},
         utils::{move_and_async_delete_path, move_and_async_delete_path_contents},
     },
+    solana_bundle::bundle_status::BundleStatusCache,
     solana_client::connection_cache::{ConnectionCache, Protocol},
     solana_entry::poh::compute_hash_time_ns,
     solana_geyser_plugin_manager::{
@@ -82,11 +93,12 @@ use {
             OptimisticallyConfirmedBankTracker,
         },
         rpc::JsonRpcConfig,
+        rpc_bundle_sender::RpcBundleSender,
         rpc_completed_slots_service::RpcCompletedSlotsService,
         rpc_pubsub_service::{PubSubConfig, PubSubService},
         rpc_service::JsonRpcService,
-        rpc_subscriptions::RpcSubscriptions,
-        transaction_notifier_interface::TransactionNotifierArc,
+        rpc_subscriptions::{RpcSubscriptions, TransactionSubscriptionNotifier},
+        transaction_notifier_interface::{TransactionNotifierArc, TransactionNotifierFanout},
         transaction_status_service::TransactionStatusService,
     },
     solana_runtime::{
@@ -276,10 +288,13 @@ pub struct ValidatorConfig {
     pub unified_scheduler_handler_threads: Option<usize>,
     pub relayer_config: Arc<Mutex<RelayerConfig>>,
     pub block_engine_config: Arc<Mutex<BlockEngineConfig>>,
+    pub embedded_relayer_config: Option<EmbeddedRelayerConfig>,
     // Using Option inside RwLock is ugly, but only convenient way to allow toggle on/off
     pub shred_receiver_address: Arc<RwLock<Option<SocketAddr>>>,
     pub tip_manager_config: TipManagerConfig,
     pub preallocated_bundle_cost: u64,
+    pub bundle_ordering_policy: BundleOrderingPolicy,
+    pub bundle_reserved_space_policy: BundleReservedSpacePolicy,
 }
 
 impl Default for ValidatorConfig {
@@ -350,9 +365,12 @@ impl Default for ValidatorConfig {
             unified_scheduler_handler_threads: None,
             relayer_config: Arc::new(Mutex::new(RelayerConfig::default())),
             block_engine_config: Arc::new(Mutex::new(BlockEngineConfig::default())),
+            embedded_relayer_config: None,
             shred_receiver_address: Arc::new(RwLock::new(None)),
             tip_manager_config: TipManagerConfig::default(),
             preallocated_bundle_cost: u64::default(),
+            bundle_ordering_policy: BundleOrderingPolicy::default(),
+            bundle_reserved_space_policy: BundleReservedSpacePolicy::default(),
         }
     }
 }
@@ -690,6 +708,26 @@ impl Validator {
             entry_notifier.is_some()
         );
 
+        // `transactionSubscribe` is fed from the transaction status service alongside any
+        // plugins; the subscriptions are attached once they are created further below.
+        let transaction_subscription_notifier = (config.rpc_addrs.is_some()
+            && config.pubsub_config.enable_transaction_subscription)
+            .then(|| Arc::new(TransactionSubscriptionNotifier::default()));
+        let transaction_notifier: Option<TransactionNotifierArc> = match (
+            transaction_notifier,
+            transaction_subscription_notifier.clone(),
+        ) {
+            (Some(plugin_notifier), Some(subscription_notifier)) => {
+                Some(Arc::new(TransactionNotifierFanout(vec![
+                    plugin_notifier,
+                    subscription_notifier,
+                ])))
+            }
+            (Some(plugin_notifier), None) => Some(plugin_notifier),
+            (None, Some(subscription_notifier)) => Some(subscription_notifier),
+            (None, None) => None,
+        };
+
         let system_monitor_service = Some(SystemMonitorService::new(
             exit.clone(),
             SystemMonitorStatsReportConfig {
@@ -840,6 +878,21 @@ impl Validator {
         // (by both replay stage and banking stage)
         let prioritization_fee_cache = Arc::new(PrioritizationFeeCache::default());
 
+        // bundle outcomes are written by bundle stage and read by RPC
+        let bundle_status_cache = Arc::new(BundleStatusCache::default());
+
+        // bundles are sent to bundle stage by the block engine, relayer and sendBundle RPC
+        let (bundle_sender, bundle_receiver) = unbounded();
+        // with an embedded relayer, sendBundle bundles take the same path as block engine bundles
+        let embedded_relayer = config
+            .embedded_relayer_config
+            .clone()
+            .map(|embedded_relayer_config| (embedded_relayer_config, unbounded()));
+        let rpc_bundle_sender_channel = embedded_relayer
+            .as_ref()
+            .map(|(_, (embedded_bundle_sender, _))| embedded_bundle_sender.clone())
+            .unwrap_or_else(|| bundle_sender.clone());
+
         match &config.block_verification_method {
             BlockVerificationMethod::BlockstoreProcessor => {
                 info!("no scheduler pool is installed for block verification...");
@@ -934,9 +987,14 @@ impl Validator {
             &config.pubsub_config,
             None,
         ));
+        if let Some(transaction_subscription_notifier) = &transaction_subscription_notifier {
+            transaction_subscription_notifier.attach(&rpc_subscriptions);
+        }
 
-        if let Some((runtime_plugin_configs, request_rx)) = runtime_plugin_configs_and_request_rx {
-            RuntimePluginService::start(
+        let runtime_plugin_manager = if let Some((runtime_plugin_configs, request_rx)) =
+            runtime_plugin_configs_and_request_rx
+        {
+            let runtime_plugin_service = RuntimePluginService::start(
                 &runtime_plugin_configs,
                 request_rx,
                 bank_forks.clone(),
@@ -944,7 +1002,10 @@ impl Validator {
                 exit.clone(),
             )
             .map_err(|e| format!("Failed to start runtime plugin service: {e:?}"))?;
-        }
+            Some(runtime_plugin_service.plugin_manager())
+        } else {
+            None
+        };
 
         let max_slots = Arc::new(MaxSlots::default());
         let (completed_data_sets_sender, completed_data_sets_receiver) =
@@ -1028,6 +1089,14 @@ impl Validator {
                 None
             };
 
+            // sendBundle applies the same blacklist as bundle stage
+            let rpc_bundle_sender = config.rpc_config.enable_send_bundle.then(|| {
+                RpcBundleSender::new(
+                    rpc_bundle_sender_channel.clone(),
+                    HashSet::from([config.tip_manager_config.tip_payment_program_id]),
+                    config.rpc_config.send_bundle_allowed_signers.clone(),
+                )
+            });
             let json_rpc_service = JsonRpcService::new(
                 rpc_addr,
                 config.rpc_config.clone(),
@@ -1051,6 +1120,8 @@ impl Validator {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache.clone(),
+                bundle_status_cache.clone(),
+                rpc_bundle_sender,
             )?;
 
             (
@@ -1367,6 +1438,13 @@ impl Validator {
             };
         }
 
+        let tip_distribution_account_config = TipDistributionAccountConfigStore::new(
+            config
+                .tip_manager_config
+                .tip_distribution_account_config
+                .clone(),
+            Some(ledger_path.join(TIP_DISTRIBUTION_ACCOUNT_CONFIG_FILE)),
+        );
         let (tpu, mut key_notifies) = Tpu::new(
             &cluster_info,
             &poh_recorder,
@@ -1411,8 +1489,18 @@ impl Validator {
             config.block_engine_config.clone(),
             config.relayer_config.clone(),
             config.tip_manager_config.clone(),
+            tip_distribution_account_config.clone(),
             config.shred_receiver_address.clone(),
             config.preallocated_bundle_cost,
+            bundle_status_cache,
+            config.bundle_ordering_policy,
+            config.bundle_reserved_space_policy,
+            bundle_sender,
+            bundle_receiver,
+            runtime_plugin_manager,
+            embedded_relayer.map(|(embedded_relayer_config, (_, embedded_bundle_receiver))| {
+                (embedded_relayer_config, embedded_bundle_receiver)
+            }),
         );
 
         datapoint_info!(
@@ -1438,7 +1526,9 @@ impl Validator {
             cluster_slots,
             block_engine_config: config.block_engine_config.clone(),
             relayer_config: config.relayer_config.clone(),
+            embedded_relayer: config.embedded_relayer_config.is_some(),
             shred_receiver_address: config.shred_receiver_address.clone(),
+            tip_distribution_account_config,
         });
 
         Ok(Self {
@@ -0,0 +1,387 @@
+//! Mock auth, block engine and relayer servers for exercising the validator's proxy stages in
+//! tests.
+//!
+//! [MockProxy] serves all three gRPC services on a single local address, so a validator can be
+//! pointed at [MockProxy::url] as both its relayer and block engine. Tests drive the mock by
+//! pushing bundles and packets into the subscriber streams and toggling relayer heartbeats, and
+//! observe the validator's behavior through [MockProxyStats].
+//!
+//! The auth flow and subscriber streams are the ones the validator's embedded relayer serves,
+//! except that any pubkey that signs its challenge is authenticated.
+
+use {
+    jito_protos::proto::{
+        auth::auth_service_server::AuthServiceServer,
+        block_engine::{
+            self,
+            block_engine_validator_server::{BlockEngineValidator, BlockEngineValidatorServer},
+            BlockBuilderFeeInfoRequest, BlockBuilderFeeInfoResponse, SubscribeBundlesRequest,
+        },
+        bundle::{Bundle, BundleUuid},
+        packet::{Meta, Packet, PacketBatch},
+        relayer::{
+            self,
+            relayer_server::{Relayer, RelayerServer},
+            GetTpuConfigsRequest, GetTpuConfigsResponse,
+        },
+    },
+    log::*,
+    solana_core::proxy::proxy_server::{
+        header, subscribe_block_engine_packets, subscribe_bundles, subscribe_packets, to_socket,
+        AuthPolicy, ProxyAuthConfig, ProxyAuthService, ProxyAuthStats,
+        SubscribeBlockEnginePacketsStream, SubscribeBundlesStream, SubscribePacketsStream,
+    },
+    solana_sdk::{pubkey::Pubkey, transaction::VersionedTransaction},
+    std::{
+        io,
+        net::{Ipv4Addr, SocketAddr, TcpListener},
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        thread::{self, JoinHandle},
+        time::Duration,
+    },
+    tokio::{
+        runtime::Runtime,
+        sync::{broadcast, oneshot},
+    },
+    tokio_stream::wrappers::TcpListenerStream,
+    tonic::{transport::Server, Request, Response, Status},
+};
+
+const BROADCAST_CHANNEL_CAPACITY: usize = 1_000;
+
+#[derive(Clone, Debug)]
+pub struct MockProxyConfig {
+    /// Lifetime of issued access tokens.
+    pub access_token_ttl: Duration,
+
+    /// Lifetime of issued refresh tokens.
+    pub refresh_token_ttl: Duration,
+
+    /// Interval at which heartbeats are sent to relayer subscribers.
+    pub heartbeat_interval: Duration,
+
+    /// TPU address returned by the relayer's GetTpuConfigs.
+    pub tpu: SocketAddr,
+
+    /// TPU forward address returned by the relayer's GetTpuConfigs.
+    pub tpu_forward: SocketAddr,
+
+    /// Block builder fee info returned by the block engine.
+    pub block_builder: Pubkey,
+    pub block_builder_commission: u64,
+}
+
+impl Default for MockProxyConfig {
+    fn default() -> Self {
+        let ProxyAuthConfig {
+            access_token_ttl,
+            refresh_token_ttl,
+        } = ProxyAuthConfig::default();
+        Self {
+            access_token_ttl,
+            refresh_token_ttl,
+            heartbeat_interval: Duration::from_millis(500),
+            tpu: SocketAddr::from((Ipv4Addr::LOCALHOST, 11_222)),
+            tpu_forward: SocketAddr::from((Ipv4Addr::LOCALHOST, 11_223)),
+            block_builder: Pubkey::new_unique(),
+            block_builder_commission: 5,
+        }
+    }
+}
+
+/// Counters for the requests the mock has served since it started.
+#[derive(Default, Debug)]
+pub struct MockProxyStats {
+    pub auth: Arc<ProxyAuthStats>,
+    pub block_builder_fee_info_requests: AtomicU64,
+    pub relayer_subscriptions: AtomicU64,
+    pub bundle_subscriptions: AtomicU64,
+    pub block_engine_packet_subscriptions: AtomicU64,
+    pub heartbeats_sent: AtomicU64,
+}
+
+struct MockProxyState {
+    config: MockProxyConfig,
+    stats: MockProxyStats,
+    heartbeats_enabled: AtomicBool,
+    bundle_broadcast: broadcast::Sender<Vec<BundleUuid>>,
+    packet_broadcast: broadcast::Sender<PacketBatch>,
+}
+
+pub struct MockProxy {
+    address: SocketAddr,
+    state: Arc<MockProxyState>,
+    shutdown_sender: Option<oneshot::Sender<()>>,
+    server_thread: Option<JoinHandle<()>>,
+}
+
+impl MockProxy {
+    /// Starts the mock on an unused local port.
+    pub fn start(config: MockProxyConfig) -> io::Result<Self> {
+        Self::start_on(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)), config)
+    }
+
+    /// Starts the mock on the given address, e.g. to restart it where clients expect it.
+    pub fn start_on(address: SocketAddr, config: MockProxyConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+
+        let state = Arc::new(MockProxyState {
+            config,
+            stats: MockProxyStats::default(),
+            heartbeats_enabled: AtomicBool::new(true),
+            bundle_broadcast: broadcast::channel(BROADCAST_CHANNEL_CAPACITY).0,
+            packet_broadcast: broadcast::channel(BROADCAST_CHANNEL_CAPACITY).0,
+        });
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel();
+        let runtime = Runtime::new()?;
+        let server_thread = thread::Builder::new()
+            .name("mockProxy".to_string())
+            .spawn({
+                let state = state.clone();
+                move || {
+                    runtime.block_on(Self::serve(listener, state, shutdown_receiver));
+                    // dropping the runtime cancels the open streams, so clients see the server
+                    // go away abruptly rather than a graceful shutdown
+                    drop(runtime);
+                }
+            })?;
+
+        info!("mock proxy listening on {address}");
+        Ok(Self {
+            address,
+            state,
+            shutdown_sender: Some(shutdown_sender),
+            server_thread: Some(server_thread),
+        })
+    }
+
+    async fn serve(
+        listener: TcpListener,
+        state: Arc<MockProxyState>,
+        shutdown_receiver: oneshot::Receiver<()>,
+    ) {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("mock proxy failed to listen: {e:?}");
+                return;
+            }
+        };
+        let auth_service = ProxyAuthService::new(
+            Arc::new(AnyPubkey),
+            ProxyAuthConfig {
+                access_token_ttl: state.config.access_token_ttl,
+                refresh_token_ttl: state.config.refresh_token_ttl,
+            },
+            state.stats.auth.clone(),
+        );
+        let server = Server::builder()
+            .add_service(AuthServiceServer::new(auth_service.clone()))
+            .add_service(RelayerServer::with_interceptor(
+                MockRelayerService {
+                    state: state.clone(),
+                },
+                auth_service.interceptor(),
+            ))
+            .add_service(BlockEngineValidatorServer::with_interceptor(
+                MockBlockEngineService { state },
+                auth_service.interceptor(),
+            ))
+            .serve_with_incoming(TcpListenerStream::new(listener));
+        tokio::select! {
+            result = server => {
+                if let Err(e) = result {
+                    error!("mock proxy server error: {e:?}");
+                }
+            }
+            _ = shutdown_receiver => {}
+        }
+    }
+
+    /// URL to configure as the validator's relayer and block engine.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    pub fn config(&self) -> &MockProxyConfig {
+        &self.state.config
+    }
+
+    pub fn stats(&self) -> &MockProxyStats {
+        &self.state.stats
+    }
+
+    /// Stops or resumes heartbeats on all relayer streams while leaving them open.
+    pub fn set_heartbeats_enabled(&self, enabled: bool) {
+        self.state
+            .heartbeats_enabled
+            .store(enabled, Ordering::Relaxed);
+    }
+
+    /// Sends bundles to all bundle subscribers, returning the number of subscribers.
+    pub fn send_bundles(&self, bundles: Vec<BundleUuid>) -> usize {
+        self.state
+            .bundle_broadcast
+            .send(bundles)
+            .unwrap_or_default()
+    }
+
+    /// Sends packets to all relayer subscribers, returning the number of subscribers.
+    pub fn send_relayer_packets(&self, packets: Vec<Packet>) -> usize {
+        self.state
+            .packet_broadcast
+            .send(PacketBatch { packets })
+            .unwrap_or_default()
+    }
+
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        if let Some(shutdown_sender) = self.shutdown_sender.take() {
+            let _ = shutdown_sender.send(());
+        }
+        if let Some(server_thread) = self.server_thread.take() {
+            if server_thread.join().is_err() {
+                error!("mock proxy server thread panicked");
+            }
+        }
+    }
+}
+
+impl Drop for MockProxy {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+pub fn packet_from_transaction(transaction: &VersionedTransaction) -> Packet {
+    let data = bincode::serialize(transaction).expect("serializes transaction");
+    Packet {
+        meta: Some(Meta {
+            size: data.len() as u64,
+            addr: Ipv4Addr::LOCALHOST.to_string(),
+            ..Meta::default()
+        }),
+        data,
+    }
+}
+
+pub fn bundle_from_transactions(uuid: String, transactions: &[VersionedTransaction]) -> BundleUuid {
+    BundleUuid {
+        bundle: Some(Bundle {
+            header: Some(header()),
+            packets: transactions.iter().map(packet_from_transaction).collect(),
+        }),
+        uuid,
+    }
+}
+
+/// Unlike the real services, any pubkey that signs its challenge is authenticated.
+struct AnyPubkey;
+
+impl AuthPolicy for AnyPubkey {
+    fn is_authorized(&self, _pubkey: &Pubkey) -> bool {
+        true
+    }
+}
+
+struct MockRelayerService {
+    state: Arc<MockProxyState>,
+}
+
+#[tonic::async_trait]
+impl Relayer for MockRelayerService {
+    type SubscribePacketsStream = SubscribePacketsStream;
+
+    async fn get_tpu_configs(
+        &self,
+        _request: Request<GetTpuConfigsRequest>,
+    ) -> Result<Response<GetTpuConfigsResponse>, Status> {
+        Ok(Response::new(GetTpuConfigsResponse {
+            tpu: Some(to_socket(self.state.config.tpu)),
+            tpu_forward: Some(to_socket(self.state.config.tpu_forward)),
+        }))
+    }
+
+    async fn subscribe_packets(
+        &self,
+        _request: Request<relayer::SubscribePacketsRequest>,
+    ) -> Result<Response<Self::SubscribePacketsStream>, Status> {
+        self.state
+            .stats
+            .relayer_subscriptions
+            .fetch_add(1, Ordering::Relaxed);
+        let state = self.state.clone();
+        Ok(Response::new(subscribe_packets(
+            &self.state.packet_broadcast,
+            self.state.config.heartbeat_interval,
+            move || {
+                let enabled = state.heartbeats_enabled.load(Ordering::Relaxed);
+                if enabled {
+                    state.stats.heartbeats_sent.fetch_add(1, Ordering::Relaxed);
+                }
+                enabled
+            },
+        )))
+    }
+}
+
+struct MockBlockEngineService {
+    state: Arc<MockProxyState>,
+}
+
+#[tonic::async_trait]
+impl BlockEngineValidator for MockBlockEngineService {
+    type SubscribePacketsStream = SubscribeBlockEnginePacketsStream;
+    type SubscribeBundlesStream = SubscribeBundlesStream;
+
+    /// Packets are sent over the relayer stream, so this stream stays open without sending.
+    async fn subscribe_packets(
+        &self,
+        _request: Request<block_engine::SubscribePacketsRequest>,
+    ) -> Result<Response<Self::SubscribePacketsStream>, Status> {
+        self.state
+            .stats
+            .block_engine_packet_subscriptions
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(subscribe_block_engine_packets()))
+    }
+
+    async fn subscribe_bundles(
+        &self,
+        _request: Request<SubscribeBundlesRequest>,
+    ) -> Result<Response<Self::SubscribeBundlesStream>, Status> {
+        self.state
+            .stats
+            .bundle_subscriptions
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(subscribe_bundles(
+            &self.state.bundle_broadcast,
+        )))
+    }
+
+    async fn get_block_builder_fee_info(
+        &self,
+        _request: Request<BlockBuilderFeeInfoRequest>,
+    ) -> Result<Response<BlockBuilderFeeInfoResponse>, Status> {
+        self.state
+            .stats
+            .block_builder_fee_info_requests
+            .fetch_add(1, Ordering::Relaxed);
+        Ok(Response::new(BlockBuilderFeeInfoResponse {
+            pubkey: self.state.config.block_builder.to_string(),
+            commission: self.state.config.block_builder_commission,
+        }))
+    }
+}
@@ -16,16 +16,26 @@ fn main() -> Result<(), std::io::Error> {
         "relayer.proto",
         "shared.proto",
     ];
+    // protos maintained in this repo rather than in the mev-protos submodule
+    let local_proto_base_path = std::path::PathBuf::from("local_protos");
+    let local_proto_files = ["bundle_results.proto"];
     let mut protos = Vec::new();
-    for proto_file in &proto_files {
-        let proto = proto_base_path.join(proto_file);
+    for proto in proto_files
+        .iter()
+        .map(|proto_file| proto_base_path.join(proto_file))
+        .chain(
+            local_proto_files
+                .iter()
+                .map(|proto_file| local_proto_base_path.join(proto_file)),
+        )
+    {
         println!("cargo:rerun-if-changed={}", proto.display());
         protos.push(proto);
     }
 
     configure()
         .build_client(true)
-        .build_server(false)
+        .build_server(true)
         .type_attribute(
             "TransactionErrorType",
             "#[cfg_attr(test, derive(enum_iterator::Sequence))]",
@@ -34,5 +44,5 @@ fn main() -> Result<(), std::io::Error> {
             "InstructionErrorType",
             "#[cfg_attr(test, derive(enum_iterator::Sequence))]",
         )
-        .compile(&protos, &[proto_base_path])
+        .compile(&protos, &[proto_base_path, local_proto_base_path])
 }
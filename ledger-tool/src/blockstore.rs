@@ -134,7 +134,8 @@ fn analyze_storage(database: &Database) -> Result<()> {
     analyze_column::<PerfSamples>(database, "PerfSamples")?;
     analyze_column::<BlockHeight>(database, "BlockHeight")?;
     analyze_column::<ProgramCosts>(database, "ProgramCosts")?;
-    analyze_column::<OptimisticSlots>(database, "OptimisticSlots")
+    analyze_column::<OptimisticSlots>(database, "OptimisticSlots")?;
+    analyze_column::<SlotTips>(database, "SlotTips")
 }
 
 fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
@@ -166,6 +167,7 @@ fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
         cf::OptimisticSlots::NAME => {
             Some(cf::OptimisticSlots::slot(cf::OptimisticSlots::index(key)))
         }
+        cf::SlotTips::NAME => Some(cf::SlotTips::slot(cf::SlotTips::index(key))),
         &_ => None,
     }
 }
@@ -34,8 +34,16 @@ use {
     },
     solana_cli_output::OutputFormat,
     solana_core::{
+        banking_trace_replay::{
+            slot_leader_vote_account, BankingTraceReplayConfig, BankingTraceReplayer,
+        },
+        bundle_stage::{
+            bundle_auction::BundleOrderingPolicy,
+            bundle_reserved_space_manager::BundleReservedSpacePolicy,
+        },
         system_monitor_service::{SystemMonitorService, SystemMonitorStatsReportConfig},
-        validator::BlockVerificationMethod,
+        tip_manager::{TipDistributionAccountConfig, TipManagerConfig},
+        validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_cost_model::{cost_model::CostModel, cost_tracker::CostTracker},
     solana_ledger::{
@@ -961,6 +969,121 @@ fn main() {
                 .arg(&accounts_db_test_skip_rewrites_but_include_in_bank_hash)
                 .arg(&use_snapshot_archives_at_startup),
         )
+        .subcommand(
+            SubCommand::with_name("replay-banking-trace")
+                .about(
+                    "Replays the packets and bundles in a banking trace through BankingStage and \
+                     BundleStage to rebuild a leader slot on top of its parent",
+                )
+                .arg(&max_genesis_archive_unpacked_size_arg)
+                .arg(&accounts_index_bins)
+                .arg(&accounts_index_limit)
+                .arg(&disable_disk_index)
+                .arg(&accountsdb_verify_refcounts)
+                .arg(&accounts_db_skip_initial_hash_calc_arg)
+                .arg(&use_snapshot_archives_at_startup)
+                .arg(
+                    Arg::with_name("slot")
+                        .index(1)
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Leader slot to replay. The ledger is processed up to its parent"),
+                )
+                .arg(
+                    Arg::with_name("banking_trace_dir")
+                        .long("banking-trace-dir")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .help("Directory of the banking trace [default: <LEDGER>/banking_trace]"),
+                )
+                .arg(
+                    Arg::with_name("block_production_method")
+                        .long("block-production-method")
+                        .value_name("METHOD")
+                        .takes_value(true)
+                        .possible_values(BlockProductionMethod::cli_names())
+                        .help(BlockProductionMethod::cli_message()),
+                )
+                .arg(
+                    Arg::with_name("bundle_ordering_policy")
+                        .long("bundle-ordering-policy")
+                        .value_name("POLICY")
+                        .takes_value(true)
+                        .possible_values(BundleOrderingPolicy::cli_names())
+                        .help(BundleOrderingPolicy::cli_message()),
+                )
+                .arg(
+                    Arg::with_name("bundle_reserved_space_policy")
+                        .long("bundle-reserved-space-policy")
+                        .value_name("POLICY")
+                        .takes_value(true)
+                        .possible_values(BundleReservedSpacePolicy::cli_names())
+                        .help(BundleReservedSpacePolicy::cli_message()),
+                )
+                .arg(
+                    Arg::with_name("preallocated_bundle_cost")
+                        .long("preallocated-bundle-cost")
+                        .value_name("PREALLOCATED_BUNDLE_COST")
+                        .takes_value(true)
+                        .validator(is_parsable::<u64>)
+                        .default_value("3000000")
+                        .help("Number of CUs to allocate for bundles at beginning of slot."),
+                )
+                .arg(
+                    Arg::with_name("tip_payment_program_pubkey")
+                        .long("tip-payment-program-pubkey")
+                        .value_name("TIP_PAYMENT_PROGRAM_PUBKEY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("The public key of the tip-payment program"),
+                )
+                .arg(
+                    Arg::with_name("tip_distribution_program_pubkey")
+                        .long("tip-distribution-program-pubkey")
+                        .value_name("TIP_DISTRIBUTION_PROGRAM_PUBKEY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("The public key of the tip-distribution program"),
+                )
+                .arg(
+                    Arg::with_name("vote_account")
+                        .long("vote-account")
+                        .value_name("ADDRESS")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .help(
+                            "Vote account receiving tips \
+                             [default: vote account of the slot's scheduled leader]",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("merkle_root_upload_authority")
+                        .long("merkle-root-upload-authority")
+                        .value_name("MERKLE_ROOT_UPLOAD_AUTHORITY")
+                        .validator(is_pubkey)
+                        .takes_value(true)
+                        .help(
+                            "The public key of the authorized merkle-root uploader, used if the \
+                             tip distribution account has to be created",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("commission_bps")
+                        .long("commission-bps")
+                        .value_name("COMMISSION_BPS")
+                        .validator(is_parsable::<u16>)
+                        .takes_value(true)
+                        .default_value("0")
+                        .help(
+                            "The commission taken from tips in basis points, used if the tip \
+                             distribution account has to be created",
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("verify")
                 .about("Verify the ledger")
@@ -1609,6 +1732,143 @@ fn main() {
                     );
                     println!("{}", &bank_forks.read().unwrap().working_bank().hash());
                 }
+                ("replay-banking-trace", Some(arg_matches)) => {
+                    let slot = value_t_or_exit!(arg_matches, "slot", Slot);
+                    let mut process_options = parse_process_options(&ledger_path, arg_matches);
+                    let genesis_config = open_genesis_config_by(&ledger_path, arg_matches);
+                    let blockstore = Arc::new(open_blockstore(
+                        &ledger_path,
+                        arg_matches,
+                        get_access_type(&process_options),
+                    ));
+                    let parent_slot = match blockstore
+                        .meta(slot)
+                        .map(|meta| meta.and_then(|meta| meta.parent_slot))
+                    {
+                        Ok(Some(parent_slot)) => parent_slot,
+                        Ok(None) => {
+                            eprintln!("Parent of slot {slot} is unknown to the blockstore");
+                            exit(1);
+                        }
+                        Err(err) => {
+                            eprintln!("Failed to read slot meta for slot {slot}: {err:?}");
+                            exit(1);
+                        }
+                    };
+                    process_options.halt_at_slot = Some(parent_slot);
+                    let banking_trace_dir = arg_matches
+                        .value_of("banking_trace_dir")
+                        .map(PathBuf::from)
+                        .unwrap_or_else(|| blockstore.banking_trace_path());
+                    let replayer =
+                        BankingTraceReplayer::load(&banking_trace_dir).unwrap_or_else(|err| {
+                            eprintln!(
+                                "Failed to load banking trace from {}: {err}",
+                                banking_trace_dir.display()
+                            );
+                            exit(1);
+                        });
+
+                    let (bank_forks, _) = load_and_process_ledger_or_exit(
+                        arg_matches,
+                        &genesis_config,
+                        blockstore.clone(),
+                        process_options,
+                        snapshot_archive_path,
+                        incremental_snapshot_archive_path,
+                        false,
+                    );
+                    let parent = bank_forks.read().unwrap().working_bank();
+                    if parent.slot() != parent_slot {
+                        eprintln!(
+                            "Ledger processing stopped at slot {} instead of parent slot \
+                             {parent_slot}",
+                            parent.slot()
+                        );
+                        exit(1);
+                    }
+                    let vote_account = pubkey_of(arg_matches, "vote_account")
+                        .or_else(|| slot_leader_vote_account(&parent, slot))
+                        .unwrap_or_else(|| {
+                            eprintln!(
+                                "Unable to find the vote account of the leader of slot {slot}, \
+                                 specify one with --vote-account"
+                            );
+                            exit(1);
+                        });
+
+                    let config = BankingTraceReplayConfig {
+                        block_production_method: value_t!(
+                            arg_matches,
+                            "block_production_method",
+                            BlockProductionMethod
+                        )
+                        .unwrap_or_default(),
+                        bundle_ordering_policy: value_t!(
+                            arg_matches,
+                            "bundle_ordering_policy",
+                            BundleOrderingPolicy
+                        )
+                        .unwrap_or_default(),
+                        tip_manager_config: TipManagerConfig {
+                            tip_payment_program_id: pubkey_of(
+                                arg_matches,
+                                "tip_payment_program_pubkey",
+                            )
+                            .unwrap(),
+                            tip_distribution_program_id: pubkey_of(
+                                arg_matches,
+                                "tip_distribution_program_pubkey",
+                            )
+                            .unwrap(),
+                            tip_distribution_account_config: TipDistributionAccountConfig {
+                                merkle_root_upload_authority: pubkey_of(
+                                    arg_matches,
+                                    "merkle_root_upload_authority",
+                                )
+                                .unwrap_or_default(),
+                                vote_account,
+                                commission_bps: value_t_or_exit!(
+                                    arg_matches,
+                                    "commission_bps",
+                                    u16
+                                ),
+                            },
+                        },
+                        preallocated_bundle_cost: value_t_or_exit!(
+                            arg_matches,
+                            "preallocated_bundle_cost",
+                            u64
+                        ),
+                        bundle_reserved_space_policy: value_t!(
+                            arg_matches,
+                            "bundle_reserved_space_policy",
+                            BundleReservedSpacePolicy
+                        )
+                        .unwrap_or_default(),
+                    };
+
+                    match replayer.replay(config, slot, &bank_forks, &genesis_config.poh_config) {
+                        Ok(summary) => {
+                            println!("Slot: {} (parent {})", summary.slot, summary.parent_slot);
+                            println!("Packet batches sent: {}", summary.num_packet_batches);
+                            println!("Bundles sent: {}", summary.num_bundles);
+                            println!("Entries: {}", summary.num_entries);
+                            println!("Transactions: {}", summary.num_transactions);
+                            println!("Bank hash: {}", summary.bank_hash);
+                            match summary.recorded_bank_hash {
+                                Some(recorded_bank_hash) => {
+                                    println!("Recorded bank hash: {recorded_bank_hash}")
+                                }
+                                None => println!("Recorded bank hash: not in trace"),
+                            }
+                        }
+                        Err(err) => {
+                            eprintln!("Failed to replay slot {slot}: {err}");
+                            exit(1);
+                        }
+                    }
+                }
                 ("verify", Some(arg_matches)) => {
                     let exit_signal = Arc::new(AtomicBool::new(false));
                     let report_os_memory_stats =
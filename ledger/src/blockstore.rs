@@ -240,6 +240,7 @@ pub struct Blockstore {
     optimistic_slots_cf: LedgerColumn<cf::OptimisticSlots>,
     max_root: AtomicU64,
     merkle_root_meta_cf: LedgerColumn<cf::MerkleRootMeta>,
+    slot_tips_cf: LedgerColumn<cf::SlotTips>,
     insert_shreds_lock: Mutex<()>,
     new_shreds_signals: Mutex<Vec<Sender<bool>>>,
     completed_slots_senders: Mutex<Vec<CompletedSlotsSender>>,
@@ -342,6 +343,7 @@ impl Blockstore {
         let bank_hash_cf = db.column();
         let optimistic_slots_cf = db.column();
         let merkle_root_meta_cf = db.column();
+        let slot_tips_cf = db.column();
 
         let db = Arc::new(db);
 
@@ -380,6 +382,7 @@ impl Blockstore {
             bank_hash_cf,
             optimistic_slots_cf,
             merkle_root_meta_cf,
+            slot_tips_cf,
             new_shreds_signals: Mutex::default(),
             completed_slots_senders: Mutex::default(),
             shred_timing_point_sender: None,
@@ -754,6 +757,7 @@ impl Blockstore {
         self.bank_hash_cf.submit_rocksdb_cf_metrics();
         self.optimistic_slots_cf.submit_rocksdb_cf_metrics();
         self.merkle_root_meta_cf.submit_rocksdb_cf_metrics();
+        self.slot_tips_cf.submit_rocksdb_cf_metrics();
     }
 
     /// Report the accumulated RPC API metrics
@@ -3412,6 +3416,16 @@ impl Blockstore {
         Ok(iter.take(num).collect())
     }
 
+    pub fn write_slot_tips(&self, slot: Slot, slot_tips: &SlotTips) -> Result<()> {
+        self.slot_tips_cf.put(slot, slot_tips)
+    }
+
+    /// Returns the tips paid during `slot`, if this validator was leader for it and committed
+    /// bundles that paid tips
+    pub fn get_slot_tips(&self, slot: Slot) -> Result<Option<SlotTips>> {
+        self.slot_tips_cf.get(slot)
+    }
+
     pub fn set_duplicate_confirmed_slots_and_hashes(
         &self,
         duplicate_confirmed_slot_hashes: impl Iterator<Item = (Slot, Hash)>,
@@ -9655,6 +9669,33 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_write_slot_tips() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let tip_account = Pubkey::new_unique();
+        let slot_tips = SlotTips {
+            total_tip_lamports: 1_000,
+            block_builder: Pubkey::new_unique(),
+            block_builder_commission: 5,
+            block_builder_commission_lamports: 50,
+            tip_accounts: vec![(tip_account, 1_000)],
+            bundles: vec![BundleTips {
+                bundle_id: "bundle".to_string(),
+                tip_lamports: 1_000,
+                tip_accounts: vec![(tip_account, 1_000)],
+            }],
+        };
+        blockstore.write_slot_tips(10, &slot_tips).unwrap();
+
+        assert_eq!(blockstore.get_slot_tips(10).unwrap(), Some(slot_tips));
+        assert_eq!(blockstore.get_slot_tips(11).unwrap(), None);
+
+        blockstore.purge_slots(10, 10, PurgeType::Exact);
+        assert_eq!(blockstore.get_slot_tips(10).unwrap(), None);
+    }
+
     #[test]
     fn test_lowest_slot() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
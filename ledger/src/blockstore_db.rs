@@ -106,6 +106,8 @@ const PROGRAM_COSTS_CF: &str = "program_costs";
 const OPTIMISTIC_SLOTS_CF: &str = "optimistic_slots";
 /// Column family for merkle roots
 const MERKLE_ROOT_META_CF: &str = "merkle_root_meta";
+/// Column family for tips paid to this validator during its leader slots
+const SLOT_TIPS_CF: &str = "slot_tips";
 
 #[derive(Error, Debug)]
 pub enum BlockstoreError {
@@ -355,6 +357,13 @@ pub mod columns {
     /// * value type: [`blockstore_meta::MerkleRootMeta`]`
     pub struct MerkleRootMeta;
 
+    #[derive(Debug)]
+    /// The slot tips column
+    ///
+    /// * index type: `u64` (see [`SlotColumn`])
+    /// * value type: [`blockstore_meta::SlotTips`]
+    pub struct SlotTips;
+
     // When adding a new column ...
     // - Add struct below and implement `Column` and `ColumnName` traits
     // - Add descriptor in Rocks::cf_descriptors() and name in Rocks::columns()
@@ -494,6 +503,7 @@ impl Rocks {
             new_cf_descriptor::<ProgramCosts>(options, oldest_slot),
             new_cf_descriptor::<OptimisticSlots>(options, oldest_slot),
             new_cf_descriptor::<MerkleRootMeta>(options, oldest_slot),
+            new_cf_descriptor::<SlotTips>(options, oldest_slot),
         ];
 
         // If the access type is Secondary, we don't need to open all of the
@@ -567,6 +577,7 @@ impl Rocks {
             ProgramCosts::NAME,
             OptimisticSlots::NAME,
             MerkleRootMeta::NAME,
+            SlotTips::NAME,
         ]
     }
 
@@ -1322,6 +1333,14 @@ impl TypedColumn for columns::MerkleRootMeta {
     type Type = MerkleRootMeta;
 }
 
+impl SlotColumn for columns::SlotTips {}
+impl ColumnName for columns::SlotTips {
+    const NAME: &'static str = SLOT_TIPS_CF;
+}
+impl TypedColumn for columns::SlotTips {
+    type Type = blockstore_meta::SlotTips;
+}
+
 #[derive(Debug)]
 pub struct Database {
     backend: Arc<Rocks>,
@@ -5,6 +5,7 @@ use {
     solana_sdk::{
         clock::{Slot, UnixTimestamp},
         hash::Hash,
+        pubkey::Pubkey,
     },
     std::{
         collections::BTreeSet,
@@ -533,6 +534,28 @@ impl OptimisticSlotMetaVersioned {
         }
     }
 }
+
+/// Tips paid to the tip accounts during one of this validator's leader slots
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SlotTips {
+    pub total_tip_lamports: u64,
+    /// Block builder and commission percent in effect when the slot's bundles executed
+    pub block_builder: Pubkey,
+    pub block_builder_commission: u64,
+    pub block_builder_commission_lamports: u64,
+    /// Lamports deposited into each tip account
+    pub tip_accounts: Vec<(Pubkey, u64)>,
+    /// Tips paid by each bundle, in the order the bundles were committed
+    pub bundles: Vec<BundleTips>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct BundleTips {
+    pub bundle_id: String,
+    pub tip_lamports: u64,
+    pub tip_accounts: Vec<(Pubkey, u64)>,
+}
+
 #[cfg(test)]
 mod test {
     use {
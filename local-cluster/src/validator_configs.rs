@@ -71,9 +71,12 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         unified_scheduler_handler_threads: config.unified_scheduler_handler_threads,
         relayer_config: config.relayer_config.clone(),
         block_engine_config: config.block_engine_config.clone(),
+        embedded_relayer_config: config.embedded_relayer_config.clone(),
         shred_receiver_address: config.shred_receiver_address.clone(),
         tip_manager_config: config.tip_manager_config.clone(),
         preallocated_bundle_cost: config.preallocated_bundle_cost,
+        bundle_ordering_policy: config.bundle_ordering_policy,
+        bundle_reserved_space_policy: config.bundle_reserved_space_policy,
     }
 }
 
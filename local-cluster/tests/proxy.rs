@@ -0,0 +1,193 @@
+//! Runs validators against a mock relayer and block engine to cover the proxy stages end to end.
+#![allow(clippy::arithmetic_side_effects)]
+use {
+    jito_mock_proxy::{bundle_from_transactions, MockProxy, MockProxyConfig},
+    serial_test::serial,
+    solana_client::connection_cache::Protocol,
+    solana_core::{proxy::relayer_stage::RelayerConfig, validator::ValidatorConfig},
+    solana_local_cluster::{
+        integration_tests::{DEFAULT_CLUSTER_LAMPORTS, DEFAULT_NODE_STAKE},
+        local_cluster::{ClusterConfig, LocalCluster},
+        validator_configs::make_identical_validator_configs,
+    },
+    solana_rpc_client::rpc_client::RpcClient,
+    solana_sdk::{
+        commitment_config::CommitmentConfig,
+        signature::{Keypair, Signer},
+        system_transaction,
+        transaction::VersionedTransaction,
+    },
+    solana_streamer::socket::SocketAddrSpace,
+    std::{
+        net::SocketAddr,
+        sync::atomic::{AtomicU64, Ordering},
+        thread::sleep,
+        time::{Duration, Instant},
+    },
+};
+
+fn start_cluster(config: ValidatorConfig) -> LocalCluster {
+    let mut cluster_config = ClusterConfig {
+        validator_configs: make_identical_validator_configs(&config, 1),
+        node_stakes: vec![DEFAULT_NODE_STAKE],
+        cluster_lamports: DEFAULT_CLUSTER_LAMPORTS,
+        ..ClusterConfig::default()
+    };
+    LocalCluster::new(&mut cluster_config, SocketAddrSpace::Unspecified)
+}
+
+fn relayer_config(url: String) -> RelayerConfig {
+    RelayerConfig {
+        relayer_url: url,
+        expected_heartbeat_interval: Duration::from_millis(500),
+        oldest_allowed_heartbeat: Duration::from_millis(1_500),
+        ..RelayerConfig::default()
+    }
+}
+
+fn wait_for(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if condition() {
+            return true;
+        }
+        sleep(Duration::from_millis(100));
+    }
+    false
+}
+
+fn load(counter: &AtomicU64) -> u64 {
+    counter.load(Ordering::Relaxed)
+}
+
+fn advertised_tpu(cluster: &LocalCluster) -> Option<SocketAddr> {
+    let validator = cluster.validators.values().next()?.validator.as_ref()?;
+    validator
+        .cluster_info
+        .my_contact_info()
+        .tpu(Protocol::UDP)
+        .ok()
+}
+
+#[test]
+#[serial]
+fn test_bundles_from_block_engine_land() {
+    solana_logger::setup_with_default("solana_core::proxy=info");
+    let mock = MockProxy::start(MockProxyConfig::default()).unwrap();
+    let config = ValidatorConfig::default_for_test();
+    config.block_engine_config.lock().unwrap().block_engine_urls = vec![mock.url()];
+    let cluster = start_cluster(config);
+
+    assert!(wait_for(Duration::from_secs(30), || load(
+        &mock.stats().bundle_subscriptions
+    ) > 0));
+    assert!(load(&mock.stats().block_builder_fee_info_requests) > 0);
+    assert!(load(&mock.stats().auth.tokens_generated) > 0);
+
+    let rpc_client = RpcClient::new_socket_with_commitment(
+        cluster.entry_point_info.rpc().unwrap(),
+        CommitmentConfig::processed(),
+    );
+    let receiver = Keypair::new();
+    let lamports = 1_000_000;
+    let transaction = VersionedTransaction::from(system_transaction::transfer(
+        &cluster.funding_keypair,
+        &receiver.pubkey(),
+        lamports,
+        rpc_client.get_latest_blockhash().unwrap(),
+    ));
+
+    // the bundle only lands while this node is leader, so keep sending it until it does
+    let mut num_bundles_sent = 0;
+    assert!(wait_for(Duration::from_secs(30), || {
+        num_bundles_sent += 1;
+        mock.send_bundles(vec![bundle_from_transactions(
+            format!("bundle-{num_bundles_sent}"),
+            &[transaction.clone()],
+        )]);
+        rpc_client
+            .get_balance(&receiver.pubkey())
+            .unwrap_or_default()
+            == lamports
+    }));
+}
+
+#[test]
+#[serial]
+fn test_relayer_heartbeats_switch_tpu() {
+    solana_logger::setup_with_default("solana_core::proxy=info");
+    let mock = MockProxy::start(MockProxyConfig::default()).unwrap();
+    let mock_tpu = mock.config().tpu;
+    let config = ValidatorConfig::default_for_test();
+    *config.relayer_config.lock().unwrap() = relayer_config(mock.url());
+    let cluster = start_cluster(config);
+
+    let fallback_tpu = advertised_tpu(&cluster).unwrap();
+    assert_ne!(fallback_tpu, mock_tpu);
+
+    // the fetch stage manager only switches over after heartbeats have been steady for a while
+    assert!(wait_for(Duration::from_secs(90), || advertised_tpu(
+        &cluster
+    ) == Some(mock_tpu)));
+    assert!(load(&mock.stats().heartbeats_sent) > 0);
+
+    // missed heartbeats switch back to the validator's own tpu and drop the stream
+    let num_subscriptions = load(&mock.stats().relayer_subscriptions);
+    mock.set_heartbeats_enabled(false);
+    assert!(wait_for(Duration::from_secs(10), || advertised_tpu(
+        &cluster
+    ) != Some(mock_tpu)));
+    assert!(wait_for(Duration::from_secs(30), || load(
+        &mock.stats().relayer_subscriptions
+    ) > num_subscriptions));
+}
+
+#[test]
+#[serial]
+fn test_auth_tokens_are_refreshed() {
+    solana_logger::setup_with_default("solana_core::proxy=info");
+    let mock = MockProxy::start(MockProxyConfig {
+        access_token_ttl: Duration::from_secs(3),
+        refresh_token_ttl: Duration::from_secs(6),
+        ..MockProxyConfig::default()
+    })
+    .unwrap();
+    let config = ValidatorConfig::default_for_test();
+    *config.relayer_config.lock().unwrap() = relayer_config(mock.url());
+    let _cluster = start_cluster(config);
+
+    // access tokens are refreshed before they expire, and once the refresh token expires the
+    // client has to go through the challenge again
+    assert!(wait_for(Duration::from_secs(30), || load(
+        &mock.stats().auth.access_tokens_refreshed
+    ) > 0
+        && load(&mock.stats().auth.tokens_generated) > 1));
+    assert!(load(&mock.stats().auth.auth_challenges) > 1);
+}
+
+#[test]
+#[serial]
+fn test_proxy_reconnects_after_restart() {
+    solana_logger::setup_with_default("solana_core::proxy=info");
+    let mock = MockProxy::start(MockProxyConfig::default()).unwrap();
+    let address = mock.address();
+    let config = ValidatorConfig::default_for_test();
+    *config.relayer_config.lock().unwrap() = relayer_config(mock.url());
+    config.block_engine_config.lock().unwrap().block_engine_urls = vec![mock.url()];
+    let _cluster = start_cluster(config);
+
+    assert!(wait_for(Duration::from_secs(30), || {
+        load(&mock.stats().relayer_subscriptions) > 0
+            && load(&mock.stats().bundle_subscriptions) > 0
+    }));
+
+    mock.shutdown();
+    let mock = MockProxy::start_on(address, MockProxyConfig::default()).unwrap();
+
+    // the old tokens aren't known to the new server, so the stages have to authenticate again
+    assert!(wait_for(Duration::from_secs(60), || {
+        load(&mock.stats().relayer_subscriptions) > 0
+            && load(&mock.stats().bundle_subscriptions) > 0
+    }));
+    assert!(load(&mock.stats().auth.tokens_generated) > 0);
+}
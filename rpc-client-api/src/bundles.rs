@@ -3,7 +3,11 @@
 use {
     crate::config::RpcSimulateTransactionAccountsConfig,
     solana_account_decoder::UiAccount,
-    solana_bundle::{bundle_execution::LoadAndExecuteBundleError, BundleExecutionError},
+    solana_bundle::{
+        bundle_execution::LoadAndExecuteBundleError,
+        bundle_status::{BundleOutcome, BundleStatus},
+        BundleExecutionError,
+    },
     solana_sdk::{
         clock::Slot,
         commitment_config::{CommitmentConfig, CommitmentLevel},
@@ -12,9 +16,14 @@ use {
     },
     solana_svm::transaction_results::TransactionExecutionResult,
     solana_transaction_status::{UiTransactionEncoding, UiTransactionReturnData},
+    std::collections::HashMap,
     thiserror::Error,
 };
 
+// Changing this? Update the JSON RPC docs!
+pub const MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS: usize = 256;
+pub const MAX_SIMULATE_BUNDLES_QUERY_ITEMS: usize = 5;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcBundleSimulationSummary {
@@ -51,6 +60,9 @@ pub enum RpcBundleExecutionError {
 
     #[error("A transaction in the bundle failed to execute: [signature={0}, error={1}]")]
     TransactionFailure(Signature, String),
+
+    #[error("Bundle rejected by a runtime plugin: {0}")]
+    RejectedByPolicy(String),
 }
 
 impl From<BundleExecutionError> for RpcBundleExecutionError {
@@ -93,6 +105,7 @@ impl From<BundleExecutionError> for RpcBundleExecutionError {
             BundleExecutionError::LockError => Self::BundleLockError,
             BundleExecutionError::PohRecordError(e) => Self::PohRecordError(e.to_string()),
             BundleExecutionError::TipError(e) => Self::TipError(e.to_string()),
+            BundleExecutionError::RejectedByPolicy(reason) => Self::RejectedByPolicy(reason),
         }
     }
 }
@@ -136,6 +149,53 @@ pub struct RpcSimulateBundleConfig {
     /// Replace recent blockhash to simulate old transactions without resigning.
     #[serde(default)]
     pub replace_recent_blockhash: bool,
+
+    /// Account state to simulate against in place of the bank's, keyed by base-58 pubkey.
+    #[serde(default)]
+    pub account_overrides: HashMap<String, RpcAccountOverride>,
+}
+
+/// Replaces parts of an account's state for simulation. Fields that aren't set keep the bank's
+/// value, or the default for an account that doesn't exist.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountOverride {
+    pub lamports: Option<u64>,
+    /// Base-64 encoded account data
+    pub data: Option<String>,
+    /// Base-58 encoded owner program id
+    pub owner: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundlesConfig {
+    /// Pre/post transaction execution account configs for each bundle. When set, the outer length
+    /// must equal the number of bundles and each inner length the number of transactions in that
+    /// bundle.
+    #[serde(default)]
+    pub pre_execution_accounts_configs: Vec<Vec<Option<RpcSimulateTransactionAccountsConfig>>>,
+    #[serde(default)]
+    pub post_execution_accounts_configs: Vec<Vec<Option<RpcSimulateTransactionAccountsConfig>>>,
+
+    /// Specifies the encoding scheme of the contained transactions.
+    pub transaction_encoding: Option<UiTransactionEncoding>,
+
+    /// Specifies the bank to run simulation against.
+    pub simulation_bank: Option<SimulationSlotConfig>,
+
+    /// Opt to skip sig-verify for faster performance.
+    #[serde(default)]
+    pub skip_sig_verify: bool,
+
+    /// Replace recent blockhash to simulate old transactions without resigning.
+    #[serde(default)]
+    pub replace_recent_blockhash: bool,
+
+    /// Account state to simulate the first bundle against in place of the bank's, keyed by
+    /// base-58 pubkey.
+    #[serde(default)]
+    pub account_overrides: HashMap<String, RpcAccountOverride>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug)]
@@ -164,3 +224,81 @@ impl Default for SimulationSlotConfig {
 pub struct RpcBundleRequest {
     pub encoded_transactions: Vec<String>,
 }
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSendBundleConfig {
+    /// Specifies the encoding scheme of the contained transactions.
+    pub transaction_encoding: Option<UiTransactionEncoding>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcBundleOutcome {
+    Landed,
+    Failed,
+    Dropped,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBundleStatus {
+    pub bundle_id: String,
+    pub slot: Slot,
+    pub outcome: RpcBundleOutcome,
+    /// reason the bundle failed to land, only set when outcome is failed
+    pub err: Option<RpcBundleExecutionError>,
+    /// signature of the transaction that caused the bundle to fail, if applicable
+    pub failing_signature: Option<String>,
+    pub compute_units_consumed: u64,
+    pub tip_lamports: u64,
+}
+
+impl From<BundleStatus> for RpcBundleStatus {
+    fn from(bundle_status: BundleStatus) -> Self {
+        let failing_signature = bundle_status.failing_signature().map(|s| s.to_string());
+        let (outcome, err) = match bundle_status.outcome {
+            BundleOutcome::Landed => (RpcBundleOutcome::Landed, None),
+            BundleOutcome::Failed(e) => (RpcBundleOutcome::Failed, Some(e.into())),
+            BundleOutcome::Dropped => (RpcBundleOutcome::Dropped, None),
+        };
+        Self {
+            bundle_id: bundle_status.bundle_id,
+            slot: bundle_status.slot,
+            outcome,
+            err,
+            failing_signature,
+            compute_units_consumed: bundle_status.compute_units_consumed,
+            tip_lamports: bundle_status.tip_lamports,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTipAccountLamports {
+    pub tip_account: String,
+    pub lamports: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBundleTips {
+    pub bundle_id: String,
+    pub tip_lamports: u64,
+    pub tip_accounts: Vec<RpcTipAccountLamports>,
+}
+
+/// Tips paid to the tip accounts during one of the validator's leader slots
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSlotTips {
+    pub slot: Slot,
+    pub total_tip_lamports: u64,
+    pub block_builder: String,
+    /// percent of the tips owed to the block builder
+    pub block_builder_commission: u64,
+    pub block_builder_commission_lamports: u64,
+    pub tip_accounts: Vec<RpcTipAccountLamports>,
+    pub bundles: Vec<RpcBundleTips>,
+}
@@ -166,6 +166,18 @@ pub struct RpcProgramAccountsConfig {
     pub with_context: Option<bool>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPaginatedConfig {
+    pub filters: Option<Vec<RpcFilterType>>,
+    #[serde(flatten)]
+    pub account_config: RpcAccountInfoConfig,
+    pub page_size: Option<usize>,
+    /// Continuation token returned with the previous page; the commitment and minimum context
+    /// slot are ignored when resuming from a cursor
+    pub cursor: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcTransactionLogsFilter {
@@ -214,6 +226,28 @@ pub struct RpcBlockSubscribeConfig {
     pub max_supported_transaction_version: Option<u8>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionSubscribeFilter {
+    /// Include vote transactions; defaults to false
+    pub vote: Option<bool>,
+    /// Include failed transactions; defaults to true
+    pub failed: Option<bool>,
+    /// Only include transactions that mention at least one of these accounts
+    pub account_include: Option<Vec<String>>,
+    /// Exclude transactions that mention any of these accounts
+    pub account_exclude: Option<Vec<String>>,
+    /// Only include transactions that mention all of these accounts
+    pub account_required: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionSubscribeConfig {
+    pub encoding: Option<UiTransactionEncoding>,
+    pub max_supported_transaction_version: Option<u8>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcSignaturesForAddressConfig {
@@ -333,6 +367,15 @@ impl RpcBlocksConfigWrapper {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPriorityFeeEstimateConfig {
+    /// Percentiles to estimate, each between 0 and 100 (100 being the maximum fee paid)
+    pub percentiles: Option<Vec<u8>>,
+    /// Number of most recent finalized blocks to sample; defaults to every cached block
+    pub slot_window: Option<usize>,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcContextConfig {
@@ -20,6 +20,7 @@ pub enum RpcRequest {
     GetBlocks,
     GetBlocksWithLimit,
     GetBlockTime,
+    GetBundleStatuses,
     GetClusterNodes,
     #[deprecated(since = "1.7.0", note = "Please use RpcRequest::GetBlock instead")]
     GetConfirmedBlock,
@@ -72,7 +73,9 @@ pub enum RpcRequest {
     GetMaxShredInsertSlot,
     GetMinimumBalanceForRentExemption,
     GetMultipleAccounts,
+    GetPriorityFeeEstimate,
     GetProgramAccounts,
+    GetProgramAccountsPaginated,
     #[deprecated(
         since = "1.9.0",
         note = "Please use RpcRequest::GetLatestBlockhash instead"
@@ -98,6 +101,7 @@ pub enum RpcRequest {
     GetStakeMinimumDelegation,
     GetStoragePubkeysForSlot,
     GetSupply,
+    GetTipsForSlot,
     GetTokenAccountBalance,
     GetTokenAccountsByDelegate,
     GetTokenAccountsByOwner,
@@ -105,6 +109,7 @@ pub enum RpcRequest {
     GetTokenSupply,
     GetTransaction,
     GetTransactionCount,
+    GetTransactionRetryStatus,
     GetVersion,
     GetVoteAccounts,
     IsBlockhashValid,
@@ -114,6 +119,8 @@ pub enum RpcRequest {
     SendTransaction,
     SimulateTransaction,
     SimulateBundle,
+    SimulateBundles,
+    SendBundle,
     SignVote,
 }
 
@@ -131,6 +138,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetBlocks => "getBlocks",
             RpcRequest::GetBlocksWithLimit => "getBlocksWithLimit",
             RpcRequest::GetBlockTime => "getBlockTime",
+            RpcRequest::GetBundleStatuses => "getBundleStatuses",
             RpcRequest::GetClusterNodes => "getClusterNodes",
             RpcRequest::GetConfirmedBlock => "getConfirmedBlock",
             RpcRequest::GetConfirmedBlocks => "getConfirmedBlocks",
@@ -157,7 +165,9 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetMaxShredInsertSlot => "getMaxShredInsertSlot",
             RpcRequest::GetMinimumBalanceForRentExemption => "getMinimumBalanceForRentExemption",
             RpcRequest::GetMultipleAccounts => "getMultipleAccounts",
+            RpcRequest::GetPriorityFeeEstimate => "getPriorityFeeEstimate",
             RpcRequest::GetProgramAccounts => "getProgramAccounts",
+            RpcRequest::GetProgramAccountsPaginated => "getProgramAccountsPaginated",
             RpcRequest::GetRecentBlockhash => "getRecentBlockhash",
             RpcRequest::GetRecentPerformanceSamples => "getRecentPerformanceSamples",
             RpcRequest::GetRecentPrioritizationFees => "getRecentPrioritizationFees",
@@ -175,6 +185,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetSlotsPerSegment => "getSlotsPerSegment",
             RpcRequest::GetStoragePubkeysForSlot => "getStoragePubkeysForSlot",
             RpcRequest::GetSupply => "getSupply",
+            RpcRequest::GetTipsForSlot => "getTipsForSlot",
             RpcRequest::GetTokenAccountBalance => "getTokenAccountBalance",
             RpcRequest::GetTokenAccountsByDelegate => "getTokenAccountsByDelegate",
             RpcRequest::GetTokenAccountsByOwner => "getTokenAccountsByOwner",
@@ -182,6 +193,7 @@ impl fmt::Display for RpcRequest {
             RpcRequest::GetTokenLargestAccounts => "getTokenLargestAccounts",
             RpcRequest::GetTransaction => "getTransaction",
             RpcRequest::GetTransactionCount => "getTransactionCount",
+            RpcRequest::GetTransactionRetryStatus => "getTransactionRetryStatus",
             RpcRequest::GetVersion => "getVersion",
             RpcRequest::GetVoteAccounts => "getVoteAccounts",
             RpcRequest::IsBlockhashValid => "isBlockhashValid",
@@ -191,6 +203,8 @@ impl fmt::Display for RpcRequest {
             RpcRequest::SendTransaction => "sendTransaction",
             RpcRequest::SimulateTransaction => "simulateTransaction",
             RpcRequest::SimulateBundle => "simulateBundle",
+            RpcRequest::SimulateBundles => "simulateBundles",
+            RpcRequest::SendBundle => "sendBundle",
             RpcRequest::SignVote => "signVote",
         };
 
@@ -206,7 +220,11 @@ pub const MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT: usize = 1_000;
 pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 pub const NUM_LARGEST_ACCOUNTS: usize = 20;
 pub const MAX_GET_PROGRAM_ACCOUNT_FILTERS: usize = 4;
+pub const DEFAULT_GET_PROGRAM_ACCOUNTS_PAGE_SIZE: usize = 1_000;
+pub const MAX_GET_PROGRAM_ACCOUNTS_PAGE_SIZE: usize = 10_000;
 pub const MAX_GET_SLOT_LEADERS: usize = 5000;
+pub const MAX_PRIORITY_FEE_ESTIMATE_PERCENTILES: usize = 20;
+pub const DEFAULT_PRIORITY_FEE_ESTIMATE_PERCENTILES: [u8; 5] = [25, 50, 75, 95, 100];
 
 // Limit the length of the `epoch_credits` array for each validator in a `get_vote_accounts`
 // response
@@ -10,8 +10,9 @@ use {
         transaction::{Result, TransactionError},
     },
     solana_transaction_status::{
-        ConfirmedTransactionStatusWithSignature, TransactionConfirmationStatus, UiConfirmedBlock,
-        UiInnerInstructions, UiTransactionReturnData,
+        ConfirmedTransactionStatusWithSignature, EncodedTransactionWithStatusMeta,
+        TransactionConfirmationStatus, UiConfirmedBlock, UiInnerInstructions,
+        UiTransactionReturnData,
     },
     std::{collections::HashMap, fmt, net::SocketAddr, str::FromStr},
     thiserror::Error,
@@ -210,6 +211,14 @@ pub struct RpcKeyedAccount {
     pub account: UiAccount,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPage {
+    pub accounts: Vec<RpcKeyedAccount>,
+    /// Continuation token for the next page, `None` once the scan is complete
+    pub cursor: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SlotInfo {
     pub slot: Slot,
@@ -538,6 +547,23 @@ pub struct RpcBlockUpdate {
     pub err: Option<RpcBlockUpdateError>,
 }
 
+#[derive(Clone, Deserialize, Serialize, Debug, Error, Eq, PartialEq)]
+pub enum RpcTransactionUpdateError {
+    #[error("unsupported transaction version ({0})")]
+    UnsupportedTransactionVersion(u8),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionUpdate {
+    pub signature: String,
+    pub slot: Slot,
+    pub index: usize,
+    pub is_vote: bool,
+    pub transaction: Option<EncodedTransactionWithStatusMeta>,
+    pub err: Option<RpcTransactionUpdateError>,
+}
+
 impl From<ConfirmedTransactionStatusWithSignature> for RpcConfirmedTransactionStatusWithSignature {
     fn from(value: ConfirmedTransactionStatusWithSignature) -> Self {
         let ConfirmedTransactionStatusWithSignature {
@@ -571,6 +597,47 @@ pub struct RpcPrioritizationFee {
     pub prioritization_fee: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPriorityFeePercentile {
+    pub percentile: u8,
+    pub prioritization_fee: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcPriorityFeeEstimate {
+    pub min_slot: Slot,
+    pub max_slot: Slot,
+    pub num_slots: u64,
+    pub num_samples: u64,
+    pub fees: Vec<RpcPriorityFeePercentile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcTransactionRetryState {
+    /// Still being retried by the node
+    Pending,
+    Rooted,
+    /// The transaction's blockhash, or durable nonce, is no longer valid
+    Expired,
+    MaxRetriesElapsed,
+    /// Landed with an error
+    Failed,
+    /// Not retried because the node's retry pool was full
+    Dropped,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionRetryStatus {
+    pub state: RpcTransactionRetryState,
+    pub retries: usize,
+    pub max_retries: Option<usize>,
+    pub last_valid_block_height: u64,
+}
+
 #[cfg(test)]
 pub mod tests {
 
@@ -14,9 +14,11 @@ use {
             Response, RpcAccountBalance, RpcBlockProduction, RpcBlockProductionRange, RpcBlockhash,
             RpcConfirmedTransactionStatusWithSignature, RpcContactInfo, RpcFees, RpcIdentity,
             RpcInflationGovernor, RpcInflationRate, RpcInflationReward, RpcKeyedAccount,
-            RpcPerfSample, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult,
-            RpcSnapshotSlotInfo, RpcStakeActivation, RpcSupply, RpcVersionInfo, RpcVoteAccountInfo,
-            RpcVoteAccountStatus, StakeActivationState,
+            RpcPerfSample, RpcPrioritizationFee, RpcPriorityFeeEstimate, RpcPriorityFeePercentile,
+            RpcProgramAccountsPage, RpcResponseContext, RpcSimulateTransactionResult,
+            RpcSnapshotSlotInfo, RpcStakeActivation, RpcSupply, RpcTransactionRetryState,
+            RpcTransactionRetryStatus, RpcVersionInfo, RpcVoteAccountInfo, RpcVoteAccountStatus,
+            StakeActivationState,
         },
     },
     solana_sdk::{
@@ -428,6 +430,22 @@ impl RpcSender for MockSender {
                 slot: 123_456_789,
                 prioritization_fee: 10_000,
             }])?,
+            "getPriorityFeeEstimate" => serde_json::to_value(Some(RpcPriorityFeeEstimate {
+                min_slot: 123_456_639,
+                max_slot: 123_456_789,
+                num_slots: 150,
+                num_samples: 1_000,
+                fees: vec![RpcPriorityFeePercentile {
+                    percentile: 50,
+                    prioritization_fee: 10_000,
+                }],
+            }))?,
+            "getTransactionRetryStatus" => serde_json::to_value(Some(RpcTransactionRetryStatus {
+                state: RpcTransactionRetryState::Pending,
+                retries: 1,
+                max_retries: None,
+                last_valid_block_height: 1234,
+            }))?,
             "getIdentity" => serde_json::to_value(RpcIdentity {
                 identity: PUBKEY.to_string(),
             })?,
@@ -460,6 +478,13 @@ impl RpcSender for MockSender {
                 context: RpcResponseContext { slot: 1, api_version: None },
                 value: vec![Value::Null, Value::Null]
             })?,
+            "getProgramAccountsPaginated" => serde_json::to_value(Response {
+                context: RpcResponseContext { slot: 1, api_version: None },
+                value: RpcProgramAccountsPage {
+                    accounts: vec![],
+                    cursor: None,
+                },
+            })?,
             "getProgramAccounts" => {
                 let pubkey = Pubkey::from_str(PUBKEY).unwrap();
                 let account = Account {
@@ -34,8 +34,8 @@ use {
     },
     solana_rpc_client_api::{
         bundles::{
-            RpcBundleRequest, RpcSimulateBundleConfig, RpcSimulateBundleResult,
-            SimulationSlotConfig,
+            RpcBundleRequest, RpcBundleStatus, RpcSendBundleConfig, RpcSimulateBundleConfig,
+            RpcSimulateBundleResult, RpcSimulateBundlesConfig, RpcSlotTips, SimulationSlotConfig,
         },
         client_error::{
             Error as ClientError, ErrorKind as ClientErrorKind, Result as ClientResult,
@@ -1525,6 +1525,86 @@ impl RpcClient {
         .await
     }
 
+    /// Simulates the bundles one after another on the same bank, with each bundle seeing the state
+    /// left by the bundles that succeeded before it. Returns a result for each bundle.
+    pub async fn simulate_bundles_with_config(
+        &self,
+        bundles: &[VersionedBundle],
+        config: RpcSimulateBundlesConfig,
+    ) -> RpcResult<Vec<RpcSimulateBundleResult>> {
+        let transaction_encoding = if let Some(enc) = config.transaction_encoding {
+            enc
+        } else {
+            self.default_cluster_transaction_encoding().await?
+        };
+        let simulation_bank = Some(config.simulation_bank.unwrap_or_default());
+
+        let rpc_bundle_requests = bundles
+            .iter()
+            .map(|bundle| {
+                let encoded_transactions = bundle
+                    .transactions
+                    .iter()
+                    .map(|tx| {
+                        serialize_and_encode::<VersionedTransaction>(tx, transaction_encoding)
+                    })
+                    .collect::<ClientResult<Vec<String>>>()?;
+                Ok(RpcBundleRequest {
+                    encoded_transactions,
+                })
+            })
+            .collect::<ClientResult<Vec<RpcBundleRequest>>>()?;
+
+        let config = RpcSimulateBundlesConfig {
+            transaction_encoding: Some(transaction_encoding),
+            simulation_bank,
+            ..config
+        };
+
+        self.send(
+            RpcRequest::SimulateBundles,
+            json!([rpc_bundle_requests, config]),
+        )
+        .await
+    }
+
+    /// Submits a bundle directly to the node's BundleStage and returns the bundle id, which can be
+    /// passed to [`get_bundle_statuses`](Self::get_bundle_statuses). The node must have sendBundle
+    /// enabled and at least one transaction must be signed by one of its allowed signers.
+    pub async fn send_bundle(&self, bundle: &VersionedBundle) -> ClientResult<String> {
+        let transaction_encoding = self.default_cluster_transaction_encoding().await?;
+        let encoded_transactions = bundle
+            .transactions
+            .iter()
+            .map(|tx| serialize_and_encode::<VersionedTransaction>(tx, transaction_encoding))
+            .collect::<ClientResult<Vec<String>>>()?;
+        let rpc_bundle_request = RpcBundleRequest {
+            encoded_transactions,
+        };
+        let config = RpcSendBundleConfig {
+            transaction_encoding: Some(transaction_encoding),
+        };
+
+        self.send(RpcRequest::SendBundle, json!([rpc_bundle_request, config]))
+            .await
+    }
+
+    /// Returns the most recent outcome this node observed for each bundle id, or `None` if the
+    /// node has no record of the bundle.
+    pub async fn get_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> RpcResult<Vec<Option<RpcBundleStatus>>> {
+        self.send(RpcRequest::GetBundleStatuses, json!([bundle_ids]))
+            .await
+    }
+
+    /// Returns the tips paid during `slot` if this node was the leader for it, or `None` if the
+    /// node didn't record any tips for the slot.
+    pub async fn get_tips_for_slot(&self, slot: Slot) -> ClientResult<Option<RpcSlotTips>> {
+        self.send(RpcRequest::GetTipsForSlot, json!([slot])).await
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -3694,6 +3774,128 @@ impl RpcClient {
             .await
     }
 
+    /// Returns percentile estimates of the prioritization fees paid in recent blocks.
+    /// Takes an optional vector of addresses; if any addresses are provided, each estimate
+    /// reflects the fees paid by transactions locking those accounts as writable, and is never
+    /// lower than the block-wide estimate.
+    ///
+    /// Estimates the 25th, 50th, 75th and 95th percentiles and the maximum fee, over every block
+    /// in the node's prioritization-fee cache. Returns `None` if the node has no finalized blocks
+    /// to estimate from.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPriorityFeeEstimate` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let alice = Keypair::new();
+    /// let addresses = vec![alice.pubkey()];
+    /// let estimate = rpc_client.get_priority_fee_estimate(&addresses).await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_priority_fee_estimate(
+        &self,
+        addresses: &[Pubkey],
+    ) -> ClientResult<Option<RpcPriorityFeeEstimate>> {
+        self.get_priority_fee_estimate_with_config(
+            addresses,
+            RpcPriorityFeeEstimateConfig::default(),
+        )
+        .await
+    }
+
+    /// Returns percentile estimates of the prioritization fees paid in recent blocks.
+    ///
+    /// The percentiles to estimate and the number of recent blocks to sample can be set with
+    /// [`RpcPriorityFeeEstimateConfig`].
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPriorityFeeEstimate` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{
+    /// #     client_error::Error,
+    /// #     config::RpcPriorityFeeEstimateConfig,
+    /// # };
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let alice = Keypair::new();
+    /// let addresses = vec![alice.pubkey()];
+    /// let config = RpcPriorityFeeEstimateConfig {
+    ///     percentiles: Some(vec![50, 90]),
+    ///     slot_window: Some(20),
+    /// };
+    /// let estimate = rpc_client
+    ///     .get_priority_fee_estimate_with_config(&addresses, config)
+    ///     .await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_priority_fee_estimate_with_config(
+        &self,
+        addresses: &[Pubkey],
+        config: RpcPriorityFeeEstimateConfig,
+    ) -> ClientResult<Option<RpcPriorityFeeEstimate>> {
+        let addresses: Vec<_> = addresses
+            .iter()
+            .map(|address| address.to_string())
+            .collect();
+        self.send(
+            RpcRequest::GetPriorityFeeEstimate,
+            json!([addresses, config]),
+        )
+        .await
+    }
+
+    /// Returns whether the node is still retrying a transaction sent through it.
+    ///
+    /// Returns `None` if the node doesn't know about the transaction, either because it wasn't
+    /// sent through this node or because it left the node's retry pool too long ago.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getTransactionRetryStatus` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::Signature;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let signature = Signature::default();
+    /// let status = rpc_client.get_transaction_retry_status(&signature).await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_transaction_retry_status(
+        &self,
+        signature: &Signature,
+    ) -> ClientResult<Option<RpcTransactionRetryStatus>> {
+        self.send(
+            RpcRequest::GetTransactionRetryStatus,
+            json!([signature.to_string()]),
+        )
+        .await
+    }
+
     /// Returns the identity pubkey for the current node.
     ///
     /// # RPC Reference
@@ -4584,6 +4786,78 @@ impl RpcClient {
         parse_keyed_accounts(accounts, RpcRequest::GetProgramAccounts)
     }
 
+    /// Returns one page of the accounts owned by the provided program pubkey, in pubkey order,
+    /// along with the cursor to request the next page with, if there is one.
+    ///
+    /// Pass the cursor back in `config.cursor` to continue the scan. Every page of a scan shows the
+    /// accounts as of the same bank, provided each page is requested within a minute of the
+    /// previous one and the scan takes less than ten minutes; the node stops holding on to the
+    /// bank after that, and the cursor expires.
+    /// See also [`RpcClient::get_program_accounts_paginated`], which
+    /// iterates over all pages.
+    ///
+    /// [`RpcClient::get_program_accounts_paginated`]: crate::rpc_client::RpcClient::get_program_accounts_paginated
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getProgramAccountsPaginated` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{
+    /// #     client_error::Error,
+    /// #     config::RpcProgramAccountsPaginatedConfig,
+    /// # };
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let alice = Keypair::new();
+    /// let mut config = RpcProgramAccountsPaginatedConfig {
+    ///     page_size: Some(100),
+    ///     ..RpcProgramAccountsPaginatedConfig::default()
+    /// };
+    /// loop {
+    ///     let (accounts, cursor) = rpc_client
+    ///         .get_program_accounts_page(&alice.pubkey(), config.clone())
+    ///         .await?;
+    ///     // process `accounts`
+    ///     if cursor.is_none() {
+    ///         break;
+    ///     }
+    ///     config.cursor = cursor;
+    /// }
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_program_accounts_page(
+        &self,
+        pubkey: &Pubkey,
+        mut config: RpcProgramAccountsPaginatedConfig,
+    ) -> ClientResult<(Vec<(Pubkey, Account)>, Option<String>)> {
+        let commitment = config
+            .account_config
+            .commitment
+            .unwrap_or_else(|| self.commitment());
+        let commitment = self.maybe_map_commitment(commitment).await?;
+        config.account_config.commitment = Some(commitment);
+        if let Some(filters) = config.filters {
+            config.filters = Some(self.maybe_map_filters(filters).await?);
+        }
+
+        let RpcProgramAccountsPage { accounts, cursor } = self
+            .send::<Response<RpcProgramAccountsPage>>(
+                RpcRequest::GetProgramAccountsPaginated,
+                json!([pubkey.to_string(), config]),
+            )
+            .await?
+            .value;
+        let accounts = parse_keyed_accounts(accounts, RpcRequest::GetProgramAccountsPaginated)?;
+        Ok((accounts, cursor))
+    }
+
     /// Returns the stake minimum delegation, in lamports.
     ///
     /// # RPC Reference
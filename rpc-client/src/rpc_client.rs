@@ -28,7 +28,10 @@ use {
         UiAccount, UiAccountEncoding,
     },
     solana_rpc_client_api::{
-        bundles::{RpcSimulateBundleConfig, RpcSimulateBundleResult},
+        bundles::{
+            RpcBundleStatus, RpcSimulateBundleConfig, RpcSimulateBundleResult,
+            RpcSimulateBundlesConfig, RpcSlotTips,
+        },
         client_error::{Error as ClientError, ErrorKind, Result as ClientResult},
         config::{RpcAccountInfoConfig, *},
         request::{RpcRequest, TokenAccountsFilter},
@@ -115,6 +118,39 @@ pub struct GetConfirmedSignaturesForAddress2Config {
     pub commitment: Option<CommitmentConfig>,
 }
 
+/// Iterator over the pages of a program's accounts, see
+/// [`RpcClient::get_program_accounts_paginated`]
+pub struct ProgramAccountsPages<'a> {
+    rpc_client: &'a RpcClient,
+    program_id: Pubkey,
+    config: RpcProgramAccountsPaginatedConfig,
+    is_finished: bool,
+}
+
+impl Iterator for ProgramAccountsPages<'_> {
+    type Item = ClientResult<Vec<(Pubkey, Account)>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_finished {
+            return None;
+        }
+        match self
+            .rpc_client
+            .get_program_accounts_page(&self.program_id, self.config.clone())
+        {
+            Ok((accounts, cursor)) => {
+                self.is_finished = cursor.is_none();
+                self.config.cursor = cursor;
+                Some(Ok(accounts))
+            }
+            Err(err) => {
+                self.is_finished = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
 /// A client of a remote Solana node.
 ///
 /// `RpcClient` communicates with a Solana node over [JSON-RPC], with the
@@ -1181,6 +1217,38 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).simulate_bundle_with_config(bundle, config))
     }
 
+    /// Simulates the bundles one after another on the same bank, with each bundle seeing the state
+    /// left by the bundles that succeeded before it. Returns a result for each bundle.
+    pub fn simulate_bundles_with_config(
+        &self,
+        bundles: &[VersionedBundle],
+        config: RpcSimulateBundlesConfig,
+    ) -> RpcResult<Vec<RpcSimulateBundleResult>> {
+        self.invoke((self.rpc_client.as_ref()).simulate_bundles_with_config(bundles, config))
+    }
+
+    /// Submits a bundle directly to the node's BundleStage and returns the bundle id, which can be
+    /// passed to [`get_bundle_statuses`](Self::get_bundle_statuses). The node must have sendBundle
+    /// enabled and at least one transaction must be signed by one of its allowed signers.
+    pub fn send_bundle(&self, bundle: &VersionedBundle) -> ClientResult<String> {
+        self.invoke((self.rpc_client.as_ref()).send_bundle(bundle))
+    }
+
+    /// Returns the most recent outcome this node observed for each bundle id, or `None` if the
+    /// node has no record of the bundle.
+    pub fn get_bundle_statuses(
+        &self,
+        bundle_ids: &[String],
+    ) -> RpcResult<Vec<Option<RpcBundleStatus>>> {
+        self.invoke((self.rpc_client.as_ref()).get_bundle_statuses(bundle_ids))
+    }
+
+    /// Returns the tips paid during `slot` if this node was the leader for it, or `None` if the
+    /// node didn't record any tips for the slot.
+    pub fn get_tips_for_slot(&self, slot: Slot) -> ClientResult<Option<RpcSlotTips>> {
+        self.invoke((self.rpc_client.as_ref()).get_tips_for_slot(slot))
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -2978,6 +3046,103 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_recent_prioritization_fees(addresses))
     }
 
+    /// Returns percentile estimates of the prioritization fees paid in recent blocks.
+    /// Takes an optional vector of addresses; if any addresses are provided, each estimate
+    /// reflects the fees paid by transactions locking those accounts as writable, and is never
+    /// lower than the block-wide estimate.
+    ///
+    /// Estimates the 25th, 50th, 75th and 95th percentiles and the maximum fee, over every block
+    /// in the node's prioritization-fee cache. Returns `None` if the node has no finalized blocks
+    /// to estimate from.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPriorityFeeEstimate` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// # let alice = Keypair::new();
+    /// let addresses = vec![alice.pubkey()];
+    /// let estimate = rpc_client.get_priority_fee_estimate(&addresses)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_priority_fee_estimate(
+        &self,
+        addresses: &[Pubkey],
+    ) -> ClientResult<Option<RpcPriorityFeeEstimate>> {
+        self.invoke((self.rpc_client.as_ref()).get_priority_fee_estimate(addresses))
+    }
+
+    /// Returns percentile estimates of the prioritization fees paid in recent blocks.
+    ///
+    /// The percentiles to estimate and the number of recent blocks to sample can be set with
+    /// [`RpcPriorityFeeEstimateConfig`].
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getPriorityFeeEstimate` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{
+    /// #     client_error::Error,
+    /// #     config::RpcPriorityFeeEstimateConfig,
+    /// # };
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// # let alice = Keypair::new();
+    /// let addresses = vec![alice.pubkey()];
+    /// let config = RpcPriorityFeeEstimateConfig {
+    ///     percentiles: Some(vec![50, 90]),
+    ///     slot_window: Some(20),
+    /// };
+    /// let estimate = rpc_client.get_priority_fee_estimate_with_config(&addresses, config)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_priority_fee_estimate_with_config(
+        &self,
+        addresses: &[Pubkey],
+        config: RpcPriorityFeeEstimateConfig,
+    ) -> ClientResult<Option<RpcPriorityFeeEstimate>> {
+        self.invoke(
+            (self.rpc_client.as_ref()).get_priority_fee_estimate_with_config(addresses, config),
+        )
+    }
+
+    /// Returns whether the node is still retrying a transaction sent through it.
+    ///
+    /// Returns `None` if the node doesn't know about the transaction, either because it wasn't
+    /// sent through this node or because it left the node's retry pool too long ago.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getTransactionRetryStatus` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::Signature;
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// # let signature = Signature::default();
+    /// let status = rpc_client.get_transaction_retry_status(&signature)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_transaction_retry_status(
+        &self,
+        signature: &Signature,
+    ) -> ClientResult<Option<RpcTransactionRetryStatus>> {
+        self.invoke((self.rpc_client.as_ref()).get_transaction_retry_status(signature))
+    }
+
     /// Returns the identity pubkey for the current node.
     ///
     /// # RPC Reference
@@ -3661,6 +3826,69 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_program_accounts_with_config(pubkey, config))
     }
 
+    /// Returns one page of the accounts owned by the provided program pubkey, in pubkey order,
+    /// along with the cursor to request the next page with, if there is one.
+    ///
+    /// Pass the cursor back in `config.cursor` to continue the scan. Every page of a scan shows the
+    /// accounts as of the same bank, provided each page is requested within a minute of the
+    /// previous one and the scan takes less than ten minutes; the node stops holding on to the
+    /// bank after that, and the cursor expires.
+    /// See also [`RpcClient::get_program_accounts_paginated`].
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getProgramAccountsPaginated` RPC method.
+    pub fn get_program_accounts_page(
+        &self,
+        pubkey: &Pubkey,
+        config: RpcProgramAccountsPaginatedConfig,
+    ) -> ClientResult<(Vec<(Pubkey, Account)>, Option<String>)> {
+        self.invoke((self.rpc_client.as_ref()).get_program_accounts_page(pubkey, config))
+    }
+
+    /// Returns an iterator over the pages of accounts owned by the provided program pubkey.
+    ///
+    /// Each page is fetched with [`RpcClient::get_program_accounts_page`] as the iterator is
+    /// advanced, starting from `config.cursor`. The iterator ends after the last page, or after
+    /// the first error.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `getProgramAccountsPaginated` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{
+    /// #     client_error::Error,
+    /// #     config::RpcProgramAccountsPaginatedConfig,
+    /// # };
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// # let alice = Keypair::new();
+    /// let config = RpcProgramAccountsPaginatedConfig {
+    ///     page_size: Some(100),
+    ///     ..RpcProgramAccountsPaginatedConfig::default()
+    /// };
+    /// for page in rpc_client.get_program_accounts_paginated(&alice.pubkey(), config) {
+    ///     let accounts = page?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_program_accounts_paginated(
+        &self,
+        pubkey: &Pubkey,
+        config: RpcProgramAccountsPaginatedConfig,
+    ) -> ProgramAccountsPages<'_> {
+        ProgramAccountsPages {
+            rpc_client: self,
+            program_id: *pubkey,
+            config,
+            is_finished: false,
+        }
+    }
+
     /// Returns the stake minimum delegation, in lamports.
     ///
     /// # RPC Reference
@@ -1,8 +1,13 @@
 //! The `rpc` module implements the Solana RPC interface.
 use {
     crate::{
-        max_slots::MaxSlots, optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        max_slots::MaxSlots,
+        optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
+        parsed_token_accounts::*,
+        rpc_bundle_sender::RpcBundleSender,
+        rpc_cache::{LargestAccountsCache, PinnedBank, PinnedBankCache},
+        rpc_health::*,
+        rpc_request_limiter::RpcRequestLimitsConfig,
     },
     base64::{prelude::BASE64_STANDARD, Engine},
     bincode::{config::Options, serialize},
@@ -19,6 +24,7 @@ use {
         inline_spl_token::{SPL_TOKEN_ACCOUNT_MINT_OFFSET, SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
         inline_spl_token_2022::{self, ACCOUNTTYPE_ACCOUNT},
     },
+    solana_bundle::bundle_status::BundleStatusCache,
     solana_client::connection_cache::{ConnectionCache, Protocol},
     solana_entry::entry::Entry,
     solana_faucet::faucet::request_airdrop_transaction,
@@ -26,23 +32,30 @@ use {
     solana_ledger::{
         blockstore::{Blockstore, SignatureInfosForAddress},
         blockstore_db::BlockstoreError,
-        blockstore_meta::{PerfSample, PerfSampleV1, PerfSampleV2},
+        blockstore_meta::{BundleTips, PerfSample, PerfSampleV1, PerfSampleV2, SlotTips},
         get_tmp_ledger_path,
         leader_schedule_cache::LeaderScheduleCache,
     },
     solana_metrics::inc_new_counter_info,
     solana_perf::packet::PACKET_DATA_SIZE,
     solana_rpc_client_api::{
+        bundles::{
+            RpcBundleStatus, RpcBundleTips, RpcSlotTips, RpcTipAccountLamports,
+            SimulationSlotConfig, MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS,
+        },
         config::*,
         custom_error::RpcCustomError,
         deprecated_config::*,
         filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
         request::{
-            TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
+            TokenAccountsFilter, DEFAULT_GET_PROGRAM_ACCOUNTS_PAGE_SIZE,
+            DEFAULT_PRIORITY_FEE_ESTIMATE_PERCENTILES, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
-            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+            MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS_SLOT_RANGE,
+            MAX_GET_PROGRAM_ACCOUNTS_PAGE_SIZE, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
             MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_PRIORITY_FEE_ESTIMATE_PERCENTILES, MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY,
+            NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
@@ -59,7 +72,7 @@ use {
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount},
         account_utils::StateMut,
-        clock::{Slot, UnixTimestamp, MAX_RECENT_BLOCKHASHES},
+        clock::{BankId, Slot, UnixTimestamp, MAX_RECENT_BLOCKHASHES},
         commitment_config::{CommitmentConfig, CommitmentLevel},
         epoch_info::EpochInfo,
         epoch_schedule::EpochSchedule,
@@ -80,7 +93,10 @@ use {
         },
     },
     solana_send_transaction_service::{
-        send_transaction_service::{SendTransactionService, TransactionInfo},
+        retry_status_cache::{
+            TransactionRetryState, TransactionRetryStatus, TransactionRetryStatusCache,
+        },
+        send_transaction_service::{self, SendTransactionService, TransactionInfo},
         tpu_info::NullTpuInfo,
     },
     solana_stake_program,
@@ -103,7 +119,7 @@ use {
         cmp::{max, min},
         collections::{HashMap, HashSet},
         convert::TryFrom,
-        net::SocketAddr,
+        net::{IpAddr, SocketAddr},
         str::FromStr,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -118,6 +134,13 @@ pub mod account_resolver;
 type RpcCustomResult<T> = std::result::Result<T, RpcCustomError>;
 
 pub const MAX_REQUEST_BODY_SIZE: usize = 50 * (1 << 10); // 50kB
+/// How long a `getProgramAccountsPaginated` cursor stays valid after it was last used. Accounts
+/// clean is held back to the oldest pinned bank's root until then.
+const PAGINATED_SCAN_BANK_PIN_DURATION: Duration = Duration::from_secs(60);
+/// How long a `getProgramAccountsPaginated` cursor stays valid after the scan started, however
+/// often it is used, so a slow client can't hold back clean indefinitely.
+const PAGINATED_SCAN_BANK_MAX_PIN_AGE: Duration = Duration::from_secs(10 * 60);
+const MAX_PAGINATED_SCAN_PINNED_BANKS: usize = 16;
 pub const PERFORMANCE_SAMPLES_LIMIT: usize = 720;
 
 fn new_response<T>(bank: &Bank, value: T) -> RpcResponse<T> {
@@ -154,6 +177,11 @@ pub struct JsonRpcConfig {
     pub max_request_body_size: Option<usize>,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
+    /// Enables sendBundle, which injects bundles directly into BundleStage
+    pub enable_send_bundle: bool,
+    /// Bundles submitted over sendBundle must be signed by one of these
+    pub send_bundle_allowed_signers: HashSet<Pubkey>,
+    pub request_limits: RpcRequestLimitsConfig,
 }
 
 impl JsonRpcConfig {
@@ -210,6 +238,14 @@ pub struct JsonRpcRequestProcessor {
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     max_complete_rewards_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+    bundle_status_cache: Arc<BundleStatusCache>,
+    bundle_sender: Option<RpcBundleSender>,
+    /// Banks that `getProgramAccountsPaginated` cursors are anchored to
+    pinned_banks: Arc<Mutex<PinnedBankCache>>,
+    /// What the send-transaction-service did with the transactions sent through this node
+    retry_status_cache: Arc<TransactionRetryStatusCache>,
+    /// Client that sent the request being processed, see `rpc_request_limiter::client_ip`
+    client_ip: Option<IpAddr>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -324,6 +360,8 @@ impl JsonRpcRequestProcessor {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        bundle_status_cache: Arc<BundleStatusCache>,
+        bundle_sender: Option<RpcBundleSender>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (sender, receiver) = unbounded();
         (
@@ -346,11 +384,32 @@ impl JsonRpcRequestProcessor {
                 max_complete_transaction_status_slot,
                 max_complete_rewards_slot,
                 prioritization_fee_cache,
+                bundle_status_cache,
+                bundle_sender,
+                pinned_banks: Arc::new(Mutex::new(PinnedBankCache::new(
+                    PAGINATED_SCAN_BANK_PIN_DURATION,
+                    PAGINATED_SCAN_BANK_MAX_PIN_AGE,
+                    MAX_PAGINATED_SCAN_PINNED_BANKS,
+                ))),
+                retry_status_cache: Arc::new(TransactionRetryStatusCache::default()),
+                client_ip: None,
             },
             receiver,
         )
     }
 
+    pub(crate) fn with_client_ip(self, client_ip: Option<IpAddr>) -> Self {
+        Self { client_ip, ..self }
+    }
+
+    pub(crate) fn client_ip(&self) -> Option<IpAddr> {
+        self.client_ip
+    }
+
+    pub(crate) fn retry_status_cache(&self) -> Arc<TransactionRetryStatusCache> {
+        self.retry_status_cache.clone()
+    }
+
     // Useful for unit testing
     pub fn new_from_bank(
         bank: Bank,
@@ -372,14 +431,19 @@ impl JsonRpcRequestProcessor {
         });
 
         let (sender, receiver) = unbounded();
-        SendTransactionService::new::<NullTpuInfo>(
+        let retry_status_cache = Arc::new(TransactionRetryStatusCache::default());
+        SendTransactionService::new_with_config::<NullTpuInfo>(
             cluster_info.clone(),
             &bank_forks,
             None,
             receiver,
             &connection_cache,
-            1000,
-            1,
+            send_transaction_service::Config {
+                retry_rate_ms: 1000,
+                leader_forward_count: 1,
+                ..send_transaction_service::Config::default()
+            },
+            retry_status_cache.clone(),
             exit.clone(),
         );
 
@@ -417,6 +481,15 @@ impl JsonRpcRequestProcessor {
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             max_complete_rewards_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Arc::new(PrioritizationFeeCache::default()),
+            bundle_status_cache: Arc::new(BundleStatusCache::default()),
+            bundle_sender: None,
+            pinned_banks: Arc::new(Mutex::new(PinnedBankCache::new(
+                PAGINATED_SCAN_BANK_PIN_DURATION,
+                PAGINATED_SCAN_BANK_MAX_PIN_AGE,
+                MAX_PAGINATED_SCAN_PINNED_BANKS,
+            ))),
+            retry_status_cache,
+            client_ip: None,
         }
     }
 
@@ -502,27 +575,114 @@ impl JsonRpcRequestProcessor {
                 self.get_filtered_program_accounts(&bank, program_id, filters)?
             }
         };
-        let accounts = if is_known_spl_token_id(program_id)
-            && encoding == UiAccountEncoding::JsonParsed
-        {
-            get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
-        } else {
-            keyed_accounts
-                .into_iter()
-                .map(|(pubkey, account)| {
-                    Ok(RpcKeyedAccount {
-                        pubkey: pubkey.to_string(),
-                        account: encode_account(&account, &pubkey, encoding, data_slice_config)?,
-                    })
-                })
-                .collect::<Result<Vec<_>>>()?
-        };
+        let accounts = encode_program_accounts(
+            &bank,
+            program_id,
+            keyed_accounts,
+            encoding,
+            data_slice_config,
+        )?;
         Ok(match with_context {
             true => OptionalContext::Context(new_response(&bank, accounts)),
             false => OptionalContext::NoContext(accounts),
         })
     }
 
+    pub fn get_program_accounts_paginated(
+        &self,
+        program_id: &Pubkey,
+        config: Option<RpcAccountInfoConfig>,
+        mut filters: Vec<RpcFilterType>,
+        page_size: usize,
+        cursor: Option<ProgramAccountsCursor>,
+    ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+        let RpcAccountInfoConfig {
+            encoding,
+            data_slice: data_slice_config,
+            commitment,
+            min_context_slot,
+        } = config.unwrap_or_default();
+        if self
+            .config
+            .account_indexes
+            .contains(&AccountIndex::ProgramId)
+            && !self.config.account_indexes.include_key(program_id)
+        {
+            return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                index_key: program_id.to_string(),
+            }
+            .into());
+        }
+        let pinned_bank = match &cursor {
+            Some(cursor) => self.get_pinned_bank(cursor)?,
+            None => {
+                let bank = self.get_bank_with_config(RpcContextConfig {
+                    commitment,
+                    min_context_slot,
+                })?;
+                self.pinned_banks.lock().unwrap().pin(&bank)
+            }
+        };
+        let PinnedBank { bank, scan_root } = pinned_bank.as_ref();
+
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        optimize_filters(&mut filters);
+        let keyed_accounts = bank
+            .get_filtered_program_accounts_page(
+                program_id,
+                |account| {
+                    filters
+                        .iter()
+                        .all(|filter_type| filter_type.allows(account))
+                },
+                cursor.as_ref().map(|cursor| &cursor.last_pubkey),
+                page_size,
+                scan_root,
+                &ScanConfig::default(),
+                bank.byte_limit_for_scans(),
+            )
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })?;
+        // a short page means the scan reached the end of the index
+        let next_cursor = keyed_accounts
+            .last()
+            .filter(|_| keyed_accounts.len() >= page_size)
+            .map(|(last_pubkey, _)| {
+                ProgramAccountsCursor {
+                    slot: bank.slot(),
+                    bank_id: bank.bank_id(),
+                    last_pubkey: *last_pubkey,
+                }
+                .encode()
+            });
+        let accounts = encode_program_accounts(
+            bank,
+            program_id,
+            keyed_accounts,
+            encoding,
+            data_slice_config,
+        )?;
+        Ok(new_response(
+            bank,
+            RpcProgramAccountsPage {
+                accounts,
+                cursor: next_cursor,
+            },
+        ))
+    }
+
+    /// The bank a `getProgramAccountsPaginated` cursor is anchored to, while still pinned
+    fn get_pinned_bank(&self, cursor: &ProgramAccountsCursor) -> Result<Arc<PinnedBank>> {
+        let pinned_bank = self.pinned_banks.lock().unwrap().get(cursor.bank_id);
+        pinned_bank.ok_or_else(|| {
+            Error::invalid_params(format!(
+                "Cursor expired: bank for slot {} is no longer pinned",
+                cursor.slot
+            ))
+        })
+    }
+
     pub async fn get_inflation_reward(
         &self,
         addresses: Vec<Pubkey>,
@@ -2196,6 +2356,89 @@ impl JsonRpcRequestProcessor {
             })
             .collect())
     }
+
+    fn get_priority_fee_estimate(
+        &self,
+        pubkeys: Vec<Pubkey>,
+        percentiles: Vec<u8>,
+        slot_window: usize,
+    ) -> Result<Option<RpcPriorityFeeEstimate>> {
+        Ok(self
+            .prioritization_fee_cache
+            .get_prioritization_fee_percentiles(&pubkeys, &percentiles, slot_window)
+            .map(|estimate| RpcPriorityFeeEstimate {
+                min_slot: estimate.min_slot,
+                max_slot: estimate.max_slot,
+                num_slots: estimate.num_slots as u64,
+                num_samples: estimate.num_samples as u64,
+                fees: percentiles
+                    .into_iter()
+                    .zip(estimate.fees)
+                    .map(
+                        |(percentile, prioritization_fee)| RpcPriorityFeePercentile {
+                            percentile,
+                            prioritization_fee,
+                        },
+                    )
+                    .collect(),
+            }))
+    }
+
+    /// Returns the bank to simulate bundles against
+    fn bundle_simulation_bank(
+        &self,
+        simulation_bank: Option<SimulationSlotConfig>,
+    ) -> Result<Arc<Bank>> {
+        match simulation_bank.unwrap_or_default() {
+            SimulationSlotConfig::Commitment(commitment) => Ok(self.bank(Some(commitment))),
+            SimulationSlotConfig::Slot(slot) => self.bank_from_slot(slot).ok_or_else(|| {
+                Error::invalid_params(format!("bank not found for the provided slot: {}", slot))
+            }),
+            SimulationSlotConfig::Tip => Ok(self.bank_forks.read().unwrap().working_bank()),
+        }
+    }
+
+    fn get_bundle_statuses(
+        &self,
+        bundle_ids: Vec<String>,
+    ) -> Result<RpcResponse<Vec<Option<RpcBundleStatus>>>> {
+        let bank = self.bank(Some(CommitmentConfig::processed()));
+        let statuses = bundle_ids
+            .iter()
+            .map(|bundle_id| {
+                self.bundle_status_cache
+                    .get(bundle_id)
+                    .map(RpcBundleStatus::from)
+            })
+            .collect();
+        Ok(new_response(&bank, statuses))
+    }
+
+    fn get_transaction_retry_status(
+        &self,
+        signature: &Signature,
+    ) -> Option<RpcTransactionRetryStatus> {
+        self.retry_status_cache
+            .get(signature)
+            .map(rpc_transaction_retry_status)
+    }
+}
+
+fn rpc_transaction_retry_status(status: TransactionRetryStatus) -> RpcTransactionRetryStatus {
+    let state = match status.state {
+        TransactionRetryState::Pending => RpcTransactionRetryState::Pending,
+        TransactionRetryState::Rooted => RpcTransactionRetryState::Rooted,
+        TransactionRetryState::Expired => RpcTransactionRetryState::Expired,
+        TransactionRetryState::MaxRetriesElapsed => RpcTransactionRetryState::MaxRetriesElapsed,
+        TransactionRetryState::Failed => RpcTransactionRetryState::Failed,
+        TransactionRetryState::Dropped => RpcTransactionRetryState::Dropped,
+    };
+    RpcTransactionRetryStatus {
+        state,
+        retries: status.retries,
+        max_retries: status.max_retries,
+        last_valid_block_height: status.last_valid_block_height,
+    }
 }
 
 fn optimize_filters(filters: &mut [RpcFilterType]) {
@@ -2225,6 +2468,68 @@ fn verify_transaction(
     Ok(())
 }
 
+/// Where a `getProgramAccountsPaginated` scan resumes: the slot and id of the bank every page is
+/// scanned against and the last pubkey returned so far. The id tells apart banks of competing
+/// forks at the same slot. Clients treat the encoding as opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramAccountsCursor {
+    slot: Slot,
+    bank_id: BankId,
+    last_pubkey: Pubkey,
+}
+
+impl ProgramAccountsCursor {
+    const ENCODED_LEN: usize =
+        std::mem::size_of::<Slot>() + std::mem::size_of::<BankId>() + std::mem::size_of::<Pubkey>();
+
+    fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(Self::ENCODED_LEN);
+        bytes.extend_from_slice(&self.slot.to_le_bytes());
+        bytes.extend_from_slice(&self.bank_id.to_le_bytes());
+        bytes.extend_from_slice(self.last_pubkey.as_ref());
+        bs58::encode(bytes).into_string()
+    }
+
+    fn decode(input: &str) -> Result<Self> {
+        let invalid_cursor = || Error::invalid_params("Invalid param: cursor");
+        let bytes = bs58::decode(input)
+            .into_vec()
+            .map_err(|_| invalid_cursor())?;
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(invalid_cursor());
+        }
+        let (slot, rest) = bytes.split_at(std::mem::size_of::<Slot>());
+        let (bank_id, last_pubkey) = rest.split_at(std::mem::size_of::<BankId>());
+        Ok(Self {
+            slot: Slot::from_le_bytes(slot.try_into().unwrap()),
+            bank_id: BankId::from_le_bytes(bank_id.try_into().unwrap()),
+            last_pubkey: Pubkey::try_from(last_pubkey).unwrap(),
+        })
+    }
+}
+
+fn encode_program_accounts(
+    bank: &Arc<Bank>,
+    program_id: &Pubkey,
+    keyed_accounts: Vec<(Pubkey, AccountSharedData)>,
+    encoding: UiAccountEncoding,
+    data_slice_config: Option<UiDataSliceConfig>,
+) -> Result<Vec<RpcKeyedAccount>> {
+    if is_known_spl_token_id(program_id) && encoding == UiAccountEncoding::JsonParsed {
+        Ok(get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect())
+    } else {
+        keyed_accounts
+            .into_iter()
+            .map(|(pubkey, account)| {
+                Ok(RpcKeyedAccount {
+                    pubkey: pubkey.to_string(),
+                    account: encode_account(&account, &pubkey, encoding, data_slice_config)?,
+                })
+            })
+            .collect()
+    }
+}
+
 fn verify_filter(input: &RpcFilterType) -> Result<()> {
     input
         .verify()
@@ -3133,6 +3438,14 @@ pub mod rpc_accounts_scan {
             config: Option<RpcProgramAccountsConfig>,
         ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>>;
 
+        #[rpc(meta, name = "getProgramAccountsPaginated")]
+        fn get_program_accounts_paginated(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPaginatedConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>>;
+
         #[rpc(meta, name = "getLargestAccounts")]
         fn get_largest_accounts(
             &self,
@@ -3213,6 +3526,51 @@ pub mod rpc_accounts_scan {
             meta.get_program_accounts(&program_id, config, filters, with_context)
         }
 
+        fn get_program_accounts_paginated(
+            &self,
+            meta: Self::Metadata,
+            program_id_str: String,
+            config: Option<RpcProgramAccountsPaginatedConfig>,
+        ) -> Result<RpcResponse<RpcProgramAccountsPage>> {
+            debug!(
+                "get_program_accounts_paginated rpc request received: {:?}",
+                program_id_str
+            );
+            let program_id = verify_pubkey(&program_id_str)?;
+            let RpcProgramAccountsPaginatedConfig {
+                filters,
+                account_config,
+                page_size,
+                cursor,
+            } = config.unwrap_or_default();
+            let filters = filters.unwrap_or_default();
+            if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+                return Err(Error::invalid_params(format!(
+                    "Too many filters provided; max {MAX_GET_PROGRAM_ACCOUNT_FILTERS}"
+                )));
+            }
+            for filter in &filters {
+                verify_filter(filter)?;
+            }
+            let page_size = page_size.unwrap_or(DEFAULT_GET_PROGRAM_ACCOUNTS_PAGE_SIZE);
+            if page_size == 0 || page_size > MAX_GET_PROGRAM_ACCOUNTS_PAGE_SIZE {
+                return Err(Error::invalid_params(format!(
+                    "Invalid page size; max {MAX_GET_PROGRAM_ACCOUNTS_PAGE_SIZE}"
+                )));
+            }
+            let cursor = cursor
+                .as_deref()
+                .map(ProgramAccountsCursor::decode)
+                .transpose()?;
+            meta.get_program_accounts_paginated(
+                &program_id,
+                Some(account_config),
+                filters,
+                page_size,
+                cursor,
+            )
+        }
+
         fn get_largest_accounts(
             &self,
             meta: Self::Metadata,
@@ -3281,24 +3639,42 @@ pub mod rpc_accounts_scan {
 
 pub mod utils {
     use {
-        crate::rpc::encode_account,
-        jsonrpc_core::Error,
+        crate::rpc::{
+            decode_and_deserialize, encode_account, sanitize_transaction, verify_transaction,
+        },
+        base64::{prelude::BASE64_STANDARD, Engine},
+        jsonrpc_core::{Error, ErrorCode},
         solana_account_decoder::{UiAccount, UiAccountEncoding},
         solana_bundle::{
-            bundle_execution::{LoadAndExecuteBundleError, LoadAndExecuteBundleOutput},
+            bundle_execution::{
+                load_and_execute_bundle, LoadAndExecuteBundleError, LoadAndExecuteBundleOutput,
+            },
             BundleExecutionError,
         },
         solana_rpc_client_api::{
             bundles::{
-                RpcBundleExecutionError, RpcBundleSimulationSummary, RpcSimulateBundleConfig,
-                RpcSimulateBundleResult, RpcSimulateBundleTransactionResult,
+                RpcAccountOverride, RpcBundleExecutionError, RpcBundleRequest,
+                RpcBundleSimulationSummary, RpcSimulateBundleResult,
+                RpcSimulateBundleTransactionResult,
             },
             config::RpcSimulateTransactionAccountsConfig,
         },
-        solana_sdk::{account::AccountSharedData, pubkey::Pubkey},
-        std::str::FromStr,
+        solana_runtime::bank::Bank,
+        solana_sdk::{
+            account::{AccountSharedData, WritableAccount},
+            bundle::{derive_bundle_id, SanitizedBundle},
+            clock::MAX_PROCESSING_AGE,
+            pubkey::Pubkey,
+            system_instruction::MAX_PERMITTED_DATA_LENGTH,
+            transaction::{SanitizedTransaction, VersionedTransaction},
+        },
+        solana_svm::account_overrides::AccountOverrides,
+        solana_transaction_status::UiTransactionEncoding,
+        std::{collections::HashMap, str::FromStr, time::Duration},
     };
 
+    const MAX_BUNDLE_SIMULATION_TIME: Duration = Duration::from_millis(500);
+
     /// Encodes the accounts, returns an error if any of the accounts failed to encode
     /// The outer error can be set by error parsing, Ok(None) means there wasn't any accounts in the parameter
     fn try_encode_accounts(
@@ -3319,7 +3695,7 @@ pub mod utils {
 
     pub fn rpc_bundle_result_from_bank_result(
         bundle_execution_result: LoadAndExecuteBundleOutput,
-        rpc_config: RpcSimulateBundleConfig,
+        pre_execution_accounts_configs: &[Option<RpcSimulateTransactionAccountsConfig>],
     ) -> Result<RpcSimulateBundleResult, Error> {
         let summary = match bundle_execution_result.result() {
             Ok(_) => RpcBundleSimulationSummary::Succeeded,
@@ -3352,8 +3728,7 @@ pub mod utils {
                 let result = execution_result.flattened_result();
                 let details = execution_result.details().unwrap();
 
-                let account_config = rpc_config
-                    .pre_execution_accounts_configs
+                let account_config = pre_execution_accounts_configs
                     .get(transaction_results.len())
                     .ok_or_else(|| Error::invalid_params("the length of pre_execution_accounts_configs must match the number of transactions"))?;
                 let account_encoding = account_config
@@ -3420,6 +3795,171 @@ pub mod utils {
         }
         Ok(execution_accounts)
     }
+
+    /// Validates the account configs for each bundle against the bundles' lengths, defaulting to
+    /// no accounts for every transaction if none were provided
+    pub fn bundles_account_configs(
+        configs: Vec<Vec<Option<RpcSimulateTransactionAccountsConfig>>>,
+        bundle_lengths: &[usize],
+    ) -> Result<Vec<Vec<Option<RpcSimulateTransactionAccountsConfig>>>, Error> {
+        if configs.is_empty() {
+            return Ok(bundle_lengths.iter().map(|len| vec![None; *len]).collect());
+        }
+        if configs.len() != bundle_lengths.len()
+            || configs
+                .iter()
+                .zip(bundle_lengths)
+                .any(|(configs, len)| configs.len() != *len)
+        {
+            return Err(Error::invalid_params(
+                "pre/post_execution_accounts_configs must be equal in length to the number of transactions in each bundle",
+            ));
+        }
+        Ok(configs)
+    }
+
+    /// Applies the caller supplied account state on top of the bank's accounts
+    pub fn account_overrides_from_config(
+        bank: &Bank,
+        rpc_account_overrides: &HashMap<String, RpcAccountOverride>,
+    ) -> Result<AccountOverrides, Error> {
+        let mut account_overrides = AccountOverrides::default();
+        for (address, rpc_account_override) in rpc_account_overrides {
+            let pubkey = Pubkey::from_str(address).map_err(|_| {
+                Error::invalid_params(format!("invalid pubkey provided: {address}"))
+            })?;
+            let mut account = bank.get_account(&pubkey).unwrap_or_default();
+            if let Some(lamports) = rpc_account_override.lamports {
+                account.set_lamports(lamports);
+            }
+            if let Some(data) = &rpc_account_override.data {
+                let data = BASE64_STANDARD.decode(data).map_err(|_| {
+                    Error::invalid_params(format!("invalid base64 data for account: {address}"))
+                })?;
+                if data.len() as u64 > MAX_PERMITTED_DATA_LENGTH {
+                    return Err(Error::invalid_params(format!(
+                        "data for account {address} exceeds {MAX_PERMITTED_DATA_LENGTH} bytes"
+                    )));
+                }
+                account.set_data_from_slice(&data);
+            }
+            if let Some(owner) = &rpc_account_override.owner {
+                let owner = Pubkey::from_str(owner).map_err(|_| {
+                    Error::invalid_params(format!("invalid owner provided for account: {address}"))
+                })?;
+                account.set_owner(owner);
+            }
+            account_overrides.set_account(&pubkey, Some(account));
+        }
+        Ok(account_overrides)
+    }
+
+    /// Decodes and sanitizes a bundle to be simulated on `bank`
+    pub fn sanitize_bundle_for_simulation(
+        bank: &Bank,
+        rpc_bundle_request: RpcBundleRequest,
+        transaction_encoding: Option<UiTransactionEncoding>,
+        skip_sig_verify: bool,
+        replace_recent_blockhash: bool,
+    ) -> Result<SanitizedBundle, Error> {
+        let tx_encoding = transaction_encoding.unwrap_or(UiTransactionEncoding::Base64);
+        let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+            Error::invalid_params(format!(
+                "Unsupported encoding: {}. Supported encodings are: base58 & base64",
+                tx_encoding
+            ))
+        })?;
+        let mut decoded_transactions = rpc_bundle_request
+            .encoded_transactions
+            .into_iter()
+            .map(|encoded_tx| {
+                decode_and_deserialize::<VersionedTransaction>(encoded_tx, binary_encoding)
+                    .map(|de| de.1)
+            })
+            .collect::<Result<Vec<VersionedTransaction>, Error>>()?;
+
+        if replace_recent_blockhash {
+            if !skip_sig_verify {
+                return Err(Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                ));
+            }
+            decoded_transactions.iter_mut().for_each(|tx| {
+                tx.message.set_recent_blockhash(bank.last_blockhash());
+            });
+        }
+
+        let bundle_id = derive_bundle_id(&decoded_transactions);
+        let sanitized_bundle = SanitizedBundle {
+            transactions: decoded_transactions
+                .into_iter()
+                .map(|tx| sanitize_transaction(tx, bank))
+                .collect::<Result<Vec<SanitizedTransaction>, Error>>()?,
+            bundle_id,
+        };
+
+        if !skip_sig_verify {
+            for tx in &sanitized_bundle.transactions {
+                verify_transaction(tx, &bank.feature_set)?;
+            }
+        }
+
+        Ok(sanitized_bundle)
+    }
+
+    /// Simulates the bundle on top of `bank` and `account_overrides`. The state changes made by
+    /// the bundle are left in `account_overrides` so later bundles can be simulated after it.
+    pub fn simulate_sanitized_bundle<'a>(
+        bank: &Bank,
+        sanitized_bundle: &'a SanitizedBundle,
+        account_overrides: &mut AccountOverrides,
+        pre_execution_accounts: &[Option<Vec<Pubkey>>],
+        post_execution_accounts: &[Option<Vec<Pubkey>>],
+    ) -> Result<LoadAndExecuteBundleOutput<'a>, Error> {
+        let bundle_execution_result = load_and_execute_bundle(
+            bank,
+            sanitized_bundle,
+            MAX_PROCESSING_AGE,
+            &MAX_BUNDLE_SIMULATION_TIME,
+            true,
+            true,
+            true,
+            true,
+            &None,
+            true,
+            Some(account_overrides),
+            pre_execution_accounts,
+            post_execution_accounts,
+        );
+
+        // only return error if irrecoverable (timeout or tx malformed)
+        // bundle execution failures w/ context are returned to client
+        match bundle_execution_result.result() {
+            Ok(()) | Err(LoadAndExecuteBundleError::TransactionError { .. }) => {}
+            Err(LoadAndExecuteBundleError::ProcessingTimeExceeded(elapsed)) => {
+                let mut error = Error::new(ErrorCode::ServerError(10_000));
+                error.message = format!(
+                    "simulation time exceeded max allowed time: {:?}ms",
+                    elapsed.as_millis()
+                );
+                return Err(error);
+            }
+            Err(LoadAndExecuteBundleError::InvalidPreOrPostAccounts) => {
+                return Err(Error::invalid_params("invalid pre or post account data"));
+            }
+            Err(LoadAndExecuteBundleError::LockError {
+                signature,
+                transaction_error,
+            }) => {
+                return Err(Error::invalid_params(format!(
+                    "error locking transaction with signature: {}, error: {:?}",
+                    signature, transaction_error
+                )));
+            }
+        }
+
+        Ok(bundle_execution_result)
+    }
 }
 
 // Full RPC interface that an API node is expected to provide
@@ -3427,16 +3967,17 @@ pub mod utils {
 pub mod rpc_full {
     use {
         super::*,
-        crate::rpc::utils::{account_configs_to_accounts, rpc_bundle_result_from_bank_result},
-        jsonrpc_core::ErrorCode,
-        solana_bundle::bundle_execution::{load_and_execute_bundle, LoadAndExecuteBundleError},
+        crate::rpc::utils::{
+            account_configs_to_accounts, account_overrides_from_config, bundles_account_configs,
+            rpc_bundle_result_from_bank_result, sanitize_bundle_for_simulation,
+            simulate_sanitized_bundle,
+        },
         solana_rpc_client_api::bundles::{
-            RpcBundleRequest, RpcSimulateBundleConfig, RpcSimulateBundleResult,
-            SimulationSlotConfig,
+            RpcBundleRequest, RpcSendBundleConfig, RpcSimulateBundleConfig,
+            RpcSimulateBundleResult, RpcSimulateBundlesConfig, MAX_SIMULATE_BUNDLES_QUERY_ITEMS,
         },
         solana_sdk::{
-            bundle::{derive_bundle_id, SanitizedBundle},
-            clock::MAX_PROCESSING_AGE,
+            bundle::derive_bundle_id,
             message::{SanitizedVersionedMessage, VersionedMessage},
         },
         solana_transaction_status::UiInnerInstructions,
@@ -3511,6 +4052,22 @@ pub mod rpc_full {
             config: Option<RpcSimulateBundleConfig>,
         ) -> Result<RpcResponse<RpcSimulateBundleResult>>;
 
+        #[rpc(meta, name = "simulateBundles")]
+        fn simulate_bundles(
+            &self,
+            meta: Self::Metadata,
+            rpc_bundle_requests: Vec<RpcBundleRequest>,
+            config: Option<RpcSimulateBundlesConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateBundleResult>>>;
+
+        #[rpc(meta, name = "sendBundle")]
+        fn send_bundle(
+            &self,
+            meta: Self::Metadata,
+            rpc_bundle_request: RpcBundleRequest,
+            config: Option<RpcSendBundleConfig>,
+        ) -> Result<String>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3602,6 +4159,35 @@ pub mod rpc_full {
             meta: Self::Metadata,
             pubkey_strs: Option<Vec<String>>,
         ) -> Result<Vec<RpcPrioritizationFee>>;
+
+        #[rpc(meta, name = "getPriorityFeeEstimate")]
+        fn get_priority_fee_estimate(
+            &self,
+            meta: Self::Metadata,
+            pubkey_strs: Option<Vec<String>>,
+            config: Option<RpcPriorityFeeEstimateConfig>,
+        ) -> Result<Option<RpcPriorityFeeEstimate>>;
+
+        #[rpc(meta, name = "getBundleStatuses")]
+        fn get_bundle_statuses(
+            &self,
+            meta: Self::Metadata,
+            bundle_ids: Vec<String>,
+        ) -> Result<RpcResponse<Vec<Option<RpcBundleStatus>>>>;
+
+        #[rpc(meta, name = "getTransactionRetryStatus")]
+        fn get_transaction_retry_status(
+            &self,
+            meta: Self::Metadata,
+            signature_str: String,
+        ) -> Result<Option<RpcTransactionRetryStatus>>;
+
+        #[rpc(meta, name = "getTipsForSlot")]
+        fn get_tips_for_slot(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> Result<Option<RpcSlotTips>>;
     }
 
     pub struct FullImpl;
@@ -4025,8 +4611,6 @@ pub mod rpc_full {
             rpc_bundle_request: RpcBundleRequest,
             config: Option<RpcSimulateBundleConfig>,
         ) -> Result<RpcResponse<RpcSimulateBundleResult>> {
-            const MAX_BUNDLE_SIMULATION_TIME: Duration = Duration::from_millis(500);
-
             debug!("simulate_bundle rpc request received");
 
             let config = config.unwrap_or_else(|| RpcSimulateBundleConfig {
@@ -4052,109 +4636,171 @@ pub mod rpc_full {
                 ));
             }
 
-            let bank = match config.simulation_bank.unwrap_or_default() {
-                SimulationSlotConfig::Commitment(commitment) => Ok(meta.bank(Some(commitment))),
-                SimulationSlotConfig::Slot(slot) => meta.bank_from_slot(slot).ok_or_else(|| {
-                    Error::invalid_params(format!("bank not found for the provided slot: {}", slot))
-                }),
-                SimulationSlotConfig::Tip => Ok(meta.bank_forks.read().unwrap().working_bank()),
-            }?;
-
-            let tx_encoding = config
-                .transaction_encoding
-                .unwrap_or(UiTransactionEncoding::Base64);
-            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
-                Error::invalid_params(format!(
-                    "Unsupported encoding: {}. Supported encodings are: base58 & base64",
-                    tx_encoding
-                ))
-            })?;
-            let mut decoded_transactions = rpc_bundle_request
-                .encoded_transactions
-                .into_iter()
-                .map(|encoded_tx| {
-                    decode_and_deserialize::<VersionedTransaction>(encoded_tx, binary_encoding)
-                        .map(|de| de.1)
-                })
-                .collect::<Result<Vec<VersionedTransaction>>>()?;
+            let bank = meta.bundle_simulation_bank(config.simulation_bank)?;
 
-            if config.replace_recent_blockhash {
-                if !config.skip_sig_verify {
-                    return Err(Error::invalid_params(
-                        "sigVerify may not be used with replaceRecentBlockhash",
-                    ));
-                }
-                decoded_transactions.iter_mut().for_each(|tx| {
-                    tx.message.set_recent_blockhash(bank.last_blockhash());
-                });
-            }
-
-            let bundle_id = derive_bundle_id(&decoded_transactions);
-            let sanitized_bundle = SanitizedBundle {
-                transactions: decoded_transactions
-                    .into_iter()
-                    .map(|tx| sanitize_transaction(tx, bank.as_ref()))
-                    .collect::<Result<Vec<SanitizedTransaction>>>()?,
-                bundle_id,
-            };
-
-            if !config.skip_sig_verify {
-                for tx in &sanitized_bundle.transactions {
-                    verify_transaction(tx, &bank.feature_set)?;
-                }
-            }
+            let sanitized_bundle = sanitize_bundle_for_simulation(
+                &bank,
+                rpc_bundle_request,
+                config.transaction_encoding,
+                config.skip_sig_verify,
+                config.replace_recent_blockhash,
+            )?;
 
             let pre_execution_accounts =
                 account_configs_to_accounts(&config.pre_execution_accounts_configs)?;
             let post_execution_accounts =
                 account_configs_to_accounts(&config.post_execution_accounts_configs)?;
+            let mut account_overrides =
+                account_overrides_from_config(&bank, &config.account_overrides)?;
 
-            let bundle_execution_result = load_and_execute_bundle(
+            let bundle_execution_result = simulate_sanitized_bundle(
                 &bank,
                 &sanitized_bundle,
-                MAX_PROCESSING_AGE,
-                &MAX_BUNDLE_SIMULATION_TIME,
-                true,
-                true,
-                true,
-                true,
-                &None,
-                true,
-                None,
+                &mut account_overrides,
                 &pre_execution_accounts,
                 &post_execution_accounts,
+            )?;
+
+            let rpc_bundle_result = rpc_bundle_result_from_bank_result(
+                bundle_execution_result,
+                &config.pre_execution_accounts_configs,
+            )?;
+
+            Ok(new_response(&bank, rpc_bundle_result))
+        }
+
+        fn simulate_bundles(
+            &self,
+            meta: Self::Metadata,
+            rpc_bundle_requests: Vec<RpcBundleRequest>,
+            config: Option<RpcSimulateBundlesConfig>,
+        ) -> Result<RpcResponse<Vec<RpcSimulateBundleResult>>> {
+            debug!(
+                "simulate_bundles rpc request received: {:?}",
+                rpc_bundle_requests.len()
             );
+            if rpc_bundle_requests.len() > MAX_SIMULATE_BUNDLES_QUERY_ITEMS {
+                return Err(Error::invalid_params(format!(
+                    "Too many inputs provided; max {MAX_SIMULATE_BUNDLES_QUERY_ITEMS}"
+                )));
+            }
 
-            // only return error if irrecoverable (timeout or tx malformed)
-            // bundle execution failures w/ context are returned to client
-            match bundle_execution_result.result() {
-                Ok(()) | Err(LoadAndExecuteBundleError::TransactionError { .. }) => {}
-                Err(LoadAndExecuteBundleError::ProcessingTimeExceeded(elapsed)) => {
-                    let mut error = Error::new(ErrorCode::ServerError(10_000));
-                    error.message = format!(
-                        "simulation time exceeded max allowed time: {:?}ms",
-                        elapsed.as_millis()
-                    );
-                    return Err(error);
-                }
-                Err(LoadAndExecuteBundleError::InvalidPreOrPostAccounts) => {
-                    return Err(Error::invalid_params("invalid pre or post account data"));
-                }
-                Err(LoadAndExecuteBundleError::LockError {
-                    signature,
-                    transaction_error,
-                }) => {
-                    return Err(Error::invalid_params(format!(
-                        "error locking transaction with signature: {}, error: {:?}",
-                        signature, transaction_error
-                    )));
+            let config = config.unwrap_or_default();
+            let bundle_lengths: Vec<usize> = rpc_bundle_requests
+                .iter()
+                .map(|request| request.encoded_transactions.len())
+                .collect();
+            let pre_execution_accounts_configs =
+                bundles_account_configs(config.pre_execution_accounts_configs, &bundle_lengths)?;
+            let post_execution_accounts_configs =
+                bundles_account_configs(config.post_execution_accounts_configs, &bundle_lengths)?;
+
+            let bank = meta.bundle_simulation_bank(config.simulation_bank)?;
+
+            let sanitized_bundles = rpc_bundle_requests
+                .into_iter()
+                .map(|rpc_bundle_request| {
+                    sanitize_bundle_for_simulation(
+                        &bank,
+                        rpc_bundle_request,
+                        config.transaction_encoding,
+                        config.skip_sig_verify,
+                        config.replace_recent_blockhash,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            // each bundle is simulated on top of the state left by the bundles before it
+            let mut account_overrides =
+                account_overrides_from_config(&bank, &config.account_overrides)?;
+            let mut rpc_bundle_results = Vec::with_capacity(sanitized_bundles.len());
+            for (
+                (sanitized_bundle, pre_execution_accounts_configs),
+                post_execution_accounts_configs,
+            ) in sanitized_bundles
+                .iter()
+                .zip(&pre_execution_accounts_configs)
+                .zip(&post_execution_accounts_configs)
+            {
+                let pre_execution_accounts =
+                    account_configs_to_accounts(pre_execution_accounts_configs)?;
+                let post_execution_accounts =
+                    account_configs_to_accounts(post_execution_accounts_configs)?;
+
+                // a failed bundle doesn't land, so drop any state it left behind
+                let account_overrides_before_bundle = account_overrides.clone();
+                let bundle_execution_result = simulate_sanitized_bundle(
+                    &bank,
+                    sanitized_bundle,
+                    &mut account_overrides,
+                    &pre_execution_accounts,
+                    &post_execution_accounts,
+                )?;
+                if bundle_execution_result.result().is_err() {
+                    account_overrides = account_overrides_before_bundle;
                 }
+
+                rpc_bundle_results.push(rpc_bundle_result_from_bank_result(
+                    bundle_execution_result,
+                    pre_execution_accounts_configs,
+                )?);
             }
 
-            let rpc_bundle_result =
-                rpc_bundle_result_from_bank_result(bundle_execution_result, config)?;
+            Ok(new_response(&bank, rpc_bundle_results))
+        }
 
-            Ok(new_response(&bank, rpc_bundle_result))
+        fn send_bundle(
+            &self,
+            meta: Self::Metadata,
+            rpc_bundle_request: RpcBundleRequest,
+            config: Option<RpcSendBundleConfig>,
+        ) -> Result<String> {
+            debug!("send_bundle rpc request received");
+            let Some(bundle_sender) = meta.bundle_sender.as_ref() else {
+                return Err(Error::method_not_found());
+            };
+
+            let RpcSendBundleConfig {
+                transaction_encoding,
+            } = config.unwrap_or_default();
+            let tx_encoding = transaction_encoding.unwrap_or(UiTransactionEncoding::Base64);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "Unsupported encoding: {}. Supported encodings are: base58 & base64",
+                    tx_encoding
+                ))
+            })?;
+            let transactions = rpc_bundle_request
+                .encoded_transactions
+                .into_iter()
+                .map(|encoded_tx| {
+                    decode_and_deserialize::<VersionedTransaction>(encoded_tx, binary_encoding)
+                        .map(|de| de.1)
+                })
+                .collect::<Result<Vec<VersionedTransaction>>>()?;
+
+            let bank = meta.bank_forks.read().unwrap().working_bank();
+            let sanitized_transactions = transactions
+                .iter()
+                .cloned()
+                .map(|tx| sanitize_transaction(tx, bank.as_ref()))
+                .collect::<Result<Vec<SanitizedTransaction>>>()?;
+            for tx in &sanitized_transactions {
+                verify_transaction(tx, &bank.feature_set)?;
+            }
+            bundle_sender
+                .verify_bundle(&bank, &sanitized_transactions)
+                .map_err(|e| Error::invalid_params(e.to_string()))?;
+
+            let bundle_id = derive_bundle_id(&transactions);
+            bundle_sender
+                .send_bundle(bundle_id.clone(), &transactions)
+                .map_err(|e| {
+                    let mut error = Error::internal_error();
+                    error.message = e.to_string();
+                    error
+                })?;
+            Ok(bundle_id)
         }
 
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
@@ -4346,30 +4992,156 @@ pub mod rpc_full {
             meta.get_stake_minimum_delegation(config.unwrap_or_default())
         }
 
-        fn get_recent_prioritization_fees(
+        fn get_recent_prioritization_fees(
+            &self,
+            meta: Self::Metadata,
+            pubkey_strs: Option<Vec<String>>,
+        ) -> Result<Vec<RpcPrioritizationFee>> {
+            let pubkey_strs = pubkey_strs.unwrap_or_default();
+            debug!(
+                "get_recent_prioritization_fees rpc request received: {:?} pubkeys",
+                pubkey_strs.len()
+            );
+            if pubkey_strs.len() > MAX_TX_ACCOUNT_LOCKS {
+                return Err(Error::invalid_params(format!(
+                    "Too many inputs provided; max {MAX_TX_ACCOUNT_LOCKS}"
+                )));
+            }
+            let pubkeys = pubkey_strs
+                .into_iter()
+                .map(|pubkey_str| verify_pubkey(&pubkey_str))
+                .collect::<Result<Vec<_>>>()?;
+            meta.get_recent_prioritization_fees(pubkeys)
+        }
+
+        fn get_priority_fee_estimate(
+            &self,
+            meta: Self::Metadata,
+            pubkey_strs: Option<Vec<String>>,
+            config: Option<RpcPriorityFeeEstimateConfig>,
+        ) -> Result<Option<RpcPriorityFeeEstimate>> {
+            let pubkey_strs = pubkey_strs.unwrap_or_default();
+            debug!(
+                "get_priority_fee_estimate rpc request received: {:?} pubkeys",
+                pubkey_strs.len()
+            );
+            if pubkey_strs.len() > MAX_TX_ACCOUNT_LOCKS {
+                return Err(Error::invalid_params(format!(
+                    "Too many inputs provided; max {MAX_TX_ACCOUNT_LOCKS}"
+                )));
+            }
+            let RpcPriorityFeeEstimateConfig {
+                percentiles,
+                slot_window,
+            } = config.unwrap_or_default();
+            let percentiles =
+                percentiles.unwrap_or_else(|| DEFAULT_PRIORITY_FEE_ESTIMATE_PERCENTILES.to_vec());
+            if percentiles.is_empty() || percentiles.len() > MAX_PRIORITY_FEE_ESTIMATE_PERCENTILES {
+                return Err(Error::invalid_params(format!(
+                    "Between 1 and {MAX_PRIORITY_FEE_ESTIMATE_PERCENTILES} percentiles must be provided"
+                )));
+            }
+            if let Some(percentile) = percentiles.iter().find(|percentile| **percentile > 100) {
+                return Err(Error::invalid_params(format!(
+                    "Invalid percentile {percentile}; must be between 0 and 100"
+                )));
+            }
+            let slot_window = slot_window.unwrap_or(usize::MAX);
+            if slot_window == 0 {
+                return Err(Error::invalid_params(
+                    "slotWindow must be greater than 0".to_string(),
+                ));
+            }
+            let pubkeys = pubkey_strs
+                .into_iter()
+                .map(|pubkey_str| verify_pubkey(&pubkey_str))
+                .collect::<Result<Vec<_>>>()?;
+            meta.get_priority_fee_estimate(pubkeys, percentiles, slot_window)
+        }
+
+        fn get_bundle_statuses(
             &self,
             meta: Self::Metadata,
-            pubkey_strs: Option<Vec<String>>,
-        ) -> Result<Vec<RpcPrioritizationFee>> {
-            let pubkey_strs = pubkey_strs.unwrap_or_default();
+            bundle_ids: Vec<String>,
+        ) -> Result<RpcResponse<Vec<Option<RpcBundleStatus>>>> {
             debug!(
-                "get_recent_prioritization_fees rpc request received: {:?} pubkeys",
-                pubkey_strs.len()
+                "get_bundle_statuses rpc request received: {:?}",
+                bundle_ids.len()
             );
-            if pubkey_strs.len() > MAX_TX_ACCOUNT_LOCKS {
+            if bundle_ids.len() > MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS {
                 return Err(Error::invalid_params(format!(
-                    "Too many inputs provided; max {MAX_TX_ACCOUNT_LOCKS}"
+                    "Too many inputs provided; max {MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS}"
                 )));
             }
-            let pubkeys = pubkey_strs
-                .into_iter()
-                .map(|pubkey_str| verify_pubkey(&pubkey_str))
-                .collect::<Result<Vec<_>>>()?;
-            meta.get_recent_prioritization_fees(pubkeys)
+            meta.get_bundle_statuses(bundle_ids)
+        }
+
+        fn get_transaction_retry_status(
+            &self,
+            meta: Self::Metadata,
+            signature_str: String,
+        ) -> Result<Option<RpcTransactionRetryStatus>> {
+            debug!(
+                "get_transaction_retry_status rpc request received: {:?}",
+                signature_str
+            );
+            let signature = verify_signature(&signature_str)?;
+            Ok(meta.get_transaction_retry_status(&signature))
+        }
+
+        fn get_tips_for_slot(
+            &self,
+            meta: Self::Metadata,
+            slot: Slot,
+        ) -> Result<Option<RpcSlotTips>> {
+            debug!("get_tips_for_slot rpc request received: {:?}", slot);
+            Ok(meta
+                .blockstore
+                .get_slot_tips(slot)
+                .map_err(|err| {
+                    warn!("get_tips_for_slot failed: {:?}", err);
+                    Error::invalid_request()
+                })?
+                .map(|slot_tips| rpc_slot_tips_from_slot_tips(slot, slot_tips)))
         }
     }
 }
 
+fn rpc_slot_tips_from_slot_tips(slot: Slot, slot_tips: SlotTips) -> RpcSlotTips {
+    let rpc_tip_accounts = |tip_accounts: Vec<(Pubkey, u64)>| {
+        tip_accounts
+            .into_iter()
+            .map(|(tip_account, lamports)| RpcTipAccountLamports {
+                tip_account: tip_account.to_string(),
+                lamports,
+            })
+            .collect()
+    };
+    RpcSlotTips {
+        slot,
+        total_tip_lamports: slot_tips.total_tip_lamports,
+        block_builder: slot_tips.block_builder.to_string(),
+        block_builder_commission: slot_tips.block_builder_commission,
+        block_builder_commission_lamports: slot_tips.block_builder_commission_lamports,
+        tip_accounts: rpc_tip_accounts(slot_tips.tip_accounts),
+        bundles: slot_tips
+            .bundles
+            .into_iter()
+            .map(
+                |BundleTips {
+                     bundle_id,
+                     tip_lamports,
+                     tip_accounts,
+                 }| RpcBundleTips {
+                    bundle_id,
+                    tip_lamports,
+                    tip_accounts: rpc_tip_accounts(tip_accounts),
+                },
+            )
+            .collect(),
+    }
+}
+
 fn rpc_perf_sample_from_perf_sample(slot: u64, sample: PerfSample) -> RpcPerfSample {
     match sample {
         PerfSample::V1(PerfSampleV1 {
@@ -4969,6 +5741,7 @@ pub mod tests {
             optimistically_confirmed_bank_tracker::{
                 BankNotification, OptimisticallyConfirmedBankTracker,
             },
+            rpc_bundle_sender::SendBundleError,
             rpc_subscriptions::RpcSubscriptions,
         },
         base64::engine::general_purpose,
@@ -4977,6 +5750,7 @@ pub mod tests {
         jsonrpc_core_client::transports::local,
         serde::de::DeserializeOwned,
         solana_accounts_db::{inline_spl_token, inline_spl_token_2022},
+        solana_bundle::bundle_status::BundleStatus,
         solana_entry::entry::next_versioned_entry,
         solana_gossip::socketaddr,
         solana_ledger::{
@@ -4985,6 +5759,10 @@ pub mod tests {
             genesis_utils::{create_genesis_config, GenesisConfigInfo},
         },
         solana_rpc_client_api::{
+            bundles::{
+                RpcBundleOutcome, RpcBundleSimulationSummary, RpcSimulateBundleResult,
+                MAX_SIMULATE_BUNDLES_QUERY_ITEMS,
+            },
             custom_error::{
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
@@ -5002,6 +5780,7 @@ pub mod tests {
                 self,
                 state::{AddressLookupTable, LookupTableMeta},
             },
+            bundle::derive_bundle_id,
             clock::MAX_RECENT_BLOCKHASHES,
             compute_budget::ComputeBudgetInstruction,
             fee_calculator::{FeeRateGovernor, DEFAULT_BURN_PERCENT},
@@ -5155,6 +5934,8 @@ pub mod tests {
                 max_complete_transaction_status_slot.clone(),
                 max_complete_rewards_slot,
                 Arc::new(PrioritizationFeeCache::default()),
+                Arc::new(BundleStatusCache::default()),
+                None,
             )
             .0;
 
@@ -6192,6 +6973,114 @@ pub mod tests {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_rpc_get_program_accounts_paginated() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+
+        let program_id = Pubkey::new_unique();
+        let mut pubkeys: Vec<_> = (0..5)
+            .map(|lamports| {
+                let pubkey = Pubkey::new_unique();
+                bank.store_account(
+                    &pubkey,
+                    &AccountSharedData::new(lamports + 1, 0, &program_id),
+                );
+                pubkey.to_string()
+            })
+            .collect();
+        pubkeys.sort_by_key(|pubkey| Pubkey::from_str(pubkey).unwrap());
+
+        let get_page = |config: serde_json::Value| {
+            let request = create_test_request(
+                "getProgramAccountsPaginated",
+                Some(json!([program_id.to_string(), config])),
+            );
+            let result: RpcResponse<RpcProgramAccountsPage> =
+                parse_success_result(rpc.handle_request_sync(request));
+            result
+        };
+
+        // pages come back in pubkey order, each anchored to the same slot
+        let mut accounts = vec![];
+        let mut cursor = None;
+        let mut num_pages = 0;
+        loop {
+            let page = get_page(json!({"pageSize": 2, "cursor": cursor}));
+            assert_eq!(page.context.slot, 0);
+            accounts.extend(
+                page.value
+                    .accounts
+                    .into_iter()
+                    .map(|account| account.pubkey),
+            );
+            num_pages += 1;
+            cursor = page.value.cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(num_pages, 3);
+        assert_eq!(accounts, pubkeys);
+
+        // a full last page still returns a cursor, which then yields an empty page
+        let page = get_page(json!({"pageSize": 5}));
+        assert_eq!(page.value.accounts.len(), 5);
+        let page = get_page(json!({"pageSize": 5, "cursor": page.value.cursor.unwrap()}));
+        assert_eq!(page.value.accounts, vec![]);
+        assert_eq!(page.value.cursor, None);
+
+        // filters apply to every page
+        let page = get_page(json!({"filters": [{"dataSize": 1}]}));
+        assert_eq!(page.value.accounts, vec![]);
+
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([program_id.to_string(), {"pageSize": 0}])),
+        );
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([program_id.to_string(), {"cursor": "bogus"}])),
+        );
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+
+        // cursors anchored to banks that are no longer available have expired
+        let expired_cursor = ProgramAccountsCursor {
+            slot: 42,
+            bank_id: 42,
+            last_pubkey: Pubkey::new_unique(),
+        };
+        assert_eq!(
+            ProgramAccountsCursor::decode(&expired_cursor.encode()).unwrap(),
+            expired_cursor
+        );
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([program_id.to_string(), {"cursor": expired_cursor.encode()}])),
+        );
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert!(message.starts_with("Cursor expired"));
+
+        // a cursor for a bank at the same slot on another fork has expired as well
+        let page = get_page(json!({"pageSize": 2}));
+        let other_fork_cursor = ProgramAccountsCursor {
+            bank_id: bank.bank_id() + 1,
+            ..ProgramAccountsCursor::decode(&page.value.cursor.unwrap()).unwrap()
+        };
+        let request = create_test_request(
+            "getProgramAccountsPaginated",
+            Some(json!([program_id.to_string(), {"cursor": other_fork_cursor.encode()}])),
+        );
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert!(message.starts_with("Cursor expired"));
+    }
+
     #[test]
     fn test_rpc_simulate_bundle_happy_path() {
         // 1. setup
@@ -6332,6 +7221,86 @@ pub mod tests {
         assert_eq!(expected_response, actual_response);
     }
 
+    #[test]
+    fn test_rpc_simulate_bundles_with_account_overrides() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let recent_blockhash = bank.confirmed_last_blockhash();
+
+        // neither alice nor bob exist in the bank
+        let (alice, bob) = (Keypair::new(), Keypair::new());
+        let carol = solana_sdk::pubkey::new_rand();
+        let encode_bundle = |tx: Transaction| {
+            json!({
+                "encodedTransactions": [BASE64_STANDARD.encode(serialize(&tx).unwrap())]
+            })
+        };
+        let alice_to_bob = encode_bundle(system_transaction::transfer(
+            &alice,
+            &bob.pubkey(),
+            10_000_000,
+            recent_blockhash,
+        ));
+        let bob_to_carol = encode_bundle(system_transaction::transfer(
+            &bob,
+            &carol,
+            1_000_000,
+            recent_blockhash,
+        ));
+        let config = json!({
+            "accountOverrides": {
+                alice.pubkey().to_string(): { "lamports": 1_000_000_000 }
+            }
+        });
+        let succeeded = |result: &RpcSimulateBundleResult| {
+            matches!(result.summary, RpcBundleSimulationSummary::Succeeded)
+        };
+
+        // alice is funded by the override
+        let request = create_test_request(
+            "simulateBundle",
+            Some(json!([alice_to_bob.clone(), config.clone()])),
+        );
+        let result: RpcResponse<RpcSimulateBundleResult> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert!(succeeded(&result.value));
+
+        // bob only has lamports once alice's bundle runs first
+        let request = create_test_request(
+            "simulateBundles",
+            Some(json!([
+                [alice_to_bob.clone(), bob_to_carol.clone()],
+                config.clone()
+            ])),
+        );
+        let result: RpcResponse<Vec<RpcSimulateBundleResult>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.value.len(), 2);
+        assert!(result.value.iter().all(succeeded));
+
+        let request = create_test_request(
+            "simulateBundles",
+            Some(json!([[bob_to_carol, alice_to_bob.clone()], config])),
+        );
+        let result: RpcResponse<Vec<RpcSimulateBundleResult>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert!(!succeeded(&result.value[0]));
+        assert!(succeeded(&result.value[1]));
+
+        let request = create_test_request(
+            "simulateBundles",
+            Some(json!([vec![
+                alice_to_bob;
+                MAX_SIMULATE_BUNDLES_QUERY_ITEMS + 1
+            ]])),
+        );
+        let (_, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(
+            message,
+            format!("Too many inputs provided; max {MAX_SIMULATE_BUNDLES_QUERY_ITEMS}")
+        );
+    }
+
     #[test]
     fn test_rpc_simulate_transaction() {
         let rpc = RpcHandler::start();
@@ -7245,6 +8214,63 @@ pub mod tests {
         assert_eq!(error["code"], ErrorCode::InvalidParams.code());
     }
 
+    #[test]
+    fn test_rpc_send_bundle() {
+        let mut rpc = RpcHandler::start();
+        let recent_blockhash = rpc.working_bank().confirmed_last_blockhash();
+        let encode =
+            |tx: &VersionedTransaction| general_purpose::STANDARD.encode(serialize(tx).unwrap());
+        let transfer = |payer: &Keypair| {
+            VersionedTransaction::from(system_transaction::transfer(
+                payer,
+                &Pubkey::new_unique(),
+                1,
+                recent_blockhash,
+            ))
+        };
+
+        // sendBundle is only served when the validator has a bundle sender configured
+        let bundle = vec![transfer(&rpc.mint_keypair)];
+        let request = create_test_request(
+            "sendBundle",
+            Some(json!([{"encodedTransactions": [encode(&bundle[0])]}])),
+        );
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request.clone()));
+        assert_eq!(code, ErrorCode::MethodNotFound.code());
+
+        let (bundle_sender, bundle_receiver) = unbounded();
+        rpc.meta.bundle_sender = Some(RpcBundleSender::new(
+            bundle_sender,
+            HashSet::new(),
+            HashSet::from([rpc.mint_keypair.pubkey()]),
+        ));
+
+        // signer not on the allowlist
+        let unknown_signer_tx = transfer(&Keypair::new());
+        let (code, message) = parse_failure_response(rpc.handle_request_sync(create_test_request(
+            "sendBundle",
+            Some(json!([{"encodedTransactions": [encode(&unknown_signer_tx)]}])),
+        )));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert_eq!(message, SendBundleError::SignerNotAllowed.to_string());
+
+        // malformed transaction
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(create_test_request(
+            "sendBundle",
+            Some(json!([{"encodedTransactions": ["bogus"]}])),
+        )));
+        assert_eq!(code, ErrorCode::InvalidParams.code());
+        assert!(bundle_receiver.is_empty());
+
+        // success returns the bundle id and forwards the bundle
+        let bundle_id: String = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(bundle_id, derive_bundle_id(&bundle));
+        let packet_bundles = bundle_receiver.try_recv().unwrap();
+        assert_eq!(packet_bundles.len(), 1);
+        assert_eq!(packet_bundles[0].bundle_id, bundle_id);
+        assert_eq!(packet_bundles[0].batch.len(), 1);
+    }
+
     #[test]
     fn test_rpc_send_transaction_preflight() {
         let exit = Arc::new(AtomicBool::new(false));
@@ -7292,6 +8318,8 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            Arc::new(BundleStatusCache::default()),
+            None,
         );
         SendTransactionService::new::<NullTpuInfo>(
             cluster_info,
@@ -7564,6 +8592,8 @@ pub mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            Arc::new(BundleStatusCache::default()),
+            None,
         );
         SendTransactionService::new::<NullTpuInfo>(
             cluster_info,
@@ -9196,6 +10226,8 @@ pub mod tests {
             max_complete_transaction_status_slot,
             max_complete_rewards_slot,
             Arc::new(PrioritizationFeeCache::default()),
+            Arc::new(BundleStatusCache::default()),
+            None,
         );
 
         let mut io = MetaIoHandler::default();
@@ -9503,6 +10535,147 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_rpc_get_bundle_statuses() {
+        let rpc = RpcHandler::start();
+        rpc.meta.bundle_status_cache.insert(BundleStatus::landed(
+            "landed".to_string(),
+            1,
+            1_000,
+            50,
+        ));
+        rpc.meta
+            .bundle_status_cache
+            .insert(BundleStatus::dropped("dropped".to_string(), 2));
+
+        let request = create_test_request(
+            "getBundleStatuses",
+            Some(json!([["landed", "dropped", "unknown"]])),
+        );
+        let result: RpcResponse<Vec<Option<RpcBundleStatus>>> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let statuses = result.value;
+        assert_eq!(statuses.len(), 3);
+
+        let landed = statuses[0].as_ref().unwrap();
+        assert_eq!(landed.bundle_id, "landed");
+        assert_eq!(landed.slot, 1);
+        assert_eq!(landed.outcome, RpcBundleOutcome::Landed);
+        assert_eq!(landed.compute_units_consumed, 1_000);
+        assert_eq!(landed.tip_lamports, 50);
+
+        let dropped = statuses[1].as_ref().unwrap();
+        assert_eq!(dropped.outcome, RpcBundleOutcome::Dropped);
+        assert!(dropped.err.is_none());
+        assert!(statuses[2].is_none());
+
+        let bundle_ids = vec!["a"; MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS + 1];
+        let request = create_test_request("getBundleStatuses", Some(json!([bundle_ids])));
+        let (_, message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(
+            message,
+            format!("Too many inputs provided; max {MAX_GET_BUNDLE_STATUSES_QUERY_ITEMS}")
+        );
+    }
+
+    #[test]
+    fn test_rpc_get_transaction_retry_status() {
+        let rpc = RpcHandler::start();
+        let signature = Signature::new_unique();
+        rpc.meta.retry_status_cache.insert(
+            signature,
+            TransactionRetryStatus {
+                state: TransactionRetryState::Pending,
+                retries: 2,
+                max_retries: Some(5),
+                last_valid_block_height: 100,
+            },
+        );
+
+        let request = create_test_request(
+            "getTransactionRetryStatus",
+            Some(json!([signature.to_string()])),
+        );
+        let status: Option<RpcTransactionRetryStatus> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            status,
+            Some(RpcTransactionRetryStatus {
+                state: RpcTransactionRetryState::Pending,
+                retries: 2,
+                max_retries: Some(5),
+                last_valid_block_height: 100,
+            })
+        );
+
+        let request = create_test_request(
+            "getTransactionRetryStatus",
+            Some(json!([Signature::new_unique().to_string()])),
+        );
+        let status: Option<RpcTransactionRetryStatus> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(status, None);
+
+        let request = create_test_request("getTransactionRetryStatus", Some(json!(["0"])));
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            String::from("Invalid param: Invalid"),
+        );
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_rpc_get_tips_for_slot() {
+        let rpc = RpcHandler::start();
+        let tip_account = Pubkey::new_unique();
+        let block_builder = Pubkey::new_unique();
+        rpc.blockstore
+            .write_slot_tips(
+                3,
+                &SlotTips {
+                    total_tip_lamports: 1_000,
+                    block_builder,
+                    block_builder_commission: 5,
+                    block_builder_commission_lamports: 50,
+                    tip_accounts: vec![(tip_account, 1_000)],
+                    bundles: vec![BundleTips {
+                        bundle_id: "bundle".to_string(),
+                        tip_lamports: 1_000,
+                        tip_accounts: vec![(tip_account, 1_000)],
+                    }],
+                },
+            )
+            .unwrap();
+
+        let request = create_test_request("getTipsForSlot", Some(json!([3])));
+        let result: Option<RpcSlotTips> = parse_success_result(rpc.handle_request_sync(request));
+        let tip_accounts = vec![RpcTipAccountLamports {
+            tip_account: tip_account.to_string(),
+            lamports: 1_000,
+        }];
+        assert_eq!(
+            result,
+            Some(RpcSlotTips {
+                slot: 3,
+                total_tip_lamports: 1_000,
+                block_builder: block_builder.to_string(),
+                block_builder_commission: 5,
+                block_builder_commission_lamports: 50,
+                tip_accounts: tip_accounts.clone(),
+                bundles: vec![RpcBundleTips {
+                    bundle_id: "bundle".to_string(),
+                    tip_lamports: 1_000,
+                    tip_accounts,
+                }],
+            })
+        );
+
+        let request = create_test_request("getTipsForSlot", Some(json!([4])));
+        let result: Option<RpcSlotTips> = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result, None);
+    }
+
     #[test]
     fn test_rpc_get_recent_prioritization_fees() {
         fn wait_for_cache_blocks(cache: &PrioritizationFeeCache, num_blocks: usize) {
@@ -9667,4 +10840,100 @@ pub mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_rpc_get_priority_fee_estimate() {
+        let rpc = RpcHandler::start();
+        let account0 = Pubkey::new_unique();
+        let account1 = Pubkey::new_unique();
+        let account2 = Pubkey::new_unique();
+
+        // no finalized blocks to estimate from yet
+        let request = create_test_request("getPriorityFeeEstimate", None);
+        let response: Option<RpcPriorityFeeEstimate> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert!(response.is_none());
+
+        let slot0 = rpc.working_bank().slot();
+        let bank0_id = rpc.working_bank().bank_id();
+        let price0 = 42;
+        let transactions = vec![
+            Transaction::new_unsigned(Message::new(
+                &[
+                    system_instruction::transfer(&account0, &account1, 1),
+                    ComputeBudgetInstruction::set_compute_unit_price(price0),
+                ],
+                Some(&account0),
+            )),
+            Transaction::new_unsigned(Message::new(
+                &[system_instruction::transfer(&account0, &account2, 1)],
+                Some(&account0),
+            )),
+        ];
+        rpc.update_prioritization_fee_cache(transactions);
+        let cache = rpc.get_prioritization_fee_cache();
+        cache.finalize_priority_fee(slot0, bank0_id);
+        while cache.available_block_count() < 1 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+
+        let estimate = |fees: [u64; 5]| {
+            Some(RpcPriorityFeeEstimate {
+                min_slot: slot0,
+                max_slot: slot0,
+                num_slots: 1,
+                num_samples: 2,
+                fees: DEFAULT_PRIORITY_FEE_ESTIMATE_PERCENTILES
+                    .into_iter()
+                    .zip(fees)
+                    .map(
+                        |(percentile, prioritization_fee)| RpcPriorityFeePercentile {
+                            percentile,
+                            prioritization_fee,
+                        },
+                    )
+                    .collect(),
+            })
+        };
+
+        // block-wide fees are [0, price0]
+        let request = create_test_request("getPriorityFeeEstimate", None);
+        let response: Option<RpcPriorityFeeEstimate> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(response, estimate([0, 0, price0, price0, price0]));
+
+        // every transaction writing account1 paid price0
+        let request = create_test_request(
+            "getPriorityFeeEstimate",
+            Some(json!([[account1.to_string()]])),
+        );
+        let response: Option<RpcPriorityFeeEstimate> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(response, estimate([price0; 5]));
+
+        let request = create_test_request(
+            "getPriorityFeeEstimate",
+            Some(json!([[account2.to_string()], {"percentiles": [50], "slotWindow": 1}])),
+        );
+        let response: Option<RpcPriorityFeeEstimate> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(
+            response.unwrap().fees,
+            vec![RpcPriorityFeePercentile {
+                percentile: 50,
+                prioritization_fee: 0,
+            }]
+        );
+
+        let request = create_test_request(
+            "getPriorityFeeEstimate",
+            Some(json!([null, {"percentiles": [50, 101]}])),
+        );
+        let response = parse_failure_response(rpc.handle_request_sync(request));
+        let expected = (
+            ErrorCode::InvalidParams.code(),
+            String::from("Invalid percentile 101; must be between 0 and 100"),
+        );
+        assert_eq!(response, expected);
+    }
 }
@@ -0,0 +1,233 @@
+//! Injects bundles received over the sendBundle RPC directly into BundleStage, allowing an
+//! operator to submit bundles to their own leader without going through a block engine.
+use {
+    crossbeam_channel::Sender,
+    solana_bundle::{packet_bundle::PacketBundle, MAX_TRANSACTIONS_PER_BUNDLE},
+    solana_perf::packet::{Packet, PacketBatch},
+    solana_runtime::bank::Bank,
+    solana_sdk::{
+        pubkey::Pubkey,
+        signature::Signature,
+        transaction::{SanitizedTransaction, VersionedTransaction},
+    },
+    std::collections::HashSet,
+    thiserror::Error,
+};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SendBundleError {
+    #[error("bundle must contain between 1 and {MAX_TRANSACTIONS_PER_BUNDLE} transactions")]
+    InvalidBundleLength,
+
+    #[error("bundle contains duplicate transactions")]
+    DuplicateTransaction,
+
+    #[error("bundle isn't signed by an allowed signer")]
+    SignerNotAllowed,
+
+    #[error("bundle mentions blacklisted account: {0}")]
+    BlacklistedAccount(Pubkey),
+
+    #[error("failed to serialize transaction")]
+    FailedToSerializeTransaction,
+
+    #[error("bundle channel closed")]
+    ChannelClosed,
+}
+
+#[derive(Clone)]
+pub struct RpcBundleSender {
+    bundle_sender: Sender<Vec<PacketBundle>>,
+    // bundles mentioning these accounts are rejected, in addition to consensus accounts
+    blacklisted_accounts: HashSet<Pubkey>,
+    // at least one transaction in a bundle must be signed by one of these
+    allowed_signers: HashSet<Pubkey>,
+}
+
+impl RpcBundleSender {
+    pub fn new(
+        bundle_sender: Sender<Vec<PacketBundle>>,
+        blacklisted_accounts: HashSet<Pubkey>,
+        allowed_signers: HashSet<Pubkey>,
+    ) -> Self {
+        Self {
+            bundle_sender,
+            blacklisted_accounts,
+            allowed_signers,
+        }
+    }
+
+    /// Rejects bundles that BundleStage would drop, along with bundles that aren't signed by an
+    /// allowed signer
+    pub fn verify_bundle(
+        &self,
+        bank: &Bank,
+        transactions: &[SanitizedTransaction],
+    ) -> Result<(), SendBundleError> {
+        if transactions.is_empty() || transactions.len() > MAX_TRANSACTIONS_PER_BUNDLE {
+            return Err(SendBundleError::InvalidBundleLength);
+        }
+
+        let unique_signatures: HashSet<&Signature> =
+            transactions.iter().map(|tx| tx.signature()).collect();
+        if unique_signatures.len() != transactions.len() {
+            return Err(SendBundleError::DuplicateTransaction);
+        }
+
+        let is_signed_by_allowed_signer = transactions.iter().any(|tx| {
+            let message = tx.message();
+            message
+                .account_keys()
+                .iter()
+                .take(message.header().num_required_signatures as usize)
+                .any(|signer| self.allowed_signers.contains(signer))
+        });
+        if !is_signed_by_allowed_signer {
+            return Err(SendBundleError::SignerNotAllowed);
+        }
+
+        let epoch_stakes = bank.epoch_stakes(bank.epoch());
+        let is_blacklisted = |account: &Pubkey| {
+            self.blacklisted_accounts.contains(account)
+                || epoch_stakes.is_some_and(|epoch_stakes| {
+                    epoch_stakes.stakes().vote_accounts().get(account).is_some()
+                        || epoch_stakes
+                            .node_id_to_vote_accounts()
+                            .contains_key(account)
+                        || epoch_stakes.epoch_authorized_voters().contains_key(account)
+                })
+        };
+        if let Some(account) = transactions
+            .iter()
+            .flat_map(|tx| tx.message().account_keys().iter())
+            .find(|account| is_blacklisted(account))
+        {
+            return Err(SendBundleError::BlacklistedAccount(*account));
+        }
+
+        Ok(())
+    }
+
+    pub fn send_bundle(
+        &self,
+        bundle_id: String,
+        transactions: &[VersionedTransaction],
+    ) -> Result<(), SendBundleError> {
+        let packets = transactions
+            .iter()
+            .map(|tx| Packet::from_data(None, tx))
+            .collect::<Result<Vec<Packet>, _>>()
+            .map_err(|_| SendBundleError::FailedToSerializeTransaction)?;
+        self.bundle_sender
+            .send(vec![PacketBundle {
+                batch: PacketBatch::new(packets),
+                bundle_id,
+            }])
+            .map_err(|_| SendBundleError::ChannelClosed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crossbeam_channel::unbounded,
+        solana_runtime::genesis_utils::{
+            create_genesis_config_with_vote_accounts, GenesisConfigInfo, ValidatorVoteKeypairs,
+        },
+        solana_sdk::{
+            signature::{Keypair, Signer},
+            system_transaction::transfer,
+        },
+    };
+
+    #[test]
+    fn test_verify_bundle() {
+        let validator_keypairs = ValidatorVoteKeypairs::new_rand();
+        let GenesisConfigInfo {
+            genesis_config,
+            mint_keypair,
+            ..
+        } = create_genesis_config_with_vote_accounts(
+            1_000_000_000,
+            &[&validator_keypairs],
+            vec![100],
+        );
+        let bank = Bank::new_for_tests(&genesis_config);
+        let blockhash = genesis_config.hash();
+
+        let blacklisted_account = Pubkey::new_unique();
+        let (bundle_sender, bundle_receiver) = unbounded();
+        let rpc_bundle_sender = RpcBundleSender::new(
+            bundle_sender,
+            HashSet::from([blacklisted_account]),
+            HashSet::from([mint_keypair.pubkey()]),
+        );
+
+        let sanitize = |txs: Vec<VersionedTransaction>| -> Vec<SanitizedTransaction> {
+            txs.into_iter()
+                .map(|tx| {
+                    SanitizedTransaction::try_from_legacy_transaction(
+                        tx.into_legacy_transaction().unwrap(),
+                    )
+                    .unwrap()
+                })
+                .collect()
+        };
+        let transfer_to = |from: &Keypair, to: &Pubkey, lamports: u64| {
+            VersionedTransaction::from(transfer(from, to, lamports, blockhash))
+        };
+
+        let bundle: Vec<_> = (0..2)
+            .map(|i| transfer_to(&mint_keypair, &Pubkey::new_unique(), 1 + i))
+            .collect();
+        assert_eq!(
+            rpc_bundle_sender.verify_bundle(&bank, &sanitize(bundle.clone())),
+            Ok(())
+        );
+        rpc_bundle_sender
+            .send_bundle("bundle".to_string(), &bundle)
+            .unwrap();
+        let packet_bundles = bundle_receiver.try_recv().unwrap();
+        assert_eq!(packet_bundles.len(), 1);
+        assert_eq!(packet_bundles[0].bundle_id, "bundle");
+        assert_eq!(packet_bundles[0].batch.len(), 2);
+
+        assert_eq!(
+            rpc_bundle_sender.verify_bundle(&bank, &[]),
+            Err(SendBundleError::InvalidBundleLength)
+        );
+        let too_long: Vec<_> = (0..MAX_TRANSACTIONS_PER_BUNDLE as u64 + 1)
+            .map(|i| transfer_to(&mint_keypair, &Pubkey::new_unique(), 1 + i))
+            .collect();
+        assert_eq!(
+            rpc_bundle_sender.verify_bundle(&bank, &sanitize(too_long)),
+            Err(SendBundleError::InvalidBundleLength)
+        );
+
+        let tx = transfer_to(&mint_keypair, &Pubkey::new_unique(), 1);
+        assert_eq!(
+            rpc_bundle_sender.verify_bundle(&bank, &sanitize(vec![tx.clone(), tx])),
+            Err(SendBundleError::DuplicateTransaction)
+        );
+
+        let not_allowed = transfer_to(&Keypair::new(), &Pubkey::new_unique(), 1);
+        assert_eq!(
+            rpc_bundle_sender.verify_bundle(&bank, &sanitize(vec![not_allowed])),
+            Err(SendBundleError::SignerNotAllowed)
+        );
+
+        let blacklisted = transfer_to(&mint_keypair, &blacklisted_account, 1);
+        assert_eq!(
+            rpc_bundle_sender.verify_bundle(&bank, &sanitize(vec![blacklisted])),
+            Err(SendBundleError::BlacklistedAccount(blacklisted_account))
+        );
+
+        let vote_account = validator_keypairs.vote_keypair.pubkey();
+        let consensus = transfer_to(&mint_keypair, &vote_account, 1);
+        assert_eq!(
+            rpc_bundle_sender.verify_bundle(&bank, &sanitize(vec![consensus])),
+            Err(SendBundleError::BlacklistedAccount(vote_account))
+        );
+    }
+}
@@ -1,8 +1,12 @@
 use {
+    solana_accounts_db::accounts::ScanRootPin,
     solana_rpc_client_api::{config::RpcLargestAccountsFilter, response::RpcAccountBalance},
+    solana_runtime::bank::Bank,
+    solana_sdk::clock::BankId,
     std::{
         collections::HashMap,
-        time::{Duration, SystemTime},
+        sync::Arc,
+        time::{Duration, Instant, SystemTime},
     },
 };
 
@@ -58,6 +62,90 @@ impl LargestAccountsCache {
     }
 }
 
+/// A bank that `getProgramAccountsPaginated` pages are read from, and the scan root pinned so
+/// that clean doesn't change what the bank's accounts look like between pages
+pub struct PinnedBank {
+    pub bank: Arc<Bank>,
+    pub scan_root: ScanRootPin,
+}
+
+struct PinnedBankEntry {
+    pinned_bank: Arc<PinnedBank>,
+    first_pinned: Instant,
+    last_used: Instant,
+}
+
+/// Keeps the banks that `getProgramAccountsPaginated` cursors are anchored to alive between
+/// pages, since they are usually pruned from `BankForks` long before a client is done paging.
+/// Clean can't progress past the oldest pinned root, so banks are only kept for a short while
+/// after they were last used, and never for longer than `max_age`.
+pub struct PinnedBankCache {
+    duration: Duration,
+    max_age: Duration,
+    capacity: usize,
+    banks: HashMap<BankId, PinnedBankEntry>,
+}
+
+impl PinnedBankCache {
+    pub(crate) fn new(duration: Duration, max_age: Duration, capacity: usize) -> Self {
+        Self {
+            duration,
+            max_age,
+            capacity,
+            banks: HashMap::new(),
+        }
+    }
+
+    /// Returns the bank pinned with `bank_id`, if still pinned, and pins it for another
+    /// `duration`
+    pub(crate) fn get(&mut self, bank_id: BankId) -> Option<Arc<PinnedBank>> {
+        self.remove_expired();
+        self.banks.get_mut(&bank_id).map(|entry| {
+            entry.last_used = Instant::now();
+            entry.pinned_bank.clone()
+        })
+    }
+
+    /// Pins `bank` for another `duration`, evicting the least recently used bank if full. A bank
+    /// that is already pinned keeps the scan root it was first pinned with.
+    pub(crate) fn pin(&mut self, bank: &Arc<Bank>) -> Arc<PinnedBank> {
+        if let Some(pinned_bank) = self.get(bank.bank_id()) {
+            return pinned_bank;
+        }
+        if self.banks.len() >= self.capacity {
+            let least_recently_used = self
+                .banks
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(bank_id, _)| *bank_id);
+            if let Some(bank_id) = least_recently_used {
+                self.banks.remove(&bank_id);
+            }
+        }
+        let pinned_bank = Arc::new(PinnedBank {
+            bank: bank.clone(),
+            scan_root: bank.pin_scan_root(),
+        });
+        let now = Instant::now();
+        self.banks.insert(
+            bank.bank_id(),
+            PinnedBankEntry {
+                pinned_bank: pinned_bank.clone(),
+                first_pinned: now,
+                last_used: now,
+            },
+        );
+        pinned_bank
+    }
+
+    fn remove_expired(&mut self) {
+        let (duration, max_age) = (self.duration, self.max_age);
+        self.banks.retain(|_, entry| {
+            entry.last_used.elapsed() < duration && entry.first_pinned.elapsed() < max_age
+        });
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
@@ -74,4 +162,60 @@ pub mod test {
         std::thread::sleep(Duration::from_secs(1));
         assert_eq!(cache.get_largest_accounts(&filter), None);
     }
+
+    #[test]
+    fn test_pinned_bank_cache() {
+        let genesis_config =
+            solana_ledger::genesis_utils::create_genesis_config(10_000).genesis_config;
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        let new_bank = |slot| {
+            Arc::new(Bank::new_from_parent(
+                bank0.clone(),
+                &solana_sdk::pubkey::Pubkey::default(),
+                slot,
+            ))
+        };
+        let (bank1, bank2) = (new_bank(1), new_bank(2));
+        // a bank for the same slot on a competing fork
+        let bank1_fork = new_bank(1);
+        assert_ne!(bank1.bank_id(), bank1_fork.bank_id());
+
+        let mut cache = PinnedBankCache::new(Duration::from_secs(60), Duration::from_secs(60), 2);
+        let pinned_bank0 = cache.pin(&bank0);
+        cache.pin(&bank1);
+        assert_eq!(cache.get(bank0.bank_id()).unwrap().bank.slot(), 0);
+        // getting bank 0 refreshed it, so bank 1 is the one evicted
+        cache.pin(&bank2);
+        assert!(cache.get(bank1.bank_id()).is_none());
+        assert!(cache.get(bank2.bank_id()).is_some());
+        // pinning again keeps the bank's scan root
+        assert!(Arc::ptr_eq(&cache.pin(&bank0), &pinned_bank0));
+
+        // banks are pinned by id, so pinning another fork at the same slot doesn't replace one
+        let mut cache = PinnedBankCache::new(Duration::from_secs(60), Duration::from_secs(60), 2);
+        cache.pin(&bank1);
+        cache.pin(&bank1_fork);
+        assert!(Arc::ptr_eq(
+            &cache.get(bank1.bank_id()).unwrap().bank,
+            &bank1
+        ));
+        assert!(Arc::ptr_eq(
+            &cache.get(bank1_fork.bank_id()).unwrap().bank,
+            &bank1_fork
+        ));
+
+        let mut cache = PinnedBankCache::new(Duration::from_millis(1), Duration::from_secs(60), 2);
+        cache.pin(&bank0);
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(cache.get(bank0.bank_id()).is_none());
+
+        // using a bank doesn't keep it pinned past the maximum age
+        let mut cache = PinnedBankCache::new(Duration::from_secs(60), Duration::from_millis(20), 2);
+        cache.pin(&bank0);
+        for _ in 0..3 {
+            std::thread::sleep(Duration::from_millis(10));
+            cache.get(bank0.bank_id());
+        }
+        assert!(cache.get(bank0.bank_id()).is_none());
+    }
 }
@@ -9,7 +9,7 @@ use {
             AccountSubscriptionParams, BlockSubscriptionKind, BlockSubscriptionParams,
             LogsSubscriptionKind, LogsSubscriptionParams, ProgramSubscriptionParams,
             SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionParams,
-            SubscriptionToken,
+            SubscriptionToken, TransactionSubscriptionParams,
         },
     },
     dashmap::DashMap,
@@ -18,14 +18,16 @@ use {
     jsonrpc_pubsub::{typed::Subscriber, SubscriptionId as PubSubSubscriptionId},
     solana_account_decoder::{UiAccount, UiAccountEncoding},
     solana_rpc_client_api::{
+        bundles::RpcBundleStatus,
         config::{
             RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
             RpcProgramAccountsConfig, RpcSignatureSubscribeConfig, RpcTransactionLogsConfig,
-            RpcTransactionLogsFilter,
+            RpcTransactionLogsFilter, RpcTransactionSubscribeConfig, RpcTransactionSubscribeFilter,
         },
         response::{
             Response as RpcResponse, RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse,
-            RpcSignatureResult, RpcVersionInfo, RpcVote, SlotInfo, SlotUpdate,
+            RpcSignatureResult, RpcTransactionUpdate, RpcVersionInfo, RpcVote, SlotInfo,
+            SlotUpdate,
         },
     },
     solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature},
@@ -214,6 +216,32 @@ pub trait RpcSolPubSub {
         id: PubSubSubscriptionId,
     ) -> Result<bool>;
 
+    // Get notification every time a transaction matching the filter is processed
+    #[pubsub(
+        subscription = "transactionNotification",
+        subscribe,
+        name = "transactionSubscribe"
+    )]
+    fn transaction_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcTransactionUpdate>>,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    );
+
+    // Unsubscribe from transaction notification subscription.
+    #[pubsub(
+        subscription = "transactionNotification",
+        unsubscribe,
+        name = "transactionUnsubscribe"
+    )]
+    fn transaction_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: PubSubSubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when vote is encountered
     #[pubsub(subscription = "voteNotification", subscribe, name = "voteSubscribe")]
     fn vote_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<RpcVote>);
@@ -230,6 +258,30 @@ pub trait RpcSolPubSub {
         id: PubSubSubscriptionId,
     ) -> Result<bool>;
 
+    // Get notification when BundleStage finishes processing a bundle
+    #[pubsub(
+        subscription = "bundleStatusNotification",
+        subscribe,
+        name = "bundleStatusSubscribe"
+    )]
+    fn bundle_status_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcBundleStatus>,
+    );
+
+    // Unsubscribe from bundle status notification subscription.
+    #[pubsub(
+        subscription = "bundleStatusNotification",
+        unsubscribe,
+        name = "bundleStatusUnsubscribe"
+    )]
+    fn bundle_status_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        id: PubSubSubscriptionId,
+    ) -> Result<bool>;
+
     // Get notification when a new root is set
     #[pubsub(subscription = "rootNotification", subscribe, name = "rootSubscribe")]
     fn root_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<Slot>);
@@ -249,6 +301,9 @@ pub trait RpcSolPubSub {
 
 pub use internal::RpcSolPubSubInternal;
 
+// Limits the size of each account list in a `transactionSubscribe` filter
+pub const MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS: usize = 256;
+
 // We have to use a separate module so the code generated by different `rpc` macro invocations do not interfere with each other.
 mod internal {
     use super::*;
@@ -334,6 +389,18 @@ mod internal {
         #[rpc(name = "blockUnsubscribe")]
         fn block_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
 
+        // Get notification every time a transaction matching the filter is processed
+        #[rpc(name = "transactionSubscribe")]
+        fn transaction_subscribe(
+            &self,
+            filter: RpcTransactionSubscribeFilter,
+            config: Option<RpcTransactionSubscribeConfig>,
+        ) -> Result<SubscriptionId>;
+
+        // Unsubscribe from transaction notification subscription.
+        #[rpc(name = "transactionUnsubscribe")]
+        fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
+
         // Get notification when vote is encountered
         #[rpc(name = "voteSubscribe")]
         fn vote_subscribe(&self) -> Result<SubscriptionId>;
@@ -342,6 +409,14 @@ mod internal {
         #[rpc(name = "voteUnsubscribe")]
         fn vote_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
 
+        // Get notification when BundleStage finishes processing a bundle
+        #[rpc(name = "bundleStatusSubscribe")]
+        fn bundle_status_subscribe(&self) -> Result<SubscriptionId>;
+
+        // Unsubscribe from bundle status notification subscription.
+        #[rpc(name = "bundleStatusUnsubscribe")]
+        fn bundle_status_unsubscribe(&self, id: SubscriptionId) -> Result<bool>;
+
         // Get notification when a new root is set
         #[rpc(name = "rootSubscribe")]
         fn root_subscribe(&self) -> Result<SubscriptionId>;
@@ -419,6 +494,26 @@ fn param<T: FromStr>(param_str: &str, thing: &str) -> Result<T> {
     })
 }
 
+fn account_list_param(keys: Option<Vec<String>>, thing: &str) -> Result<Vec<Pubkey>> {
+    let keys = keys.unwrap_or_default();
+    if keys.len() > MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS {
+        return Err(Error {
+            code: ErrorCode::InvalidParams,
+            message: format!(
+                "Invalid Request: Too many {thing} addresses; max {MAX_TRANSACTION_SUBSCRIBE_FILTER_ACCOUNTS}"
+            ),
+            data: None,
+        });
+    }
+    let mut keys = keys
+        .iter()
+        .map(|key| param::<Pubkey>(key, thing))
+        .collect::<Result<Vec<_>>>()?;
+    keys.sort_unstable();
+    keys.dedup();
+    Ok(keys)
+}
+
 impl RpcSolPubSubInternal for RpcSolPubSubImpl {
     fn account_subscribe(
         &self,
@@ -568,6 +663,41 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
+    fn transaction_subscribe(
+        &self,
+        filter: RpcTransactionSubscribeFilter,
+        config: Option<RpcTransactionSubscribeConfig>,
+    ) -> Result<SubscriptionId> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        let RpcTransactionSubscribeFilter {
+            vote,
+            failed,
+            account_include,
+            account_exclude,
+            account_required,
+        } = filter;
+        let config = config.unwrap_or_default();
+        let params = TransactionSubscriptionParams {
+            account_include: account_list_param(account_include, "accountInclude")?,
+            account_exclude: account_list_param(account_exclude, "accountExclude")?,
+            account_required: account_list_param(account_required, "accountRequired")?,
+            vote: vote.unwrap_or(false),
+            failed: failed.unwrap_or(true),
+            encoding: config.encoding.unwrap_or(UiTransactionEncoding::Json),
+            max_supported_transaction_version: config.max_supported_transaction_version,
+        };
+        self.subscribe(SubscriptionParams::Transaction(params))
+    }
+
+    fn transaction_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        if !self.config.enable_transaction_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        self.unsubscribe(id)
+    }
+
     fn vote_subscribe(&self) -> Result<SubscriptionId> {
         if !self.config.enable_vote_subscription {
             return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
@@ -582,6 +712,20 @@ impl RpcSolPubSubInternal for RpcSolPubSubImpl {
         self.unsubscribe(id)
     }
 
+    fn bundle_status_subscribe(&self) -> Result<SubscriptionId> {
+        if !self.config.enable_bundle_status_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        self.subscribe(SubscriptionParams::BundleStatus)
+    }
+
+    fn bundle_status_unsubscribe(&self, id: SubscriptionId) -> Result<bool> {
+        if !self.config.enable_bundle_status_subscription {
+            return Err(Error::new(jsonrpc_core::ErrorCode::MethodNotFound));
+        }
+        self.unsubscribe(id)
+    }
+
     fn root_subscribe(&self) -> Result<SubscriptionId> {
         self.subscribe(SubscriptionParams::Root)
     }
@@ -611,6 +755,7 @@ mod tests {
         jsonrpc_core::{IoHandler, Response},
         serial_test::serial,
         solana_account_decoder::{parse_account_data::parse_account_data, UiAccountEncoding},
+        solana_bundle::bundle_status::BundleStatus,
         solana_rpc_client_api::response::{
             ProcessedSignatureResult, ReceivedSignatureResult, RpcSignatureResult, SlotInfo,
         },
@@ -637,9 +782,10 @@ mod tests {
                 state::{Authorized, Lockup, StakeAuthorize, StakeStateV2},
             },
             system_instruction, system_program, system_transaction,
-            transaction::{self, Transaction},
+            transaction::{self, SanitizedTransaction, Transaction},
         },
         solana_stake_program::stake_state,
+        solana_transaction_status::TransactionStatusMeta,
         solana_vote::vote_transaction::VoteTransaction,
         solana_vote_program::vote_state::Vote,
         std::{
@@ -1403,6 +1549,103 @@ mod tests {
         assert!(rpc.vote_unsubscribe(sub_id).is_ok());
     }
 
+    #[test]
+    #[serial]
+    fn test_bundle_status_subscribe() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let max_complete_rewards_slot = Arc::new(AtomicU64::default());
+        let rpc_subscriptions = Arc::new(RpcSubscriptions::default_with_bank_forks(
+            max_complete_transaction_status_slot,
+            max_complete_rewards_slot,
+            bank_forks,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&rpc_subscriptions);
+        let sub_id = rpc.bundle_status_subscribe().unwrap();
+
+        rpc_subscriptions.notify_bundle_status(BundleStatus::landed(
+            "bundle".to_string(),
+            1,
+            1_000,
+            50,
+        ));
+        let response = receiver.recv();
+        let expected = r#"{"jsonrpc":"2.0","method":"bundleStatusNotification","params":{"result":{"bundleId":"bundle","slot":1,"outcome":"landed","err":null,"failingSignature":null,"computeUnitsConsumed":1000,"tipLamports":50},"subscription":0}}"#;
+        assert_eq!(expected, response);
+
+        assert!(rpc.bundle_status_unsubscribe(42.into()).is_err());
+        assert!(rpc.bundle_status_unsubscribe(sub_id).is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn test_transaction_subscribe() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank = Bank::new_for_tests(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank);
+        let max_complete_transaction_status_slot = Arc::new(AtomicU64::default());
+        let max_complete_rewards_slot = Arc::new(AtomicU64::default());
+        let rpc_subscriptions = Arc::new(RpcSubscriptions::default_with_bank_forks(
+            max_complete_transaction_status_slot,
+            max_complete_rewards_slot,
+            bank_forks,
+        ));
+        let (rpc, mut receiver) = rpc_pubsub_service::test_connection(&rpc_subscriptions);
+
+        let alice = Keypair::new();
+        let bob = Pubkey::new_unique();
+        let sub_id = rpc
+            .transaction_subscribe(
+                RpcTransactionSubscribeFilter {
+                    account_include: Some(vec![bob.to_string()]),
+                    ..RpcTransactionSubscribeFilter::default()
+                },
+                None,
+            )
+            .unwrap();
+        assert!(rpc
+            .transaction_subscribe(
+                RpcTransactionSubscribeFilter {
+                    account_required: Some(vec!["not a pubkey".to_string()]),
+                    ..RpcTransactionSubscribeFilter::default()
+                },
+                None,
+            )
+            .is_err());
+
+        let meta = TransactionStatusMeta::default();
+        let other_transfer = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&alice, &Pubkey::new_unique(), 1, Hash::default()),
+        );
+        let bob_transfer = SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(&alice, &bob, 1, Hash::default()),
+        );
+        // only the transfer to bob passes the filter
+        rpc_subscriptions.notify_transaction(7, 0, &other_transfer, &meta);
+        rpc_subscriptions.notify_transaction(7, 1, &bob_transfer, &meta);
+
+        let response = receiver.recv();
+        let response = serde_json::from_str::<serde_json::Value>(&response).unwrap();
+        assert_eq!(response["method"], "transactionNotification");
+        assert_eq!(response["params"]["subscription"], 0);
+        assert_eq!(response["params"]["result"]["context"]["slot"], 7);
+        let value = &response["params"]["result"]["value"];
+        assert_eq!(value["signature"], bob_transfer.signature().to_string());
+        assert_eq!(value["slot"], 7);
+        assert_eq!(value["index"], 1);
+        assert_eq!(value["isVote"], false);
+        assert_eq!(value["err"], serde_json::Value::Null);
+        assert_eq!(
+            value["transaction"]["transaction"]["signatures"][0],
+            bob_transfer.signature().to_string()
+        );
+
+        assert!(rpc.transaction_unsubscribe(42.into()).is_err());
+        assert!(rpc.transaction_unsubscribe(sub_id).is_ok());
+    }
+
     #[test]
     fn test_get_version() {
         let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
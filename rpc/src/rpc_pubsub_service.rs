@@ -39,6 +39,8 @@ pub const DEFAULT_WORKER_THREADS: usize = 1;
 pub struct PubSubConfig {
     pub enable_block_subscription: bool,
     pub enable_vote_subscription: bool,
+    pub enable_bundle_status_subscription: bool,
+    pub enable_transaction_subscription: bool,
     pub max_active_subscriptions: usize,
     pub queue_capacity_items: usize,
     pub queue_capacity_bytes: usize,
@@ -51,6 +53,8 @@ impl Default for PubSubConfig {
         Self {
             enable_block_subscription: false,
             enable_vote_subscription: false,
+            enable_bundle_status_subscription: false,
+            enable_transaction_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_QUEUE_CAPACITY_ITEMS,
             queue_capacity_bytes: DEFAULT_QUEUE_CAPACITY_BYTES,
@@ -65,6 +69,8 @@ impl PubSubConfig {
         Self {
             enable_block_subscription: false,
             enable_vote_subscription: false,
+            enable_bundle_status_subscription: false,
+            enable_transaction_subscription: false,
             max_active_subscriptions: MAX_ACTIVE_SUBSCRIPTIONS,
             queue_capacity_items: DEFAULT_TEST_QUEUE_CAPACITY_ITEMS,
             queue_capacity_bytes: DEFAULT_QUEUE_CAPACITY_BYTES,
@@ -132,6 +138,7 @@ struct SentNotificationStats {
     num_root: AtomicUsize,
     num_vote: AtomicUsize,
     num_block: AtomicUsize,
+    num_bundle_status: AtomicUsize,
     total_creation_to_queue_time_us: AtomicU64,
     last_report: AtomicInterval,
 }
@@ -186,6 +193,11 @@ impl SentNotificationStats {
                     self.num_block.swap(0, Ordering::Relaxed) as i64,
                     i64
                 ),
+                (
+                    "num_bundle_status",
+                    self.num_bundle_status.swap(0, Ordering::Relaxed) as i64,
+                    i64
+                ),
                 (
                     "total_creation_to_queue_time_us",
                     self.total_creation_to_queue_time_us
@@ -235,6 +247,9 @@ fn increment_sent_notification_stats(
         SubscriptionParams::Block(_) => {
             stats.num_block.fetch_add(1, Ordering::Relaxed);
         }
+        SubscriptionParams::BundleStatus => {
+            stats.num_bundle_status.fetch_add(1, Ordering::Relaxed);
+        }
     }
     stats.total_creation_to_queue_time_us.fetch_add(
         notification.created_at.elapsed().as_micros() as u64,
@@ -331,6 +346,8 @@ pub fn test_connection(
         PubSubConfig {
             enable_block_subscription: true,
             enable_vote_subscription: true,
+            enable_bundle_status_subscription: true,
+            enable_transaction_subscription: true,
             queue_capacity_items: 100,
             ..PubSubConfig::default()
         },
@@ -0,0 +1,667 @@
+//! The `rpc_request_limiter` module implements batch size limits, per-method request weights
+//! and per-client token buckets for the JSON RPC service, along with per-method metrics.
+
+use {
+    crate::rpc::JsonRpcRequestProcessor,
+    jsonrpc_core::{
+        futures::{
+            future::{self, Either},
+            Future, FutureExt,
+        },
+        middleware::Middleware,
+        Call, Error, ErrorCode, Failure, FutureOutput, FutureResponse, MethodCall, Notification,
+        Output, Request, Response, Version,
+    },
+    jsonrpc_http_server::hyper,
+    solana_rpc_client_api::custom_error::RpcCustomError,
+    solana_sdk::timing::AtomicInterval,
+    std::{
+        collections::{HashMap, HashSet},
+        net::{IpAddr, SocketAddr},
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+        time::{Duration, Instant},
+    },
+};
+
+/// Weight of any method without a configured weight
+pub const DEFAULT_RPC_METHOD_WEIGHT: u64 = 1;
+
+const METRICS_REPORT_INTERVAL_MS: u64 = 10_000;
+
+/// Maximum number of clients with a token bucket of their own. Requests from clients beyond this
+/// share a single bucket until idle clients are pruned.
+const MAX_CLIENT_TOKEN_BUCKETS: usize = 100_000;
+
+/// Upper bounds of the latency histogram buckets; the last bucket is unbounded
+const LATENCY_BUCKET_BOUNDS: [Duration; 4] = [
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+];
+
+/// Metrics for calls to methods the service doesn't serve are grouped under this name
+const UNKNOWN_METHOD: &str = "unknown";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcTokenBucketConfig {
+    /// Maximum number of tokens a client can accumulate, i.e. its burst size
+    pub capacity: u64,
+    /// Number of tokens added to each client's bucket every second
+    pub refill_per_second: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RpcRequestLimitsConfig {
+    /// Maximum number of calls in a batch request, unlimited if `None`
+    pub max_batch_size: Option<usize>,
+    /// Tokens charged for a call to each method, see `DEFAULT_RPC_METHOD_WEIGHT`
+    pub method_weights: HashMap<String, u64>,
+    /// Rate limits each client with a token bucket, unlimited if `None`
+    pub client_token_bucket: Option<RpcTokenBucketConfig>,
+    /// Reverse proxies whose `X-Forwarded-For` and `X-Real-IP` headers identify the client, see
+    /// `client_ip`
+    pub trusted_proxies: HashSet<IpAddr>,
+}
+
+/// Parses a `METHOD=WEIGHT` pair, as accepted by `--rpc-method-weight`
+pub fn parse_method_weight(value: &str) -> Result<(String, u64), String> {
+    let (method, weight) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected METHOD=WEIGHT, got '{value}'"))?;
+    if method.is_empty() {
+        return Err(format!("missing method name in '{value}'"));
+    }
+    let weight = weight
+        .parse::<u64>()
+        .map_err(|err| format!("invalid weight in '{value}': {err}"))?;
+    Ok((method.to_string(), weight))
+}
+
+/// Address of the peer a request was received from, recorded in the request's extensions by the
+/// HTTP server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PeerAddr(pub(crate) SocketAddr);
+
+/// Identifies the client that sent `request`.
+///
+/// This is the address of the peer the request was received from, unless that peer is one of
+/// `trusted_proxies`. Requests relayed by a trusted proxy are attributed to the last address in
+/// `X-Forwarded-For` that isn't itself a trusted proxy, or else to the one in `X-Real-IP`.
+/// Forwarding headers sent by any other peer are ignored, since clients can set them to anything.
+pub(crate) fn client_ip(
+    request: &hyper::Request<hyper::Body>,
+    trusted_proxies: &HashSet<IpAddr>,
+) -> Option<IpAddr> {
+    let PeerAddr(peer_addr) = request.extensions().get::<PeerAddr>()?;
+    let peer_ip = peer_addr.ip();
+    if !trusted_proxies.contains(&peer_ip) {
+        return Some(peer_ip);
+    }
+    let headers = request.headers();
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    let forwarded_ip = match header("x-forwarded-for") {
+        Some(forwarded_for) => forwarded_for
+            .rsplit(',')
+            .map(|ip| ip.trim().parse::<IpAddr>().ok())
+            .find(|ip| ip.map_or(true, |ip| !trusted_proxies.contains(&ip)))
+            .flatten(),
+        None => header("x-real-ip").and_then(|ip| ip.trim().parse().ok()),
+    };
+    Some(forwarded_ip.unwrap_or(peer_ip))
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self, config: &RpcTokenBucketConfig, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * config.refill_per_second as f64).min(config.capacity as f64);
+        self.last_refill = now;
+    }
+
+    fn is_full(&self, config: &RpcTokenBucketConfig) -> bool {
+        self.tokens >= config.capacity as f64
+    }
+}
+
+struct ClientTokenBuckets {
+    config: RpcTokenBucketConfig,
+    buckets: Mutex<HashMap<Option<IpAddr>, TokenBucket>>,
+    max_clients: usize,
+}
+
+impl ClientTokenBuckets {
+    fn new(config: RpcTokenBucketConfig, max_clients: usize) -> Self {
+        Self {
+            config,
+            buckets: Mutex::default(),
+            max_clients,
+        }
+    }
+
+    /// Takes `cost` tokens from the client's bucket, if it holds that many. Once `max_clients`
+    /// clients have a bucket, new clients are charged against the bucket of unknown clients.
+    fn try_consume(&self, client: Option<IpAddr>, cost: u64, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap();
+        let client = if buckets.len() >= self.max_clients && !buckets.contains_key(&client) {
+            None
+        } else {
+            client
+        };
+        let bucket = buckets.entry(client).or_insert_with(|| TokenBucket {
+            tokens: self.config.capacity as f64,
+            last_refill: now,
+        });
+        bucket.refill(&self.config, now);
+        if bucket.tokens < cost as f64 {
+            return false;
+        }
+        bucket.tokens -= cost as f64;
+        true
+    }
+
+    /// Drops the buckets that have refilled completely, since those are no different from the
+    /// bucket a new client starts out with. Returns the number of buckets left.
+    fn prune(&self, now: Instant) -> usize {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.retain(|_, bucket| {
+            bucket.refill(&self.config, now);
+            !bucket.is_full(&self.config)
+        });
+        buckets.len()
+    }
+}
+
+#[derive(Default)]
+struct MethodMetrics {
+    num_calls: AtomicU64,
+    num_rejected: AtomicU64,
+    total_latency_us: AtomicU64,
+    max_latency_us: AtomicU64,
+    latency_histogram: [AtomicU64; LATENCY_BUCKET_BOUNDS.len() + 1],
+}
+
+impl MethodMetrics {
+    fn record_call(&self, latency: Duration) {
+        let latency_us = latency.as_micros().try_into().unwrap_or(u64::MAX);
+        self.num_calls.fetch_add(1, Ordering::Relaxed);
+        self.total_latency_us
+            .fetch_add(latency_us, Ordering::Relaxed);
+        self.max_latency_us.fetch_max(latency_us, Ordering::Relaxed);
+        let bucket = LATENCY_BUCKET_BOUNDS
+            .iter()
+            .position(|bound| latency < *bound)
+            .unwrap_or(LATENCY_BUCKET_BOUNDS.len());
+        self.latency_histogram[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn report(&self, method: &str) {
+        let num_calls = self.num_calls.swap(0, Ordering::Relaxed);
+        let num_rejected = self.num_rejected.swap(0, Ordering::Relaxed);
+        if num_calls == 0 && num_rejected == 0 {
+            return;
+        }
+        let [lt_1ms, lt_10ms, lt_100ms, lt_1s, ge_1s] = &self.latency_histogram;
+        datapoint_info!(
+            "rpc_method_metrics",
+            "method" => method,
+            ("num_calls", num_calls, i64),
+            ("num_rejected", num_rejected, i64),
+            (
+                "total_latency_us",
+                self.total_latency_us.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "max_latency_us",
+                self.max_latency_us.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            ("latency_lt_1ms", lt_1ms.swap(0, Ordering::Relaxed), i64),
+            ("latency_lt_10ms", lt_10ms.swap(0, Ordering::Relaxed), i64),
+            ("latency_lt_100ms", lt_100ms.swap(0, Ordering::Relaxed), i64),
+            ("latency_lt_1s", lt_1s.swap(0, Ordering::Relaxed), i64),
+            ("latency_ge_1s", ge_1s.swap(0, Ordering::Relaxed), i64)
+        );
+    }
+}
+
+/// JSON RPC middleware that enforces `RpcRequestLimitsConfig` and records `rpc_method_metrics`.
+///
+/// A request is admitted or rejected as a whole: a batch is charged the sum of the weights of its
+/// calls against the client's token bucket before any of them runs.
+pub struct RpcRequestLimiter {
+    max_batch_size: Option<usize>,
+    method_weights: HashMap<String, u64>,
+    client_token_buckets: Option<ClientTokenBuckets>,
+    /// One entry for each method the service serves, so that arbitrary method names sent by
+    /// clients can't grow the map or the set of reported metrics
+    method_metrics: HashMap<String, Arc<MethodMetrics>>,
+    unknown_method_metrics: Arc<MethodMetrics>,
+    last_report: AtomicInterval,
+}
+
+impl RpcRequestLimiter {
+    pub fn new<'a>(config: RpcRequestLimitsConfig, methods: impl Iterator<Item = &'a str>) -> Self {
+        let method_metrics: HashMap<_, _> = methods
+            .map(|method| (method.to_string(), Arc::<MethodMetrics>::default()))
+            .collect();
+        for method in config.method_weights.keys() {
+            if !method_metrics.contains_key(method) {
+                warn!("rpc method weight configured for unknown method {method}");
+            }
+        }
+        Self {
+            max_batch_size: config.max_batch_size,
+            method_weights: config.method_weights,
+            client_token_buckets: config
+                .client_token_bucket
+                .map(|config| ClientTokenBuckets::new(config, MAX_CLIENT_TOKEN_BUCKETS)),
+            method_metrics,
+            unknown_method_metrics: Arc::default(),
+            last_report: AtomicInterval::default(),
+        }
+    }
+
+    fn method_metrics(&self, call: &Call) -> &Arc<MethodMetrics> {
+        call_method(call)
+            .and_then(|method| self.method_metrics.get(method))
+            .unwrap_or(&self.unknown_method_metrics)
+    }
+
+    fn call_weight(&self, call: &Call) -> u64 {
+        call_method(call)
+            .and_then(|method| self.method_weights.get(method))
+            .copied()
+            .unwrap_or(DEFAULT_RPC_METHOD_WEIGHT)
+    }
+
+    /// Checks `calls` against the configured limits, returning the error to reject them with
+    fn check_limits(
+        &self,
+        calls: &[Call],
+        is_batch: bool,
+        client: Option<IpAddr>,
+    ) -> Option<Error> {
+        if let Some(max_batch_size) = self.max_batch_size {
+            if is_batch && calls.len() > max_batch_size {
+                return Some(Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: format!(
+                        "Batch of {} requests exceeds the limit of {max_batch_size}",
+                        calls.len()
+                    ),
+                    data: None,
+                });
+            }
+        }
+        let client_token_buckets = self.client_token_buckets.as_ref()?;
+        let cost = calls
+            .iter()
+            .map(|call| self.call_weight(call))
+            .fold(0u64, u64::saturating_add);
+        // retrying can never help a request that costs more than a full bucket
+        let capacity = client_token_buckets.config.capacity;
+        if cost > capacity {
+            return Some(Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!(
+                    "Request costs {cost} tokens, more than the per-client capacity of {capacity}"
+                ),
+                data: None,
+            });
+        }
+        (!client_token_buckets.try_consume(client, cost, Instant::now()))
+            .then(|| RpcCustomError::RateLimited.into())
+    }
+
+    fn maybe_report(&self) {
+        if self.last_report.should_update(METRICS_REPORT_INTERVAL_MS) {
+            for (method, metrics) in &self.method_metrics {
+                metrics.report(method);
+            }
+            self.unknown_method_metrics.report(UNKNOWN_METHOD);
+            if let Some(client_token_buckets) = &self.client_token_buckets {
+                let num_clients = client_token_buckets.prune(Instant::now());
+                datapoint_info!(
+                    "rpc_client_token_buckets",
+                    ("num_clients", num_clients, i64)
+                );
+            }
+        }
+    }
+}
+
+fn call_method(call: &Call) -> Option<&str> {
+    match call {
+        Call::MethodCall(MethodCall { method, .. })
+        | Call::Notification(Notification { method, .. }) => Some(method.as_str()),
+        Call::Invalid { .. } => None,
+    }
+}
+
+fn reject_call(call: Call, error: Error) -> Option<Output> {
+    let (jsonrpc, id) = match call {
+        Call::MethodCall(MethodCall { jsonrpc, id, .. }) => (jsonrpc, id),
+        Call::Invalid { id } => (Some(Version::V2), id),
+        // notifications never get a response
+        Call::Notification(_) => return None,
+    };
+    Some(Output::Failure(Failure { jsonrpc, error, id }))
+}
+
+impl Middleware<JsonRpcRequestProcessor> for RpcRequestLimiter {
+    type Future = FutureResponse;
+    type CallFuture = FutureOutput;
+
+    fn on_request<F, X>(
+        &self,
+        request: Request,
+        meta: JsonRpcRequestProcessor,
+        next: F,
+    ) -> Either<Self::Future, X>
+    where
+        F: Fn(Request, JsonRpcRequestProcessor) -> X + Send + Sync,
+        X: Future<Output = Option<Response>> + Send + 'static,
+    {
+        let (calls, is_batch) = match &request {
+            Request::Single(call) => (std::slice::from_ref(call), false),
+            Request::Batch(calls) => (calls.as_slice(), true),
+        };
+        let Some(error) = self.check_limits(calls, is_batch, meta.client_ip()) else {
+            return Either::Right(next(request, meta));
+        };
+        for call in calls {
+            self.method_metrics(call)
+                .num_rejected
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        self.maybe_report();
+
+        let response = match request {
+            Request::Single(call) => reject_call(call, error).map(Response::Single),
+            Request::Batch(calls) => {
+                let outputs: Vec<_> = calls
+                    .into_iter()
+                    .filter_map(|call| reject_call(call, error.clone()))
+                    .collect();
+                (!outputs.is_empty()).then_some(Response::Batch(outputs))
+            }
+        };
+        Either::Left(Box::pin(future::ready(response)))
+    }
+
+    fn on_call<F, X>(
+        &self,
+        call: Call,
+        meta: JsonRpcRequestProcessor,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, JsonRpcRequestProcessor) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        self.maybe_report();
+        let metrics = self.method_metrics(&call).clone();
+        let start = Instant::now();
+        Either::Left(Box::pin(next(call, meta).map(move |output| {
+            metrics.record_call(start.elapsed());
+            output
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, jsonrpc_core::Params};
+
+    fn method_call(method: &str, id: u64) -> Call {
+        Call::MethodCall(MethodCall {
+            jsonrpc: Some(Version::V2),
+            method: method.to_string(),
+            params: Params::None,
+            id: jsonrpc_core::Id::Num(id),
+        })
+    }
+
+    #[test]
+    fn test_parse_method_weight() {
+        assert_eq!(
+            parse_method_weight("getProgramAccounts=100"),
+            Ok(("getProgramAccounts".to_string(), 100))
+        );
+        assert!(parse_method_weight("getProgramAccounts").is_err());
+        assert!(parse_method_weight("=100").is_err());
+        assert!(parse_method_weight("getProgramAccounts=-1").is_err());
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let request = |peer_ip: &str, headers: &[(&str, &str)]| {
+            let mut builder = hyper::Request::builder();
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            let mut request = builder.body(hyper::Body::empty()).unwrap();
+            let peer_addr = SocketAddr::new(peer_ip.parse().unwrap(), 8899);
+            request.extensions_mut().insert(PeerAddr(peer_addr));
+            request
+        };
+        let ip = |ip: &str| Some(ip.parse::<IpAddr>().unwrap());
+        let proxy = "127.0.0.1";
+        let trusted_proxies = HashSet::from([proxy.parse().unwrap(), "10.0.0.2".parse().unwrap()]);
+
+        let no_peer = hyper::Request::new(hyper::Body::empty());
+        assert_eq!(client_ip(&no_peer, &trusted_proxies), None);
+
+        // forwarding headers from untrusted peers are ignored
+        let forwarded = [("X-Forwarded-For", "10.0.0.1"), ("X-Real-IP", "10.0.0.1")];
+        assert_eq!(
+            client_ip(&request("192.168.0.1", &forwarded), &trusted_proxies),
+            ip("192.168.0.1")
+        );
+        assert_eq!(
+            client_ip(&request(proxy, &forwarded), &HashSet::new()),
+            ip(proxy)
+        );
+
+        // trusted proxies are skipped from the end of X-Forwarded-For
+        assert_eq!(
+            client_ip(
+                &request(
+                    proxy,
+                    &[("X-Forwarded-For", "10.0.0.1, 192.168.0.1, 10.0.0.2")]
+                ),
+                &trusted_proxies
+            ),
+            ip("192.168.0.1")
+        );
+        assert_eq!(
+            client_ip(
+                &request(proxy, &[("X-Real-IP", "10.0.0.1")]),
+                &trusted_proxies
+            ),
+            ip("10.0.0.1")
+        );
+        // otherwise the proxy itself is the client
+        assert_eq!(client_ip(&request(proxy, &[]), &trusted_proxies), ip(proxy));
+        assert_eq!(
+            client_ip(
+                &request(proxy, &[("X-Forwarded-For", "bogus, 10.0.0.2")]),
+                &trusted_proxies
+            ),
+            ip(proxy)
+        );
+    }
+
+    #[test]
+    fn test_token_buckets() {
+        let buckets = ClientTokenBuckets::new(
+            RpcTokenBucketConfig {
+                capacity: 10,
+                refill_per_second: 5,
+            },
+            MAX_CLIENT_TOKEN_BUCKETS,
+        );
+        let client = Some("10.0.0.1".parse().unwrap());
+        let now = Instant::now();
+
+        assert!(buckets.try_consume(client, 8, now));
+        assert!(!buckets.try_consume(client, 3, now));
+        // each client has a bucket of its own
+        assert!(buckets.try_consume(None, 10, now));
+        assert!(buckets.try_consume(client, 2, now));
+        assert!(!buckets.try_consume(client, 1, now));
+
+        // refills at the configured rate, up to the capacity
+        let now = now + Duration::from_millis(400);
+        assert!(buckets.try_consume(client, 2, now));
+        assert!(!buckets.try_consume(client, 1, now));
+        let now = now + Duration::from_secs(10);
+        assert!(!buckets.try_consume(client, 11, now));
+        assert!(buckets.try_consume(client, 10, now));
+
+        // buckets that have refilled completely are dropped
+        assert_eq!(buckets.prune(now), 1);
+        assert_eq!(buckets.prune(now + Duration::from_secs(1)), 1);
+        assert_eq!(buckets.prune(now + Duration::from_secs(2)), 0);
+    }
+
+    #[test]
+    fn test_token_buckets_max_clients() {
+        let buckets = ClientTokenBuckets::new(
+            RpcTokenBucketConfig {
+                capacity: 10,
+                refill_per_second: 5,
+            },
+            2,
+        );
+        let clients: Vec<Option<IpAddr>> = ["10.0.0.1", "10.0.0.2", "10.0.0.3"]
+            .iter()
+            .map(|ip| Some(ip.parse().unwrap()))
+            .collect();
+        let now = Instant::now();
+
+        assert!(buckets.try_consume(clients[0], 10, now));
+        assert!(buckets.try_consume(clients[1], 10, now));
+        // clients beyond the limit share the bucket of unknown clients
+        assert!(buckets.try_consume(clients[2], 10, now));
+        assert!(!buckets.try_consume(None, 1, now));
+        assert!(!buckets.try_consume(clients[2], 1, now));
+        assert_eq!(buckets.buckets.lock().unwrap().len(), 3);
+
+        // until idle clients are pruned
+        let now = now + Duration::from_secs(2);
+        assert_eq!(buckets.prune(now), 0);
+        assert!(buckets.try_consume(clients[2], 10, now));
+        assert!(buckets.try_consume(None, 10, now));
+    }
+
+    #[test]
+    fn test_check_limits() {
+        let limiter = RpcRequestLimiter::new(
+            RpcRequestLimitsConfig {
+                max_batch_size: Some(3),
+                method_weights: HashMap::from([("getProgramAccounts".to_string(), 5)]),
+                client_token_bucket: Some(RpcTokenBucketConfig {
+                    capacity: 8,
+                    refill_per_second: 1,
+                }),
+                ..RpcRequestLimitsConfig::default()
+            },
+            ["getProgramAccounts", "getSlot"].into_iter(),
+        );
+        let client = Some("10.0.0.1".parse().unwrap());
+
+        let batch: Vec<_> = (0..4).map(|id| method_call("getSlot", id)).collect();
+        assert_eq!(
+            limiter.check_limits(&batch, true, client).unwrap().code,
+            ErrorCode::InvalidRequest
+        );
+        assert!(limiter.check_limits(&batch[..3], true, client).is_none());
+
+        // 3 of 8 tokens are spent, leaving just enough for one getProgramAccounts
+        let get_program_accounts = [method_call("getProgramAccounts", 0)];
+        assert!(limiter
+            .check_limits(&get_program_accounts, false, client)
+            .is_none());
+        let error = limiter
+            .check_limits(&get_program_accounts, false, client)
+            .unwrap();
+        assert_eq!(error.message, "Too many requests from this client");
+        assert!(limiter
+            .check_limits(&get_program_accounts, false, None)
+            .is_none());
+
+        // a request costing more than a full bucket is rejected as invalid, not rate limited
+        let too_costly: Vec<_> = (0..2)
+            .map(|id| method_call("getProgramAccounts", id))
+            .collect();
+        let error = limiter
+            .check_limits(&too_costly, true, Some("10.0.0.2".parse().unwrap()))
+            .unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidRequest);
+        assert_eq!(
+            error.message,
+            "Request costs 10 tokens, more than the per-client capacity of 8"
+        );
+
+        // unknown methods cost the default weight and share a single set of metrics
+        assert_eq!(limiter.call_weight(&method_call("bogus", 0)), 1);
+        assert!(Arc::ptr_eq(
+            limiter.method_metrics(&method_call("bogus", 0)),
+            &limiter.unknown_method_metrics
+        ));
+    }
+
+    #[test]
+    fn test_reject_call() {
+        let error = Error::from(RpcCustomError::RateLimited);
+        assert_eq!(
+            reject_call(method_call("getSlot", 7), error.clone()),
+            Some(Output::Failure(Failure {
+                jsonrpc: Some(Version::V2),
+                error: error.clone(),
+                id: jsonrpc_core::Id::Num(7),
+            }))
+        );
+        assert_eq!(
+            reject_call(
+                Call::Notification(Notification {
+                    jsonrpc: Some(Version::V2),
+                    method: "getSlot".to_string(),
+                    params: Params::None,
+                }),
+                error,
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_method_metrics() {
+        let metrics = MethodMetrics::default();
+        metrics.record_call(Duration::from_micros(500));
+        metrics.record_call(Duration::from_millis(50));
+        metrics.record_call(Duration::from_secs(2));
+        assert_eq!(metrics.num_calls.load(Ordering::Relaxed), 3);
+        assert_eq!(metrics.max_latency_us.load(Ordering::Relaxed), 2_000_000);
+        let histogram: Vec<_> = metrics
+            .latency_histogram
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect();
+        assert_eq!(histogram, vec![1, 0, 1, 0, 1]);
+    }
+}
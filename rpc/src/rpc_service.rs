@@ -9,16 +9,25 @@ use {
             rpc_accounts::*, rpc_accounts_scan::*, rpc_bank::*, rpc_deprecated_v1_7::*,
             rpc_deprecated_v1_9::*, rpc_full::*, rpc_minimal::*, rpc_obsolete_v1_7::*, *,
         },
+        rpc_bundle_sender::RpcBundleSender,
         rpc_cache::LargestAccountsCache,
         rpc_health::*,
+        rpc_request_limiter::{client_ip, PeerAddr, RpcRequestLimiter},
     },
     crossbeam_channel::unbounded,
-    jsonrpc_core::{futures::prelude::*, MetaIoHandler},
+    jsonrpc_core::{futures::prelude::*, MetaIoHandler, RemoteProcedure},
     jsonrpc_http_server::{
-        hyper, AccessControlAllowOrigin, CloseHandle, DomainsValidation, RequestMiddleware,
-        RequestMiddlewareAction, ServerBuilder,
+        cors::AccessControlAllowHeaders,
+        hyper::{
+            self,
+            server::conn::AddrStream,
+            service::{make_service_fn, service_fn, Service},
+        },
+        AccessControlAllowOrigin, RequestMiddleware, RequestMiddlewareAction, RestApi, Rpc,
+        ServerHandler,
     },
     regex::Regex,
+    solana_bundle::bundle_status::BundleStatusCache,
     solana_client::connection_cache::ConnectionCache,
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::{
@@ -42,7 +51,9 @@ use {
     solana_send_transaction_service::send_transaction_service::{self, SendTransactionService},
     solana_storage_bigtable::CredentialType,
     std::{
-        net::SocketAddr,
+        convert::Infallible,
+        io,
+        net::{SocketAddr, TcpListener},
         path::{Path, PathBuf},
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
@@ -50,6 +61,7 @@ use {
         },
         thread::{self, Builder, JoinHandle},
     },
+    tokio::sync::Notify,
     tokio_util::codec::{BytesCodec, FramedRead},
 };
 
@@ -313,6 +325,66 @@ impl RequestMiddleware for RpcRequestMiddleware {
     }
 }
 
+/// Stops the JSON RPC HTTP server
+#[derive(Clone, Default)]
+struct CloseHandle(Arc<Notify>);
+
+impl CloseHandle {
+    fn close(&self) {
+        self.0.notify_one();
+    }
+
+    async fn closed(&self) {
+        self.0.notified().await
+    }
+}
+
+/// Serves `rpc` over HTTP on `rpc_addr` until `close_handle` is closed.
+///
+/// This does what `jsonrpc_http_server::ServerBuilder::start_http` does, except that it records
+/// the address of the peer each request is received from as a `PeerAddr`, which `start_http`
+/// discards, so that the request limiter can tell clients apart. Must be called from within a
+/// tokio runtime.
+fn bind_http_server(
+    rpc_addr: &SocketAddr,
+    rpc: Rpc<JsonRpcRequestProcessor, RpcRequestLimiter>,
+    request_middleware: RpcRequestMiddleware,
+    max_request_body_size: usize,
+    close_handle: CloseHandle,
+) -> io::Result<impl Future<Output = hyper::Result<()>>> {
+    let server = hyper::Server::from_tcp(TcpListener::bind(rpc_addr)?)
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+        .http1_keepalive(true)
+        .tcp_nodelay(true)
+        .tcp_sleep_on_accept_errors(true);
+
+    let request_middleware: Arc<dyn RequestMiddleware> = Arc::new(request_middleware);
+    let make_service = make_service_fn(move |conn: &AddrStream| {
+        let peer_addr = PeerAddr(conn.remote_addr());
+        let mut handler = ServerHandler::new(
+            rpc.downgrade(),
+            Some(vec![AccessControlAllowOrigin::Any]),
+            Some(86400),
+            AccessControlAllowHeaders::Any,
+            None,
+            request_middleware.clone(),
+            RestApi::Disabled,
+            None,
+            max_request_body_size,
+            true,
+        );
+        let service = service_fn(move |mut request: hyper::Request<hyper::Body>| {
+            request.extensions_mut().insert(peer_addr);
+            handler.call(request)
+        });
+        async move { Ok::<_, Infallible>(service) }
+    });
+
+    Ok(server
+        .serve(make_service)
+        .with_graceful_shutdown(async move { close_handle.closed().await }))
+}
+
 fn process_rest(bank_forks: &Arc<RwLock<BankForks>>, path: &str) -> Option<String> {
     match path {
         "/v0/circulating-supply" => {
@@ -361,6 +433,8 @@ impl JsonRpcService {
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
+        bundle_status_cache: Arc<BundleStatusCache>,
+        bundle_sender: Option<RpcBundleSender>,
     ) -> Result<Self, String> {
         info!("rpc bound to {:?}", rpc_addr);
         info!("rpc configuration: {:?}", config);
@@ -451,6 +525,7 @@ impl JsonRpcService {
 
         let full_api = config.full_api;
         let obsolete_v1_7_api = config.obsolete_v1_7_api;
+        let request_limits = config.request_limits.clone();
         let max_request_body_size = config
             .max_request_body_size
             .unwrap_or(MAX_REQUEST_BODY_SIZE);
@@ -472,6 +547,8 @@ impl JsonRpcService {
             max_complete_transaction_status_slot,
             max_complete_rewards_slot,
             prioritization_fee_cache,
+            bundle_status_cache,
+            bundle_sender,
         );
 
         let leader_info =
@@ -483,6 +560,7 @@ impl JsonRpcService {
             receiver,
             &connection_cache,
             send_transaction_service_config,
+            request_processor.retry_status_cache(),
             exit,
         ));
 
@@ -497,40 +575,53 @@ impl JsonRpcService {
             .spawn(move || {
                 renice_this_thread(rpc_niceness_adj).unwrap();
 
-                let mut io = MetaIoHandler::default();
-
-                io.extend_with(rpc_minimal::MinimalImpl.to_delegate());
+                let mut methods: Vec<(String, RemoteProcedure<JsonRpcRequestProcessor>)> =
+                    rpc_minimal::MinimalImpl.to_delegate().into_iter().collect();
                 if full_api {
-                    io.extend_with(rpc_bank::BankDataImpl.to_delegate());
-                    io.extend_with(rpc_accounts::AccountsDataImpl.to_delegate());
-                    io.extend_with(rpc_accounts_scan::AccountsScanImpl.to_delegate());
-                    io.extend_with(rpc_full::FullImpl.to_delegate());
-                    io.extend_with(rpc_deprecated_v1_7::DeprecatedV1_7Impl.to_delegate());
-                    io.extend_with(rpc_deprecated_v1_9::DeprecatedV1_9Impl.to_delegate());
+                    methods.extend(rpc_bank::BankDataImpl.to_delegate());
+                    methods.extend(rpc_accounts::AccountsDataImpl.to_delegate());
+                    methods.extend(rpc_accounts_scan::AccountsScanImpl.to_delegate());
+                    methods.extend(rpc_full::FullImpl.to_delegate());
+                    methods.extend(rpc_deprecated_v1_7::DeprecatedV1_7Impl.to_delegate());
+                    methods.extend(rpc_deprecated_v1_9::DeprecatedV1_9Impl.to_delegate());
                 }
                 if obsolete_v1_7_api {
-                    io.extend_with(rpc_obsolete_v1_7::ObsoleteV1_7Impl.to_delegate());
+                    methods.extend(rpc_obsolete_v1_7::ObsoleteV1_7Impl.to_delegate());
                 }
 
+                let trusted_proxies = request_limits.trusted_proxies.clone();
+                let request_limiter = RpcRequestLimiter::new(
+                    request_limits,
+                    methods.iter().map(|(method, _)| method.as_str()),
+                );
+                let mut io = MetaIoHandler::with_middleware(request_limiter);
+                io.extend_with(methods);
+
                 let request_middleware = RpcRequestMiddleware::new(
                     ledger_path,
                     snapshot_config,
                     bank_forks.clone(),
                     health.clone(),
                 );
-                let server = ServerBuilder::with_meta_extractor(
-                    io,
-                    move |_req: &hyper::Request<hyper::Body>| request_processor.clone(),
-                )
-                .event_loop_executor(runtime.handle().clone())
-                .threads(1)
-                .cors(DomainsValidation::AllowOnly(vec![
-                    AccessControlAllowOrigin::Any,
-                ]))
-                .cors_max_age(86400)
-                .request_middleware(request_middleware)
-                .max_request_body_size(max_request_body_size)
-                .start_http(&rpc_addr);
+                let rpc = Rpc {
+                    handler: Arc::new(io),
+                    extractor: Arc::new(move |req: &hyper::Request<hyper::Body>| {
+                        request_processor
+                            .clone()
+                            .with_client_ip(client_ip(req, &trusted_proxies))
+                    }),
+                };
+                let close_handle = CloseHandle::default();
+                let server = {
+                    let _guard = runtime.enter();
+                    bind_http_server(
+                        &rpc_addr,
+                        rpc,
+                        request_middleware,
+                        max_request_body_size,
+                        close_handle.clone(),
+                    )
+                };
 
                 if let Err(e) = server {
                     warn!(
@@ -543,9 +634,10 @@ impl JsonRpcService {
                     return;
                 }
 
-                let server = server.unwrap();
-                close_handle_sender.send(Ok(server.close_handle())).unwrap();
-                server.wait();
+                close_handle_sender.send(Ok(close_handle)).unwrap();
+                if let Err(err) = runtime.block_on(server.unwrap()) {
+                    error!("JSON RPC service error: {err}");
+                }
                 exit_bigtable_ledger_upload_service.store(true, Ordering::Relaxed);
             })
             .unwrap();
@@ -650,6 +742,8 @@ mod tests {
             Arc::new(AtomicU64::default()),
             Arc::new(AtomicU64::default()),
             Arc::new(PrioritizationFeeCache::default()),
+            Arc::new(BundleStatusCache::default()),
+            None,
         )
         .expect("assume successful JsonRpcService start");
         let thread = rpc_service.thread_hdl.thread();
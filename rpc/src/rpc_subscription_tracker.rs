@@ -16,7 +16,7 @@ use {
         collections::hash_map::{Entry, HashMap},
         fmt,
         sync::{
-            atomic::{AtomicU64, Ordering},
+            atomic::{AtomicU64, AtomicUsize, Ordering},
             Arc, RwLock, Weak,
         },
     },
@@ -46,10 +46,12 @@ pub enum SubscriptionParams {
     Logs(LogsSubscriptionParams),
     Program(ProgramSubscriptionParams),
     Signature(SignatureSubscriptionParams),
+    Transaction(TransactionSubscriptionParams),
     Slot,
     SlotsUpdates,
     Root,
     Vote,
+    BundleStatus,
 }
 
 impl SubscriptionParams {
@@ -59,11 +61,13 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(_) => "logsNotification",
             SubscriptionParams::Program(_) => "programNotification",
             SubscriptionParams::Signature(_) => "signatureNotification",
+            SubscriptionParams::Transaction(_) => "transactionNotification",
             SubscriptionParams::Slot => "slotNotification",
             SubscriptionParams::SlotsUpdates => "slotsUpdatesNotification",
             SubscriptionParams::Block(_) => "blockNotification",
             SubscriptionParams::Root => "rootNotification",
             SubscriptionParams::Vote => "voteNotification",
+            SubscriptionParams::BundleStatus => "bundleStatusNotification",
         }
     }
 
@@ -74,10 +78,12 @@ impl SubscriptionParams {
             SubscriptionParams::Program(params) => Some(params.commitment),
             SubscriptionParams::Signature(params) => Some(params.commitment),
             SubscriptionParams::Block(params) => Some(params.commitment),
-            SubscriptionParams::Slot
+            SubscriptionParams::Transaction(_)
+            | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
             | SubscriptionParams::Root
-            | SubscriptionParams::Vote => None,
+            | SubscriptionParams::Vote
+            | SubscriptionParams::BundleStatus => None,
         }
     }
 
@@ -88,10 +94,12 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
-            SubscriptionParams::Root
+            SubscriptionParams::Transaction(_)
+            | SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
-            | SubscriptionParams::Vote => return false,
+            | SubscriptionParams::Vote
+            | SubscriptionParams::BundleStatus => return false,
         };
         !commitment.is_confirmed()
     }
@@ -103,10 +111,12 @@ impl SubscriptionParams {
             SubscriptionParams::Logs(params) => &params.commitment,
             SubscriptionParams::Program(params) => &params.commitment,
             SubscriptionParams::Signature(params) => &params.commitment,
-            SubscriptionParams::Root
+            SubscriptionParams::Transaction(_)
+            | SubscriptionParams::Root
             | SubscriptionParams::Slot
             | SubscriptionParams::SlotsUpdates
-            | SubscriptionParams::Vote => return false,
+            | SubscriptionParams::Vote
+            | SubscriptionParams::BundleStatus => return false,
         };
         commitment.is_confirmed()
     }
@@ -118,6 +128,7 @@ impl SubscriptionParams {
                 | SubscriptionParams::SlotsUpdates
                 | SubscriptionParams::Root
                 | SubscriptionParams::Vote
+                | SubscriptionParams::BundleStatus
         )
     }
 }
@@ -176,6 +187,33 @@ pub struct SignatureSubscriptionParams {
     pub enable_received_notification: bool,
 }
 
+/// Transactions are pushed to these subscriptions as they are processed, so they have no
+/// commitment level of their own. Account lists are sorted and deduplicated so that equivalent
+/// filters share a subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransactionSubscriptionParams {
+    pub account_include: Vec<Pubkey>,
+    pub account_exclude: Vec<Pubkey>,
+    pub account_required: Vec<Pubkey>,
+    pub vote: bool,
+    pub failed: bool,
+    pub encoding: UiTransactionEncoding,
+    pub max_supported_transaction_version: Option<u8>,
+}
+
+impl TransactionSubscriptionParams {
+    /// Returns whether a transaction mentioning `account_keys` passes this subscription's filters.
+    pub fn is_match(&self, account_keys: &[Pubkey], is_vote: bool, is_failed: bool) -> bool {
+        if (is_vote && !self.vote) || (is_failed && !self.failed) {
+            return false;
+        }
+        let mentions = |key: &Pubkey| account_keys.contains(key);
+        (self.account_include.is_empty() || self.account_include.iter().any(mentions))
+            && !self.account_exclude.iter().any(mentions)
+            && self.account_required.iter().all(mentions)
+    }
+}
+
 #[derive(Clone)]
 pub struct SubscriptionControl(Arc<SubscriptionControlInner>);
 pub struct WeakSubscriptionTokenRef(Weak<SubscriptionTokenInner>, SubscriptionId);
@@ -187,6 +225,7 @@ struct SubscriptionControlInner {
     sender: crossbeam_channel::Sender<TimestampedNotificationEntry>,
     broadcast_sender: broadcast::Sender<RpcNotification>,
     counter: TokenCounter,
+    num_transaction_subscriptions: AtomicUsize,
 }
 
 impl SubscriptionControl {
@@ -202,6 +241,7 @@ impl SubscriptionControl {
             sender,
             broadcast_sender,
             counter: TokenCounter::new("rpc_pubsub_total_subscriptions"),
+            num_transaction_subscriptions: AtomicUsize::new(0),
         }))
     }
 
@@ -248,6 +288,11 @@ impl SubscriptionControl {
                 }
                 let id = SubscriptionId::from(self.0.next_id.fetch_add(1, Ordering::AcqRel));
                 let (token, weak_ref) = create_token_and_weak_ref(id, entry.key().clone());
+                if matches!(entry.key(), SubscriptionParams::Transaction(_)) {
+                    self.0
+                        .num_transaction_subscriptions
+                        .fetch_add(1, Ordering::Relaxed);
+                }
                 let _ = self
                     .0
                     .sender
@@ -266,6 +311,12 @@ impl SubscriptionControl {
         self.0.subscriptions.len()
     }
 
+    /// Whether anyone is subscribed to transactions, so that producers can skip building
+    /// notifications nobody will receive.
+    pub fn has_transaction_subscriptions(&self) -> bool {
+        self.0.num_transaction_subscriptions.load(Ordering::Relaxed) > 0
+    }
+
     #[cfg(test)]
     pub fn assert_subscribed(&self, params: &SubscriptionParams) {
         assert!(self.0.subscriptions.contains_key(params));
@@ -421,8 +472,10 @@ pub struct SubscriptionsTracker {
     commitment_watchers: HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
     // Accounts, logs, programs, signatures (gossip)
     gossip_watchers: HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
-    // Slots, slots updates, roots, votes.
+    // Slots, slots updates, roots, votes, bundle statuses.
     node_progress_watchers: HashMap<SubscriptionParams, Arc<SubscriptionInfo>>,
+    // Transactions, notified as they are processed.
+    transaction_watchers: HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
 }
 
 impl SubscriptionsTracker {
@@ -438,6 +491,7 @@ impl SubscriptionsTracker {
             commitment_watchers: HashMap::new(),
             gossip_watchers: HashMap::new(),
             node_progress_watchers: HashMap::new(),
+            transaction_watchers: HashMap::new(),
         }
     }
 
@@ -464,6 +518,9 @@ impl SubscriptionsTracker {
                     .or_default()
                     .insert(id, Arc::clone(&info));
             }
+            SubscriptionParams::Transaction(_) => {
+                self.transaction_watchers.insert(id, Arc::clone(&info));
+            }
             _ => {}
         }
         if info.params.is_commitment_watcher() {
@@ -496,6 +553,11 @@ impl SubscriptionsTracker {
                     warn!("Subscriptions inconsistency (missing entry in by_signature)");
                 }
             }
+            SubscriptionParams::Transaction(_) => {
+                if self.transaction_watchers.remove(&id).is_none() {
+                    warn!("Subscriptions inconsistency (missing entry in transaction_watchers)");
+                }
+            }
             _ => {}
         }
         if params.is_commitment_watcher() {
@@ -532,6 +594,10 @@ impl SubscriptionsTracker {
     pub fn node_progress_watchers(&self) -> &HashMap<SubscriptionParams, Arc<SubscriptionInfo>> {
         &self.node_progress_watchers
     }
+
+    pub fn transaction_watchers(&self) -> &HashMap<SubscriptionId, Arc<SubscriptionInfo>> {
+        &self.transaction_watchers
+    }
 }
 
 struct SubscriptionTokenInner {
@@ -562,6 +628,11 @@ impl Drop for SubscriptionTokenInner {
                     .control
                     .sender
                     .send(NotificationEntry::Unsubscribed(self.params.clone(), self.id).into());
+                if matches!(self.params, SubscriptionParams::Transaction(_)) {
+                    self.control
+                        .num_transaction_subscriptions
+                        .fetch_sub(1, Ordering::Relaxed);
+                }
                 entry.remove();
                 datapoint_info!(
                     "rpc-subscription",
@@ -786,4 +857,70 @@ mod tests {
         tracker.unsubscribe(signature_params, 3.into());
         assert_eq!(counts(&tracker), (0, 0, 0, 0));
     }
+
+    #[test]
+    fn transaction_subscription_filters() {
+        let account_a = Pubkey::new_unique();
+        let account_b = Pubkey::new_unique();
+        let account_c = Pubkey::new_unique();
+        let params = TransactionSubscriptionParams {
+            account_include: vec![],
+            account_exclude: vec![],
+            account_required: vec![],
+            vote: false,
+            failed: true,
+            encoding: UiTransactionEncoding::Base64,
+            max_supported_transaction_version: None,
+        };
+        assert!(params.is_match(&[account_a], false, false));
+        assert!(params.is_match(&[account_a], false, true));
+        assert!(!params.is_match(&[account_a], true, false));
+
+        let params = TransactionSubscriptionParams {
+            account_include: vec![account_a, account_b],
+            account_exclude: vec![account_c],
+            failed: false,
+            ..params
+        };
+        assert!(params.is_match(&[account_a], false, false));
+        assert!(params.is_match(&[account_b], false, false));
+        assert!(!params.is_match(&[account_a], false, true));
+        assert!(!params.is_match(&[account_a, account_c], false, false));
+        assert!(!params.is_match(&[Pubkey::new_unique()], false, false));
+
+        let params = TransactionSubscriptionParams {
+            account_include: vec![],
+            account_exclude: vec![],
+            account_required: vec![account_a, account_b],
+            ..params
+        };
+        assert!(params.is_match(&[account_b, account_c, account_a], false, false));
+        assert!(!params.is_match(&[account_a, account_c], false, false));
+    }
+
+    #[test]
+    fn transaction_subscription_count() {
+        let control = ControlWrapper::new();
+        let params = SubscriptionParams::Transaction(TransactionSubscriptionParams {
+            account_include: vec![],
+            account_exclude: vec![],
+            account_required: vec![],
+            vote: false,
+            failed: true,
+            encoding: UiTransactionEncoding::Base64,
+            max_supported_transaction_version: None,
+        });
+        assert!(!control.control.has_transaction_subscriptions());
+
+        let token1 = control.control.subscribe(params.clone()).unwrap();
+        control.assert_subscribed(&params, 0);
+        let token2 = control.control.subscribe(params.clone()).unwrap();
+        assert!(control.control.has_transaction_subscriptions());
+
+        drop(token1);
+        assert!(control.control.has_transaction_subscriptions());
+        drop(token2);
+        control.assert_unsubscribed(&params, 0);
+        assert!(!control.control.has_transaction_subscriptions());
+    }
 }
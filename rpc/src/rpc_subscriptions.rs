@@ -11,19 +11,25 @@ use {
             SignatureSubscriptionParams, SubscriptionControl, SubscriptionId, SubscriptionInfo,
             SubscriptionParams, SubscriptionsTracker,
         },
+        transaction_notifier_interface::TransactionNotifier,
     },
     crossbeam_channel::{Receiver, RecvTimeoutError, SendError, Sender},
     itertools::Either,
     rayon::prelude::*,
     serde::Serialize,
     solana_account_decoder::{parse_token::is_known_spl_token_id, UiAccount, UiAccountEncoding},
+    solana_bundle::bundle_status::BundleStatus,
     solana_ledger::{blockstore::Blockstore, get_tmp_ledger_path},
     solana_measure::measure::Measure,
     solana_rayon_threadlimit::get_thread_count,
-    solana_rpc_client_api::response::{
-        ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse, RpcBlockUpdate,
-        RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse, RpcResponseContext,
-        RpcSignatureResult, RpcVote, SlotInfo, SlotUpdate,
+    solana_rpc_client_api::{
+        bundles::RpcBundleStatus,
+        response::{
+            ProcessedSignatureResult, ReceivedSignatureResult, Response as RpcResponse,
+            RpcBlockUpdate, RpcBlockUpdateError, RpcKeyedAccount, RpcLogsResponse,
+            RpcResponseContext, RpcSignatureResult, RpcTransactionUpdate,
+            RpcTransactionUpdateError, RpcVote, SlotInfo, SlotUpdate,
+        },
     },
     solana_runtime::{
         bank::{Bank, TransactionLogInfo},
@@ -36,10 +42,11 @@ use {
         pubkey::Pubkey,
         signature::Signature,
         timing::timestamp,
-        transaction,
+        transaction::{self, SanitizedTransaction, VersionedTransaction},
     },
     solana_transaction_status::{
-        BlockEncodingOptions, ConfirmedBlock, EncodeError, VersionedConfirmedBlock,
+        BlockEncodingOptions, ConfirmedBlock, EncodeError, TransactionStatusMeta,
+        VersionedConfirmedBlock, VersionedTransactionWithStatusMeta,
     },
     solana_vote::vote_transaction::VoteTransaction,
     std::{
@@ -91,10 +98,23 @@ impl From<NotificationEntry> for TimestampedNotificationEntry {
     }
 }
 
+/// A processed transaction along with what `transactionSubscribe` filters on.
+pub struct TransactionNotification {
+    slot: Slot,
+    index: usize,
+    signature: Signature,
+    is_vote: bool,
+    account_keys: Vec<Pubkey>,
+    transaction: VersionedTransaction,
+    meta: TransactionStatusMeta,
+}
+
 pub enum NotificationEntry {
     Slot(SlotInfo),
     SlotUpdate(SlotUpdate),
     Vote((Pubkey, VoteTransaction, Signature)),
+    BundleStatus(BundleStatus),
+    Transaction(Box<TransactionNotification>),
     Root(Slot),
     Bank(CommitmentSlots),
     Gossip(Slot),
@@ -108,6 +128,12 @@ impl std::fmt::Debug for NotificationEntry {
         match self {
             NotificationEntry::Root(root) => write!(f, "Root({root})"),
             NotificationEntry::Vote(vote) => write!(f, "Vote({vote:?})"),
+            NotificationEntry::BundleStatus(bundle_status) => {
+                write!(f, "BundleStatus({bundle_status:?})")
+            }
+            NotificationEntry::Transaction(notification) => {
+                write!(f, "Transaction({:?})", notification.signature)
+            }
             NotificationEntry::Slot(slot_info) => write!(f, "Slot({slot_info:?})"),
             NotificationEntry::SlotUpdate(slot_update) => {
                 write!(f, "SlotUpdate({slot_update:?})")
@@ -744,6 +770,40 @@ impl RpcSubscriptions {
         self.enqueue_notification(NotificationEntry::Vote((vote_pubkey, vote, signature)));
     }
 
+    pub fn notify_bundle_status(&self, bundle_status: BundleStatus) {
+        self.enqueue_notification(NotificationEntry::BundleStatus(bundle_status));
+    }
+
+    /// Notify `transactionSubscribe` subscribers of a processed transaction. Skipped entirely
+    /// while there are no such subscriptions, since it copies the transaction and its metadata.
+    pub fn notify_transaction(
+        &self,
+        slot: Slot,
+        index: usize,
+        transaction: &SanitizedTransaction,
+        meta: &TransactionStatusMeta,
+    ) {
+        if !self.control.has_transaction_subscriptions() {
+            return;
+        }
+        self.enqueue_notification(NotificationEntry::Transaction(Box::new(
+            TransactionNotification {
+                slot,
+                index,
+                signature: *transaction.signature(),
+                is_vote: transaction.is_simple_vote_transaction(),
+                account_keys: transaction
+                    .message()
+                    .account_keys()
+                    .iter()
+                    .copied()
+                    .collect(),
+                transaction: transaction.to_versioned_transaction(),
+                meta: meta.clone(),
+            },
+        )));
+    }
+
     pub fn notify_roots(&self, mut rooted_slots: Vec<Slot>) {
         rooted_slots.sort_unstable();
         rooted_slots.into_iter().for_each(|root| {
@@ -845,6 +905,23 @@ impl RpcSubscriptions {
                                 notifier.notify(&rpc_vote, sub, false);
                             }
                         }
+                        NotificationEntry::BundleStatus(bundle_status) => {
+                            if let Some(sub) = subscriptions
+                                .node_progress_watchers()
+                                .get(&SubscriptionParams::BundleStatus)
+                            {
+                                debug!("bundle status notify: {:?}", bundle_status);
+                                inc_new_counter_info!("rpc-subscription-notify-bundle-status", 1);
+                                notifier.notify(RpcBundleStatus::from(bundle_status), sub, false);
+                            }
+                        }
+                        NotificationEntry::Transaction(notification) => {
+                            RpcSubscriptions::notify_transaction_watchers(
+                                subscriptions.transaction_watchers(),
+                                &notification,
+                                &notifier,
+                            );
+                        }
                         NotificationEntry::Root(root) => {
                             if let Some(sub) = subscriptions
                                 .node_progress_watchers()
@@ -929,6 +1006,58 @@ impl RpcSubscriptions {
         }
     }
 
+    fn notify_transaction_watchers(
+        subscriptions: &HashMap<SubscriptionId, Arc<SubscriptionInfo>>,
+        notification: &TransactionNotification,
+        notifier: &RpcNotifier,
+    ) {
+        let is_failed = notification.meta.status.is_err();
+        for subscription in subscriptions.values() {
+            let SubscriptionParams::Transaction(params) = subscription.params() else {
+                error!("invalid params type in transaction_watchers");
+                continue;
+            };
+            if !params.is_match(&notification.account_keys, notification.is_vote, is_failed) {
+                continue;
+            }
+            let transaction_with_meta = VersionedTransactionWithStatusMeta {
+                transaction: notification.transaction.clone(),
+                meta: notification.meta.clone(),
+            };
+            let (transaction, err) = match transaction_with_meta.encode(
+                params.encoding,
+                params.max_supported_transaction_version,
+                true,
+            ) {
+                Ok(transaction) => (Some(transaction), None),
+                Err(EncodeError::UnsupportedTransactionVersion(version)) => (
+                    None,
+                    Some(RpcTransactionUpdateError::UnsupportedTransactionVersion(
+                        version,
+                    )),
+                ),
+            };
+            inc_new_counter_info!("rpc-subscription-notify-transaction", 1);
+            notifier.notify(
+                RpcResponse::from(RpcNotificationResponse {
+                    context: RpcNotificationContext {
+                        slot: notification.slot,
+                    },
+                    value: RpcTransactionUpdate {
+                        signature: notification.signature.to_string(),
+                        slot: notification.slot,
+                        index: notification.index,
+                        is_vote: notification.is_vote,
+                        transaction,
+                        err,
+                    },
+                }),
+                subscription,
+                false,
+            );
+        }
+    }
+
     fn notify_watchers(
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         max_complete_rewards_slot: Arc<AtomicU64>,
@@ -1246,6 +1375,41 @@ impl RpcSubscriptions {
     }
 }
 
+/// Forwards transactions from the `TransactionStatusService` to `RpcSubscriptions`.
+///
+/// The transaction status service is started while the ledger is loaded, before the bank forks
+/// that `RpcSubscriptions` depends on exist, so the subscriptions are attached once created.
+#[derive(Default)]
+pub struct TransactionSubscriptionNotifier {
+    rpc_subscriptions: RwLock<Weak<RpcSubscriptions>>,
+}
+
+impl TransactionSubscriptionNotifier {
+    pub fn attach(&self, rpc_subscriptions: &Arc<RpcSubscriptions>) {
+        *self.rpc_subscriptions.write().unwrap() = Arc::downgrade(rpc_subscriptions);
+    }
+}
+
+impl TransactionNotifier for TransactionSubscriptionNotifier {
+    fn notify_transaction(
+        &self,
+        slot: Slot,
+        transaction_slot_index: usize,
+        _signature: &Signature,
+        transaction_status_meta: &TransactionStatusMeta,
+        transaction: &SanitizedTransaction,
+    ) {
+        if let Some(rpc_subscriptions) = self.rpc_subscriptions.read().unwrap().upgrade() {
+            rpc_subscriptions.notify_transaction(
+                slot,
+                transaction_slot_index,
+                transaction,
+                transaction_status_meta,
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use {
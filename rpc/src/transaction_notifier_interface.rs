@@ -16,3 +16,27 @@ pub trait TransactionNotifier {
 }
 
 pub type TransactionNotifierArc = Arc<dyn TransactionNotifier + Sync + Send>;
+
+/// Notifies each of several notifiers in turn, e.g. Geyser plugins and RPC subscriptions.
+pub struct TransactionNotifierFanout(pub Vec<TransactionNotifierArc>);
+
+impl TransactionNotifier for TransactionNotifierFanout {
+    fn notify_transaction(
+        &self,
+        slot: Slot,
+        transaction_slot_index: usize,
+        signature: &Signature,
+        transaction_status_meta: &TransactionStatusMeta,
+        transaction: &SanitizedTransaction,
+    ) {
+        for notifier in &self.0 {
+            notifier.notify_transaction(
+                slot,
+                transaction_slot_index,
+                signature,
+                transaction_status_meta,
+                transaction,
+            );
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use {
-    solana_runtime::{bank_forks::BankForks, commitment::BlockCommitmentCache},
+    solana_runtime::{bank::Bank, bank_forks::BankForks, commitment::BlockCommitmentCache},
+    solana_sdk::bundle::SanitizedBundle,
     std::{
         any::Any,
         error,
@@ -12,6 +13,11 @@ use {
 
 pub type Result<T> = std::result::Result<T, RuntimePluginError>;
 
+/// Version of the [`RuntimePlugin`] interface. Plugins are loaded as trait objects, so any change
+/// to the trait (including adding a method with a default implementation) changes its vtable
+/// layout and must bump this version.
+pub const RUNTIME_PLUGIN_INTERFACE_VERSION: u32 = 1;
+
 /// Errors returned by plugin calls
 #[derive(Error, Debug)]
 pub enum RuntimePluginError {
@@ -34,8 +40,32 @@ pub struct PluginDependencies {
     pub exit: Arc<AtomicBool>,
 }
 
+/// A plugin's verdict on whether BundleStage should execute a bundle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BundleAcceptance {
+    /// Execute the bundle as usual
+    Accept,
+    /// Drop the bundle without executing it, with a reason reported back in its status
+    Reject(String),
+    /// Execute the bundle only after the accepted bundles in the same batch
+    Deprioritize,
+}
+
+/// The interface for runtime plugins. The dynamic library must export a "C" function
+/// `_create_plugin` which creates the implementation of the plugin, and a "C" function
+/// `_runtime_plugin_interface_version` which returns the [`RUNTIME_PLUGIN_INTERFACE_VERSION`] it
+/// was built against. Libraries built against a different version are refused at load time and
+/// must be rebuilt.
 pub trait RuntimePlugin: Any + Debug + Send + Sync {
     fn name(&self) -> &'static str;
     fn on_load(&mut self, config_file: &str, dependencies: PluginDependencies) -> Result<()>;
     fn on_unload(&mut self);
+
+    /// Called by BundleStage for every bundle before its accounts are locked, with the bank it
+    /// would be executed against. Operators can use this to enforce their own bundle acceptance
+    /// rules (minimum tips, program allowlists, sanctioned addresses, ...). This runs on the hot
+    /// path of block production, so implementations should be fast and must not block.
+    fn check_bundle(&self, _bank: &Bank, _bundle: &SanitizedBundle) -> BundleAcceptance {
+        BundleAcceptance::Accept
+    }
 }
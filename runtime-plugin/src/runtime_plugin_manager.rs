@@ -1,9 +1,12 @@
 use {
-    crate::runtime_plugin::{PluginDependencies, RuntimePlugin},
+    crate::runtime_plugin::{
+        BundleAcceptance, PluginDependencies, RuntimePlugin, RUNTIME_PLUGIN_INTERFACE_VERSION,
+    },
     jsonrpc_core::{serde_json, ErrorCode, Result as JsonRpcResult},
     libloading::Library,
     log::*,
-    solana_runtime::{bank_forks::BankForks, commitment::BlockCommitmentCache},
+    solana_runtime::{bank::Bank, bank_forks::BankForks, commitment::BlockCommitmentCache},
+    solana_sdk::bundle::SanitizedBundle,
     std::{
         fs::File,
         io::Read,
@@ -32,6 +35,9 @@ pub enum RuntimePluginManagerError {
     #[error("Cannot load plugin shared library")]
     PluginLoadError(String),
 
+    #[error("The plugin was built against an incompatible runtime plugin interface")]
+    IncompatiblePluginInterface(String),
+
     #[error("The runtime plugin {0} is already loaded shared library")]
     PluginAlreadyLoaded(String),
 
@@ -201,6 +207,28 @@ impl RuntimePluginManager {
         Ok(self.plugins.iter().map(|p| p.name().to_owned()).collect())
     }
 
+    /// Asks every loaded plugin whether `bundle` should be executed. A rejection from any plugin
+    /// rejects the bundle; otherwise it's deprioritized if any plugin asks for it.
+    pub fn check_bundle(&self, bank: &Bank, bundle: &SanitizedBundle) -> BundleAcceptance {
+        let mut acceptance = BundleAcceptance::Accept;
+        for plugin in &self.plugins {
+            match plugin.check_bundle(bank, bundle) {
+                BundleAcceptance::Accept => {}
+                BundleAcceptance::Reject(reason) => {
+                    return BundleAcceptance::Reject(format!("{}: {reason}", plugin.name()));
+                }
+                BundleAcceptance::Deprioritize => acceptance = BundleAcceptance::Deprioritize,
+            }
+        }
+        acceptance
+    }
+
+    /// Adds an already constructed plugin, bypassing shared library loading
+    #[cfg(any(test, feature = "dev-context-only-utils"))]
+    pub fn add_plugin_for_tests(&mut self, plugin: Box<dyn RuntimePlugin>) {
+        self.plugins.push(plugin);
+    }
+
     fn try_drop_plugin(&mut self, idx: usize) {
         if idx < self.plugins.len() {
             let mut plugin = self.plugins.remove(idx);
@@ -217,6 +245,7 @@ fn load_plugin_from_config(
     plugin_config_path: &Path,
 ) -> Result<(Box<dyn RuntimePlugin>, Library, &str), RuntimePluginManagerError> {
     type PluginConstructor = unsafe fn() -> *mut dyn RuntimePlugin;
+    type PluginInterfaceVersion = unsafe fn() -> u32;
     use libloading::Symbol;
 
     let mut file = match File::open(plugin_config_path) {
@@ -263,8 +292,25 @@ fn load_plugin_from_config(
         .ok_or(RuntimePluginManagerError::InvalidPluginPath)?;
 
     let (plugin, lib) = unsafe {
-        let lib = Library::new(libpath)
+        let lib = Library::new(&libpath)
             .map_err(|e| RuntimePluginManagerError::PluginLoadError(e.to_string()))?;
+        // check the interface version before touching the plugin's vtable
+        let interface_version: Symbol<PluginInterfaceVersion> =
+            lib.get(b"_runtime_plugin_interface_version").map_err(|e| {
+                RuntimePluginManagerError::IncompatiblePluginInterface(format!(
+                    "{libpath:?} doesn't report its interface version, rebuild it against \
+                     version {RUNTIME_PLUGIN_INTERFACE_VERSION}: {e}"
+                ))
+            })?;
+        let interface_version = interface_version();
+        if interface_version != RUNTIME_PLUGIN_INTERFACE_VERSION {
+            return Err(RuntimePluginManagerError::IncompatiblePluginInterface(
+                format!(
+                    "{libpath:?} was built against interface version {interface_version}, rebuild \
+                 it against version {RUNTIME_PLUGIN_INTERFACE_VERSION}"
+                ),
+            ));
+        }
         let constructor: Symbol<PluginConstructor> = lib
             .get(b"_create_plugin")
             .map_err(|e| RuntimePluginManagerError::PluginLoadError(e.to_string()))?;
@@ -273,3 +319,91 @@ fn load_plugin_from_config(
 
     Ok((plugin, lib, config_file))
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::runtime_plugin::Result as PluginResult};
+
+    /// Returns the same verdict for every bundle
+    #[derive(Debug)]
+    struct StaticPlugin {
+        name: &'static str,
+        acceptance: BundleAcceptance,
+    }
+
+    impl RuntimePlugin for StaticPlugin {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn on_load(
+            &mut self,
+            _config_file: &str,
+            _dependencies: PluginDependencies,
+        ) -> PluginResult<()> {
+            Ok(())
+        }
+
+        fn on_unload(&mut self) {}
+
+        fn check_bundle(&self, _bank: &Bank, _bundle: &SanitizedBundle) -> BundleAcceptance {
+            self.acceptance.clone()
+        }
+    }
+
+    fn new_manager(plugins: &[(&'static str, BundleAcceptance)]) -> RuntimePluginManager {
+        let mut manager = RuntimePluginManager::new(
+            BankForks::new_rw_arc(Bank::default_for_tests()),
+            Arc::default(),
+            Arc::default(),
+        );
+        for (name, acceptance) in plugins {
+            manager.add_plugin_for_tests(Box::new(StaticPlugin {
+                name: *name,
+                acceptance: acceptance.clone(),
+            }));
+        }
+        manager
+    }
+
+    #[test]
+    fn test_check_bundle_combines_plugin_verdicts() {
+        let bank = Bank::default_for_tests();
+        let bundle = SanitizedBundle {
+            transactions: vec![],
+            bundle_id: "bundle".to_string(),
+        };
+        let reject = || BundleAcceptance::Reject("too small".to_string());
+
+        assert_eq!(
+            new_manager(&[]).check_bundle(&bank, &bundle),
+            BundleAcceptance::Accept
+        );
+        assert_eq!(
+            new_manager(&[
+                ("a", BundleAcceptance::Accept),
+                ("b", BundleAcceptance::Accept)
+            ])
+            .check_bundle(&bank, &bundle),
+            BundleAcceptance::Accept
+        );
+        assert_eq!(
+            new_manager(&[
+                ("a", BundleAcceptance::Deprioritize),
+                ("b", BundleAcceptance::Accept)
+            ])
+            .check_bundle(&bank, &bundle),
+            BundleAcceptance::Deprioritize
+        );
+        // any rejection wins, and is attributed to the plugin that rejected the bundle
+        assert_eq!(
+            new_manager(&[
+                ("a", BundleAcceptance::Deprioritize),
+                ("b", reject()),
+                ("c", BundleAcceptance::Accept)
+            ])
+            .check_bundle(&bank, &bundle),
+            BundleAcceptance::Reject("b: too small".to_string())
+        );
+    }
+}
@@ -51,6 +51,10 @@ impl RuntimePluginService {
         })
     }
 
+    pub fn plugin_manager(&self) -> Arc<RwLock<RuntimePluginManager>> {
+        self.plugin_manager.clone()
+    }
+
     pub fn join(self) {
         if let Err(e) = self.rpc_thread.join() {
             error!("error joining rpc thread: {e:?}");
@@ -71,7 +71,7 @@ use {
     },
     serde::Serialize,
     solana_accounts_db::{
-        accounts::{AccountAddressFilter, AccountLocks, Accounts, PubkeyAccountSlot},
+        accounts::{AccountAddressFilter, AccountLocks, Accounts, PubkeyAccountSlot, ScanRootPin},
         accounts_db::{
             AccountShrinkThreshold, AccountStorageEntry, AccountsDb, AccountsDbConfig,
             CalcAccountsHashDataSource, VerifyAccountsHashAndLamportsConfig,
@@ -6211,6 +6211,37 @@ impl Bank {
         )
     }
 
+    /// Pins the current root for scans of this bank; see `Accounts::pin_scan_root`
+    pub fn pin_scan_root(&self) -> ScanRootPin {
+        self.rc.accounts.pin_scan_root()
+    }
+
+    /// Returns a page of at most `limit` program accounts in pubkey order, starting after the
+    /// `start_after` pubkey; see `Accounts::load_page_by_program_with_filter`
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_filtered_program_accounts_page<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        program_id: &Pubkey,
+        filter: F,
+        start_after: Option<&Pubkey>,
+        limit: usize,
+        scan_root: &ScanRootPin,
+        config: &ScanConfig,
+        byte_limit_for_scan: Option<usize>,
+    ) -> ScanResult<Vec<TransactionAccount>> {
+        self.rc.accounts.load_page_by_program_with_filter(
+            &self.ancestors,
+            self.bank_id,
+            program_id,
+            filter,
+            start_after,
+            limit,
+            scan_root,
+            config,
+            byte_limit_for_scan,
+        )
+    }
+
     pub fn get_filtered_indexed_accounts<F: Fn(&AccountSharedData) -> bool>(
         &self,
         index_key: &IndexKey,
@@ -1,9 +1,13 @@
 use {
     solana_measure::measure,
     solana_sdk::{clock::Slot, pubkey::Pubkey, saturating_add_assign},
-    std::collections::HashMap,
+    std::{cmp::Reverse, collections::HashMap},
 };
 
+/// Maximum number of writable accounts whose fee distributions are retained per block; only the
+/// accounts write-locked by the most transactions are kept.
+const MAX_WRITABLE_ACCOUNT_FEE_DISTRIBUTIONS: usize = 1_000;
+
 #[derive(Debug, Default)]
 struct PrioritizationFeeMetrics {
     // Count of writable accounts in slot
@@ -136,6 +140,9 @@ pub enum PrioritizationFeeError {
 /// block; and the minimum fee for each writable account in all transactions in this block. The only relevant
 /// write account minimum fees are those greater than the block minimum transaction fee, because the minimum fee needed to land
 /// a transaction is determined by Max( min_transaction_fee, min_writable_account_fees(key), ...)
+/// The full distribution of fees, block-wide and per writable account, is retained as well so that
+/// percentile estimates can be served from the cache. A writable account's distribution is kept
+/// even if its minimum fee isn't relevant, since its higher percentiles may still be.
 #[derive(Debug)]
 pub struct PrioritizationFee {
     // The minimum prioritization fee of transactions that landed in this block.
@@ -144,6 +151,14 @@ pub struct PrioritizationFee {
     // The minimum prioritization fee of each writable account in transactions in this block.
     min_writable_account_fees: HashMap<Pubkey, u64>,
 
+    // The prioritization fee of every transaction that landed in this block, sorted once the
+    // block is finalized.
+    transaction_fees: Vec<u64>,
+
+    // The prioritization fee of every transaction that write-locked each account in this block,
+    // sorted once the block is finalized. Pruned to the most contended accounts.
+    writable_account_fees: HashMap<Pubkey, Vec<u64>>,
+
     // Default to `false`, set to `true` when a block is completed, therefore the minimum fees recorded
     // are finalized, and can be made available for use (e.g., RPC query)
     is_finalized: bool,
@@ -157,6 +172,8 @@ impl Default for PrioritizationFee {
         PrioritizationFee {
             min_transaction_fee: u64::MAX,
             min_writable_account_fees: HashMap::new(),
+            transaction_fees: Vec::new(),
+            writable_account_fees: HashMap::new(),
             is_finalized: false,
             metrics: PrioritizationFeeMetrics::default(),
         }
@@ -176,6 +193,7 @@ impl PrioritizationFee {
                     if transaction_fee < self.min_transaction_fee {
                         self.min_transaction_fee = transaction_fee;
                     }
+                    self.transaction_fees.push(transaction_fee);
 
                     for write_account in writable_accounts.iter() {
                         self.min_writable_account_fees
@@ -184,6 +202,10 @@ impl PrioritizationFee {
                                 *write_lock_fee = std::cmp::min(*write_lock_fee, transaction_fee)
                             })
                             .or_insert(transaction_fee);
+                        self.writable_account_fees
+                            .entry(*write_account)
+                            .or_default()
+                            .push(transaction_fee);
                     }
 
                     self.metrics
@@ -203,11 +225,22 @@ impl PrioritizationFee {
     }
 
     /// Accounts that have minimum fees lesser or equal to the minimum fee in the block are redundant, they are
-    /// removed to reduce memory footprint when mark_block_completed() is called.
+    /// removed to reduce memory footprint when mark_block_completed() is called. Fee distributions
+    /// are kept regardless, bounded to those of the `MAX_WRITABLE_ACCOUNT_FEE_DISTRIBUTIONS` most
+    /// contended accounts.
     fn prune_irrelevant_writable_accounts(&mut self) {
         self.metrics.total_writable_accounts_count = self.get_writable_accounts_count() as u64;
         self.min_writable_account_fees
             .retain(|_, account_fee| account_fee > &mut self.min_transaction_fee);
+        if self.writable_account_fees.len() > MAX_WRITABLE_ACCOUNT_FEE_DISTRIBUTIONS {
+            let mut writable_account_fees: Vec<_> = self.writable_account_fees.drain().collect();
+            writable_account_fees
+                .select_nth_unstable_by_key(MAX_WRITABLE_ACCOUNT_FEE_DISTRIBUTIONS, |(_, fees)| {
+                    Reverse(fees.len())
+                });
+            writable_account_fees.truncate(MAX_WRITABLE_ACCOUNT_FEE_DISTRIBUTIONS);
+            self.writable_account_fees = writable_account_fees.into_iter().collect();
+        }
         self.metrics.relevant_writable_accounts_count = self.get_writable_accounts_count() as u64;
     }
 
@@ -216,6 +249,10 @@ impl PrioritizationFee {
             return Err(PrioritizationFeeError::BlockIsAlreadyFinalized);
         }
         self.prune_irrelevant_writable_accounts();
+        self.transaction_fees.sort_unstable();
+        self.writable_account_fees
+            .values_mut()
+            .for_each(|fees| fees.sort_unstable());
         self.is_finalized = true;
         Ok(())
     }
@@ -232,6 +269,17 @@ impl PrioritizationFee {
         self.min_writable_account_fees.iter()
     }
 
+    /// Prioritization fees of all transactions in the block, sorted ascending once finalized.
+    pub fn get_transaction_fees(&self) -> &[u64] {
+        &self.transaction_fees
+    }
+
+    /// Prioritization fees of all transactions that write-locked `key` in the block, sorted
+    /// ascending once finalized.
+    pub fn get_writable_account_fee_distribution(&self, key: &Pubkey) -> Option<&[u64]> {
+        self.writable_account_fees.get(key).map(Vec::as_slice)
+    }
+
     pub fn get_writable_accounts_count(&self) -> usize {
         self.min_writable_account_fees.len()
     }
@@ -379,9 +427,76 @@ mod tests {
             assert!(prioritization_fee
                 .get_writable_account_fee(&write_account_c)
                 .is_none());
+            // the fee distributions of every account are kept
+            assert_eq!(3, prioritization_fee.writable_account_fees.len());
+            assert!(prioritization_fee
+                .get_writable_account_fee_distribution(&write_account_a)
+                .is_some());
         }
     }
 
+    #[test]
+    fn test_fee_distributions() {
+        let write_account_a = Pubkey::new_unique();
+        let write_account_b = Pubkey::new_unique();
+
+        let mut prioritization_fee = PrioritizationFee::default();
+        assert!(prioritization_fee
+            .update(9, &[write_account_a, write_account_b])
+            .is_ok());
+        assert!(prioritization_fee.update(2, &[write_account_a]).is_ok());
+        assert!(prioritization_fee.update(5, &[write_account_b]).is_ok());
+        assert!(prioritization_fee.mark_block_completed().is_ok());
+
+        // distributions are sorted on finalization, and kept for accounts whose minimum fee is
+        // pruned
+        assert_eq!(&[2, 5, 9], prioritization_fee.get_transaction_fees());
+        assert!(prioritization_fee
+            .get_writable_account_fee(&write_account_a)
+            .is_none());
+        assert_eq!(
+            Some(&[2, 9][..]),
+            prioritization_fee.get_writable_account_fee_distribution(&write_account_a)
+        );
+        assert_eq!(
+            Some(&[5, 9][..]),
+            prioritization_fee.get_writable_account_fee_distribution(&write_account_b)
+        );
+        assert!(prioritization_fee
+            .get_writable_account_fee_distribution(&Pubkey::new_unique())
+            .is_none());
+
+        // updates after finalization are ignored
+        assert!(prioritization_fee.update(1, &[write_account_a]).is_ok());
+        assert_eq!(&[2, 5, 9], prioritization_fee.get_transaction_fees());
+    }
+
+    #[test]
+    fn test_fee_distributions_pruned_to_most_contended_accounts() {
+        let contended_accounts: Vec<_> = (0..MAX_WRITABLE_ACCOUNT_FEE_DISTRIBUTIONS)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        let uncontended_account = Pubkey::new_unique();
+
+        let mut prioritization_fee = PrioritizationFee::default();
+        for _ in 0..2 {
+            assert!(prioritization_fee.update(1, &contended_accounts).is_ok());
+        }
+        assert!(prioritization_fee.update(1, &[uncontended_account]).is_ok());
+        assert!(prioritization_fee.mark_block_completed().is_ok());
+
+        assert_eq!(
+            MAX_WRITABLE_ACCOUNT_FEE_DISTRIBUTIONS,
+            prioritization_fee.writable_account_fees.len()
+        );
+        assert!(contended_accounts.iter().all(|account| prioritization_fee
+            .get_writable_account_fee_distribution(account)
+            .is_some()));
+        assert!(prioritization_fee
+            .get_writable_account_fee_distribution(&uncontended_account)
+            .is_none());
+    }
+
     #[test]
     fn test_mark_block_completed() {
         let mut prioritization_fee = PrioritizationFee::default();
@@ -11,7 +11,8 @@ use {
         transaction::SanitizedTransaction,
     },
     std::{
-        collections::HashMap,
+        cmp::Reverse,
+        collections::{BinaryHeap, HashMap},
         sync::{
             atomic::{AtomicU64, Ordering},
             Arc, RwLock,
@@ -25,6 +26,72 @@ use {
 /// land a transaction in the current block.
 const MAX_NUM_RECENT_BLOCKS: u64 = 150;
 
+/// Prioritization fee percentiles sampled over a window of recent finalized blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrioritizationFeePercentiles {
+    /// Oldest finalized slot included in the sample window.
+    pub min_slot: Slot,
+    /// Newest finalized slot included in the sample window.
+    pub max_slot: Slot,
+    /// Number of finalized blocks in the sample window.
+    pub num_slots: usize,
+    /// Number of transactions sampled across the window.
+    pub num_samples: usize,
+    /// Estimated fee for each requested percentile, in the order requested.
+    pub fees: Vec<u64>,
+}
+
+/// Index of the `percentile` (0 to 100) of `num_fees` sorted fees using the nearest-rank method.
+fn nearest_rank_index(num_fees: usize, percentile: u8) -> usize {
+    num_fees
+        .saturating_mul(usize::from(percentile.min(100)))
+        .div_ceil(100)
+        .saturating_sub(1)
+}
+
+/// Returns the `percentiles` (0 to 100) of the union of `sorted_distributions` using the
+/// nearest-rank method, or `None` for each if the distributions are empty. The distributions are
+/// walked in a single k-way merge, up to the highest requested percentile, rather than being
+/// collected and sorted.
+fn merged_nearest_rank_percentiles(
+    sorted_distributions: &[&[u64]],
+    percentiles: &[u8],
+) -> Vec<Option<u64>> {
+    let mut results = vec![None; percentiles.len()];
+    let num_fees = sorted_distributions.iter().map(|fees| fees.len()).sum();
+    if num_fees == 0 {
+        return results;
+    }
+    // merged index of each requested percentile, visited in ascending order
+    let mut targets: Vec<(usize, usize)> = percentiles
+        .iter()
+        .enumerate()
+        .map(|(i, percentile)| (nearest_rank_index(num_fees, *percentile), i))
+        .collect();
+    targets.sort_unstable();
+    let mut targets = targets.into_iter().peekable();
+
+    let mut heap: BinaryHeap<Reverse<(u64, usize, usize)>> = sorted_distributions
+        .iter()
+        .enumerate()
+        .filter_map(|(distribution, fees)| Some(Reverse((*fees.first()?, distribution, 0))))
+        .collect();
+    let mut merged_index = 0;
+    while let Some(Reverse((fee, distribution, index))) = heap.pop() {
+        while let Some((_, i)) = targets.next_if(|(target, _)| *target == merged_index) {
+            results[i] = Some(fee);
+        }
+        if targets.peek().is_none() {
+            break;
+        }
+        if let Some(next_fee) = sorted_distributions[distribution].get(index + 1) {
+            heap.push(Reverse((*next_fee, distribution, index + 1)));
+        }
+        merged_index += 1;
+    }
+    results
+}
+
 #[derive(Debug, Default)]
 struct PrioritizationFeeCacheMetrics {
     // Count of transactions that successfully updated each slot's prioritization fee cache.
@@ -426,6 +493,70 @@ impl PrioritizationFeeCache {
             .flatten()
             .collect()
     }
+
+    /// Returns the requested `percentiles` (0 to 100) of prioritization fees paid in the
+    /// `num_slots` most recent finalized blocks, or `None` if no block has been finalized yet.
+    /// Like `get_prioritization_fees`, the estimate for each percentile is the greater of the
+    /// block-wide distribution and the distribution of every provided writable account.
+    pub fn get_prioritization_fee_percentiles(
+        &self,
+        account_keys: &[Pubkey],
+        percentiles: &[u8],
+        num_slots: usize,
+    ) -> Option<PrioritizationFeePercentiles> {
+        let mut slots: Vec<(Slot, Arc<SlotPrioritizationFee>)> = self
+            .cache
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_slot, slot_prioritization_fee)| {
+                slot_prioritization_fee
+                    .iter()
+                    .any(|prioritization_fee| prioritization_fee.is_finalized())
+            })
+            .map(|(slot, slot_prioritization_fee)| (*slot, Arc::clone(slot_prioritization_fee)))
+            .collect();
+        slots.sort_unstable_by(|(lhs, _), (rhs, _)| rhs.cmp(lhs));
+        slots.truncate(num_slots);
+
+        // finalized fees are no longer updated, so the distributions are already sorted
+        let finalized_fees: Vec<_> = slots
+            .iter()
+            .filter_map(|(_slot, slot_prioritization_fee)| {
+                slot_prioritization_fee
+                    .iter()
+                    .find(|prioritization_fee| prioritization_fee.is_finalized())
+            })
+            .collect();
+        let transaction_fees: Vec<&[u64]> = finalized_fees
+            .iter()
+            .map(|prioritization_fee| prioritization_fee.get_transaction_fees())
+            .collect();
+        let mut fees: Vec<u64> = merged_nearest_rank_percentiles(&transaction_fees, percentiles)
+            .into_iter()
+            .map(Option::unwrap_or_default)
+            .collect();
+        for account_key in account_keys {
+            let account_fees: Vec<&[u64]> = finalized_fees
+                .iter()
+                .filter_map(|prioritization_fee| {
+                    prioritization_fee.get_writable_account_fee_distribution(account_key)
+                })
+                .collect();
+            let account_percentiles = merged_nearest_rank_percentiles(&account_fees, percentiles);
+            for (fee, account_fee) in fees.iter_mut().zip(account_percentiles) {
+                *fee = (*fee).max(account_fee.unwrap_or_default());
+            }
+        }
+
+        Some(PrioritizationFeePercentiles {
+            min_slot: slots.last()?.0,
+            max_slot: slots.first()?.0,
+            num_slots: slots.len(),
+            num_samples: transaction_fees.iter().map(|fees| fees.len()).sum(),
+            fees,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -955,4 +1086,172 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_merged_nearest_rank_percentiles() {
+        let percentiles = [100, 0, 50, 90, 50, 1];
+        assert_eq!(
+            vec![None; percentiles.len()],
+            merged_nearest_rank_percentiles(&[&[], &[]], &percentiles)
+        );
+
+        let distributions: [&[u64]; 4] = [&[1, 4, 4, 9], &[], &[2, 3, 10, 11, 12], &[0, 4]];
+        let mut union: Vec<u64> = distributions.concat();
+        union.sort_unstable();
+        let expected: Vec<_> = percentiles
+            .iter()
+            .map(|percentile| {
+                union
+                    .get(nearest_rank_index(union.len(), *percentile))
+                    .copied()
+            })
+            .collect();
+        assert_eq!(
+            vec![Some(12), Some(0), Some(4), Some(11), Some(4), Some(0)],
+            expected
+        );
+        assert_eq!(
+            expected,
+            merged_nearest_rank_percentiles(&distributions, &percentiles)
+        );
+    }
+
+    #[test]
+    fn test_get_prioritization_fee_percentiles() {
+        solana_logger::setup();
+        let write_account_a = Pubkey::new_unique();
+        let write_account_b = Pubkey::new_unique();
+        let write_account_c = Pubkey::new_unique();
+        let write_account_d = Pubkey::new_unique();
+
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank0 = Bank::new_for_benches(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank0);
+        let bank = bank_forks.read().unwrap().working_bank();
+        let collector = solana_sdk::pubkey::new_rand();
+        let bank1 = Arc::new(Bank::new_from_parent(bank.clone(), &collector, 1));
+        let bank2 = Arc::new(Bank::new_from_parent(bank, &collector, 2));
+
+        let prioritization_fee_cache = PrioritizationFeeCache::default();
+        let percentiles = [0, 50, 100];
+        assert!(prioritization_fee_cache
+            .get_prioritization_fee_percentiles(&[], &percentiles, MAX_NUM_RECENT_BLOCKS as usize)
+            .is_none());
+
+        // account a is contended and pays far more than the rest of the block
+        let txs = vec![
+            build_sanitized_transaction_for_test(100, &write_account_a, &write_account_b),
+            build_sanitized_transaction_for_test(1, &write_account_c, &write_account_d),
+            build_sanitized_transaction_for_test(2, &write_account_c, &write_account_d),
+        ];
+        sync_update(&prioritization_fee_cache, bank1.clone(), txs.iter());
+        // unfinalized blocks are not sampled
+        assert!(prioritization_fee_cache
+            .get_prioritization_fee_percentiles(&[], &percentiles, MAX_NUM_RECENT_BLOCKS as usize)
+            .is_none());
+        sync_finalize_priority_fee_for_test(&prioritization_fee_cache, 1, bank1.bank_id());
+
+        let txs = vec![
+            build_sanitized_transaction_for_test(200, &write_account_a, &write_account_b),
+            build_sanitized_transaction_for_test(3, &write_account_c, &write_account_d),
+        ];
+        sync_update(&prioritization_fee_cache, bank2.clone(), txs.iter());
+        sync_finalize_priority_fee_for_test(&prioritization_fee_cache, 2, bank2.bank_id());
+
+        // block-wide fees across both slots are [1, 2, 3, 100, 200]
+        assert_eq!(
+            Some(PrioritizationFeePercentiles {
+                min_slot: 1,
+                max_slot: 2,
+                num_slots: 2,
+                num_samples: 5,
+                fees: vec![1, 3, 200],
+            }),
+            prioritization_fee_cache.get_prioritization_fee_percentiles(
+                &[],
+                &percentiles,
+                MAX_NUM_RECENT_BLOCKS as usize
+            )
+        );
+        // account a's fees are [100, 200], which dominate the block-wide distribution
+        assert_eq!(
+            vec![100, 100, 200],
+            prioritization_fee_cache
+                .get_prioritization_fee_percentiles(
+                    &[write_account_a, write_account_c],
+                    &percentiles,
+                    MAX_NUM_RECENT_BLOCKS as usize
+                )
+                .unwrap()
+                .fees
+        );
+        // account c's fees are [1, 2, 3], which never exceed the block-wide distribution
+        assert_eq!(
+            vec![1, 3, 200],
+            prioritization_fee_cache
+                .get_prioritization_fee_percentiles(
+                    &[write_account_c],
+                    &percentiles,
+                    MAX_NUM_RECENT_BLOCKS as usize
+                )
+                .unwrap()
+                .fees
+        );
+        // only the most recent slot is sampled with a window of one
+        assert_eq!(
+            Some(PrioritizationFeePercentiles {
+                min_slot: 2,
+                max_slot: 2,
+                num_slots: 1,
+                num_samples: 2,
+                fees: vec![3, 3, 200],
+            }),
+            prioritization_fee_cache.get_prioritization_fee_percentiles(&[], &percentiles, 1)
+        );
+    }
+    #[test]
+    fn test_get_prioritization_fee_percentiles_with_unprioritized_transaction() {
+        solana_logger::setup();
+        let write_account = Pubkey::new_unique();
+
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(10_000);
+        let bank0 = Bank::new_for_benches(&genesis_config);
+        let bank_forks = BankForks::new_rw_arc(bank0);
+        let bank = bank_forks.read().unwrap().working_bank();
+        let collector = solana_sdk::pubkey::new_rand();
+        let bank1 = Arc::new(Bank::new_from_parent(bank, &collector, 1));
+
+        let prioritization_fee_cache = PrioritizationFeeCache::default();
+        // a transaction paying no prioritization fee also write-locks the contended account, so
+        // its minimum fee equals the block's
+        let mut txs: Vec<_> = [0, 1_000, 1_000, 1_000]
+            .into_iter()
+            .map(|fee| {
+                build_sanitized_transaction_for_test(fee, &Pubkey::new_unique(), &write_account)
+            })
+            .collect();
+        txs.extend((1..=8).map(|fee| {
+            build_sanitized_transaction_for_test(fee, &Pubkey::new_unique(), &Pubkey::new_unique())
+        }));
+        sync_update(&prioritization_fee_cache, bank1.clone(), txs.iter());
+        sync_finalize_priority_fee_for_test(&prioritization_fee_cache, 1, bank1.bank_id());
+
+        // block-wide fees are [0, 1, ..., 8, 1000, 1000, 1000]
+        let percentiles = [75];
+        assert_eq!(
+            vec![8],
+            prioritization_fee_cache
+                .get_prioritization_fee_percentiles(&[], &percentiles, 1)
+                .unwrap()
+                .fees
+        );
+        // the account's fees are [0, 1000, 1000, 1000]
+        assert_eq!(
+            vec![1_000],
+            prioritization_fee_cache
+                .get_prioritization_fee_percentiles(&[write_account], &percentiles, 1)
+                .unwrap()
+                .fees
+        );
+    }
 }
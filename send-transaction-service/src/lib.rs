@@ -1,4 +1,6 @@
 #![allow(clippy::arithmetic_side_effects)]
+pub mod retry_journal;
+pub mod retry_status_cache;
 pub mod send_transaction_service;
 pub mod tpu_info;
 
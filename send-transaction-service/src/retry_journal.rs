@@ -0,0 +1,303 @@
+//! Append-only on-disk journal of the send-transaction-service retry pool, so that an RPC node
+//! that restarts keeps retrying the transactions it accepted before going down.
+//!
+//! Every change to the pool is appended as a length-prefixed bincode record. Replaying the
+//! records on startup recovers the transactions that were still pending, and the journal is
+//! periodically rewritten to just those transactions so it doesn't grow without bound.
+//! Records are flushed to the OS after every batch of changes but not synced, so a restart of
+//! the process loses nothing while a crash of the host may lose the most recent changes.
+//!
+//! Changes are collected in a [JournalBuffer], which doesn't touch the file, so the retry pool
+//! only needs to be locked while they're recorded and not while the journal is written.
+use {
+    log::*,
+    serde_derive::{Deserialize, Serialize},
+    solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature},
+    std::{
+        collections::HashMap,
+        fs::{self, File, OpenOptions},
+        io::{self, BufWriter, Write},
+        mem,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The journal is only compacted once it holds at least this many records
+const MIN_RECORDS_BEFORE_COMPACTION: usize = 10_000;
+
+/// A transaction in the retry pool, as recorded in the journal
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournaledTransaction {
+    pub signature: Signature,
+    pub wire_transaction: Vec<u8>,
+    pub last_valid_block_height: u64,
+    pub durable_nonce_info: Option<(Pubkey, Hash)>,
+    pub max_retries: Option<usize>,
+    pub retries: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+enum JournalRecord {
+    Added(JournaledTransaction),
+    Retried(Signature),
+    Removed(Signature),
+}
+
+/// Changes to the retry pool waiting to be written to the journal
+#[derive(Debug, Default)]
+pub struct JournalBuffer {
+    /// The retry pool as of the first of `records`, replacing the contents of the journal
+    compacted: Option<Vec<JournaledTransaction>>,
+    records: Vec<JournalRecord>,
+    /// Number of records in the journal once the buffer is written, used to decide when to
+    /// compact it
+    num_records: usize,
+}
+
+impl JournalBuffer {
+    /// Returns an empty buffer for a journal holding `num_records` records, ex: the
+    /// transactions [RetryJournal::open] returned
+    pub fn new(num_records: usize) -> Self {
+        Self {
+            num_records,
+            ..Self::default()
+        }
+    }
+
+    pub fn record_added(&mut self, transaction: JournaledTransaction) {
+        self.push(JournalRecord::Added(transaction));
+    }
+
+    pub fn record_retried(&mut self, signature: Signature) {
+        self.push(JournalRecord::Retried(signature));
+    }
+
+    pub fn record_removed(&mut self, signature: Signature) {
+        self.push(JournalRecord::Removed(signature));
+    }
+
+    /// Whether the journal holds enough stale records, given the number of transactions
+    /// currently in the retry pool, to be worth rewriting
+    pub fn should_compact(&self, num_pending: usize) -> bool {
+        self.num_records >= MIN_RECORDS_BEFORE_COMPACTION
+            && self.num_records > num_pending.saturating_mul(2)
+    }
+
+    /// Replaces the contents of the journal, and the changes buffered so far, with `pending`
+    pub fn compact(&mut self, pending: Vec<JournaledTransaction>) {
+        self.records.clear();
+        self.num_records = pending.len();
+        self.compacted = Some(pending);
+    }
+
+    /// Takes the buffered changes to be written, leaving the buffer empty
+    pub fn take(&mut self) -> Self {
+        Self {
+            compacted: self.compacted.take(),
+            records: mem::take(&mut self.records),
+            num_records: self.num_records,
+        }
+    }
+
+    fn push(&mut self, record: JournalRecord) {
+        self.records.push(record);
+        self.num_records += 1;
+    }
+}
+
+pub struct RetryJournal {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl RetryJournal {
+    /// Opens the journal at `path`, creating it if it doesn't exist, and returns the
+    /// transactions that were still pending when it was last written. The journal is compacted
+    /// down to those transactions, which also discards a partially written last record.
+    pub fn open(path: &Path) -> io::Result<(Self, Vec<JournaledTransaction>)> {
+        let pending = match fs::read(path) {
+            Ok(bytes) => Self::replay(&bytes),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+            Err(err) => return Err(err),
+        };
+        let journal = Self {
+            path: path.to_path_buf(),
+            writer: Self::rewrite(path, &pending)?,
+        };
+        Ok((journal, pending))
+    }
+
+    /// Writes the changes in `buffer` to the journal and flushes them to the OS
+    pub fn write(&mut self, buffer: JournalBuffer) -> io::Result<()> {
+        if let Some(pending) = buffer.compacted {
+            self.writer.flush()?;
+            self.writer = Self::rewrite(&self.path, &pending)?;
+        }
+        for record in &buffer.records {
+            self.append(record)?;
+        }
+        self.writer.flush()
+    }
+
+    fn append(&mut self, record: &JournalRecord) -> io::Result<()> {
+        let bytes = bincode::serialize(record)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        self.writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&bytes)
+    }
+
+    fn replay(mut bytes: &[u8]) -> Vec<JournaledTransaction> {
+        let mut pending = HashMap::new();
+        while !bytes.is_empty() {
+            let Some(record) = Self::read_record(&mut bytes) else {
+                warn!("Ignoring truncated or corrupt records at the end of the retry journal");
+                break;
+            };
+            match record {
+                JournalRecord::Added(transaction) => {
+                    pending.insert(transaction.signature, transaction);
+                }
+                JournalRecord::Retried(signature) => {
+                    if let Some(transaction) = pending.get_mut(&signature) {
+                        transaction.retries = transaction.retries.saturating_add(1);
+                    }
+                }
+                JournalRecord::Removed(signature) => {
+                    pending.remove(&signature);
+                }
+            }
+        }
+        pending.into_values().collect()
+    }
+
+    fn read_record(bytes: &mut &[u8]) -> Option<JournalRecord> {
+        if bytes.len() < 4 {
+            return None;
+        }
+        let (len, rest) = bytes.split_at(4);
+        let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+        if rest.len() < len {
+            return None;
+        }
+        let (record, rest) = rest.split_at(len);
+        let record = bincode::deserialize(record).ok()?;
+        *bytes = rest;
+        Some(record)
+    }
+
+    /// Atomically replaces the journal at `path` with one holding `pending`, and returns a
+    /// writer appending to it
+    fn rewrite(path: &Path, pending: &[JournaledTransaction]) -> io::Result<BufWriter<File>> {
+        let tmp_path = path.with_extension("tmp");
+        {
+            let mut journal = Self {
+                path: tmp_path.clone(),
+                writer: BufWriter::new(File::create(&tmp_path)?),
+            };
+            for transaction in pending {
+                journal.append(&JournalRecord::Added(transaction.clone()))?;
+            }
+            journal.writer.flush()?;
+            journal.writer.get_ref().sync_all()?;
+        }
+        fs::rename(&tmp_path, path)?;
+        Ok(BufWriter::new(OpenOptions::new().append(true).open(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tempfile::TempDir};
+
+    fn new_transaction(retries: usize) -> JournaledTransaction {
+        JournaledTransaction {
+            signature: Signature::new_unique(),
+            wire_transaction: vec![1, 2, 3],
+            last_valid_block_height: 42,
+            durable_nonce_info: Some((Pubkey::new_unique(), Hash::new_unique())),
+            max_retries: Some(10),
+            retries,
+        }
+    }
+
+    fn sorted(mut transactions: Vec<JournaledTransaction>) -> Vec<JournaledTransaction> {
+        transactions.sort_by_key(|transaction| transaction.signature);
+        transactions
+    }
+
+    #[test]
+    fn test_retry_journal_replay() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("retry_journal");
+
+        let (mut journal, pending) = RetryJournal::open(&path).unwrap();
+        assert!(pending.is_empty());
+        let mut buffer = JournalBuffer::new(pending.len());
+        let retried = new_transaction(1);
+        let removed = new_transaction(0);
+        buffer.record_added(retried.clone());
+        buffer.record_added(removed.clone());
+        buffer.record_retried(retried.signature);
+        buffer.record_removed(removed.signature);
+        // records for transactions that aren't in the journal are ignored
+        buffer.record_retried(Signature::new_unique());
+        // nothing is written until the buffer is
+        assert!(fs::read(&path).unwrap().is_empty());
+        journal.write(buffer.take()).unwrap();
+        drop(journal);
+
+        let expected = JournaledTransaction {
+            retries: 2,
+            ..retried
+        };
+        let (mut journal, pending) = RetryJournal::open(&path).unwrap();
+        assert_eq!(pending, vec![expected.clone()]);
+
+        // a partially written record, ex: from a crash, is dropped
+        let mut buffer = JournalBuffer::new(pending.len());
+        buffer.record_added(new_transaction(0));
+        journal.write(buffer.take()).unwrap();
+        drop(journal);
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.truncate(bytes.len() - 1);
+        fs::write(&path, bytes).unwrap();
+
+        let (_journal, pending) = RetryJournal::open(&path).unwrap();
+        assert_eq!(pending, vec![expected]);
+    }
+
+    #[test]
+    fn test_retry_journal_compact() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("retry_journal");
+        let (mut journal, pending) = RetryJournal::open(&path).unwrap();
+        let mut buffer = JournalBuffer::new(pending.len());
+
+        let transactions: Vec<_> = (0..3).map(new_transaction).collect();
+        for transaction in &transactions {
+            buffer.record_added(transaction.clone());
+        }
+        journal.write(buffer.take()).unwrap();
+        for _ in 0..MIN_RECORDS_BEFORE_COMPACTION {
+            buffer.record_retried(transactions[0].signature);
+        }
+        assert!(buffer.should_compact(transactions.len()));
+        assert!(!buffer.should_compact(MIN_RECORDS_BEFORE_COMPACTION));
+
+        // compacting discards the records buffered before it
+        let pending = &transactions[1..];
+        buffer.compact(pending.to_vec());
+        assert!(!buffer.should_compact(pending.len()));
+
+        // the journal keeps appending after being compacted
+        let added = new_transaction(0);
+        buffer.record_added(added.clone());
+        journal.write(buffer.take()).unwrap();
+        drop(journal);
+
+        let mut expected = pending.to_vec();
+        expected.push(added);
+        let (_journal, replayed) = RetryJournal::open(&path).unwrap();
+        assert_eq!(sorted(replayed), sorted(expected));
+    }
+}
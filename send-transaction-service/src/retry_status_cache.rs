@@ -0,0 +1,150 @@
+//! Keeps track of what the send-transaction-service is doing with the transactions in its retry
+//! pool, and what it did with the ones that recently left it, so clients can ask whether a
+//! transaction they submitted is still being retried.
+use {
+    solana_sdk::signature::Signature,
+    std::{
+        collections::{HashMap, VecDeque},
+        sync::RwLock,
+    },
+};
+
+/// Number of statuses of transactions that left the retry pool retained before the oldest ones
+/// are evicted
+pub const DEFAULT_RETRY_STATUS_CACHE_CAPACITY: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionRetryState {
+    /// The transaction is in the retry pool and will be sent again until it lands or expires
+    Pending,
+    /// The transaction was found in the root bank
+    Rooted,
+    /// The transaction's blockhash, or durable nonce, is no longer valid
+    Expired,
+    /// The transaction was sent as many times as it was allowed to be
+    MaxRetriesElapsed,
+    /// The transaction landed with an error
+    Failed,
+    /// The retry pool was full, so the transaction was only sent once
+    Dropped,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionRetryStatus {
+    pub state: TransactionRetryState,
+    /// Number of times the transaction was sent again after its first broadcast
+    pub retries: usize,
+    /// Maximum number of retries the service applies to the transaction, none if unbounded
+    pub max_retries: Option<usize>,
+    pub last_valid_block_height: u64,
+}
+
+#[derive(Default)]
+struct TransactionRetryStatusCacheInner {
+    // statuses of the transactions in the retry pool, bounded by the size of the pool
+    pending: HashMap<Signature, TransactionRetryStatus>,
+    finished: HashMap<Signature, TransactionRetryStatus>,
+    // order in which transactions left the retry pool, used for evicting the oldest entries
+    finished_order: VecDeque<Signature>,
+}
+
+/// In-memory store of the retry status of each transaction handed to the
+/// send-transaction-service. Transactions in the retry pool are always present; only the
+/// `capacity` most recent transactions to leave the pool are remembered.
+pub struct TransactionRetryStatusCache {
+    inner: RwLock<TransactionRetryStatusCacheInner>,
+    capacity: usize,
+}
+
+impl Default for TransactionRetryStatusCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETRY_STATUS_CACHE_CAPACITY)
+    }
+}
+
+impl TransactionRetryStatusCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: RwLock::new(TransactionRetryStatusCacheInner::default()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn insert(&self, signature: Signature, status: TransactionRetryStatus) {
+        let mut inner = self.inner.write().unwrap();
+        if status.state == TransactionRetryState::Pending {
+            inner.pending.insert(signature, status);
+            return;
+        }
+        inner.pending.remove(&signature);
+        if !inner.finished.contains_key(&signature) {
+            while inner.finished_order.len() >= self.capacity {
+                if let Some(evicted) = inner.finished_order.pop_front() {
+                    inner.finished.remove(&evicted);
+                }
+            }
+            inner.finished_order.push_back(signature);
+        }
+        inner.finished.insert(signature, status);
+    }
+
+    pub fn get(&self, signature: &Signature) -> Option<TransactionRetryStatus> {
+        let inner = self.inner.read().unwrap();
+        inner
+            .pending
+            .get(signature)
+            .or_else(|| inner.finished.get(signature))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(state: TransactionRetryState, retries: usize) -> TransactionRetryStatus {
+        TransactionRetryStatus {
+            state,
+            retries,
+            max_retries: Some(5),
+            last_valid_block_height: 100,
+        }
+    }
+
+    #[test]
+    fn test_retry_status_cache_tracks_pending_transactions() {
+        let cache = TransactionRetryStatusCache::new(1);
+        let signature = Signature::new_unique();
+        assert_eq!(cache.get(&signature), None);
+
+        cache.insert(signature, status(TransactionRetryState::Pending, 0));
+        cache.insert(signature, status(TransactionRetryState::Pending, 1));
+        assert_eq!(
+            cache.get(&signature),
+            Some(status(TransactionRetryState::Pending, 1))
+        );
+
+        cache.insert(signature, status(TransactionRetryState::Rooted, 1));
+        assert_eq!(
+            cache.get(&signature),
+            Some(status(TransactionRetryState::Rooted, 1))
+        );
+    }
+
+    #[test]
+    fn test_retry_status_cache_evicts_oldest_finished() {
+        let cache = TransactionRetryStatusCache::new(2);
+        let signatures: Vec<_> = (0..3).map(|_| Signature::new_unique()).collect();
+        let pending = Signature::new_unique();
+        cache.insert(pending, status(TransactionRetryState::Pending, 0));
+        for signature in &signatures {
+            cache.insert(*signature, status(TransactionRetryState::Expired, 0));
+        }
+
+        // pending transactions are never evicted
+        assert!(cache.get(&pending).is_some());
+        assert!(cache.get(&signatures[0]).is_none());
+        assert!(cache.get(&signatures[1]).is_some());
+        assert!(cache.get(&signatures[2]).is_some());
+    }
+}
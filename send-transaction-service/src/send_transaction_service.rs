@@ -1,5 +1,11 @@
 use {
-    crate::tpu_info::TpuInfo,
+    crate::{
+        retry_journal::{JournalBuffer, JournaledTransaction, RetryJournal},
+        retry_status_cache::{
+            TransactionRetryState, TransactionRetryStatus, TransactionRetryStatusCache,
+        },
+        tpu_info::TpuInfo,
+    },
     crossbeam_channel::{Receiver, RecvTimeoutError},
     log::*,
     solana_client::{
@@ -19,6 +25,7 @@ use {
             HashSet,
         },
         net::SocketAddr,
+        path::PathBuf,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
             Arc, Mutex, RwLock,
@@ -92,6 +99,58 @@ impl TransactionInfo {
             last_sent_time,
         }
     }
+
+    fn get_max_retries(
+        &self,
+        default_max_retries: Option<usize>,
+        service_max_retries: usize,
+    ) -> Option<usize> {
+        self.max_retries
+            .or(default_max_retries)
+            .map(|max_retries| max_retries.min(service_max_retries))
+    }
+
+    fn retry_status(
+        &self,
+        state: TransactionRetryState,
+        config: &Config,
+    ) -> TransactionRetryStatus {
+        TransactionRetryStatus {
+            state,
+            retries: self.retries,
+            max_retries: self
+                .get_max_retries(config.default_max_retries, config.service_max_retries),
+            last_valid_block_height: self.last_valid_block_height,
+        }
+    }
+}
+
+impl From<&TransactionInfo> for JournaledTransaction {
+    fn from(transaction_info: &TransactionInfo) -> Self {
+        Self {
+            signature: transaction_info.signature,
+            wire_transaction: transaction_info.wire_transaction.clone(),
+            last_valid_block_height: transaction_info.last_valid_block_height,
+            durable_nonce_info: transaction_info.durable_nonce_info,
+            max_retries: transaction_info.max_retries,
+            retries: transaction_info.retries,
+        }
+    }
+}
+
+impl From<JournaledTransaction> for TransactionInfo {
+    fn from(transaction: JournaledTransaction) -> Self {
+        Self {
+            signature: transaction.signature,
+            wire_transaction: transaction.wire_transaction,
+            last_valid_block_height: transaction.last_valid_block_height,
+            durable_nonce_info: transaction.durable_nonce_info,
+            max_retries: transaction.max_retries,
+            retries: transaction.retries,
+            // not sent since the restart, so it goes out on the next retry pass
+            last_sent_time: None,
+        }
+    }
 }
 
 #[derive(Default, Debug, PartialEq, Eq)]
@@ -116,6 +175,9 @@ pub struct Config {
     pub batch_send_rate_ms: u64,
     /// When the retry pool exceeds this max size, new transactions are dropped after their first broadcast attempt
     pub retry_pool_max_size: usize,
+    /// When set, the retry pool is journaled to this file and the transactions in it are
+    /// retried again after a restart
+    pub retry_journal_path: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -128,6 +190,7 @@ impl Default for Config {
             batch_size: DEFAULT_TRANSACTION_BATCH_SIZE,
             batch_send_rate_ms: DEFAULT_BATCH_SEND_RATE_MS,
             retry_pool_max_size: MAX_TRANSACTION_RETRY_POOL_SIZE,
+            retry_journal_path: None,
         }
     }
 }
@@ -185,6 +248,141 @@ where
     }
 }
 
+/// Keeps the retry status cache, and the retry journal when one is configured, in step with the
+/// retry pool
+#[derive(Default)]
+struct RetryPoolTracker {
+    status_cache: Arc<TransactionRetryStatusCache>,
+    journal: Option<Mutex<RetryJournal>>,
+    /// Changes to the retry pool not yet written to the journal
+    journal_buffer: Mutex<JournalBuffer>,
+}
+
+impl RetryPoolTracker {
+    /// Opens the retry journal, if one is configured, and returns the tracker along with the
+    /// transactions that were in the retry pool when the journal was last written
+    fn restore(
+        config: &Config,
+        status_cache: Arc<TransactionRetryStatusCache>,
+    ) -> (Self, HashMap<Signature, TransactionInfo>) {
+        let mut retry_transactions = HashMap::new();
+        let journal = config.retry_journal_path.as_ref().and_then(|path| {
+            let (mut journal, transactions) = RetryJournal::open(path)
+                .map_err(|err| {
+                    error!(
+                        "Failed to open the retry journal at {}, the retry pool won't survive a \
+                         restart: {err}",
+                        path.display()
+                    );
+                })
+                .ok()?;
+            let num_transactions = transactions.len();
+            for transaction in transactions.into_iter().take(config.retry_pool_max_size) {
+                let transaction_info = TransactionInfo::from(transaction);
+                status_cache.insert(
+                    transaction_info.signature,
+                    transaction_info.retry_status(TransactionRetryState::Pending, config),
+                );
+                retry_transactions.insert(transaction_info.signature, transaction_info);
+            }
+            let mut journal_buffer = JournalBuffer::new(num_transactions);
+            if retry_transactions.len() < num_transactions {
+                // the retry pool shrank since the journal was written
+                journal_buffer.compact(
+                    retry_transactions
+                        .values()
+                        .map(JournaledTransaction::from)
+                        .collect(),
+                );
+                if let Err(err) = journal.write(journal_buffer.take()) {
+                    warn!("Failed to compact the retry journal: {err}");
+                }
+            }
+            info!(
+                "Restored {} of {num_transactions} transactions from the retry journal",
+                retry_transactions.len()
+            );
+            Some((Mutex::new(journal), journal_buffer))
+        });
+        let (journal, journal_buffer) = journal.unzip();
+        let tracker = Self {
+            status_cache,
+            journal,
+            journal_buffer: Mutex::new(journal_buffer.unwrap_or_default()),
+        };
+        (tracker, retry_transactions)
+    }
+
+    fn update_status(
+        &self,
+        transaction_info: &TransactionInfo,
+        state: TransactionRetryState,
+        config: &Config,
+    ) {
+        self.status_cache.insert(
+            transaction_info.signature,
+            transaction_info.retry_status(state, config),
+        );
+    }
+
+    fn update_journal(&self, update: impl FnOnce(&mut JournalBuffer)) {
+        if self.journal.is_some() {
+            update(&mut self.journal_buffer.lock().unwrap());
+        }
+    }
+
+    /// The transaction was added to the retry pool
+    fn added(&self, transaction_info: &TransactionInfo, config: &Config) {
+        self.update_status(transaction_info, TransactionRetryState::Pending, config);
+        self.update_journal(|buffer| buffer.record_added(transaction_info.into()));
+    }
+
+    /// The transaction in the retry pool was sent again
+    fn retried(&self, transaction_info: &TransactionInfo, config: &Config) {
+        self.update_status(transaction_info, TransactionRetryState::Pending, config);
+        self.update_journal(|buffer| buffer.record_retried(transaction_info.signature));
+    }
+
+    /// The transaction was removed from the retry pool
+    fn removed(
+        &self,
+        transaction_info: &TransactionInfo,
+        state: TransactionRetryState,
+        config: &Config,
+    ) {
+        self.update_status(transaction_info, state, config);
+        self.update_journal(|buffer| buffer.record_removed(transaction_info.signature));
+    }
+
+    /// Snapshots the retry pool to compact the journal down to if it has accumulated enough
+    /// stale records. Called with the retry pool locked, ahead of `write_journal`.
+    fn maybe_compact_journal(&self, retry_transactions: &HashMap<Signature, TransactionInfo>) {
+        self.update_journal(|buffer| {
+            if buffer.should_compact(retry_transactions.len()) {
+                buffer.compact(
+                    retry_transactions
+                        .values()
+                        .map(JournaledTransaction::from)
+                        .collect(),
+                );
+            }
+        });
+    }
+
+    /// Writes the buffered changes to the retry pool to the journal. Called with the retry pool
+    /// unlocked, so receiving and retrying transactions don't wait on the file.
+    fn write_journal(&self) {
+        if let Some(journal) = &self.journal {
+            let mut journal = journal.lock().unwrap();
+            // taken while holding the journal so concurrent writes keep the changes in order
+            let buffer = self.journal_buffer.lock().unwrap().take();
+            if let Err(err) = journal.write(buffer) {
+                warn!("Failed to update the retry journal: {err}");
+            }
+        }
+    }
+}
+
 /// Metrics of the send-transaction-service.
 #[derive(Default)]
 struct SendTransactionServiceStats {
@@ -352,6 +550,7 @@ impl SendTransactionService {
             receiver,
             connection_cache,
             config,
+            Arc::<TransactionRetryStatusCache>::default(),
             exit,
         )
     }
@@ -363,11 +562,15 @@ impl SendTransactionService {
         receiver: Receiver<TransactionInfo>,
         connection_cache: &Arc<ConnectionCache>,
         config: Config,
+        retry_status_cache: Arc<TransactionRetryStatusCache>,
         exit: Arc<AtomicBool>,
     ) -> Self {
         let stats_report = Arc::new(SendTransactionServiceStatsReport::default());
 
-        let retry_transactions = Arc::new(Mutex::new(HashMap::new()));
+        let (retry_pool_tracker, retry_transactions) =
+            RetryPoolTracker::restore(&config, retry_status_cache);
+        let retry_pool_tracker = Arc::new(retry_pool_tracker);
+        let retry_transactions = Arc::new(Mutex::new(retry_transactions));
 
         let leader_info_provider = Arc::new(Mutex::new(CurrentLeaderInfo::new(leader_info)));
 
@@ -378,6 +581,7 @@ impl SendTransactionService {
             connection_cache.clone(),
             config.clone(),
             retry_transactions.clone(),
+            retry_pool_tracker.clone(),
             stats_report.clone(),
             exit.clone(),
         );
@@ -389,6 +593,7 @@ impl SendTransactionService {
             connection_cache.clone(),
             config,
             retry_transactions,
+            retry_pool_tracker,
             stats_report,
             exit.clone(),
         );
@@ -407,6 +612,7 @@ impl SendTransactionService {
         connection_cache: Arc<ConnectionCache>,
         config: Config,
         retry_transactions: Arc<Mutex<HashMap<Signature, TransactionInfo>>>,
+        retry_pool_tracker: Arc<RetryPoolTracker>,
         stats_report: Arc<SendTransactionServiceStatsReport>,
         exit: Arc<AtomicBool>,
     ) -> JoinHandle<()> {
@@ -485,14 +691,20 @@ impl SendTransactionService {
                             let entry = retry_transactions.entry(signature);
                             if let Entry::Vacant(_) = entry {
                                 if retry_len >= config.retry_pool_max_size {
-                                    break;
+                                    retry_pool_tracker.update_status(
+                                        &transaction_info,
+                                        TransactionRetryState::Dropped,
+                                        &config,
+                                    );
                                 } else {
                                     transaction_info.last_sent_time = Some(last_sent_time);
                                     saturating_add_assign!(transactions_added_to_retry, 1);
+                                    retry_pool_tracker.added(&transaction_info, &config);
                                     entry.or_insert(transaction_info);
                                 }
                             }
                         }
+                        retry_pool_tracker.maybe_compact_journal(&retry_transactions);
                         stats.retry_queue_overflow.fetch_add(
                             transactions_to_retry.saturating_sub(transactions_added_to_retry)
                                 as u64,
@@ -502,6 +714,7 @@ impl SendTransactionService {
                             .retry_queue_size
                             .store(retry_transactions.len() as u64, Ordering::Relaxed);
                     }
+                    retry_pool_tracker.write_journal();
                     last_batch_sent = Instant::now();
                 }
                 stats_report.report();
@@ -517,6 +730,7 @@ impl SendTransactionService {
         connection_cache: Arc<ConnectionCache>,
         config: Config,
         retry_transactions: Arc<Mutex<HashMap<Signature, TransactionInfo>>>,
+        retry_pool_tracker: Arc<RetryPoolTracker>,
         stats_report: Arc<SendTransactionServiceStatsReport>,
         exit: Arc<AtomicBool>,
     ) -> JoinHandle<()> {
@@ -556,8 +770,11 @@ impl SendTransactionService {
                         &leader_info_provider,
                         &connection_cache,
                         &config,
+                        &retry_pool_tracker,
                         stats,
                     );
+                    drop(transactions);
+                    retry_pool_tracker.write_journal();
                     stats_report.report();
                 }
             })
@@ -606,6 +823,7 @@ impl SendTransactionService {
         leader_info_provider: &Arc<Mutex<CurrentLeaderInfo<T>>>,
         connection_cache: &Arc<ConnectionCache>,
         config: &Config,
+        retry_pool_tracker: &RetryPoolTracker,
         stats: &SendTransactionServiceStats,
     ) -> ProcessTransactionsResult {
         let mut result = ProcessTransactionsResult::default();
@@ -621,6 +839,7 @@ impl SendTransactionService {
                 info!("Transaction is rooted: {}", signature);
                 result.rooted += 1;
                 stats.rooted_transactions.fetch_add(1, Ordering::Relaxed);
+                retry_pool_tracker.removed(transaction_info, TransactionRetryState::Rooted, config);
                 return false;
             }
             let signature_status = working_bank.get_signature_status_slot(signature);
@@ -637,6 +856,11 @@ impl SendTransactionService {
                     info!("Dropping expired durable-nonce transaction: {}", signature);
                     result.expired += 1;
                     stats.expired_transactions.fetch_add(1, Ordering::Relaxed);
+                    retry_pool_tracker.removed(
+                        transaction_info,
+                        TransactionRetryState::Expired,
+                        config,
+                    );
                     return false;
                 }
             }
@@ -644,13 +868,16 @@ impl SendTransactionService {
                 info!("Dropping expired transaction: {}", signature);
                 result.expired += 1;
                 stats.expired_transactions.fetch_add(1, Ordering::Relaxed);
+                retry_pool_tracker.removed(
+                    transaction_info,
+                    TransactionRetryState::Expired,
+                    config,
+                );
                 return false;
             }
 
             let max_retries = transaction_info
-                .max_retries
-                .or(config.default_max_retries)
-                .map(|max_retries| max_retries.min(config.service_max_retries));
+                .get_max_retries(config.default_max_retries, config.service_max_retries);
 
             if let Some(max_retries) = max_retries {
                 if transaction_info.retries >= max_retries {
@@ -659,6 +886,11 @@ impl SendTransactionService {
                     stats
                         .transactions_exceeding_max_retries
                         .fetch_add(1, Ordering::Relaxed);
+                    retry_pool_tracker.removed(
+                        transaction_info,
+                        TransactionRetryState::MaxRetriesElapsed,
+                        config,
+                    );
                     return false;
                 }
             }
@@ -679,6 +911,7 @@ impl SendTransactionService {
                             result.retried += 1;
                             transaction_info.retries += 1;
                             stats.retries.fetch_add(1, Ordering::Relaxed);
+                            retry_pool_tracker.retried(transaction_info, config);
                         }
 
                         batched_transactions.insert(*signature);
@@ -691,6 +924,11 @@ impl SendTransactionService {
                         info!("Dropping failed transaction: {}", signature);
                         result.failed += 1;
                         stats.failed_transactions.fetch_add(1, Ordering::Relaxed);
+                        retry_pool_tracker.removed(
+                            transaction_info,
+                            TransactionRetryState::Failed,
+                            config,
+                        );
                         false
                     } else {
                         result.retained += 1;
@@ -699,6 +937,7 @@ impl SendTransactionService {
                 }
             }
         });
+        retry_pool_tracker.maybe_compact_journal(transactions);
 
         if !batched_transactions.is_empty() {
             // Processing the transactions in batch
@@ -832,6 +1071,7 @@ mod test {
         },
         solana_streamer::socket::SocketAddrSpace,
         std::ops::Sub,
+        tempfile::TempDir,
     };
 
     fn new_test_cluster_info() -> Arc<ClusterInfo> {
@@ -965,6 +1205,7 @@ mod test {
         info!("Expired transactions are dropped...");
         let leader_info_provider = Arc::new(Mutex::new(CurrentLeaderInfo::new(None)));
         let stats = SendTransactionServiceStats::default();
+        let retry_pool_tracker = RetryPoolTracker::default();
         transactions.insert(
             Signature::default(),
             TransactionInfo::new(
@@ -985,6 +1226,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1016,6 +1258,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1047,6 +1290,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1078,6 +1322,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert_eq!(transactions.len(), 1);
@@ -1111,6 +1356,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert_eq!(transactions.len(), 1);
@@ -1154,6 +1400,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert_eq!(transactions.len(), 1);
@@ -1173,6 +1420,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1262,6 +1510,7 @@ mod test {
         );
         let leader_info_provider = Arc::new(Mutex::new(CurrentLeaderInfo::new(None)));
         let stats = SendTransactionServiceStats::default();
+        let retry_pool_tracker = RetryPoolTracker::default();
         let connection_cache = Arc::new(ConnectionCache::new("connection_cache_test"));
         let result = SendTransactionService::process_transactions::<NullTpuInfo>(
             &working_bank,
@@ -1271,6 +1520,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1301,6 +1551,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1333,6 +1584,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1363,6 +1615,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1394,6 +1647,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert!(transactions.is_empty());
@@ -1425,6 +1679,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert_eq!(transactions.len(), 1);
@@ -1458,6 +1713,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert_eq!(transactions.len(), 1);
@@ -1488,6 +1744,7 @@ mod test {
             &leader_info_provider,
             &connection_cache,
             &config,
+            &retry_pool_tracker,
             &stats,
         );
         assert_eq!(transactions.len(), 0);
@@ -1499,4 +1756,95 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn test_retry_pool_journal() {
+        solana_logger::setup();
+
+        let (genesis_config, mint_keypair) = create_genesis_config(4);
+        let (_, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+        let bank = Bank::new_from_parent(
+            bank_forks.read().unwrap().working_bank(),
+            &Pubkey::default(),
+            1,
+        );
+        let bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(bank)
+            .clone_without_scheduler();
+        let rooted_signature = bank
+            .transfer(1, &mint_keypair, &mint_keypair.pubkey())
+            .unwrap();
+        let pending_signature = Signature::new_unique();
+
+        let journal_dir = TempDir::new().unwrap();
+        let config = Config {
+            retry_journal_path: Some(journal_dir.path().join("retry_journal")),
+            ..Config::default()
+        };
+        let (retry_pool_tracker, mut transactions) =
+            RetryPoolTracker::restore(&config, Arc::default());
+        assert!(transactions.is_empty());
+        for signature in [rooted_signature, pending_signature] {
+            let transaction_info = TransactionInfo::new(
+                signature,
+                vec![],
+                bank.block_height() + 10,
+                None,
+                Some(5),
+                Some(Instant::now()),
+            );
+            retry_pool_tracker.added(&transaction_info, &config);
+            transactions.insert(signature, transaction_info);
+        }
+
+        let tpu_address = "127.0.0.1:0".parse().unwrap();
+        let leader_info_provider = Arc::new(Mutex::new(CurrentLeaderInfo::new(None)));
+        let connection_cache = Arc::new(ConnectionCache::new("connection_cache_test"));
+        let result = SendTransactionService::process_transactions::<NullTpuInfo>(
+            &bank,
+            &bank,
+            &tpu_address,
+            &mut transactions,
+            &leader_info_provider,
+            &connection_cache,
+            &config,
+            &retry_pool_tracker,
+            &SendTransactionServiceStats::default(),
+        );
+        retry_pool_tracker.write_journal();
+        assert_eq!(
+            result,
+            ProcessTransactionsResult {
+                rooted: 1,
+                ..ProcessTransactionsResult::default()
+            }
+        );
+        let status_cache = &retry_pool_tracker.status_cache;
+        assert_eq!(
+            status_cache.get(&rooted_signature).unwrap().state,
+            TransactionRetryState::Rooted
+        );
+        let pending_status = TransactionRetryStatus {
+            state: TransactionRetryState::Pending,
+            retries: 0,
+            max_retries: Some(5),
+            last_valid_block_height: bank.block_height() + 10,
+        };
+        assert_eq!(status_cache.get(&pending_signature), Some(pending_status));
+        drop(retry_pool_tracker);
+
+        info!("Only the pending transaction is restored after a restart...");
+        let status_cache = Arc::<TransactionRetryStatusCache>::default();
+        let (_retry_pool_tracker, transactions) =
+            RetryPoolTracker::restore(&config, status_cache.clone());
+        assert_eq!(
+            transactions.keys().collect::<Vec<_>>(),
+            vec![&pending_signature]
+        );
+        assert!(transactions[&pending_signature].last_sent_time.is_none());
+        assert_eq!(status_cache.get(&pending_signature), Some(pending_status));
+        assert_eq!(status_cache.get(&rooted_signature), None);
+    }
 }
@@ -2,8 +2,11 @@
 //! using a user provided [StakeMetaCollection] JSON file.
 
 use {
-    clap::Parser, log::*,
-    solana_tip_distributor::merkle_root_generator_workflow::generate_merkle_root,
+    clap::Parser,
+    log::*,
+    solana_tip_distributor::{
+        merkle_root_generator_workflow::generate_merkle_root, tip_split_policy::TipSplitPolicyArgs,
+    },
     std::path::PathBuf,
 };
 
@@ -21,6 +24,9 @@ struct Args {
     /// Path to JSON file to get populated with tree node data.
     #[arg(long, env)]
     out_path: PathBuf,
+
+    #[command(flatten)]
+    tip_split_policy: TipSplitPolicyArgs,
 }
 
 fn main() {
@@ -28,7 +34,16 @@ fn main() {
     info!("Starting merkle-root-generator workflow...");
 
     let args: Args = Args::parse();
-    generate_merkle_root(&args.stake_meta_coll_path, &args.out_path, &args.rpc_url)
-        .expect("merkle tree produced");
+    let tip_split_policy = args
+        .tip_split_policy
+        .build()
+        .expect("valid tip split policy");
+    generate_merkle_root(
+        &args.stake_meta_coll_path,
+        &args.out_path,
+        &args.rpc_url,
+        tip_split_policy.as_ref(),
+    )
+    .expect("merkle tree produced");
     info!("saved merkle roots to {:?}", args.stake_meta_coll_path);
 }
@@ -0,0 +1,323 @@
+//! This binary runs the tip distribution workflows for an epoch end to end.
+use {
+    clap::{Parser, Subcommand},
+    gethostname::gethostname,
+    log::*,
+    solana_metrics::set_host_id,
+    solana_sdk::{clock::Slot, pubkey::Pubkey, signature::read_keypair_file},
+    solana_tip_distributor::{
+        claim_proof_workflow::{claim_mine, export_claim_proofs, ClaimantProofs},
+        pipeline_workflow::{run_pipeline, PipelineConfig},
+        read_json_from_file,
+        tip_split_policy::TipSplitPolicyArgs,
+        verify_workflow::verify_merkle_roots,
+    },
+    std::{fs::File, io::BufWriter, path::PathBuf, process::exit, sync::Arc, time::Duration},
+    tokio::runtime::Runtime,
+};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Generates stake meta and merkle trees from a snapshot, uploads the merkle roots, claims tips
+    /// and reclaims rent. Progress is checkpointed in the output directory after each stage and
+    /// rerunning the command resumes from the last completed stage.
+    Run {
+        /// Ledger path, where you created the snapshot.
+        #[arg(long, env)]
+        ledger_path: PathBuf,
+
+        /// The expected snapshot slot.
+        #[arg(long, env)]
+        snapshot_slot: Slot,
+
+        /// Directory the stake meta, merkle trees and checkpoint are written to.
+        #[arg(long, env)]
+        out_dir: PathBuf,
+
+        /// RPC to send transactions through
+        #[arg(long, env, default_value = "http://localhost:8899")]
+        rpc_url: String,
+
+        /// Path to the keypair used to upload merkle roots and pay for claims
+        #[arg(long, env)]
+        keypair_path: PathBuf,
+
+        /// Tip distribution program ID
+        #[arg(long, env)]
+        tip_distribution_program_id: Pubkey,
+
+        /// Tip payment program ID
+        #[arg(long, env)]
+        tip_payment_program_id: Pubkey,
+
+        /// Rate-limits the maximum number of requests per RPC connection
+        #[arg(long, env, default_value_t = 100)]
+        max_concurrent_rpc_get_reqs: usize,
+
+        /// Number of transactions to send to RPC at a time.
+        #[arg(long, env, default_value_t = 64)]
+        txn_send_batch_size: usize,
+
+        /// Limits how long before send loop runs before stopping
+        #[arg(long, env, default_value_t = 60 * 60)]
+        max_retry_duration_secs: u64,
+
+        /// The price to pay for priority fee
+        #[arg(long, env, default_value_t = 1)]
+        micro_lamports: u64,
+
+        /// Specifies whether to reclaim any rent.
+        #[arg(long, env, default_value_t = true)]
+        should_reclaim_rent: bool,
+
+        /// Specifies whether to reclaim rent on behalf of validators from respective TDAs.
+        #[arg(long, env)]
+        should_reclaim_tdas: bool,
+
+        #[command(flatten)]
+        tip_split_policy: TipSplitPolicyArgs,
+    },
+
+    /// Rebuilds the merkle trees from a snapshot and checks them against the merkle roots uploaded
+    /// to the tip distribution accounts. Exits with an error if any tree doesn't match.
+    Verify {
+        /// Ledger path, where you created the snapshot.
+        #[arg(long, env)]
+        ledger_path: PathBuf,
+
+        /// The expected snapshot slot.
+        #[arg(long, env)]
+        snapshot_slot: Slot,
+
+        /// RPC to fetch the tip distribution accounts from
+        #[arg(long, env, default_value = "http://localhost:8899")]
+        rpc_url: String,
+
+        /// Tip distribution program ID
+        #[arg(long, env)]
+        tip_distribution_program_id: Pubkey,
+
+        /// Tip payment program ID
+        #[arg(long, env)]
+        tip_payment_program_id: Pubkey,
+
+        /// Published merkle trees to compare per claimant amounts against.
+        #[arg(long, env)]
+        merkle_trees_path: Option<PathBuf>,
+
+        /// Path to write the JSON verification report to.
+        #[arg(long, env)]
+        out_path: Option<PathBuf>,
+
+        /// The tip split policy the merkle trees were generated with.
+        #[command(flatten)]
+        tip_split_policy: TipSplitPolicyArgs,
+    },
+
+    /// Writes a proof file per claimant and an index.json listing every claimant's claims, so
+    /// stakers can claim their own tips without the full merkle tree collection.
+    ExportProofs {
+        /// Path to JSON file containing the [GeneratedMerkleTreeCollection] object.
+        #[arg(long, env)]
+        merkle_trees_path: PathBuf,
+
+        /// Directory the proof files and index are written to.
+        #[arg(long, env)]
+        out_dir: PathBuf,
+    },
+
+    /// Claims the tips in a single claimant's proof file.
+    ClaimMine {
+        /// Path to the claimant's proof file, as written by export-proofs.
+        #[arg(long, env)]
+        proof_path: PathBuf,
+
+        /// RPC to send transactions through
+        #[arg(long, env, default_value = "http://localhost:8899")]
+        rpc_url: String,
+
+        /// Path to the keypair paying for the claim
+        #[arg(long, env)]
+        keypair_path: PathBuf,
+
+        /// Tip distribution program ID
+        #[arg(long, env)]
+        tip_distribution_program_id: Pubkey,
+
+        /// Limits how long before send loop runs before stopping
+        #[arg(long, env, default_value_t = 5 * 60)]
+        max_retry_duration_secs: u64,
+
+        /// The price to pay for priority fee
+        #[arg(long, env, default_value_t = 1)]
+        micro_lamports: u64,
+    },
+}
+
+fn main() {
+    env_logger::init();
+
+    gethostname()
+        .into_string()
+        .map(set_host_id)
+        .expect("set hostname");
+
+    let args: Args = Args::parse();
+    match args.command {
+        Commands::Run {
+            ledger_path,
+            snapshot_slot,
+            out_dir,
+            rpc_url,
+            keypair_path,
+            tip_distribution_program_id,
+            tip_payment_program_id,
+            max_concurrent_rpc_get_reqs,
+            txn_send_batch_size,
+            max_retry_duration_secs,
+            micro_lamports,
+            should_reclaim_rent,
+            should_reclaim_tdas,
+            tip_split_policy,
+        } => {
+            let tip_split_policy = match tip_split_policy.build() {
+                Ok(tip_split_policy) => tip_split_policy,
+                Err(e) => {
+                    error!("invalid tip split policy: {e}");
+                    exit(1);
+                }
+            };
+            info!("Starting tip distribution pipeline for snapshot slot {snapshot_slot}...");
+            let result = run_pipeline(&PipelineConfig {
+                ledger_path,
+                snapshot_slot,
+                out_dir,
+                rpc_url,
+                keypair_path,
+                tip_distribution_program_id,
+                tip_payment_program_id,
+                max_concurrent_rpc_get_reqs,
+                txn_send_batch_size,
+                max_retry_duration: Duration::from_secs(max_retry_duration_secs),
+                micro_lamports,
+                should_reclaim_rent,
+                should_reclaim_tdas,
+                tip_split_policy,
+            });
+            solana_metrics::flush(); // sometimes last datapoint doesn't get emitted. this increases likelihood.
+            match result {
+                Ok(checkpoint) => info!(
+                    "tip distribution pipeline complete for epoch {:?}",
+                    checkpoint.epoch
+                ),
+                Err(e) => {
+                    error!("tip distribution pipeline failed, rerun to resume: {e}");
+                    exit(1);
+                }
+            }
+        }
+        Commands::Verify {
+            ledger_path,
+            snapshot_slot,
+            rpc_url,
+            tip_distribution_program_id,
+            tip_payment_program_id,
+            merkle_trees_path,
+            out_path,
+            tip_split_policy,
+        } => {
+            let tip_split_policy = match tip_split_policy.build() {
+                Ok(tip_split_policy) => tip_split_policy,
+                Err(e) => {
+                    error!("invalid tip split policy: {e}");
+                    exit(1);
+                }
+            };
+            info!("Verifying merkle roots for snapshot slot {snapshot_slot}...");
+            let report = match verify_merkle_roots(
+                &ledger_path,
+                &snapshot_slot,
+                &tip_distribution_program_id,
+                &tip_payment_program_id,
+                &rpc_url,
+                merkle_trees_path.as_ref(),
+                tip_split_policy.as_ref(),
+            ) {
+                Ok(report) => report,
+                Err(e) => {
+                    error!("failed to verify merkle roots: {e}");
+                    exit(1);
+                }
+            };
+
+            for tree in &report.failed_trees {
+                for mismatch in &tree.mismatches {
+                    println!("{}: {mismatch}", tree.tip_distribution_account);
+                }
+            }
+            println!(
+                "epoch {}: {} of {} merkle trees failed verification",
+                report.epoch,
+                report.failed_trees.len(),
+                report.num_trees
+            );
+
+            if let Some(out_path) = out_path {
+                let file = File::create(&out_path).expect("create report file");
+                serde_json::to_writer_pretty(BufWriter::new(file), &report).expect("write report");
+            }
+            if !report.is_ok() {
+                exit(1);
+            }
+        }
+        Commands::ExportProofs {
+            merkle_trees_path,
+            out_dir,
+        } => match export_claim_proofs(&merkle_trees_path, &out_dir) {
+            Ok(index) => info!(
+                "exported proofs for {} claimants in epoch {}",
+                index.claimants.len(),
+                index.epoch
+            ),
+            Err(e) => {
+                error!("failed to export claim proofs: {e}");
+                exit(1);
+            }
+        },
+        Commands::ClaimMine {
+            proof_path,
+            rpc_url,
+            keypair_path,
+            tip_distribution_program_id,
+            max_retry_duration_secs,
+            micro_lamports,
+        } => {
+            let keypair = Arc::new(read_keypair_file(&keypair_path).expect("read keypair file"));
+            let proofs: ClaimantProofs =
+                read_json_from_file(&proof_path).expect("read ClaimantProofs");
+            info!(
+                "Claiming tips for {} in epoch {}...",
+                proofs.claimant, proofs.epoch
+            );
+            let runtime = Runtime::new().expect("build runtime");
+            if let Err(e) = runtime.block_on(claim_mine(
+                &proofs,
+                rpc_url,
+                tip_distribution_program_id,
+                keypair,
+                Duration::from_secs(max_retry_duration_secs),
+                micro_lamports,
+            )) {
+                error!("failed to claim tips: {e}");
+                exit(1);
+            }
+        }
+    }
+}
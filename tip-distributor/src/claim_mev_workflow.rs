@@ -1,9 +1,12 @@
 use {
-    crate::{send_until_blockhash_expires, GeneratedMerkleTreeCollection},
+    crate::{
+        client::{first_simulation_error, TipDistributorClient},
+        GeneratedMerkleTreeCollection, TreeNode,
+    },
     anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas},
     itertools::Itertools,
     jito_tip_distribution::state::{ClaimStatus, Config, TipDistributionAccount},
-    log::{error, info, warn},
+    log::{error, info},
     rand::{prelude::SliceRandom, thread_rng},
     solana_client::nonblocking::rpc_client::RpcClient,
     solana_metrics::datapoint_info,
@@ -11,7 +14,6 @@ use {
         fee_calculator::DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE, native_token::LAMPORTS_PER_SOL,
         system_program,
     },
-    solana_rpc_client_api::config::RpcSimulateTransactionConfig,
     solana_sdk::{
         account::Account,
         commitment_config::CommitmentConfig,
@@ -59,7 +61,7 @@ pub enum ClaimMevError {
 }
 
 pub async fn get_claim_transactions_for_valid_unclaimed(
-    rpc_client: &RpcClient,
+    client: &dyn TipDistributorClient,
     merkle_trees: &GeneratedMerkleTreeCollection,
     tip_distribution_program_id: Pubkey,
     micro_lamports: u64,
@@ -83,7 +85,8 @@ pub async fn get_claim_transactions_for_valid_unclaimed(
         .iter()
         .map(|tree| tree.tip_distribution_account)
         .collect_vec();
-    let tdas: HashMap<Pubkey, Account> = crate::get_batched_accounts(rpc_client, &tda_pubkeys)
+    let tdas: HashMap<Pubkey, Account> = client
+        .get_multiple_accounts(&tda_pubkeys)
         .await?
         .into_iter()
         .filter_map(|(pubkey, a)| Some((pubkey, a?)))
@@ -93,23 +96,23 @@ pub async fn get_claim_transactions_for_valid_unclaimed(
         .iter()
         .map(|tree_node| tree_node.claimant)
         .collect_vec();
-    let claimants: HashMap<Pubkey, Account> =
-        crate::get_batched_accounts(rpc_client, &claimant_pubkeys)
-            .await?
-            .into_iter()
-            .filter_map(|(pubkey, a)| Some((pubkey, a?)))
-            .collect();
+    let claimants: HashMap<Pubkey, Account> = client
+        .get_multiple_accounts(&claimant_pubkeys)
+        .await?
+        .into_iter()
+        .filter_map(|(pubkey, a)| Some((pubkey, a?)))
+        .collect();
 
     let claim_status_pubkeys = tree_nodes
         .iter()
         .map(|tree_node| tree_node.claim_status_pubkey)
         .collect_vec();
-    let claim_statuses: HashMap<Pubkey, Account> =
-        crate::get_batched_accounts(rpc_client, &claim_status_pubkeys)
-            .await?
-            .into_iter()
-            .filter_map(|(pubkey, a)| Some((pubkey, a?)))
-            .collect();
+    let claim_statuses: HashMap<Pubkey, Account> = client
+        .get_multiple_accounts(&claim_status_pubkeys)
+        .await?
+        .into_iter()
+        .filter_map(|(pubkey, a)| Some((pubkey, a?)))
+        .collect();
 
     let elapsed_us = start.elapsed().as_micros();
 
@@ -151,11 +154,29 @@ pub async fn claim_mev_tips(
         Duration::from_secs(300),
         CommitmentConfig::confirmed(),
     );
+    claim_mev_tips_with_client(
+        &rpc_client,
+        merkle_trees,
+        tip_distribution_program_id,
+        keypair,
+        max_loop_duration,
+        micro_lamports,
+    )
+    .await
+}
 
+pub async fn claim_mev_tips_with_client(
+    client: &dyn TipDistributorClient,
+    merkle_trees: &GeneratedMerkleTreeCollection,
+    tip_distribution_program_id: Pubkey,
+    keypair: Arc<Keypair>,
+    max_loop_duration: Duration,
+    micro_lamports: u64,
+) -> Result<(), ClaimMevError> {
     let start = Instant::now();
     while start.elapsed() <= max_loop_duration {
         let mut all_claim_transactions = get_claim_transactions_for_valid_unclaimed(
-            &rpc_client,
+            client,
             merkle_trees,
             tip_distribution_program_id,
             micro_lamports,
@@ -177,7 +198,7 @@ pub async fn claim_mev_tips(
 
         // only check balance for the ones we need to currently send since reclaim rent running in parallel
         if let Some((start_balance, desired_balance, sol_to_deposit)) =
-            is_sufficient_balance(&keypair.pubkey(), &rpc_client, transactions.len() as u64).await
+            is_sufficient_balance(&keypair.pubkey(), client, transactions.len() as u64).await
         {
             return Err(ClaimMevError::InsufficientBalance {
                 desired_balance,
@@ -187,12 +208,11 @@ pub async fn claim_mev_tips(
             });
         }
 
-        let blockhash = rpc_client.get_latest_blockhash().await?;
-        let _ = send_until_blockhash_expires(&rpc_client, transactions, blockhash, &keypair).await;
+        let _ = client.send_transactions(transactions, &keypair).await;
     }
 
     let transactions = get_claim_transactions_for_valid_unclaimed(
-        &rpc_client,
+        client,
         merkle_trees,
         tip_distribution_program_id,
         micro_lamports,
@@ -204,45 +224,11 @@ pub async fn claim_mev_tips(
     }
 
     // if more transactions left, we'll simulate them all to make sure its not an uncaught error
-    let mut is_error = false;
-    let mut error_str = String::new();
-    for tx in &transactions {
-        match rpc_client
-            .simulate_transaction_with_config(
-                tx,
-                RpcSimulateTransactionConfig {
-                    sig_verify: false,
-                    replace_recent_blockhash: true,
-                    commitment: Some(CommitmentConfig::processed()),
-                    ..RpcSimulateTransactionConfig::default()
-                },
-            )
-            .await
-        {
-            Ok(_) => {}
-            Err(e) => {
-                error_str = e.to_string();
-                is_error = true;
-
-                match e.get_transaction_error() {
-                    None => {
-                        break;
-                    }
-                    Some(e) => {
-                        warn!("transaction error. tx: {:?} error: {:?}", tx, e);
-                        break;
-                    }
-                }
-            }
-        }
-    }
-
-    if is_error {
-        Err(ClaimMevError::UncaughtError { e: error_str })
-    } else {
-        Err(ClaimMevError::NotFinished {
+    match first_simulation_error(client, &transactions).await {
+        Some(e) => Err(ClaimMevError::UncaughtError { e }),
+        None => Err(ClaimMevError::NotFinished {
             transactions_left: transactions.len(),
-        })
+        }),
     }
 }
 
@@ -325,52 +311,76 @@ fn build_mev_claim_transactions(
                 continue;
             }
 
-            instructions.push(Instruction {
-                program_id: tip_distribution_program_id,
-                data: jito_tip_distribution::instruction::Claim {
-                    proof: node.proof.clone().unwrap(),
-                    amount: node.amount,
-                    bump: node.claim_status_bump,
-                }
-                .data(),
-                accounts: jito_tip_distribution::accounts::Claim {
-                    config: tip_distribution_config,
-                    tip_distribution_account: tree.tip_distribution_account,
-                    claimant: node.claimant,
-                    claim_status: node.claim_status_pubkey,
-                    payer: payer_pubkey,
-                    system_program: system_program::id(),
-                }
-                .to_account_metas(None),
-            });
+            instructions.push(claim_instruction(
+                tip_distribution_program_id,
+                tip_distribution_config,
+                tree.tip_distribution_account,
+                node,
+                payer_pubkey,
+            ));
         }
     }
 
     // TODO (LB): see if we can do >1 claim here
     let transactions: Vec<Transaction> = instructions
         .into_iter()
-        .map(|claim_ix| {
-            let priority_fee_ix = ComputeBudgetInstruction::set_compute_unit_price(micro_lamports);
-            Transaction::new_with_payer(&[priority_fee_ix, claim_ix], Some(&payer_pubkey))
-        })
+        .map(|claim_ix| claim_transaction(claim_ix, micro_lamports, payer_pubkey))
         .collect();
 
     transactions
 }
 
+/// Builds the instruction claiming `node`'s tips from `tip_distribution_account`. Panics if the
+/// node doesn't have a proof.
+pub fn claim_instruction(
+    tip_distribution_program_id: Pubkey,
+    tip_distribution_config: Pubkey,
+    tip_distribution_account: Pubkey,
+    node: &TreeNode,
+    payer_pubkey: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: tip_distribution_program_id,
+        data: jito_tip_distribution::instruction::Claim {
+            proof: node.proof.clone().unwrap(),
+            amount: node.amount,
+            bump: node.claim_status_bump,
+        }
+        .data(),
+        accounts: jito_tip_distribution::accounts::Claim {
+            config: tip_distribution_config,
+            tip_distribution_account,
+            claimant: node.claimant,
+            claim_status: node.claim_status_pubkey,
+            payer: payer_pubkey,
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+    }
+}
+
+pub fn claim_transaction(
+    claim_ix: Instruction,
+    micro_lamports: u64,
+    payer_pubkey: Pubkey,
+) -> Transaction {
+    let priority_fee_ix = ComputeBudgetInstruction::set_compute_unit_price(micro_lamports);
+    Transaction::new_with_payer(&[priority_fee_ix, claim_ix], Some(&payer_pubkey))
+}
+
 /// heuristic to make sure we have enough funds to cover the rent costs if epoch has many validators
 /// If insufficient funds, returns start balance, desired balance, and amount of sol to deposit
-async fn is_sufficient_balance(
+pub(crate) async fn is_sufficient_balance(
     payer: &Pubkey,
-    rpc_client: &RpcClient,
+    client: &dyn TipDistributorClient,
     instruction_count: u64,
 ) -> Option<(u64, u64, u64)> {
-    let start_balance = rpc_client
+    let start_balance = client
         .get_balance(payer)
         .await
         .expect("Failed to get starting balance");
     // most amounts are for 0 lamports. had 1736 non-zero claims out of 164742
-    let min_rent_per_claim = rpc_client
+    let min_rent_per_claim = client
         .get_minimum_balance_for_rent_exemption(ClaimStatus::SIZE)
         .await
         .expect("Failed to calculate min rent");
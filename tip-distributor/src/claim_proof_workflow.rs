@@ -0,0 +1,425 @@
+//! Splits a [GeneratedMerkleTreeCollection] into one proof file per claimant so stakers can claim
+//! their own tips without the whole collection, and claims the tips in such a file.
+use {
+    crate::{
+        claim_mev_workflow::{claim_instruction, claim_transaction, is_sufficient_balance},
+        pubkey_string_conversion, read_json_from_file, send_until_blockhash_expires,
+        GeneratedMerkleTreeCollection, TreeNode,
+    },
+    anchor_lang::AccountDeserialize,
+    jito_tip_distribution::state::{Config, TipDistributionAccount},
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_sdk::{
+        clock::Slot,
+        commitment_config::CommitmentConfig,
+        hash::Hash,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        stake_history::Epoch,
+        transaction::Transaction,
+    },
+    std::{
+        collections::BTreeMap,
+        fs::{self, File},
+        io::{BufWriter, Write},
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+};
+
+/// Name of the index file written next to the per-claimant proof files.
+pub const CLAIM_PROOF_INDEX_FILENAME: &str = "index.json";
+
+#[derive(Error, Debug)]
+pub enum ClaimProofError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    RpcError(#[from] solana_rpc_client_api::client_error::Error),
+
+    #[error("tree node for claimant {claimant} in {tip_distribution_account} has no proof")]
+    MissingProof {
+        claimant: Pubkey,
+        tip_distribution_account: Pubkey,
+    },
+
+    #[error("tip distribution account {0} not found")]
+    TipDistributionAccountNotFound(Pubkey),
+
+    #[error("claimant {0} not found")]
+    ClaimantNotFound(Pubkey),
+
+    #[error("merkle root of {tip_distribution_account} is {actual:?}, proof is for {expected}")]
+    MerkleRootMismatch {
+        tip_distribution_account: Pubkey,
+        expected: Hash,
+        actual: Option<Hash>,
+    },
+
+    #[error("Expected to have at least {desired_balance} lamports in {payer:?}. Current balance is {start_balance} lamports. Deposit {sol_to_deposit} SOL to continue.")]
+    InsufficientBalance {
+        desired_balance: u64,
+        payer: Pubkey,
+        start_balance: u64,
+        sol_to_deposit: u64,
+    },
+
+    #[error("Not finished with job, transactions left {transactions_left}")]
+    NotFinished { transactions_left: usize },
+}
+
+/// Everything needed to claim a single [TreeNode] from a [TipDistributionAccount].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ClaimProof {
+    #[serde(with = "pubkey_string_conversion")]
+    pub tip_distribution_account: Pubkey,
+
+    /// The root the proof was generated against, checked against the uploaded root before
+    /// claiming.
+    pub merkle_root: Hash,
+
+    #[serde(flatten)]
+    pub tree_node: TreeNode,
+}
+
+/// The proofs for every tip distribution account a claimant can claim from in an epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ClaimantProofs {
+    #[serde(with = "pubkey_string_conversion")]
+    pub claimant: Pubkey,
+    pub epoch: Epoch,
+    pub slot: Slot,
+    pub bank_hash: String,
+    pub claims: Vec<ClaimProof>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ClaimProofIndexEntry {
+    #[serde(with = "pubkey_string_conversion")]
+    pub tip_distribution_account: Pubkey,
+    pub amount: u64,
+}
+
+/// Lists what each claimant can claim, keyed by base58 encoded claimant pubkey. The proofs are in
+/// `<claimant>.json` next to the index.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ClaimProofIndex {
+    pub epoch: Epoch,
+    pub slot: Slot,
+    pub bank_hash: String,
+    pub claimants: BTreeMap<String, Vec<ClaimProofIndexEntry>>,
+}
+
+/// Groups the non-zero claims in `merkle_trees` by claimant.
+pub fn claimant_proofs(
+    merkle_trees: &GeneratedMerkleTreeCollection,
+) -> Result<Vec<ClaimantProofs>, ClaimProofError> {
+    let mut claims_by_claimant: BTreeMap<Pubkey, Vec<ClaimProof>> = BTreeMap::new();
+    for tree in &merkle_trees.generated_merkle_trees {
+        // nothing to claim, so no reason to hand out a proof
+        for node in tree.tree_nodes.iter().filter(|node| node.amount > 0) {
+            if node.proof.is_none() {
+                return Err(ClaimProofError::MissingProof {
+                    claimant: node.claimant,
+                    tip_distribution_account: tree.tip_distribution_account,
+                });
+            }
+            claims_by_claimant
+                .entry(node.claimant)
+                .or_default()
+                .push(ClaimProof {
+                    tip_distribution_account: tree.tip_distribution_account,
+                    merkle_root: tree.merkle_root,
+                    tree_node: node.clone(),
+                });
+        }
+    }
+
+    Ok(claims_by_claimant
+        .into_iter()
+        .map(|(claimant, claims)| ClaimantProofs {
+            claimant,
+            epoch: merkle_trees.epoch,
+            slot: merkle_trees.slot,
+            bank_hash: merkle_trees.bank_hash.clone(),
+            claims,
+        })
+        .collect())
+}
+
+pub fn claim_proof_path(out_dir: &Path, claimant: &Pubkey) -> PathBuf {
+    out_dir.join(format!("{claimant}.json"))
+}
+
+/// Writes a `<claimant>.json` proof file for every claimant with tips to claim, plus an
+/// [ClaimProofIndex] in `out_dir`.
+pub fn export_claim_proofs(
+    merkle_trees_path: &PathBuf,
+    out_dir: &Path,
+) -> Result<ClaimProofIndex, ClaimProofError> {
+    let merkle_trees: GeneratedMerkleTreeCollection = read_json_from_file(merkle_trees_path)?;
+    let claimant_proofs = claimant_proofs(&merkle_trees)?;
+
+    fs::create_dir_all(out_dir)?;
+    let mut index = ClaimProofIndex {
+        epoch: merkle_trees.epoch,
+        slot: merkle_trees.slot,
+        bank_hash: merkle_trees.bank_hash,
+        claimants: BTreeMap::new(),
+    };
+    for proofs in claimant_proofs {
+        write_to_json_file(&proofs, &claim_proof_path(out_dir, &proofs.claimant))?;
+        index.claimants.insert(
+            proofs.claimant.to_string(),
+            proofs
+                .claims
+                .iter()
+                .map(|claim| ClaimProofIndexEntry {
+                    tip_distribution_account: claim.tip_distribution_account,
+                    amount: claim.tree_node.amount,
+                })
+                .collect(),
+        );
+    }
+    write_to_json_file(&index, &out_dir.join(CLAIM_PROOF_INDEX_FILENAME))?;
+
+    info!(
+        "exported claim proofs for {} claimants to {out_dir:?}",
+        index.claimants.len()
+    );
+    Ok(index)
+}
+
+/// Claims the tips in a claimant's proof file, paying for the claim status accounts with
+/// `keypair`. Claims that were already made are skipped.
+pub async fn claim_mine(
+    proofs: &ClaimantProofs,
+    rpc_url: String,
+    tip_distribution_program_id: Pubkey,
+    keypair: Arc<Keypair>,
+    max_loop_duration: Duration,
+    micro_lamports: u64,
+) -> Result<(), ClaimProofError> {
+    let rpc_client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+
+    let start = Instant::now();
+    loop {
+        let transactions = get_unclaimed_transactions(
+            &rpc_client,
+            proofs,
+            tip_distribution_program_id,
+            micro_lamports,
+            keypair.pubkey(),
+        )
+        .await?;
+        if transactions.is_empty() {
+            info!("all tips claimed for {}", proofs.claimant);
+            return Ok(());
+        }
+        if start.elapsed() > max_loop_duration {
+            return Err(ClaimProofError::NotFinished {
+                transactions_left: transactions.len(),
+            });
+        }
+
+        if let Some((start_balance, desired_balance, sol_to_deposit)) =
+            is_sufficient_balance(&keypair.pubkey(), &rpc_client, transactions.len() as u64).await
+        {
+            return Err(ClaimProofError::InsufficientBalance {
+                desired_balance,
+                payer: keypair.pubkey(),
+                start_balance,
+                sol_to_deposit,
+            });
+        }
+
+        let blockhash = rpc_client.get_latest_blockhash().await?;
+        send_until_blockhash_expires(&rpc_client, transactions, blockhash, &keypair).await?;
+    }
+}
+
+async fn get_unclaimed_transactions(
+    rpc_client: &RpcClient,
+    proofs: &ClaimantProofs,
+    tip_distribution_program_id: Pubkey,
+    micro_lamports: u64,
+    payer_pubkey: Pubkey,
+) -> Result<Vec<Transaction>, ClaimProofError> {
+    if rpc_client
+        .get_account_with_commitment(&proofs.claimant, rpc_client.commitment())
+        .await?
+        .value
+        .is_none()
+    {
+        return Err(ClaimProofError::ClaimantNotFound(proofs.claimant));
+    }
+
+    let tda_pubkeys: Vec<Pubkey> = proofs
+        .claims
+        .iter()
+        .map(|claim| claim.tip_distribution_account)
+        .collect();
+    let tdas = crate::get_batched_accounts(rpc_client, &tda_pubkeys).await?;
+    let claim_status_pubkeys: Vec<Pubkey> = proofs
+        .claims
+        .iter()
+        .map(|claim| claim.tree_node.claim_status_pubkey)
+        .collect();
+    let claim_statuses = crate::get_batched_accounts(rpc_client, &claim_status_pubkeys).await?;
+
+    let tip_distribution_config =
+        Pubkey::find_program_address(&[Config::SEED], &tip_distribution_program_id).0;
+
+    proofs
+        .claims
+        .iter()
+        .filter(|claim| {
+            let is_claimed = matches!(
+                claim_statuses.get(&claim.tree_node.claim_status_pubkey),
+                Some(Some(_))
+            );
+            if is_claimed {
+                info!(
+                    "already claimed {} lamports from {}",
+                    claim.tree_node.amount, claim.tip_distribution_account
+                );
+            }
+            !is_claimed
+        })
+        .map(|claim| {
+            check_merkle_root(claim, tdas.get(&claim.tip_distribution_account))?;
+            Ok(claim_transaction(
+                claim_instruction(
+                    tip_distribution_program_id,
+                    tip_distribution_config,
+                    claim.tip_distribution_account,
+                    &claim.tree_node,
+                    payer_pubkey,
+                ),
+                micro_lamports,
+                payer_pubkey,
+            ))
+        })
+        .collect()
+}
+
+fn check_merkle_root(
+    claim: &ClaimProof,
+    tda: Option<&Option<solana_sdk::account::Account>>,
+) -> Result<(), ClaimProofError> {
+    let tip_distribution_account = claim.tip_distribution_account;
+    let tda = tda
+        .and_then(|tda| tda.as_ref())
+        .and_then(|tda| TipDistributionAccount::try_deserialize(&mut tda.data.as_slice()).ok())
+        .ok_or(ClaimProofError::TipDistributionAccountNotFound(
+            tip_distribution_account,
+        ))?;
+    let actual = tda
+        .merkle_root
+        .map(|merkle_root| Hash::new_from_array(merkle_root.root));
+    if actual != Some(claim.merkle_root) {
+        return Err(ClaimProofError::MerkleRootMismatch {
+            tip_distribution_account,
+            expected: claim.merkle_root,
+            actual,
+        });
+    }
+    Ok(())
+}
+
+fn write_to_json_file<T: Serialize>(value: &T, path: &Path) -> Result<(), ClaimProofError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(&mut writer, value)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::GeneratedMerkleTree, tempfile::TempDir};
+
+    fn tree_node(claimant: Pubkey, amount: u64) -> TreeNode {
+        TreeNode {
+            claimant,
+            claim_status_pubkey: Pubkey::new_unique(),
+            claim_status_bump: 255,
+            staker_pubkey: Pubkey::new_unique(),
+            withdrawer_pubkey: Pubkey::new_unique(),
+            amount,
+            proof: Some(vec![[1; 32], [2; 32]]),
+        }
+    }
+
+    #[test]
+    fn test_export_claim_proofs() {
+        let (claimant_0, claimant_1, claimant_2) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let tree = GeneratedMerkleTree {
+            tip_distribution_account: Pubkey::new_unique(),
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            merkle_root: Hash::new_unique(),
+            tree_nodes: vec![
+                tree_node(claimant_0, 100),
+                tree_node(claimant_1, 200),
+                tree_node(claimant_2, 0),
+            ],
+            max_total_claim: 300,
+            max_num_nodes: 3,
+        };
+        let merkle_trees = GeneratedMerkleTreeCollection {
+            generated_merkle_trees: vec![tree.clone()],
+            bank_hash: Hash::new_unique().to_string(),
+            epoch: 100,
+            slot: 2_000_000,
+            tip_split_policy: None,
+        };
+
+        let dir = TempDir::new().unwrap();
+        let merkle_trees_path = dir.path().join("merkle-trees.json");
+        fs::write(
+            &merkle_trees_path,
+            serde_json::to_string(&merkle_trees).unwrap(),
+        )
+        .unwrap();
+        let out_dir = dir.path().join("proofs");
+        let index = export_claim_proofs(&merkle_trees_path, &out_dir).unwrap();
+
+        // claimants with nothing to claim don't get a proof file
+        assert_eq!(index.claimants.len(), 2);
+        assert!(!claim_proof_path(&out_dir, &claimant_2).exists());
+        assert_eq!(
+            index.claimants[&claimant_1.to_string()],
+            vec![ClaimProofIndexEntry {
+                tip_distribution_account: tree.tip_distribution_account,
+                amount: 200,
+            }]
+        );
+        let read_index: ClaimProofIndex =
+            read_json_from_file(&out_dir.join(CLAIM_PROOF_INDEX_FILENAME)).unwrap();
+        assert_eq!(read_index, index);
+
+        let proofs: ClaimantProofs =
+            read_json_from_file(&claim_proof_path(&out_dir, &claimant_0)).unwrap();
+        assert_eq!(proofs.claimant, claimant_0);
+        assert_eq!(proofs.epoch, 100);
+        assert_eq!(
+            proofs.claims,
+            vec![ClaimProof {
+                tip_distribution_account: tree.tip_distribution_account,
+                merkle_root: tree.merkle_root,
+                tree_node: tree.tree_nodes[0].clone(),
+            }]
+        );
+    }
+}
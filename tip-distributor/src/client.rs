@@ -0,0 +1,135 @@
+//! The subset of cluster access the claim, upload and reclaim workflows need, so they can run
+//! against something other than a live RPC node, e.g. a local bank in tests.
+use {
+    crate::{get_batched_accounts, send_until_blockhash_expires},
+    async_trait::async_trait,
+    log::warn,
+    solana_client::nonblocking::rpc_client::RpcClient,
+    solana_rpc_client_api::{client_error::Result, config::RpcSimulateTransactionConfig},
+    solana_sdk::{
+        account::Account,
+        clock::Epoch,
+        commitment_config::CommitmentConfig,
+        pubkey::Pubkey,
+        signature::Keypair,
+        transaction::{Transaction, TransactionError},
+    },
+    std::{collections::HashMap, sync::Arc},
+};
+
+#[async_trait]
+pub trait TipDistributorClient: Send + Sync {
+    /// Fetches any number of accounts, batching requests as needed.
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, Option<Account>>>;
+
+    async fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>> {
+        Ok(self
+            .get_multiple_accounts(&[*pubkey])
+            .await?
+            .remove(pubkey)
+            .flatten())
+    }
+
+    async fn get_program_accounts(&self, program_id: &Pubkey) -> Result<Vec<(Pubkey, Account)>>;
+
+    async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64>;
+
+    async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64>;
+
+    async fn get_epoch(&self) -> Result<Epoch>;
+
+    /// Signs the transactions with a recent blockhash and sends them until they land or the
+    /// blockhash expires. Transactions that fail aren't reported; callers are expected to re-read
+    /// the state they were changing.
+    async fn send_transactions(
+        &self,
+        transactions: Vec<Transaction>,
+        signer: &Arc<Keypair>,
+    ) -> Result<()>;
+
+    /// Returns the error the transaction fails with, if any.
+    async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Option<TransactionError>>;
+}
+
+#[async_trait]
+impl TipDistributorClient for RpcClient {
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, Option<Account>>> {
+        get_batched_accounts(self, pubkeys).await
+    }
+
+    async fn get_program_accounts(&self, program_id: &Pubkey) -> Result<Vec<(Pubkey, Account)>> {
+        RpcClient::get_program_accounts(self, program_id).await
+    }
+
+    async fn get_balance(&self, pubkey: &Pubkey) -> Result<u64> {
+        RpcClient::get_balance(self, pubkey).await
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64> {
+        RpcClient::get_minimum_balance_for_rent_exemption(self, data_len).await
+    }
+
+    async fn get_epoch(&self) -> Result<Epoch> {
+        Ok(self.get_epoch_info().await?.epoch)
+    }
+
+    async fn send_transactions(
+        &self,
+        transactions: Vec<Transaction>,
+        signer: &Arc<Keypair>,
+    ) -> Result<()> {
+        let blockhash = self.get_latest_blockhash().await?;
+        send_until_blockhash_expires(self, transactions, blockhash, signer).await
+    }
+
+    async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Option<TransactionError>> {
+        match self
+            .simulate_transaction_with_config(
+                transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(CommitmentConfig::processed()),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )
+            .await
+        {
+            Ok(response) => Ok(response.value.err),
+            Err(e) => match e.get_transaction_error() {
+                Some(transaction_error) => Ok(Some(transaction_error)),
+                None => Err(e),
+            },
+        }
+    }
+}
+
+/// Simulates the transactions until one fails, returning its error.
+pub(crate) async fn first_simulation_error(
+    client: &dyn TipDistributorClient,
+    transactions: &[Transaction],
+) -> Option<String> {
+    for tx in transactions {
+        match client.simulate_transaction(tx).await {
+            Ok(None) => {}
+            Ok(Some(e)) => {
+                warn!("transaction error. tx: {:?} error: {:?}", tx, e);
+                return Some(e.to_string());
+            }
+            Err(e) => return Some(e.to_string()),
+        }
+    }
+    None
+}
@@ -1,13 +1,19 @@
 pub mod claim_mev_workflow;
+pub mod claim_proof_workflow;
+pub mod client;
 pub mod merkle_root_generator_workflow;
 pub mod merkle_root_upload_workflow;
+pub mod pipeline_workflow;
 pub mod reclaim_rent_workflow;
 pub mod stake_meta_generator_workflow;
+pub mod tip_split_policy;
+pub mod verify_workflow;
 
 use {
     crate::{
         merkle_root_generator_workflow::MerkleRootGeneratorError,
         stake_meta_generator_workflow::StakeMetaGeneratorError::CheckedMathError,
+        tip_split_policy::{TipSplitPolicy, TipSplitPolicyDescription},
     },
     anchor_lang::Id,
     jito_tip_distribution::{
@@ -21,12 +27,8 @@ use {
     },
     log::*,
     serde::{de::DeserializeOwned, Deserialize, Serialize},
-    solana_client::{
-        nonblocking::rpc_client::RpcClient,
-        rpc_client::{RpcClient as SyncRpcClient, SerializableTransaction},
-    },
+    solana_client::{nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction},
     solana_merkle_tree::MerkleTree,
-    solana_metrics::{datapoint_error, datapoint_warn},
     solana_program::{
         instruction::InstructionError,
         rent::{
@@ -70,6 +72,10 @@ pub struct GeneratedMerkleTreeCollection {
     pub bank_hash: String,
     pub epoch: Epoch,
     pub slot: Slot,
+    /// The policy the tips were split with, missing from collections generated before policies
+    /// were configurable.
+    #[serde(default)]
+    pub tip_split_policy: Option<TipSplitPolicyDescription>,
 }
 
 #[derive(Clone, Eq, Debug, Hash, PartialEq, Deserialize, Serialize)]
@@ -89,67 +95,21 @@ pub struct TipPaymentPubkeys {
     tip_pdas: Vec<Pubkey>,
 }
 
-fn emit_inconsistent_tree_node_amount_dp(
-    tree_nodes: &[TreeNode],
-    tip_distribution_account: &Pubkey,
-    rpc_client: &SyncRpcClient,
-) {
-    let actual_claims: u64 = tree_nodes.iter().map(|t| t.amount).sum();
-    let tda = rpc_client.get_account(tip_distribution_account).unwrap();
-    let min_rent = rpc_client
-        .get_minimum_balance_for_rent_exemption(tda.data.len())
-        .unwrap();
-
-    let expected_claims = tda.lamports.checked_sub(min_rent).unwrap();
-    if actual_claims == expected_claims {
-        return;
-    }
-
-    if actual_claims > expected_claims {
-        datapoint_error!(
-            "tip-distributor",
-            (
-                "actual_claims_exceeded",
-                format!("tip_distribution_account={tip_distribution_account},actual_claims={actual_claims}, expected_claims={expected_claims}"),
-                String
-            ),
-        );
-    } else {
-        datapoint_warn!(
-            "tip-distributor",
-            (
-                "actual_claims_below",
-                format!("tip_distribution_account={tip_distribution_account},actual_claims={actual_claims}, expected_claims={expected_claims}"),
-                String
-            ),
-        );
-    }
-}
-
 impl GeneratedMerkleTreeCollection {
     pub fn new_from_stake_meta_collection(
         stake_meta_coll: StakeMetaCollection,
-        maybe_rpc_client: Option<SyncRpcClient>,
+        tip_split_policy: &dyn TipSplitPolicy,
     ) -> Result<GeneratedMerkleTreeCollection, MerkleRootGeneratorError> {
         let generated_merkle_trees = stake_meta_coll
             .stake_metas
             .into_iter()
             .filter(|stake_meta| stake_meta.maybe_tip_distribution_meta.is_some())
             .filter_map(|stake_meta| {
-                let mut tree_nodes = match TreeNode::vec_from_stake_meta(&stake_meta) {
-                    Err(e) => return Some(Err(e)),
-                    Ok(maybe_tree_nodes) => maybe_tree_nodes,
-                }?;
-
-                if let Some(rpc_client) = &maybe_rpc_client {
-                    if let Some(tda) = stake_meta.maybe_tip_distribution_meta.as_ref() {
-                        emit_inconsistent_tree_node_amount_dp(
-                            &tree_nodes[..],
-                            &tda.tip_distribution_pubkey,
-                            rpc_client,
-                        );
-                    }
-                }
+                let mut tree_nodes =
+                    match TreeNode::vec_from_stake_meta(&stake_meta, tip_split_policy) {
+                        Err(e) => return Some(Err(e)),
+                        Ok(maybe_tree_nodes) => maybe_tree_nodes,
+                    }?;
 
                 let hashed_nodes: Vec<[u8; 32]> =
                     tree_nodes.iter().map(|n| n.hash().to_bytes()).collect();
@@ -180,6 +140,7 @@ impl GeneratedMerkleTreeCollection {
             bank_hash: stake_meta_coll.bank_hash,
             epoch: stake_meta_coll.epoch,
             slot: stake_meta_coll.slot,
+            tip_split_policy: Some(tip_split_policy.description()),
         })
     }
 }
@@ -247,67 +208,41 @@ pub struct TreeNode {
 impl TreeNode {
     fn vec_from_stake_meta(
         stake_meta: &StakeMeta,
+        tip_split_policy: &dyn TipSplitPolicy,
     ) -> Result<Option<Vec<TreeNode>>, MerkleRootGeneratorError> {
         if let Some(tip_distribution_meta) = stake_meta.maybe_tip_distribution_meta.as_ref() {
-            let validator_amount = (tip_distribution_meta.total_tips as u128)
-                .checked_mul(tip_distribution_meta.validator_fee_bps as u128)
-                .unwrap()
-                .checked_div(10_000)
-                .unwrap() as u64;
-            let (claim_status_pubkey, claim_status_bump) = Pubkey::find_program_address(
-                &[
-                    ClaimStatus::SEED,
-                    &stake_meta.validator_vote_account.to_bytes(),
-                    &tip_distribution_meta.tip_distribution_pubkey.to_bytes(),
-                ],
-                &JitoTipDistribution::id(),
-            );
-            let mut tree_nodes = vec![TreeNode {
-                claimant: stake_meta.validator_vote_account,
-                claim_status_pubkey,
-                claim_status_bump,
-                staker_pubkey: Pubkey::default(),
-                withdrawer_pubkey: Pubkey::default(),
-                amount: validator_amount,
-                proof: None,
-            }];
-
-            let remaining_total_rewards = tip_distribution_meta
-                .total_tips
-                .checked_sub(validator_amount)
-                .unwrap() as u128;
+            let splits = tip_split_policy.split_tips(stake_meta, tip_distribution_meta);
+            let total_claims: u128 = splits.iter().map(|split| split.amount as u128).sum();
+            if total_claims > tip_distribution_meta.total_tips as u128 {
+                return Err(MerkleRootGeneratorError::TipSplitExceedsTotalTips {
+                    tip_distribution_account: tip_distribution_meta.tip_distribution_pubkey,
+                    total_claims,
+                    total_tips: tip_distribution_meta.total_tips,
+                });
+            }
 
-            let total_delegated = stake_meta.total_delegated as u128;
-            tree_nodes.extend(
-                stake_meta
-                    .delegations
-                    .iter()
-                    .map(|delegation| {
-                        let amount_delegated = delegation.lamports_delegated as u128;
-                        let reward_amount = (amount_delegated.checked_mul(remaining_total_rewards))
-                            .unwrap()
-                            .checked_div(total_delegated)
-                            .unwrap();
-                        let (claim_status_pubkey, claim_status_bump) = Pubkey::find_program_address(
-                            &[
-                                ClaimStatus::SEED,
-                                &delegation.stake_account_pubkey.to_bytes(),
-                                &tip_distribution_meta.tip_distribution_pubkey.to_bytes(),
-                            ],
-                            &JitoTipDistribution::id(),
-                        );
-                        Ok(TreeNode {
-                            claimant: delegation.stake_account_pubkey,
-                            claim_status_pubkey,
-                            claim_status_bump,
-                            staker_pubkey: delegation.staker_pubkey,
-                            withdrawer_pubkey: delegation.withdrawer_pubkey,
-                            amount: reward_amount as u64,
-                            proof: None,
-                        })
-                    })
-                    .collect::<Result<Vec<TreeNode>, MerkleRootGeneratorError>>()?,
-            );
+            let tree_nodes = splits
+                .into_iter()
+                .map(|split| {
+                    let (claim_status_pubkey, claim_status_bump) = Pubkey::find_program_address(
+                        &[
+                            ClaimStatus::SEED,
+                            &split.claimant.to_bytes(),
+                            &tip_distribution_meta.tip_distribution_pubkey.to_bytes(),
+                        ],
+                        &JitoTipDistribution::id(),
+                    );
+                    TreeNode {
+                        claimant: split.claimant,
+                        claim_status_pubkey,
+                        claim_status_bump,
+                        staker_pubkey: split.staker_pubkey,
+                        withdrawer_pubkey: split.withdrawer_pubkey,
+                        amount: split.amount,
+                        proof: None,
+                    }
+                })
+                .collect();
 
             Ok(Some(tree_nodes))
         } else {
@@ -769,7 +704,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use {super::*, jito_tip_distribution::merkle_proof};
+    use {super::*, crate::tip_split_policy::ProRataPolicy, jito_tip_distribution::merkle_proof};
 
     #[test]
     fn test_merkle_tree_verify() {
@@ -907,7 +842,7 @@ mod tests {
 
         let merkle_tree_collection = GeneratedMerkleTreeCollection::new_from_stake_meta_collection(
             stake_meta_collection.clone(),
-            None,
+            &ProRataPolicy,
         )
         .unwrap();
 
@@ -1,7 +1,12 @@
 use {
-    crate::{read_json_from_file, GeneratedMerkleTreeCollection, StakeMetaCollection},
+    crate::{
+        read_json_from_file, tip_split_policy::TipSplitPolicy,
+        verify_workflow::emit_allocation_datapoints, GeneratedMerkleTreeCollection,
+        StakeMetaCollection,
+    },
     log::*,
     solana_client::rpc_client::RpcClient,
+    solana_sdk::pubkey::Pubkey,
     std::{
         fmt::Debug,
         fs::File,
@@ -21,21 +26,33 @@ pub enum MerkleRootGeneratorError {
 
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
+
+    #[error("tip split for {tip_distribution_account} claims {total_claims} lamports, more than the {total_tips} lamports of tips")]
+    TipSplitExceedsTotalTips {
+        tip_distribution_account: Pubkey,
+        total_claims: u128,
+        total_tips: u64,
+    },
 }
 
 pub fn generate_merkle_root(
     stake_meta_coll_path: &PathBuf,
     out_path: &PathBuf,
     rpc_url: &str,
+    tip_split_policy: &dyn TipSplitPolicy,
 ) -> Result<(), MerkleRootGeneratorError> {
     let stake_meta_coll: StakeMetaCollection = read_json_from_file(stake_meta_coll_path)?;
 
-    let rpc_client = RpcClient::new(rpc_url);
     let merkle_tree_coll = GeneratedMerkleTreeCollection::new_from_stake_meta_collection(
         stake_meta_coll,
-        Some(rpc_client),
+        tip_split_policy,
     )?;
 
+    let rpc_client = RpcClient::new(rpc_url);
+    if let Err(e) = emit_allocation_datapoints(&merkle_tree_coll, &rpc_client) {
+        error!("failed to check merkle tree allocations: {e}");
+    }
+
     write_to_json_file(&merkle_tree_coll, out_path)?;
     Ok(())
 }
@@ -1,7 +1,7 @@
 use {
     crate::{
-        read_json_from_file, sign_and_send_transactions_with_retries, GeneratedMerkleTree,
-        GeneratedMerkleTreeCollection,
+        client::TipDistributorClient, read_json_from_file, sign_and_send_transactions_with_retries,
+        GeneratedMerkleTree, GeneratedMerkleTreeCollection,
     },
     anchor_lang::AccountDeserialize,
     jito_tip_distribution::{
@@ -16,10 +16,14 @@ use {
     solana_sdk::{
         commitment_config::CommitmentConfig,
         pubkey::Pubkey,
-        signature::{read_keypair_file, Signer},
+        signature::{read_keypair_file, Keypair, Signer},
         transaction::Transaction,
     },
-    std::{path::PathBuf, time::Duration},
+    std::{
+        path::PathBuf,
+        sync::Arc,
+        time::{Duration, Instant},
+    },
     thiserror::Error,
     tokio::runtime::Builder,
 };
@@ -31,6 +35,26 @@ pub enum MerkleRootUploadError {
 
     #[error(transparent)]
     JsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    RpcError(#[from] solana_rpc_client_api::client_error::Error),
+
+    #[error(transparent)]
+    AnchorError(anchor_lang::error::Error),
+
+    #[error("tip distribution account {0} not found")]
+    TipDistributionAccountNotFound(Pubkey),
+
+    #[error("Expected to have at least {desired_balance} lamports in {payer}, current balance is {start_balance} lamports, deposit {sol_to_deposit} SOL to continue.")]
+    InsufficientBalance {
+        desired_balance: u64,
+        payer: Pubkey,
+        start_balance: u64,
+        sol_to_deposit: u64,
+    },
+
+    #[error("Not finished with job, transactions left {transactions_left}")]
+    NotFinished { transactions_left: usize },
 }
 
 pub fn upload_merkle_root(
@@ -47,9 +71,6 @@ pub fn upload_merkle_root(
         read_json_from_file(merkle_root_path).expect("read GeneratedMerkleTreeCollection");
     let keypair = read_keypair_file(keypair_path).expect("read keypair file");
 
-    let tip_distribution_config =
-        Pubkey::find_program_address(&[Config::SEED], tip_distribution_program_id).0;
-
     let runtime = Builder::new_multi_thread()
         .worker_threads(16)
         .enable_all()
@@ -59,80 +80,157 @@ pub fn upload_merkle_root(
     runtime.block_on(async move {
         let rpc_client =
             RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
-        let trees: Vec<GeneratedMerkleTree> = merkle_tree
-            .generated_merkle_trees
-            .into_iter()
-            .filter(|tree| tree.merkle_root_upload_authority == keypair.pubkey())
-            .collect();
-
-        info!("num trees to upload: {:?}", trees.len());
-
-        // heuristic to make sure we have enough funds to cover execution, assumes all trees need updating 
-        {
-            let initial_balance = rpc_client.get_balance(&keypair.pubkey()).await.expect("failed to get balance");
-            let desired_balance = (trees.len() as u64).checked_mul(DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE).unwrap();
-            if initial_balance < desired_balance {
-                let sol_to_deposit = desired_balance.checked_sub(initial_balance).unwrap().checked_add(LAMPORTS_PER_SOL).unwrap().checked_sub(1).unwrap().checked_div(LAMPORTS_PER_SOL).unwrap(); // rounds up to nearest sol
-                panic!("Expected to have at least {} lamports in {}, current balance is {} lamports, deposit {} SOL to continue.",
-                       desired_balance, &keypair.pubkey(), initial_balance, sol_to_deposit)
-            }
-        }
-        let mut trees_needing_update: Vec<GeneratedMerkleTree> = vec![];
-        for tree in trees {
-            let account = rpc_client
-                .get_account(&tree.tip_distribution_account)
-                .await
-                .expect("fetch expect");
-
-            let mut data = account.data.as_slice();
-            let fetched_tip_distribution_account =
-                TipDistributionAccount::try_deserialize(&mut data)
-                    .expect("failed to deserialize tip_distribution_account state");
-
-            let needs_upload = match fetched_tip_distribution_account.merkle_root {
-                Some(merkle_root) => {
-                    merkle_root.total_funds_claimed == 0
-                        && merkle_root.root != tree.merkle_root.to_bytes()
-                }
-                None => true,
-            };
-
-            if needs_upload {
-                trees_needing_update.push(tree);
-            }
-        }
-
-        info!("num trees need uploading: {:?}", trees_needing_update.len());
-
-        let transactions: Vec<Transaction> = trees_needing_update
-            .iter()
-            .map(|tree| {
-                let ix = upload_merkle_root_ix(
-                    *tip_distribution_program_id,
-                    UploadMerkleRootArgs {
-                        root: tree.merkle_root.to_bytes(),
-                        max_total_claim: tree.max_total_claim,
-                        max_num_nodes: tree.max_num_nodes,
-                    },
-                    UploadMerkleRootAccounts {
-                        config: tip_distribution_config,
-                        merkle_root_upload_authority: keypair.pubkey(),
-                        tip_distribution_account: tree.tip_distribution_account,
-                    },
-                );
-                Transaction::new_with_payer(
-                    &[ix],
-                    Some(&keypair.pubkey()),
-                )
-            })
-            .collect();
+        let transactions = get_upload_transactions(
+            &rpc_client,
+            &merkle_tree,
+            tip_distribution_program_id,
+            keypair.pubkey(),
+        )
+        .await?;
 
         let (to_process, failed_transactions) = sign_and_send_transactions_with_retries(
-            &keypair, &rpc_client, max_concurrent_rpc_get_reqs, transactions, txn_send_batch_size, MAX_RETRY_DURATION).await;
+            &keypair,
+            &rpc_client,
+            max_concurrent_rpc_get_reqs,
+            transactions,
+            txn_send_batch_size,
+            MAX_RETRY_DURATION,
+        )
+        .await;
         if !to_process.is_empty() {
-            panic!("{} remaining mev claim transactions, {} failed requests.", to_process.len(), failed_transactions.len());
+            panic!(
+                "{} remaining mev claim transactions, {} failed requests.",
+                to_process.len(),
+                failed_transactions.len()
+            );
+        }
+        Ok(())
+    })
+}
+
+/// Uploads the merkle roots `keypair` is the upload authority for, resending until they all land
+/// or `max_loop_duration` passes.
+pub async fn upload_merkle_root_with_client(
+    client: &dyn TipDistributorClient,
+    merkle_tree: &GeneratedMerkleTreeCollection,
+    tip_distribution_program_id: &Pubkey,
+    keypair: Arc<Keypair>,
+    max_loop_duration: Duration,
+) -> Result<(), MerkleRootUploadError> {
+    let start = Instant::now();
+    loop {
+        let transactions = get_upload_transactions(
+            client,
+            merkle_tree,
+            tip_distribution_program_id,
+            keypair.pubkey(),
+        )
+        .await?;
+        if transactions.is_empty() {
+            return Ok(());
         }
-    });
+        if start.elapsed() > max_loop_duration {
+            return Err(MerkleRootUploadError::NotFinished {
+                transactions_left: transactions.len(),
+            });
+        }
+        client.send_transactions(transactions, &keypair).await?;
+    }
+}
 
-    Ok(())
+/// Builds upload transactions for the trees `upload_authority` is responsible for whose root
+/// isn't on-chain yet and hasn't been claimed against.
+pub async fn get_upload_transactions(
+    client: &dyn TipDistributorClient,
+    merkle_tree: &GeneratedMerkleTreeCollection,
+    tip_distribution_program_id: &Pubkey,
+    upload_authority: Pubkey,
+) -> Result<Vec<Transaction>, MerkleRootUploadError> {
+    let tip_distribution_config =
+        Pubkey::find_program_address(&[Config::SEED], tip_distribution_program_id).0;
+
+    let trees: Vec<&GeneratedMerkleTree> = merkle_tree
+        .generated_merkle_trees
+        .iter()
+        .filter(|tree| tree.merkle_root_upload_authority == upload_authority)
+        .collect();
+
+    info!("num trees to upload: {:?}", trees.len());
+
+    // heuristic to make sure we have enough funds to cover execution, assumes all trees need updating
+    let start_balance = client.get_balance(&upload_authority).await?;
+    let desired_balance = (trees.len() as u64)
+        .checked_mul(DEFAULT_TARGET_LAMPORTS_PER_SIGNATURE)
+        .unwrap();
+    if start_balance < desired_balance {
+        let sol_to_deposit = desired_balance
+            .checked_sub(start_balance)
+            .unwrap()
+            .checked_add(LAMPORTS_PER_SOL)
+            .unwrap()
+            .checked_sub(1)
+            .unwrap()
+            .checked_div(LAMPORTS_PER_SOL)
+            .unwrap(); // rounds up to nearest sol
+        return Err(MerkleRootUploadError::InsufficientBalance {
+            desired_balance,
+            payer: upload_authority,
+            start_balance,
+            sol_to_deposit,
+        });
+    }
+
+    let tda_pubkeys: Vec<Pubkey> = trees
+        .iter()
+        .map(|tree| tree.tip_distribution_account)
+        .collect();
+    let tdas = client.get_multiple_accounts(&tda_pubkeys).await?;
+
+    let mut trees_needing_update = vec![];
+    for tree in trees {
+        let account = tdas
+            .get(&tree.tip_distribution_account)
+            .cloned()
+            .flatten()
+            .ok_or(MerkleRootUploadError::TipDistributionAccountNotFound(
+                tree.tip_distribution_account,
+            ))?;
+        let fetched_tip_distribution_account =
+            TipDistributionAccount::try_deserialize(&mut account.data.as_slice())
+                .map_err(MerkleRootUploadError::AnchorError)?;
+
+        let needs_upload = match fetched_tip_distribution_account.merkle_root {
+            Some(merkle_root) => {
+                merkle_root.total_funds_claimed == 0
+                    && merkle_root.root != tree.merkle_root.to_bytes()
+            }
+            None => true,
+        };
+
+        if needs_upload {
+            trees_needing_update.push(tree);
+        }
+    }
+
+    info!("num trees need uploading: {:?}", trees_needing_update.len());
+
+    Ok(trees_needing_update
+        .iter()
+        .map(|tree| {
+            let ix = upload_merkle_root_ix(
+                *tip_distribution_program_id,
+                UploadMerkleRootArgs {
+                    root: tree.merkle_root.to_bytes(),
+                    max_total_claim: tree.max_total_claim,
+                    max_num_nodes: tree.max_num_nodes,
+                },
+                UploadMerkleRootAccounts {
+                    config: tip_distribution_config,
+                    merkle_root_upload_authority: upload_authority,
+                    tip_distribution_account: tree.tip_distribution_account,
+                },
+            );
+            Transaction::new_with_payer(&[ix], Some(&upload_authority))
+        })
+        .collect())
 }
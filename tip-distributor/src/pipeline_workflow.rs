@@ -0,0 +1,415 @@
+//! Runs every step of distributing an epoch's tips, from generating the stake meta to reclaiming
+//! rent, in one go. A checkpoint manifest is written after each completed stage so an interrupted
+//! run picks up where it left off instead of redoing (or skipping) work.
+use {
+    crate::{
+        claim_mev_workflow::{claim_mev_tips, ClaimMevError},
+        merkle_root_generator_workflow::{generate_merkle_root, MerkleRootGeneratorError},
+        merkle_root_upload_workflow::{upload_merkle_root, MerkleRootUploadError},
+        read_json_from_file,
+        reclaim_rent_workflow::reclaim_rent,
+        stake_meta_generator_workflow::{generate_stake_meta, StakeMetaGeneratorError},
+        tip_split_policy::TipSplitPolicy,
+        GeneratedMerkleTreeCollection, StakeMetaCollection,
+    },
+    log::*,
+    serde::{Deserialize, Serialize},
+    solana_metrics::datapoint_info,
+    solana_sdk::{
+        clock::Slot,
+        pubkey::Pubkey,
+        signature::{read_keypair_file, Keypair},
+        stake_history::Epoch,
+    },
+    std::{
+        fs::{self, File},
+        io::{BufWriter, Write},
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::{Duration, Instant},
+    },
+    thiserror::Error,
+    tokio::runtime::Builder,
+};
+
+#[derive(Error, Debug)]
+pub enum PipelineError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("stake meta generation failed: {0}")]
+    StakeMetaGeneratorError(#[from] StakeMetaGeneratorError),
+
+    #[error("merkle root generation failed: {0}")]
+    MerkleRootGeneratorError(#[from] MerkleRootGeneratorError),
+
+    #[error("merkle root upload failed: {0}")]
+    MerkleRootUploadError(#[from] MerkleRootUploadError),
+
+    #[error("claiming failed: {0}")]
+    ClaimMevError(#[from] ClaimMevError),
+
+    #[error("failed to read keypair: {0}")]
+    KeypairError(String),
+
+    #[error("checkpoint is for snapshot slot {actual}, expected {expected}")]
+    SnapshotSlotMismatch { expected: Slot, actual: Slot },
+
+    #[error("no stake meta has been generated yet")]
+    MissingStakeMeta,
+
+    #[error("merkle trees were generated at bank hash {actual}, expected {expected}")]
+    BankHashMismatch { expected: String, actual: String },
+}
+
+/// The stages of the pipeline, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Generate the [StakeMetaCollection] from the snapshot.
+    StakeMeta,
+    /// Generate the [GeneratedMerkleTreeCollection] from the stake meta.
+    MerkleTrees,
+    /// Upload the merkle roots this keypair is the upload authority for.
+    UploadMerkleRoots,
+    /// Claim tips on behalf of every tree node.
+    ClaimMevTips,
+    /// Close expired claim status and, optionally, tip distribution accounts.
+    ReclaimRent,
+}
+
+impl PipelineStage {
+    pub const ALL: [PipelineStage; 5] = [
+        PipelineStage::StakeMeta,
+        PipelineStage::MerkleTrees,
+        PipelineStage::UploadMerkleRoots,
+        PipelineStage::ClaimMevTips,
+        PipelineStage::ReclaimRent,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            PipelineStage::StakeMeta => "stake_meta",
+            PipelineStage::MerkleTrees => "merkle_trees",
+            PipelineStage::UploadMerkleRoots => "upload_merkle_roots",
+            PipelineStage::ClaimMevTips => "claim_mev_tips",
+            PipelineStage::ReclaimRent => "reclaim_rent",
+        }
+    }
+}
+
+pub struct PipelineConfig {
+    /// Ledger path, where the snapshot was created.
+    pub ledger_path: PathBuf,
+
+    /// The expected snapshot slot.
+    pub snapshot_slot: Slot,
+
+    /// Directory the stake meta, merkle trees and checkpoint files are written to.
+    pub out_dir: PathBuf,
+
+    pub rpc_url: String,
+
+    /// The keypair used to upload merkle roots and pay for claims.
+    pub keypair_path: PathBuf,
+
+    pub tip_distribution_program_id: Pubkey,
+
+    pub tip_payment_program_id: Pubkey,
+
+    /// Rate-limits the maximum number of requests per RPC connection while uploading.
+    pub max_concurrent_rpc_get_reqs: usize,
+
+    /// Number of upload transactions to send to RPC at a time.
+    pub txn_send_batch_size: usize,
+
+    /// Limits how long the claim and reclaim send loops run before stopping.
+    pub max_retry_duration: Duration,
+
+    /// The price to pay for priority fee.
+    pub micro_lamports: u64,
+
+    pub should_reclaim_rent: bool,
+
+    pub should_reclaim_tdas: bool,
+
+    /// How tips are split when generating the merkle trees.
+    pub tip_split_policy: Box<dyn TipSplitPolicy>,
+}
+
+/// Progress of a pipeline run for a snapshot slot, persisted after every completed stage.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PipelineCheckpoint {
+    pub snapshot_slot: Slot,
+
+    /// Epoch of the stake meta, known once [PipelineStage::StakeMeta] completed.
+    pub epoch: Option<Epoch>,
+
+    /// Bank hash of the stake meta, known once [PipelineStage::StakeMeta] completed. The merkle
+    /// trees are checked against it before they're used.
+    pub bank_hash: Option<String>,
+
+    pub stake_meta_path: PathBuf,
+
+    pub merkle_trees_path: PathBuf,
+
+    pub completed_stages: Vec<PipelineStage>,
+}
+
+impl PipelineCheckpoint {
+    fn new(out_dir: &Path, snapshot_slot: Slot) -> Self {
+        Self {
+            snapshot_slot,
+            epoch: None,
+            bank_hash: None,
+            stake_meta_path: out_dir.join(format!("stake-meta-{snapshot_slot}.json")),
+            merkle_trees_path: out_dir.join(format!("merkle-trees-{snapshot_slot}.json")),
+            completed_stages: vec![],
+        }
+    }
+
+    pub fn path(out_dir: &Path, snapshot_slot: Slot) -> PathBuf {
+        out_dir.join(format!("tip-distributor-checkpoint-{snapshot_slot}.json"))
+    }
+
+    /// Returns the checkpoint at `path`, if a previous run wrote one.
+    pub fn load(path: &Path) -> Result<Option<Self>, PipelineError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(read_json_from_file(&path.to_path_buf())?))
+    }
+
+    /// Writes the checkpoint to a temporary file first so a crash never leaves a partial one.
+    fn write(&self, path: &Path) -> Result<(), PipelineError> {
+        let tmp_path = path.with_extension("json.tmp");
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn is_completed(&self, stage: PipelineStage) -> bool {
+        self.completed_stages.contains(&stage)
+    }
+
+    /// Reads the merkle trees, making sure they were generated from this run's stake meta.
+    pub fn load_merkle_trees(&self) -> Result<GeneratedMerkleTreeCollection, PipelineError> {
+        let expected_bank_hash = self
+            .bank_hash
+            .as_ref()
+            .ok_or(PipelineError::MissingStakeMeta)?;
+        let merkle_trees: GeneratedMerkleTreeCollection =
+            read_json_from_file(&self.merkle_trees_path)?;
+        if merkle_trees.slot != self.snapshot_slot {
+            return Err(PipelineError::SnapshotSlotMismatch {
+                expected: self.snapshot_slot,
+                actual: merkle_trees.slot,
+            });
+        }
+        if &merkle_trees.bank_hash != expected_bank_hash {
+            return Err(PipelineError::BankHashMismatch {
+                expected: expected_bank_hash.clone(),
+                actual: merkle_trees.bank_hash,
+            });
+        }
+        Ok(merkle_trees)
+    }
+}
+
+/// Runs the pipeline for `config.snapshot_slot`, skipping stages a previous run completed.
+pub fn run_pipeline(config: &PipelineConfig) -> Result<PipelineCheckpoint, PipelineError> {
+    fs::create_dir_all(&config.out_dir)?;
+    let checkpoint_path = PipelineCheckpoint::path(&config.out_dir, config.snapshot_slot);
+    let mut checkpoint = match PipelineCheckpoint::load(&checkpoint_path)? {
+        Some(checkpoint) => {
+            if checkpoint.snapshot_slot != config.snapshot_slot {
+                return Err(PipelineError::SnapshotSlotMismatch {
+                    expected: config.snapshot_slot,
+                    actual: checkpoint.snapshot_slot,
+                });
+            }
+            info!(
+                "resuming from checkpoint {:?}, completed stages: {:?}",
+                checkpoint_path, checkpoint.completed_stages
+            );
+            checkpoint
+        }
+        None => PipelineCheckpoint::new(&config.out_dir, config.snapshot_slot),
+    };
+
+    for stage in PipelineStage::ALL {
+        if checkpoint.is_completed(stage) {
+            info!("skipping completed stage {}", stage.name());
+            continue;
+        }
+
+        info!("running stage {}...", stage.name());
+        let start = Instant::now();
+        run_stage(stage, config, &mut checkpoint)?;
+        checkpoint.completed_stages.push(stage);
+        checkpoint.write(&checkpoint_path)?;
+
+        datapoint_info!(
+            "tip_distributor_pipeline-stage_completion",
+            ("epoch", checkpoint.epoch.unwrap_or_default(), i64),
+            ("stage", stage.name(), String),
+            ("elapsed_us", start.elapsed().as_micros(), i64),
+        );
+    }
+
+    Ok(checkpoint)
+}
+
+fn run_stage(
+    stage: PipelineStage,
+    config: &PipelineConfig,
+    checkpoint: &mut PipelineCheckpoint,
+) -> Result<(), PipelineError> {
+    match stage {
+        PipelineStage::StakeMeta => {
+            generate_stake_meta(
+                &config.ledger_path,
+                &config.snapshot_slot,
+                &config.tip_distribution_program_id,
+                &checkpoint.stake_meta_path.to_string_lossy(),
+                &config.tip_payment_program_id,
+            )?;
+            let stake_meta_coll: StakeMetaCollection =
+                read_json_from_file(&checkpoint.stake_meta_path)?;
+            checkpoint.epoch = Some(stake_meta_coll.epoch);
+            checkpoint.bank_hash = Some(stake_meta_coll.bank_hash);
+        }
+        PipelineStage::MerkleTrees => {
+            generate_merkle_root(
+                &checkpoint.stake_meta_path,
+                &checkpoint.merkle_trees_path,
+                &config.rpc_url,
+                config.tip_split_policy.as_ref(),
+            )?;
+            checkpoint.load_merkle_trees()?;
+        }
+        PipelineStage::UploadMerkleRoots => {
+            checkpoint.load_merkle_trees()?;
+            upload_merkle_root(
+                &checkpoint.merkle_trees_path,
+                &config.keypair_path,
+                &config.rpc_url,
+                &config.tip_distribution_program_id,
+                config.max_concurrent_rpc_get_reqs,
+                config.txn_send_batch_size,
+            )?;
+        }
+        PipelineStage::ClaimMevTips => {
+            let merkle_trees = checkpoint.load_merkle_trees()?;
+            let keypair = read_keypair(&config.keypair_path)?;
+            build_runtime()?.block_on(claim_mev_tips(
+                &merkle_trees,
+                config.rpc_url.clone(),
+                config.tip_distribution_program_id,
+                keypair,
+                config.max_retry_duration,
+                config.micro_lamports,
+            ))?;
+        }
+        PipelineStage::ReclaimRent => {
+            if !config.should_reclaim_rent {
+                info!("rent reclamation disabled");
+                return Ok(());
+            }
+            let keypair = read_keypair(&config.keypair_path)?;
+            build_runtime()?.block_on(reclaim_rent(
+                config.rpc_url.clone(),
+                config.tip_distribution_program_id,
+                keypair,
+                config.max_retry_duration,
+                config.should_reclaim_tdas,
+                config.micro_lamports,
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+fn read_keypair(keypair_path: &Path) -> Result<Arc<Keypair>, PipelineError> {
+    read_keypair_file(keypair_path)
+        .map(Arc::new)
+        .map_err(|e| PipelineError::KeypairError(e.to_string()))
+}
+
+// upload_merkle_root builds its own runtime, so the pipeline itself can't run inside one
+fn build_runtime() -> std::io::Result<tokio::runtime::Runtime> {
+    Builder::new_multi_thread().enable_all().build()
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::hash::Hash, tempfile::TempDir};
+
+    fn write_merkle_trees(path: &Path, slot: Slot, bank_hash: &str) {
+        let merkle_trees = GeneratedMerkleTreeCollection {
+            generated_merkle_trees: vec![],
+            bank_hash: bank_hash.to_string(),
+            epoch: 100,
+            slot,
+            tip_split_policy: None,
+        };
+        fs::write(path, serde_json::to_string(&merkle_trees).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let out_dir = TempDir::new().unwrap();
+        let path = PipelineCheckpoint::path(out_dir.path(), 2_000_000);
+        assert_eq!(PipelineCheckpoint::load(&path).unwrap(), None);
+
+        let mut checkpoint = PipelineCheckpoint::new(out_dir.path(), 2_000_000);
+        checkpoint.epoch = Some(100);
+        checkpoint.bank_hash = Some(Hash::new_unique().to_string());
+        checkpoint.completed_stages = vec![PipelineStage::StakeMeta, PipelineStage::MerkleTrees];
+        checkpoint.write(&path).unwrap();
+
+        let loaded = PipelineCheckpoint::load(&path).unwrap().unwrap();
+        assert_eq!(loaded, checkpoint);
+        assert!(loaded.is_completed(PipelineStage::MerkleTrees));
+        assert!(!loaded.is_completed(PipelineStage::UploadMerkleRoots));
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn test_load_merkle_trees_checks_bank_hash() {
+        let out_dir = TempDir::new().unwrap();
+        let mut checkpoint = PipelineCheckpoint::new(out_dir.path(), 2_000_000);
+        assert!(matches!(
+            checkpoint.load_merkle_trees(),
+            Err(PipelineError::MissingStakeMeta)
+        ));
+
+        let bank_hash = Hash::new_unique().to_string();
+        checkpoint.bank_hash = Some(bank_hash.clone());
+
+        write_merkle_trees(&checkpoint.merkle_trees_path, 2_000_000, &bank_hash);
+        assert_eq!(checkpoint.load_merkle_trees().unwrap().bank_hash, bank_hash);
+
+        write_merkle_trees(
+            &checkpoint.merkle_trees_path,
+            2_000_000,
+            &Hash::new_unique().to_string(),
+        );
+        assert!(matches!(
+            checkpoint.load_merkle_trees(),
+            Err(PipelineError::BankHashMismatch { .. })
+        ));
+
+        write_merkle_trees(&checkpoint.merkle_trees_path, 1_999_999, &bank_hash);
+        assert!(matches!(
+            checkpoint.load_merkle_trees(),
+            Err(PipelineError::SnapshotSlotMismatch { .. })
+        ));
+    }
+}
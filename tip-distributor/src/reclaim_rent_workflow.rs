@@ -1,7 +1,8 @@
 use {
     crate::{
-        claim_mev_workflow::ClaimMevError, get_batched_accounts,
-        reclaim_rent_workflow::ClaimMevError::AnchorError, send_until_blockhash_expires,
+        claim_mev_workflow::ClaimMevError,
+        client::{first_simulation_error, TipDistributorClient},
+        reclaim_rent_workflow::ClaimMevError::AnchorError,
     },
     anchor_lang::AccountDeserialize,
     jito_tip_distribution::{
@@ -15,12 +16,11 @@ use {
         },
         state::{ClaimStatus, Config, TipDistributionAccount},
     },
-    log::{info, warn},
+    log::info,
     rand::{prelude::SliceRandom, thread_rng},
     solana_client::nonblocking::rpc_client::RpcClient,
     solana_metrics::datapoint_info,
     solana_program::{clock::Epoch, pubkey::Pubkey},
-    solana_rpc_client_api::config::RpcSimulateTransactionConfig,
     solana_sdk::{
         account::Account,
         commitment_config::CommitmentConfig,
@@ -45,23 +45,48 @@ pub async fn reclaim_rent(
     micro_lamports: u64,
 ) -> Result<(), ClaimMevError> {
     let rpc_client = RpcClient::new_with_timeout_and_commitment(
-        rpc_url.clone(),
+        rpc_url,
         Duration::from_secs(300),
         CommitmentConfig::processed(),
     );
+    reclaim_rent_with_client(
+        &rpc_client,
+        tip_distribution_program_id,
+        signer,
+        max_loop_duration,
+        should_reclaim_tdas,
+        micro_lamports,
+    )
+    .await
+}
 
+pub async fn reclaim_rent_with_client(
+    client: &dyn TipDistributorClient,
+    tip_distribution_program_id: Pubkey,
+    signer: Arc<Keypair>,
+    max_loop_duration: Duration,
+    // Optionally reclaim TipDistributionAccount rents on behalf of validators.
+    should_reclaim_tdas: bool,
+    micro_lamports: u64,
+) -> Result<(), ClaimMevError> {
     let start = Instant::now();
 
-    let accounts = rpc_client
+    let accounts = client
         .get_program_accounts(&tip_distribution_program_id)
         .await?;
 
     let config_pubkey = derive_config_account_address(&tip_distribution_program_id).0;
-    let config_account = rpc_client.get_account(&config_pubkey).await?;
+    let config_account =
+        client
+            .get_account(&config_pubkey)
+            .await?
+            .ok_or_else(|| ClaimMevError::UncaughtError {
+                e: format!("tip distribution config {config_pubkey} not found"),
+            })?;
     let config_account =
         Config::try_deserialize(&mut config_account.data.as_slice()).map_err(AnchorError)?;
 
-    let epoch = rpc_client.get_epoch_info().await?.epoch;
+    let epoch = client.get_epoch().await?;
     let mut claim_status_pubkeys_to_expire =
         find_expired_claim_status_accounts(&accounts, epoch, signer.pubkey());
     let mut tda_pubkeys_to_expire = find_expired_tda_accounts(&accounts, epoch);
@@ -96,51 +121,18 @@ pub async fn reclaim_rent(
 
         transactions.shuffle(&mut thread_rng());
         let transactions: Vec<_> = transactions.into_iter().take(10_000).collect();
-        let blockhash = rpc_client.get_latest_blockhash().await?;
-        send_until_blockhash_expires(&rpc_client, transactions, blockhash, &signer).await?;
+        client.send_transactions(transactions, &signer).await?;
 
         // can just refresh calling get_multiple_accounts since these operations should be subtractive and not additive
-        let claim_status_pubkeys: Vec<_> = claim_status_pubkeys_to_expire
-            .iter()
-            .map(|(pubkey, _)| *pubkey)
-            .collect();
-        claim_status_pubkeys_to_expire = get_batched_accounts(&rpc_client, &claim_status_pubkeys)
-            .await?
-            .into_iter()
-            .filter_map(|(pubkey, account)| Some((pubkey, account?)))
-            .collect();
-
-        let tda_pubkeys: Vec<_> = tda_pubkeys_to_expire
-            .iter()
-            .map(|(pubkey, _)| *pubkey)
-            .collect();
-        tda_pubkeys_to_expire = get_batched_accounts(&rpc_client, &tda_pubkeys)
-            .await?
-            .into_iter()
-            .filter_map(|(pubkey, account)| Some((pubkey, account?)))
-            .collect();
+        claim_status_pubkeys_to_expire =
+            refresh_accounts(client, &claim_status_pubkeys_to_expire).await?;
+        tda_pubkeys_to_expire = refresh_accounts(client, &tda_pubkeys_to_expire).await?;
     }
 
     // one final refresh before double checking everything
-    let claim_status_pubkeys: Vec<_> = claim_status_pubkeys_to_expire
-        .iter()
-        .map(|(pubkey, _)| *pubkey)
-        .collect();
-    claim_status_pubkeys_to_expire = get_batched_accounts(&rpc_client, &claim_status_pubkeys)
-        .await?
-        .into_iter()
-        .filter_map(|(pubkey, account)| Some((pubkey, account?)))
-        .collect();
-
-    let tda_pubkeys: Vec<_> = tda_pubkeys_to_expire
-        .iter()
-        .map(|(pubkey, _)| *pubkey)
-        .collect();
-    tda_pubkeys_to_expire = get_batched_accounts(&rpc_client, &tda_pubkeys)
-        .await?
-        .into_iter()
-        .filter_map(|(pubkey, account)| Some((pubkey, account?)))
-        .collect();
+    claim_status_pubkeys_to_expire =
+        refresh_accounts(client, &claim_status_pubkeys_to_expire).await?;
+    tda_pubkeys_to_expire = refresh_accounts(client, &tda_pubkeys_to_expire).await?;
 
     let mut transactions = build_close_claim_status_transactions(
         &claim_status_pubkeys_to_expire,
@@ -164,48 +156,28 @@ pub async fn reclaim_rent(
     }
 
     // if more transactions left, we'll simulate them all to make sure its not an uncaught error
-    let mut is_error = false;
-    let mut error_str = String::new();
-    for tx in &transactions {
-        match rpc_client
-            .simulate_transaction_with_config(
-                tx,
-                RpcSimulateTransactionConfig {
-                    sig_verify: false,
-                    replace_recent_blockhash: true,
-                    commitment: Some(CommitmentConfig::processed()),
-                    ..RpcSimulateTransactionConfig::default()
-                },
-            )
-            .await
-        {
-            Ok(_) => {}
-            Err(e) => {
-                error_str = e.to_string();
-                is_error = true;
-
-                match e.get_transaction_error() {
-                    None => {
-                        break;
-                    }
-                    Some(e) => {
-                        warn!("transaction error. tx: {:?} error: {:?}", tx, e);
-                        break;
-                    }
-                }
-            }
-        }
-    }
-
-    if is_error {
-        Err(ClaimMevError::UncaughtError { e: error_str })
-    } else {
-        Err(ClaimMevError::NotFinished {
+    match first_simulation_error(client, &transactions).await {
+        Some(e) => Err(ClaimMevError::UncaughtError { e }),
+        None => Err(ClaimMevError::NotFinished {
             transactions_left: transactions.len(),
-        })
+        }),
     }
 }
 
+/// Re-fetches the accounts, dropping the ones that no longer exist.
+async fn refresh_accounts(
+    client: &dyn TipDistributorClient,
+    accounts: &[(Pubkey, Account)],
+) -> Result<Vec<(Pubkey, Account)>, ClaimMevError> {
+    let pubkeys: Vec<_> = accounts.iter().map(|(pubkey, _)| *pubkey).collect();
+    Ok(client
+        .get_multiple_accounts(&pubkeys)
+        .await?
+        .into_iter()
+        .filter_map(|(pubkey, account)| Some((pubkey, account?)))
+        .collect())
+}
+
 fn find_expired_claim_status_accounts(
     accounts: &[(Pubkey, Account)],
     epoch: Epoch,
@@ -95,7 +95,7 @@ pub fn generate_stake_meta(
     Ok(())
 }
 
-fn create_bank_from_snapshot(
+pub fn create_bank_from_snapshot(
     ledger_path: &Path,
     snapshot_slot: &Slot,
 ) -> Result<Arc<Bank>, StakeMetaGeneratorError> {
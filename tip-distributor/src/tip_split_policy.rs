@@ -0,0 +1,549 @@
+//! Policies deciding how a [TipDistributionAccount]'s tips are split between the validator and its
+//! delegators when generating merkle trees. Each policy describes itself so the description can be
+//! embedded in the generated [GeneratedMerkleTreeCollection] for auditability.
+use {
+    crate::{Delegation, StakeMeta, TipDistributionMeta},
+    clap::ValueEnum,
+    serde::{Deserialize, Serialize},
+    solana_sdk::pubkey::Pubkey,
+    std::{collections::HashSet, fmt::Display, str::FromStr},
+    thiserror::Error,
+};
+
+const MAX_BPS: u64 = 10_000;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TipSplitPolicyError {
+    #[error("invalid stake tier {0}, expected <min_lamports_delegated>:<share_bps>")]
+    InvalidStakeTier(String),
+
+    #[error("share_bps {0} exceeds {MAX_BPS}")]
+    InvalidShareBps(u64),
+
+    #[error("the tiered policy requires at least one --stake-tier")]
+    MissingStakeTiers,
+
+    #[error("the {policy} policy doesn't take {arg}")]
+    UnexpectedArg {
+        policy: TipSplitPolicyKind,
+        arg: &'static str,
+    },
+}
+
+/// A claimant's share of the tips, before it's turned into a [crate::TreeNode].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TipSplit {
+    pub claimant: Pubkey,
+    pub staker_pubkey: Pubkey,
+    pub withdrawer_pubkey: Pubkey,
+    pub amount: u64,
+}
+
+impl TipSplit {
+    fn validator(stake_meta: &StakeMeta, amount: u64) -> Self {
+        Self {
+            claimant: stake_meta.validator_vote_account,
+            staker_pubkey: Pubkey::default(),
+            withdrawer_pubkey: Pubkey::default(),
+            amount,
+        }
+    }
+
+    fn delegator(delegation: &Delegation, amount: u64) -> Self {
+        Self {
+            claimant: delegation.stake_account_pubkey,
+            staker_pubkey: delegation.staker_pubkey,
+            withdrawer_pubkey: delegation.withdrawer_pubkey,
+            amount,
+        }
+    }
+}
+
+/// Describes the policy a merkle tree collection was generated with.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TipSplitPolicyDescription {
+    pub name: String,
+    pub description: String,
+}
+
+pub trait TipSplitPolicy: Send + Sync {
+    fn description(&self) -> TipSplitPolicyDescription;
+
+    /// Splits the validator's tips between its claimants. The validator's vote account must be
+    /// the first split, and the splits must not add up to more than
+    /// [TipDistributionMeta::total_tips].
+    fn split_tips(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipSplit>;
+}
+
+/// The validator takes its commission from `validator_fee_bps` and the rest is split between the
+/// delegators in proportion to their delegated stake.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProRataPolicy;
+
+impl TipSplitPolicy for ProRataPolicy {
+    fn description(&self) -> TipSplitPolicyDescription {
+        TipSplitPolicyDescription {
+            name: TipSplitPolicyKind::ProRata.to_string(),
+            description: "validator commission from validator_fee_bps, remainder pro-rata by \
+                          delegated stake"
+                .to_string(),
+        }
+    }
+
+    fn split_tips(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipSplit> {
+        let validator_amount = validator_commission(tip_distribution_meta);
+        let remaining_total_rewards = tip_distribution_meta.total_tips - validator_amount;
+
+        let mut splits = vec![TipSplit::validator(stake_meta, validator_amount)];
+        splits.extend(stake_meta.delegations.iter().map(|delegation| {
+            TipSplit::delegator(
+                delegation,
+                pro_rata(
+                    remaining_total_rewards,
+                    delegation.lamports_delegated,
+                    stake_meta.total_delegated,
+                ),
+            )
+        }));
+        splits
+    }
+}
+
+/// Like [ProRataPolicy], but the excluded stake accounts don't receive tips and their share is
+/// split between the remaining delegators. If no delegators remain, the validator receives it.
+#[derive(Clone, Debug, Default)]
+pub struct ExcludeStakeAccountsPolicy {
+    pub excluded_stake_accounts: HashSet<Pubkey>,
+}
+
+impl TipSplitPolicy for ExcludeStakeAccountsPolicy {
+    fn description(&self) -> TipSplitPolicyDescription {
+        let mut excluded: Vec<String> = self
+            .excluded_stake_accounts
+            .iter()
+            .map(|pubkey| pubkey.to_string())
+            .collect();
+        excluded.sort();
+        TipSplitPolicyDescription {
+            name: TipSplitPolicyKind::ExcludeStakeAccounts.to_string(),
+            description: format!(
+                "validator commission from validator_fee_bps, remainder pro-rata by delegated \
+                 stake excluding stake accounts [{}]",
+                excluded.join(", ")
+            ),
+        }
+    }
+
+    fn split_tips(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipSplit> {
+        let validator_amount = validator_commission(tip_distribution_meta);
+        let remaining_total_rewards = tip_distribution_meta.total_tips - validator_amount;
+
+        let included: Vec<&Delegation> = stake_meta
+            .delegations
+            .iter()
+            .filter(|delegation| {
+                !self
+                    .excluded_stake_accounts
+                    .contains(&delegation.stake_account_pubkey)
+            })
+            .collect();
+        if included.is_empty() {
+            return vec![TipSplit::validator(
+                stake_meta,
+                tip_distribution_meta.total_tips,
+            )];
+        }
+        let included_delegated = included
+            .iter()
+            .map(|delegation| delegation.lamports_delegated)
+            .sum();
+
+        let mut splits = vec![TipSplit::validator(stake_meta, validator_amount)];
+        splits.extend(included.into_iter().map(|delegation| {
+            TipSplit::delegator(
+                delegation,
+                pro_rata(
+                    remaining_total_rewards,
+                    delegation.lamports_delegated,
+                    included_delegated,
+                ),
+            )
+        }));
+        splits
+    }
+}
+
+/// Delegators whose delegation is at least `min_lamports_delegated` receive `share_bps` of their
+/// pro-rata share.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakeTier {
+    pub min_lamports_delegated: u64,
+    pub share_bps: u64,
+}
+
+impl FromStr for StakeTier {
+    type Err = TipSplitPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min_lamports_delegated, share_bps) = s
+            .split_once(':')
+            .and_then(|(min_lamports_delegated, share_bps)| {
+                Some((
+                    min_lamports_delegated.parse().ok()?,
+                    share_bps.parse().ok()?,
+                ))
+            })
+            .ok_or_else(|| TipSplitPolicyError::InvalidStakeTier(s.to_string()))?;
+        if share_bps > MAX_BPS {
+            return Err(TipSplitPolicyError::InvalidShareBps(share_bps));
+        }
+        Ok(Self {
+            min_lamports_delegated,
+            share_bps,
+        })
+    }
+}
+
+/// Tiers the delegators' share of the tips by the size of their delegation. After the validator's
+/// commission, each delegator's pro-rata share is scaled by the `share_bps` of the highest tier it
+/// qualifies for and whatever isn't shared goes to the validator. Delegations below every tier
+/// receive nothing.
+#[derive(Clone, Debug)]
+pub struct TieredPolicy {
+    /// Sorted by `min_lamports_delegated` in descending order.
+    tiers: Vec<StakeTier>,
+}
+
+impl TieredPolicy {
+    pub fn new(mut tiers: Vec<StakeTier>) -> Result<Self, TipSplitPolicyError> {
+        if tiers.is_empty() {
+            return Err(TipSplitPolicyError::MissingStakeTiers);
+        }
+        if let Some(tier) = tiers.iter().find(|tier| tier.share_bps > MAX_BPS) {
+            return Err(TipSplitPolicyError::InvalidShareBps(tier.share_bps));
+        }
+        tiers.sort_by(|a, b| b.min_lamports_delegated.cmp(&a.min_lamports_delegated));
+        Ok(Self { tiers })
+    }
+
+    fn share_bps(&self, lamports_delegated: u64) -> u64 {
+        self.tiers
+            .iter()
+            .find(|tier| lamports_delegated >= tier.min_lamports_delegated)
+            .map(|tier| tier.share_bps)
+            .unwrap_or_default()
+    }
+}
+
+impl TipSplitPolicy for TieredPolicy {
+    fn description(&self) -> TipSplitPolicyDescription {
+        let tiers: Vec<String> = self
+            .tiers
+            .iter()
+            .rev()
+            .map(|tier| {
+                format!(
+                    ">={} lamports: {} bps",
+                    tier.min_lamports_delegated, tier.share_bps
+                )
+            })
+            .collect();
+        TipSplitPolicyDescription {
+            name: TipSplitPolicyKind::Tiered.to_string(),
+            description: format!(
+                "validator commission from validator_fee_bps, remainder pro-rata by delegated \
+                 stake scaled by stake tier [{}], unshared tips go to the validator",
+                tiers.join(", ")
+            ),
+        }
+    }
+
+    fn split_tips(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipSplit> {
+        let mut pro_rata_splits = ProRataPolicy
+            .split_tips(stake_meta, tip_distribution_meta)
+            .into_iter();
+        let mut validator_split = pro_rata_splits.next().unwrap();
+
+        let mut delegator_splits = Vec::with_capacity(stake_meta.delegations.len());
+        for (mut split, delegation) in pro_rata_splits.zip(&stake_meta.delegations) {
+            let share_bps = self.share_bps(delegation.lamports_delegated);
+            let shared = pro_rata(split.amount, share_bps, MAX_BPS);
+            validator_split.amount += split.amount - shared;
+            split.amount = shared;
+            if share_bps > 0 {
+                delegator_splits.push(split);
+            }
+        }
+
+        let mut splits = vec![validator_split];
+        splits.extend(delegator_splits);
+        splits
+    }
+}
+
+/// Wraps another policy, dropping delegators whose share is below `min_claim_lamports` and rolling
+/// their dust over to the validator.
+pub struct MinimumClaimPolicy {
+    pub inner: Box<dyn TipSplitPolicy>,
+    pub min_claim_lamports: u64,
+}
+
+impl TipSplitPolicy for MinimumClaimPolicy {
+    fn description(&self) -> TipSplitPolicyDescription {
+        let inner = self.inner.description();
+        TipSplitPolicyDescription {
+            name: format!("{}+min-claim", inner.name),
+            description: format!(
+                "{}; delegator claims below {} lamports roll over to the validator",
+                inner.description, self.min_claim_lamports
+            ),
+        }
+    }
+
+    fn split_tips(
+        &self,
+        stake_meta: &StakeMeta,
+        tip_distribution_meta: &TipDistributionMeta,
+    ) -> Vec<TipSplit> {
+        let mut splits = self.inner.split_tips(stake_meta, tip_distribution_meta);
+        let dust: u64 = splits[1..]
+            .iter()
+            .filter(|split| split.amount < self.min_claim_lamports)
+            .map(|split| split.amount)
+            .sum();
+        let validator_split = splits.remove(0);
+        splits.retain(|split| split.amount >= self.min_claim_lamports);
+        splits.insert(
+            0,
+            TipSplit {
+                amount: validator_split.amount + dust,
+                ..validator_split
+            },
+        );
+        splits
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TipSplitPolicyKind {
+    #[default]
+    ProRata,
+    ExcludeStakeAccounts,
+    Tiered,
+}
+
+impl Display for TipSplitPolicyKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TipSplitPolicyKind::ProRata => write!(f, "pro-rata"),
+            TipSplitPolicyKind::ExcludeStakeAccounts => write!(f, "exclude-stake-accounts"),
+            TipSplitPolicyKind::Tiered => write!(f, "tiered"),
+        }
+    }
+}
+
+/// Command line arguments selecting a [TipSplitPolicy], shared by the binaries that generate
+/// merkle trees.
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct TipSplitPolicyArgs {
+    /// How tips are split between the validator and its delegators
+    #[arg(long, env, value_enum, default_value_t = TipSplitPolicyKind::ProRata)]
+    pub tip_split_policy: TipSplitPolicyKind,
+
+    /// Stake accounts excluded from receiving tips, for the exclude-stake-accounts policy
+    #[arg(long, env, value_delimiter = ',')]
+    pub excluded_stake_accounts: Vec<Pubkey>,
+
+    /// Stake tiers as <min_lamports_delegated>:<share_bps>, for the tiered policy
+    #[arg(long, env, value_delimiter = ',')]
+    pub stake_tier: Vec<StakeTier>,
+
+    /// Delegator claims below this many lamports roll over to the validator
+    #[arg(long, env)]
+    pub min_claim_lamports: Option<u64>,
+}
+
+impl TipSplitPolicyArgs {
+    pub fn build(&self) -> Result<Box<dyn TipSplitPolicy>, TipSplitPolicyError> {
+        if self.tip_split_policy != TipSplitPolicyKind::ExcludeStakeAccounts
+            && !self.excluded_stake_accounts.is_empty()
+        {
+            return Err(TipSplitPolicyError::UnexpectedArg {
+                policy: self.tip_split_policy,
+                arg: "--excluded-stake-accounts",
+            });
+        }
+        if self.tip_split_policy != TipSplitPolicyKind::Tiered && !self.stake_tier.is_empty() {
+            return Err(TipSplitPolicyError::UnexpectedArg {
+                policy: self.tip_split_policy,
+                arg: "--stake-tier",
+            });
+        }
+
+        let policy: Box<dyn TipSplitPolicy> = match self.tip_split_policy {
+            TipSplitPolicyKind::ProRata => Box::new(ProRataPolicy),
+            TipSplitPolicyKind::ExcludeStakeAccounts => Box::new(ExcludeStakeAccountsPolicy {
+                excluded_stake_accounts: self.excluded_stake_accounts.iter().copied().collect(),
+            }),
+            TipSplitPolicyKind::Tiered => Box::new(TieredPolicy::new(self.stake_tier.clone())?),
+        };
+        Ok(match self.min_claim_lamports {
+            Some(min_claim_lamports) => Box::new(MinimumClaimPolicy {
+                inner: policy,
+                min_claim_lamports,
+            }),
+            None => policy,
+        })
+    }
+}
+
+fn validator_commission(tip_distribution_meta: &TipDistributionMeta) -> u64 {
+    pro_rata(
+        tip_distribution_meta.total_tips,
+        tip_distribution_meta.validator_fee_bps as u64,
+        MAX_BPS,
+    )
+}
+
+/// `amount * numerator / denominator`, rounded down.
+fn pro_rata(amount: u64, numerator: u64, denominator: u64) -> u64 {
+    (amount as u128)
+        .checked_mul(numerator as u128)
+        .unwrap()
+        .checked_div(denominator as u128)
+        .unwrap_or_default() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delegation(lamports_delegated: u64) -> Delegation {
+        Delegation {
+            stake_account_pubkey: Pubkey::new_unique(),
+            staker_pubkey: Pubkey::new_unique(),
+            withdrawer_pubkey: Pubkey::new_unique(),
+            lamports_delegated,
+        }
+    }
+
+    fn stake_meta(delegations: Vec<Delegation>) -> (StakeMeta, TipDistributionMeta) {
+        let tip_distribution_meta = TipDistributionMeta {
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            tip_distribution_pubkey: Pubkey::new_unique(),
+            total_tips: 1_000_000,
+            validator_fee_bps: 1_000,
+        };
+        let stake_meta = StakeMeta {
+            validator_vote_account: Pubkey::new_unique(),
+            validator_node_pubkey: Pubkey::new_unique(),
+            maybe_tip_distribution_meta: Some(tip_distribution_meta.clone()),
+            total_delegated: delegations.iter().map(|d| d.lamports_delegated).sum(),
+            delegations,
+            commission: 10,
+        };
+        (stake_meta, tip_distribution_meta)
+    }
+
+    fn amounts(splits: &[TipSplit]) -> Vec<u64> {
+        splits.iter().map(|split| split.amount).collect()
+    }
+
+    #[test]
+    fn test_pro_rata_policy() {
+        let (stake_meta, tip_distribution_meta) =
+            stake_meta(vec![delegation(100), delegation(300), delegation(600)]);
+        let splits = ProRataPolicy.split_tips(&stake_meta, &tip_distribution_meta);
+        assert_eq!(amounts(&splits), vec![100_000, 90_000, 270_000, 540_000]);
+        assert_eq!(splits[0].claimant, stake_meta.validator_vote_account);
+        assert_eq!(
+            splits[1].claimant,
+            stake_meta.delegations[0].stake_account_pubkey
+        );
+    }
+
+    #[test]
+    fn test_exclude_stake_accounts_policy() {
+        let (stake_meta, tip_distribution_meta) =
+            stake_meta(vec![delegation(100), delegation(300), delegation(600)]);
+        let excluded = stake_meta.delegations[2].stake_account_pubkey;
+        let policy = ExcludeStakeAccountsPolicy {
+            excluded_stake_accounts: HashSet::from([excluded]),
+        };
+        let splits = policy.split_tips(&stake_meta, &tip_distribution_meta);
+        assert_eq!(amounts(&splits), vec![100_000, 225_000, 675_000]);
+        assert!(splits.iter().all(|split| split.claimant != excluded));
+
+        let policy = ExcludeStakeAccountsPolicy {
+            excluded_stake_accounts: stake_meta
+                .delegations
+                .iter()
+                .map(|d| d.stake_account_pubkey)
+                .collect(),
+        };
+        let splits = policy.split_tips(&stake_meta, &tip_distribution_meta);
+        assert_eq!(amounts(&splits), vec![1_000_000]);
+    }
+
+    #[test]
+    fn test_tiered_policy() {
+        let (stake_meta, tip_distribution_meta) =
+            stake_meta(vec![delegation(100), delegation(300), delegation(600)]);
+        let policy = TieredPolicy::new(vec![
+            "200:5000".parse().unwrap(),
+            "500:10000".parse().unwrap(),
+        ])
+        .unwrap();
+        let splits = policy.split_tips(&stake_meta, &tip_distribution_meta);
+        // the smallest delegation is below every tier and gets dropped
+        assert_eq!(amounts(&splits), vec![325_000, 135_000, 540_000]);
+        assert_eq!(
+            splits[1].claimant,
+            stake_meta.delegations[1].stake_account_pubkey
+        );
+
+        assert_eq!(
+            "200".parse::<StakeTier>(),
+            Err(TipSplitPolicyError::InvalidStakeTier("200".to_string()))
+        );
+        assert_eq!(
+            "200:10001".parse::<StakeTier>(),
+            Err(TipSplitPolicyError::InvalidShareBps(10_001))
+        );
+        assert!(matches!(
+            TieredPolicy::new(vec![]),
+            Err(TipSplitPolicyError::MissingStakeTiers)
+        ));
+    }
+
+    #[test]
+    fn test_minimum_claim_policy() {
+        let (stake_meta, tip_distribution_meta) =
+            stake_meta(vec![delegation(100), delegation(300), delegation(600)]);
+        let policy = TipSplitPolicyArgs {
+            min_claim_lamports: Some(100_000),
+            ..TipSplitPolicyArgs::default()
+        }
+        .build()
+        .unwrap();
+        let splits = policy.split_tips(&stake_meta, &tip_distribution_meta);
+        assert_eq!(amounts(&splits), vec![190_000, 270_000, 540_000]);
+        assert_eq!(policy.description().name, "pro-rata+min-claim");
+    }
+}
@@ -0,0 +1,630 @@
+//! Independently checks the merkle roots uploaded to [TipDistributionAccount]s by rebuilding the
+//! merkle trees from a snapshot and comparing them with what's on-chain and, optionally, with a
+//! published [GeneratedMerkleTreeCollection].
+use {
+    crate::{
+        merkle_root_generator_workflow::MerkleRootGeneratorError,
+        minimum_balance, read_json_from_file,
+        stake_meta_generator_workflow::{
+            create_bank_from_snapshot, generate_stake_meta_collection, StakeMetaGeneratorError,
+        },
+        tip_split_policy::{TipSplitPolicy, TipSplitPolicyDescription},
+        GeneratedMerkleTree, GeneratedMerkleTreeCollection,
+    },
+    anchor_lang::AccountDeserialize,
+    jito_tip_distribution::state::TipDistributionAccount,
+    log::*,
+    serde::Serialize,
+    solana_client::rpc_client::RpcClient,
+    solana_metrics::{datapoint_error, datapoint_warn},
+    solana_rpc_client_api::request::MAX_MULTIPLE_ACCOUNTS,
+    solana_sdk::{
+        account::Account, clock::Slot, commitment_config::CommitmentConfig, hash::Hash,
+        pubkey::Pubkey, stake_history::Epoch,
+    },
+    std::{
+        collections::HashMap,
+        fmt::{Display, Formatter},
+        path::{Path, PathBuf},
+    },
+    thiserror::Error,
+};
+
+#[derive(Error, Debug)]
+pub enum VerifyError {
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    RpcError(#[from] Box<solana_client::client_error::ClientError>),
+
+    #[error("stake meta generation failed: {0}")]
+    StakeMetaGeneratorError(#[from] StakeMetaGeneratorError),
+
+    #[error(transparent)]
+    MerkleRootGeneratorError(#[from] MerkleRootGeneratorError),
+
+    #[error("published merkle trees are for slot {actual}, expected {expected}")]
+    PublishedSlotMismatch { expected: Slot, actual: Slot },
+
+    #[error("published merkle trees were split with {actual}, expected {expected}")]
+    PublishedTipSplitPolicyMismatch { expected: String, actual: String },
+}
+
+/// The state of a [TipDistributionAccount] relevant to verifying its merkle root.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OnChainTipDistributionAccount {
+    pub merkle_root: Option<UploadedMerkleRoot>,
+
+    /// Lamports available to claimants: the balance above the rent exempt reserve plus whatever
+    /// was already claimed.
+    pub claimable_lamports: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UploadedMerkleRoot {
+    pub root: Hash,
+    pub max_total_claim: u64,
+    pub max_num_nodes: u64,
+}
+
+impl OnChainTipDistributionAccount {
+    pub fn try_from_account(account: &Account) -> Result<Self, anchor_lang::error::Error> {
+        let tip_distribution_account =
+            TipDistributionAccount::try_deserialize(&mut account.data.as_slice())?;
+        let total_funds_claimed = tip_distribution_account
+            .merkle_root
+            .as_ref()
+            .map(|merkle_root| merkle_root.total_funds_claimed)
+            .unwrap_or_default();
+        Ok(Self {
+            merkle_root: tip_distribution_account.merkle_root.map(|merkle_root| {
+                UploadedMerkleRoot {
+                    root: Hash::new_from_array(merkle_root.root),
+                    max_total_claim: merkle_root.max_total_claim,
+                    max_num_nodes: merkle_root.max_num_nodes,
+                }
+            }),
+            claimable_lamports: account
+                .lamports
+                .saturating_sub(minimum_balance(account.data.len()))
+                .saturating_add(total_funds_claimed),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TreeMismatch {
+    /// The tip distribution account doesn't exist or couldn't be deserialized.
+    AccountNotFound,
+    /// The published merkle trees don't include a tree for the account.
+    TreeNotPublished,
+    RootNotUploaded,
+    Root {
+        expected: String,
+        actual: String,
+    },
+    MaxTotalClaim {
+        expected: u64,
+        actual: u64,
+    },
+    MaxNumNodes {
+        expected: u64,
+        actual: u64,
+    },
+    /// The tree hands out more lamports than the account holds.
+    OverAllocated {
+        total_claims: u64,
+        claimable_lamports: u64,
+    },
+    /// The tree hands out fewer lamports than the account holds, by more than the lamport per
+    /// claimant lost to rounding down each share.
+    UnderAllocated {
+        total_claims: u64,
+        claimable_lamports: u64,
+    },
+    /// A claimant's amount in the published tree differs from the rebuilt one. A claimant missing
+    /// from the published tree has no actual amount.
+    ClaimantAmount {
+        claimant: String,
+        expected: u64,
+        actual: Option<u64>,
+    },
+    /// A claimant in the published tree isn't in the rebuilt one.
+    UnexpectedClaimant {
+        claimant: String,
+        amount: u64,
+    },
+}
+
+impl Display for TreeMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeMismatch::AccountNotFound => write!(f, "tip distribution account not found"),
+            TreeMismatch::TreeNotPublished => write!(f, "merkle tree not published"),
+            TreeMismatch::RootNotUploaded => write!(f, "merkle root not uploaded"),
+            TreeMismatch::Root { expected, actual } => {
+                write!(f, "merkle root is {actual}, expected {expected}")
+            }
+            TreeMismatch::MaxTotalClaim { expected, actual } => {
+                write!(f, "max_total_claim is {actual}, expected {expected}")
+            }
+            TreeMismatch::MaxNumNodes { expected, actual } => {
+                write!(f, "max_num_nodes is {actual}, expected {expected}")
+            }
+            TreeMismatch::OverAllocated {
+                total_claims,
+                claimable_lamports,
+            } => write!(
+                f,
+                "claims total {total_claims} lamports but only {claimable_lamports} are claimable"
+            ),
+            TreeMismatch::UnderAllocated {
+                total_claims,
+                claimable_lamports,
+            } => write!(
+                f,
+                "claims total {total_claims} lamports but {claimable_lamports} are claimable"
+            ),
+            TreeMismatch::ClaimantAmount {
+                claimant,
+                expected,
+                actual: Some(actual),
+            } => write!(
+                f,
+                "claimant {claimant} is allocated {actual} lamports, expected {expected}"
+            ),
+            TreeMismatch::ClaimantAmount {
+                claimant,
+                expected,
+                actual: None,
+            } => write!(
+                f,
+                "claimant {claimant} is missing, expected {expected} lamports"
+            ),
+            TreeMismatch::UnexpectedClaimant { claimant, amount } => write!(
+                f,
+                "unexpected claimant {claimant} allocated {amount} lamports"
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TreeVerification {
+    pub tip_distribution_account: String,
+    pub expected_root: String,
+    pub mismatches: Vec<TreeMismatch>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct VerificationReport {
+    pub epoch: Epoch,
+    pub slot: Slot,
+    pub bank_hash: String,
+    pub tip_split_policy: Option<TipSplitPolicyDescription>,
+    pub num_trees: usize,
+    /// Only trees with at least one mismatch are listed.
+    pub failed_trees: Vec<TreeVerification>,
+}
+
+impl VerificationReport {
+    pub fn is_ok(&self) -> bool {
+        self.failed_trees.is_empty()
+    }
+}
+
+/// Compares the sum of a tree's claims with the lamports its account can pay out.
+pub fn check_allocation(
+    tree: &GeneratedMerkleTree,
+    claimable_lamports: u64,
+) -> Option<TreeMismatch> {
+    let total_claims = tree
+        .tree_nodes
+        .iter()
+        .map(|node| node.amount)
+        .fold(0u64, u64::saturating_add);
+    match total_claims.cmp(&claimable_lamports) {
+        std::cmp::Ordering::Equal => None,
+        std::cmp::Ordering::Greater => Some(TreeMismatch::OverAllocated {
+            total_claims,
+            claimable_lamports,
+        }),
+        std::cmp::Ordering::Less => Some(TreeMismatch::UnderAllocated {
+            total_claims,
+            claimable_lamports,
+        }),
+    }
+}
+
+/// Checks a tree rebuilt from the snapshot against the on-chain account and, if given, the tree
+/// that was published for it.
+pub fn verify_tree(
+    expected: &GeneratedMerkleTree,
+    on_chain: Option<&OnChainTipDistributionAccount>,
+    published: Option<&GeneratedMerkleTree>,
+) -> Vec<TreeMismatch> {
+    let mut mismatches = vec![];
+
+    match on_chain {
+        None => mismatches.push(TreeMismatch::AccountNotFound),
+        Some(on_chain) => {
+            match &on_chain.merkle_root {
+                None => mismatches.push(TreeMismatch::RootNotUploaded),
+                Some(uploaded) => {
+                    if uploaded.root != expected.merkle_root {
+                        mismatches.push(TreeMismatch::Root {
+                            expected: expected.merkle_root.to_string(),
+                            actual: uploaded.root.to_string(),
+                        });
+                    }
+                    if uploaded.max_total_claim != expected.max_total_claim {
+                        mismatches.push(TreeMismatch::MaxTotalClaim {
+                            expected: expected.max_total_claim,
+                            actual: uploaded.max_total_claim,
+                        });
+                    }
+                    if uploaded.max_num_nodes != expected.max_num_nodes {
+                        mismatches.push(TreeMismatch::MaxNumNodes {
+                            expected: expected.max_num_nodes,
+                            actual: uploaded.max_num_nodes,
+                        });
+                    }
+                }
+            }
+            match check_allocation(expected, on_chain.claimable_lamports) {
+                // every share is rounded down, so a valid tree can come up a lamport per claimant
+                // short of what the account holds
+                Some(TreeMismatch::UnderAllocated {
+                    total_claims,
+                    claimable_lamports,
+                }) if claimable_lamports.saturating_sub(total_claims)
+                    <= expected.tree_nodes.len() as u64 =>
+                {
+                    warn!(
+                        "{}: claims total {total_claims} lamports but {claimable_lamports} are \
+                         claimable",
+                        expected.tip_distribution_account
+                    );
+                }
+                mismatch => mismatches.extend(mismatch),
+            }
+        }
+    }
+
+    if let Some(published) = published {
+        let published_amounts: HashMap<Pubkey, u64> = published
+            .tree_nodes
+            .iter()
+            .map(|node| (node.claimant, node.amount))
+            .collect();
+        let expected_amounts: HashMap<Pubkey, u64> = expected
+            .tree_nodes
+            .iter()
+            .map(|node| (node.claimant, node.amount))
+            .collect();
+        for node in &expected.tree_nodes {
+            let actual = published_amounts.get(&node.claimant).copied();
+            if actual != Some(node.amount) {
+                mismatches.push(TreeMismatch::ClaimantAmount {
+                    claimant: node.claimant.to_string(),
+                    expected: node.amount,
+                    actual,
+                });
+            }
+        }
+        for node in &published.tree_nodes {
+            if !expected_amounts.contains_key(&node.claimant) {
+                mismatches.push(TreeMismatch::UnexpectedClaimant {
+                    claimant: node.claimant.to_string(),
+                    amount: node.amount,
+                });
+            }
+        }
+    }
+
+    mismatches
+}
+
+/// Fetches and deserializes the given tip distribution accounts. Accounts that don't exist or
+/// aren't initialized are left out.
+pub fn fetch_tip_distribution_accounts(
+    rpc_client: &RpcClient,
+    pubkeys: &[Pubkey],
+) -> Result<HashMap<Pubkey, OnChainTipDistributionAccount>, VerifyError> {
+    let mut accounts = HashMap::with_capacity(pubkeys.len());
+    for pubkeys_chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let fetched = rpc_client
+            .get_multiple_accounts(pubkeys_chunk)
+            .map_err(Box::new)?;
+        accounts.extend(
+            pubkeys_chunk
+                .iter()
+                .zip(fetched)
+                .filter_map(|(pubkey, account)| {
+                    let account = OnChainTipDistributionAccount::try_from_account(&account?)
+                        .map_err(|e| warn!("failed to deserialize {pubkey}: {e}"))
+                        .ok()?;
+                    Some((*pubkey, account))
+                }),
+        );
+    }
+    Ok(accounts)
+}
+
+/// Emits a datapoint for every tree whose claims don't add up to what its account can pay out.
+pub fn emit_allocation_datapoints(
+    merkle_tree_coll: &GeneratedMerkleTreeCollection,
+    rpc_client: &RpcClient,
+) -> Result<(), VerifyError> {
+    let tda_pubkeys: Vec<Pubkey> = merkle_tree_coll
+        .generated_merkle_trees
+        .iter()
+        .map(|tree| tree.tip_distribution_account)
+        .collect();
+    let accounts = fetch_tip_distribution_accounts(rpc_client, &tda_pubkeys)?;
+    for tree in &merkle_tree_coll.generated_merkle_trees {
+        let tip_distribution_account = &tree.tip_distribution_account;
+        let Some(account) = accounts.get(tip_distribution_account) else {
+            continue;
+        };
+        match check_allocation(tree, account.claimable_lamports) {
+            Some(TreeMismatch::OverAllocated {
+                total_claims,
+                claimable_lamports,
+            }) => datapoint_error!(
+                "tip-distributor",
+                (
+                    "actual_claims_exceeded",
+                    format!("tip_distribution_account={tip_distribution_account},actual_claims={total_claims}, expected_claims={claimable_lamports}"),
+                    String
+                ),
+            ),
+            Some(TreeMismatch::UnderAllocated {
+                total_claims,
+                claimable_lamports,
+            }) => datapoint_warn!(
+                "tip-distributor",
+                (
+                    "actual_claims_below",
+                    format!("tip_distribution_account={tip_distribution_account},actual_claims={total_claims}, expected_claims={claimable_lamports}"),
+                    String
+                ),
+            ),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Rebuilds the merkle trees from the snapshot at `snapshot_slot` and verifies each against
+/// its on-chain account and, if `published_merkle_trees_path` is given, the published trees.
+pub fn verify_merkle_roots(
+    ledger_path: &Path,
+    snapshot_slot: &Slot,
+    tip_distribution_program_id: &Pubkey,
+    tip_payment_program_id: &Pubkey,
+    rpc_url: &str,
+    published_merkle_trees_path: Option<&PathBuf>,
+    tip_split_policy: &dyn TipSplitPolicy,
+) -> Result<VerificationReport, VerifyError> {
+    info!("Creating bank from ledger path...");
+    let bank = create_bank_from_snapshot(ledger_path, snapshot_slot)?;
+
+    info!("Rebuilding merkle trees...");
+    let stake_meta_coll =
+        generate_stake_meta_collection(&bank, tip_distribution_program_id, tip_payment_program_id)?;
+    let merkle_tree_coll = GeneratedMerkleTreeCollection::new_from_stake_meta_collection(
+        stake_meta_coll,
+        tip_split_policy,
+    )?;
+
+    let published_trees: HashMap<Pubkey, GeneratedMerkleTree> = match published_merkle_trees_path {
+        Some(path) => {
+            let published: GeneratedMerkleTreeCollection = read_json_from_file(path)?;
+            if published.slot != merkle_tree_coll.slot {
+                return Err(VerifyError::PublishedSlotMismatch {
+                    expected: merkle_tree_coll.slot,
+                    actual: published.slot,
+                });
+            }
+            // collections generated before tip split policies existed don't record one
+            if let Some(published_policy) = &published.tip_split_policy {
+                if Some(published_policy) != merkle_tree_coll.tip_split_policy.as_ref() {
+                    return Err(VerifyError::PublishedTipSplitPolicyMismatch {
+                        expected: tip_split_policy.description().name,
+                        actual: published_policy.name.clone(),
+                    });
+                }
+            }
+            published
+                .generated_merkle_trees
+                .into_iter()
+                .map(|tree| (tree.tip_distribution_account, tree))
+                .collect()
+        }
+        None => HashMap::new(),
+    };
+
+    info!("Fetching tip distribution accounts...");
+    let rpc_client = RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+    let tda_pubkeys: Vec<Pubkey> = merkle_tree_coll
+        .generated_merkle_trees
+        .iter()
+        .map(|tree| tree.tip_distribution_account)
+        .collect();
+    let accounts = fetch_tip_distribution_accounts(&rpc_client, &tda_pubkeys)?;
+
+    let failed_trees = merkle_tree_coll
+        .generated_merkle_trees
+        .iter()
+        .filter_map(|tree| {
+            let published = published_trees.get(&tree.tip_distribution_account);
+            let mut mismatches = verify_tree(
+                tree,
+                accounts.get(&tree.tip_distribution_account),
+                published,
+            );
+            if published_merkle_trees_path.is_some() && published.is_none() {
+                mismatches.push(TreeMismatch::TreeNotPublished);
+            }
+            (!mismatches.is_empty()).then(|| TreeVerification {
+                tip_distribution_account: tree.tip_distribution_account.to_string(),
+                expected_root: tree.merkle_root.to_string(),
+                mismatches,
+            })
+        })
+        .collect();
+
+    Ok(VerificationReport {
+        epoch: merkle_tree_coll.epoch,
+        slot: merkle_tree_coll.slot,
+        bank_hash: merkle_tree_coll.bank_hash,
+        tip_split_policy: merkle_tree_coll.tip_split_policy,
+        num_trees: merkle_tree_coll.generated_merkle_trees.len(),
+        failed_trees,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::TreeNode};
+
+    fn tree_node(claimant: Pubkey, amount: u64) -> TreeNode {
+        TreeNode {
+            claimant,
+            claim_status_pubkey: Pubkey::new_unique(),
+            claim_status_bump: 255,
+            staker_pubkey: Pubkey::default(),
+            withdrawer_pubkey: Pubkey::default(),
+            amount,
+            proof: None,
+        }
+    }
+
+    fn merkle_tree(tree_nodes: Vec<TreeNode>) -> GeneratedMerkleTree {
+        GeneratedMerkleTree {
+            tip_distribution_account: Pubkey::new_unique(),
+            merkle_root_upload_authority: Pubkey::new_unique(),
+            merkle_root: Hash::new_unique(),
+            max_total_claim: tree_nodes.iter().map(|node| node.amount).sum(),
+            max_num_nodes: tree_nodes.len() as u64,
+            tree_nodes,
+        }
+    }
+
+    #[test]
+    fn test_verify_tree() {
+        let (validator, staker_0, staker_1) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let expected = merkle_tree(vec![
+            tree_node(validator, 100),
+            tree_node(staker_0, 450),
+            tree_node(staker_1, 450),
+        ]);
+        let mut on_chain = OnChainTipDistributionAccount {
+            merkle_root: Some(UploadedMerkleRoot {
+                root: expected.merkle_root,
+                max_total_claim: expected.max_total_claim,
+                max_num_nodes: expected.max_num_nodes,
+            }),
+            claimable_lamports: 1_000,
+        };
+        assert_eq!(
+            verify_tree(&expected, Some(&on_chain), Some(&expected)),
+            vec![]
+        );
+
+        assert_eq!(
+            verify_tree(&expected, None, None),
+            vec![TreeMismatch::AccountNotFound]
+        );
+
+        let uploaded_root = Hash::new_unique();
+        on_chain.merkle_root = Some(UploadedMerkleRoot {
+            root: uploaded_root,
+            max_total_claim: 999,
+            max_num_nodes: 3,
+        });
+        on_chain.claimable_lamports = 1_200;
+        assert_eq!(
+            verify_tree(&expected, Some(&on_chain), None),
+            vec![
+                TreeMismatch::Root {
+                    expected: expected.merkle_root.to_string(),
+                    actual: uploaded_root.to_string(),
+                },
+                TreeMismatch::MaxTotalClaim {
+                    expected: 1_000,
+                    actual: 999,
+                },
+                TreeMismatch::UnderAllocated {
+                    total_claims: 1_000,
+                    claimable_lamports: 1_200,
+                },
+            ]
+        );
+
+        // the shares are rounded down, leaving up to a lamport per claimant unallocated
+        on_chain.merkle_root = Some(UploadedMerkleRoot {
+            root: expected.merkle_root,
+            max_total_claim: expected.max_total_claim,
+            max_num_nodes: expected.max_num_nodes,
+        });
+        on_chain.claimable_lamports = 1_003;
+        assert_eq!(verify_tree(&expected, Some(&on_chain), None), vec![]);
+        on_chain.claimable_lamports = 1_004;
+        assert_eq!(
+            verify_tree(&expected, Some(&on_chain), None),
+            vec![TreeMismatch::UnderAllocated {
+                total_claims: 1_000,
+                claimable_lamports: 1_004,
+            }]
+        );
+
+        on_chain.merkle_root = None;
+        on_chain.claimable_lamports = 900;
+        assert_eq!(
+            verify_tree(&expected, Some(&on_chain), None),
+            vec![
+                TreeMismatch::RootNotUploaded,
+                TreeMismatch::OverAllocated {
+                    total_claims: 1_000,
+                    claimable_lamports: 900,
+                },
+            ]
+        );
+
+        // the published tree pays one staker too much and leaves out the other
+        let unexpected = Pubkey::new_unique();
+        let published = merkle_tree(vec![
+            tree_node(validator, 100),
+            tree_node(staker_0, 500),
+            tree_node(unexpected, 400),
+        ]);
+        assert_eq!(
+            verify_tree(&expected, None, Some(&published)),
+            vec![
+                TreeMismatch::AccountNotFound,
+                TreeMismatch::ClaimantAmount {
+                    claimant: staker_0.to_string(),
+                    expected: 450,
+                    actual: Some(500),
+                },
+                TreeMismatch::ClaimantAmount {
+                    claimant: staker_1.to_string(),
+                    expected: 450,
+                    actual: None,
+                },
+                TreeMismatch::UnexpectedClaimant {
+                    claimant: unexpected.to_string(),
+                    amount: 400,
+                },
+            ]
+        );
+    }
+}
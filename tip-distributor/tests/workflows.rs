@@ -0,0 +1,414 @@
+//! Runs the upload, claim and reclaim workflows for a full epoch against a local bank with the
+//! tip distribution program loaded.
+use {
+    anchor_lang::AccountDeserialize,
+    async_trait::async_trait,
+    jito_tip_distribution::{
+        sdk::{
+            derive_config_account_address, derive_tip_distribution_account_address,
+            instruction::{
+                initialize_ix, initialize_tip_distribution_account_ix, InitializeAccounts,
+                InitializeArgs, InitializeTipDistributionAccountAccounts,
+                InitializeTipDistributionAccountArgs,
+            },
+        },
+        state::{ClaimStatus, TipDistributionAccount},
+    },
+    log::*,
+    solana_accounts_db::accounts_index::ScanConfig,
+    solana_program_test::programs::spl_programs,
+    solana_rpc_client_api::client_error::{ErrorKind, Result as ClientResult},
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, genesis_utils::create_genesis_config_with_leader_ex,
+    },
+    solana_sdk::{
+        account::Account,
+        clock::Epoch,
+        epoch_schedule::EpochSchedule,
+        fee_calculator::FeeRateGovernor,
+        genesis_config::ClusterType,
+        instruction::Instruction,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_instruction, system_program,
+        transaction::{SanitizedTransaction, Transaction, TransactionError},
+    },
+    solana_tip_distributor::{
+        claim_mev_workflow::claim_mev_tips_with_client, client::TipDistributorClient,
+        merkle_root_upload_workflow::upload_merkle_root_with_client,
+        reclaim_rent_workflow::reclaim_rent_with_client, tip_split_policy::ProRataPolicy,
+        Delegation, GeneratedMerkleTreeCollection, StakeMeta, StakeMetaCollection,
+        TipDistributionMeta,
+    },
+    std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+        time::Duration,
+    },
+};
+
+const SLOTS_PER_EPOCH: u64 = 32;
+const NUM_EPOCHS_VALID: u64 = 3;
+const VALIDATOR_COMMISSION_BPS: u16 = 1_000;
+const MAX_LOOP_DURATION: Duration = Duration::from_secs(10);
+
+/// A [TipDistributorClient] that processes transactions directly on a bank.
+struct LocalBankClient {
+    bank_forks: Arc<RwLock<BankForks>>,
+    bank: RwLock<Arc<Bank>>,
+}
+
+impl LocalBankClient {
+    fn bank(&self) -> Arc<Bank> {
+        self.bank.read().unwrap().clone()
+    }
+
+    fn process_instructions(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) {
+        let bank = self.bank();
+        let mut tx = Transaction::new_with_payer(instructions, Some(&payer.pubkey()));
+        tx.sign(signers, bank.last_blockhash());
+        bank.process_transaction(&tx).unwrap();
+    }
+
+    fn transfer(&self, from: &Keypair, to: &Pubkey, lamports: u64) {
+        self.process_instructions(
+            &[system_instruction::transfer(&from.pubkey(), to, lamports)],
+            from,
+            &[from],
+        );
+    }
+
+    fn advance_to_epoch(&self, epoch: Epoch) {
+        let parent = self.bank();
+        let slot = parent.epoch_schedule().get_first_slot_in_epoch(epoch);
+        let bank = Bank::new_from_parent(parent, &Pubkey::default(), slot);
+        let bank = self
+            .bank_forks
+            .write()
+            .unwrap()
+            .insert(bank)
+            .clone_without_scheduler();
+        *self.bank.write().unwrap() = bank;
+    }
+}
+
+#[async_trait]
+impl TipDistributorClient for LocalBankClient {
+    async fn get_multiple_accounts(
+        &self,
+        pubkeys: &[Pubkey],
+    ) -> ClientResult<HashMap<Pubkey, Option<Account>>> {
+        let bank = self.bank();
+        Ok(pubkeys
+            .iter()
+            .map(|pubkey| (*pubkey, bank.get_account(pubkey).map(Account::from)))
+            .collect())
+    }
+
+    async fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+    ) -> ClientResult<Vec<(Pubkey, Account)>> {
+        Ok(self
+            .bank()
+            .get_program_accounts(program_id, &ScanConfig::default())
+            .map_err(|e| ErrorKind::Custom(e.to_string()))?
+            .into_iter()
+            .map(|(pubkey, account)| (pubkey, Account::from(account)))
+            .collect())
+    }
+
+    async fn get_balance(&self, pubkey: &Pubkey) -> ClientResult<u64> {
+        Ok(self.bank().get_balance(pubkey))
+    }
+
+    async fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> ClientResult<u64> {
+        Ok(self.bank().get_minimum_balance_for_rent_exemption(data_len))
+    }
+
+    async fn get_epoch(&self) -> ClientResult<Epoch> {
+        Ok(self.bank().epoch())
+    }
+
+    async fn send_transactions(
+        &self,
+        transactions: Vec<Transaction>,
+        signer: &Arc<Keypair>,
+    ) -> ClientResult<()> {
+        let bank = self.bank();
+        for mut tx in transactions {
+            tx.sign(&[signer.as_ref()], bank.last_blockhash());
+            if let Err(e) = bank.process_transaction(&tx) {
+                warn!("failed to process {}: {e:?}", tx.signatures[0]);
+            }
+        }
+        Ok(())
+    }
+
+    async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> ClientResult<Option<TransactionError>> {
+        let bank = self.bank();
+        let mut transaction = transaction.clone();
+        transaction.message.recent_blockhash = bank.last_blockhash();
+        let transaction = SanitizedTransaction::from_transaction_for_tests(transaction);
+        Ok(bank
+            .simulate_transaction_unchecked(&transaction, false)
+            .result
+            .err())
+    }
+}
+
+struct TestFixture {
+    client: LocalBankClient,
+    mint_keypair: Keypair,
+    node_keypair: Keypair,
+    vote_account: Pubkey,
+    expired_funds_account: Pubkey,
+}
+
+fn create_test_fixture() -> TestFixture {
+    let mint_keypair = Keypair::new();
+    let node_keypair = Keypair::new();
+    let vote_keypair = Keypair::new();
+    let rent = Rent::default();
+
+    let mut genesis_config = create_genesis_config_with_leader_ex(
+        1_000_000 * LAMPORTS_PER_SOL,
+        &mint_keypair.pubkey(),
+        &node_keypair.pubkey(),
+        &vote_keypair.pubkey(),
+        &Pubkey::new_unique(),
+        1_000 * LAMPORTS_PER_SOL,
+        1_000 * LAMPORTS_PER_SOL,
+        FeeRateGovernor::default(),
+        rent.clone(),
+        ClusterType::Development,
+        spl_programs(&rent),
+    );
+    genesis_config.epoch_schedule = EpochSchedule::custom(SLOTS_PER_EPOCH, SLOTS_PER_EPOCH, false);
+
+    // the programs deployed in genesis can only be used starting with the next slot
+    let (bank, bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+    let bank = bank_forks
+        .write()
+        .unwrap()
+        .insert(Bank::new_from_parent(bank, &Pubkey::default(), 1))
+        .clone_without_scheduler();
+
+    let fixture = TestFixture {
+        client: LocalBankClient {
+            bank_forks,
+            bank: RwLock::new(bank),
+        },
+        mint_keypair,
+        node_keypair,
+        vote_account: vote_keypair.pubkey(),
+        expired_funds_account: Pubkey::new_unique(),
+    };
+
+    let program_id = jito_tip_distribution::id();
+    let (config, bump) = derive_config_account_address(&program_id);
+    fixture.client.process_instructions(
+        &[initialize_ix(
+            program_id,
+            InitializeArgs {
+                authority: fixture.mint_keypair.pubkey(),
+                expired_funds_account: fixture.expired_funds_account,
+                num_epochs_valid: NUM_EPOCHS_VALID,
+                max_validator_commission_bps: 10_000,
+                bump,
+            },
+            InitializeAccounts {
+                config,
+                system_program: system_program::id(),
+                initializer: fixture.mint_keypair.pubkey(),
+            },
+        )],
+        &fixture.mint_keypair,
+        &[&fixture.mint_keypair],
+    );
+    // keeps the account rent-exempt when it receives the rounding dust left in closed accounts
+    fixture.client.transfer(
+        &fixture.mint_keypair,
+        &fixture.expired_funds_account,
+        LAMPORTS_PER_SOL,
+    );
+    fixture
+}
+
+fn funded_keypair(fixture: &TestFixture, lamports: u64) -> Arc<Keypair> {
+    let keypair = Keypair::new();
+    fixture
+        .client
+        .transfer(&fixture.mint_keypair, &keypair.pubkey(), lamports);
+    Arc::new(keypair)
+}
+
+fn tip_distribution_account(client: &LocalBankClient, pubkey: &Pubkey) -> TipDistributionAccount {
+    let account = client.bank().get_account(pubkey).unwrap();
+    TipDistributionAccount::try_deserialize(&mut Account::from(account).data.as_slice()).unwrap()
+}
+
+#[tokio::test]
+async fn test_tip_distribution_epoch() {
+    solana_logger::setup();
+    let fixture = create_test_fixture();
+    let client = &fixture.client;
+    let program_id = jito_tip_distribution::id();
+    let config = derive_config_account_address(&program_id).0;
+
+    // the validator creates its tip distribution account for the epoch and receives tips
+    let uploader = funded_keypair(&fixture, LAMPORTS_PER_SOL);
+    let epoch = client.bank().epoch();
+    let (tda, bump) =
+        derive_tip_distribution_account_address(&program_id, &fixture.vote_account, epoch);
+    client.process_instructions(
+        &[initialize_tip_distribution_account_ix(
+            program_id,
+            InitializeTipDistributionAccountArgs {
+                merkle_root_upload_authority: uploader.pubkey(),
+                validator_commission_bps: VALIDATOR_COMMISSION_BPS,
+                bump,
+            },
+            InitializeTipDistributionAccountAccounts {
+                config,
+                tip_distribution_account: tda,
+                system_program: system_program::id(),
+                signer: fixture.node_keypair.pubkey(),
+                validator_vote_account: fixture.vote_account,
+            },
+        )],
+        &fixture.node_keypair,
+        &[&fixture.node_keypair],
+    );
+    client.transfer(&fixture.mint_keypair, &tda, 10 * LAMPORTS_PER_SOL);
+
+    // stake accounts only need to exist to be claimed for
+    let delegations: Vec<Delegation> = [100, 300, 600]
+        .into_iter()
+        .map(|sol_delegated| {
+            let stake_account = Pubkey::new_unique();
+            client.transfer(&fixture.mint_keypair, &stake_account, LAMPORTS_PER_SOL);
+            Delegation {
+                stake_account_pubkey: stake_account,
+                staker_pubkey: Pubkey::new_unique(),
+                withdrawer_pubkey: Pubkey::new_unique(),
+                lamports_delegated: sol_delegated * LAMPORTS_PER_SOL,
+            }
+        })
+        .collect();
+
+    let bank = client.bank();
+    let tda_account = bank.get_account(&tda).unwrap();
+    let total_tips = tda_account.lamports()
+        - bank.get_minimum_balance_for_rent_exemption(tda_account.data().len());
+    let merkle_trees = GeneratedMerkleTreeCollection::new_from_stake_meta_collection(
+        StakeMetaCollection {
+            stake_metas: vec![StakeMeta {
+                validator_vote_account: fixture.vote_account,
+                validator_node_pubkey: fixture.node_keypair.pubkey(),
+                maybe_tip_distribution_meta: Some(TipDistributionMeta {
+                    merkle_root_upload_authority: uploader.pubkey(),
+                    tip_distribution_pubkey: tda,
+                    total_tips,
+                    validator_fee_bps: VALIDATOR_COMMISSION_BPS,
+                }),
+                total_delegated: delegations.iter().map(|d| d.lamports_delegated).sum(),
+                delegations,
+                commission: 10,
+            }],
+            tip_distribution_program_id: program_id,
+            bank_hash: bank.hash().to_string(),
+            epoch,
+            slot: bank.slot(),
+        },
+        &ProRataPolicy,
+    )
+    .unwrap();
+    let tree = &merkle_trees.generated_merkle_trees[0];
+
+    // roots can only be uploaded once the epoch is over
+    client.advance_to_epoch(epoch + 1);
+    upload_merkle_root_with_client(
+        client,
+        &merkle_trees,
+        &program_id,
+        uploader.clone(),
+        MAX_LOOP_DURATION,
+    )
+    .await
+    .unwrap();
+    let merkle_root = tip_distribution_account(client, &tda).merkle_root.unwrap();
+    assert_eq!(merkle_root.root, tree.merkle_root.to_bytes());
+    assert_eq!(merkle_root.max_total_claim, tree.max_total_claim);
+    assert_eq!(merkle_root.max_num_nodes, tree.max_num_nodes);
+
+    let claimer = funded_keypair(&fixture, 10 * LAMPORTS_PER_SOL);
+    let balances_before: Vec<u64> = tree
+        .tree_nodes
+        .iter()
+        .map(|node| client.bank().get_balance(&node.claimant))
+        .collect();
+    claim_mev_tips_with_client(
+        client,
+        &merkle_trees,
+        program_id,
+        claimer.clone(),
+        MAX_LOOP_DURATION,
+        1,
+    )
+    .await
+    .unwrap();
+    let bank = client.bank();
+    for (node, balance_before) in tree.tree_nodes.iter().zip(balances_before) {
+        assert_eq!(
+            bank.get_balance(&node.claimant),
+            balance_before + node.amount
+        );
+        let claim_status = bank.get_account(&node.claim_status_pubkey).unwrap();
+        let claim_status =
+            ClaimStatus::try_deserialize(&mut Account::from(claim_status).data.as_slice()).unwrap();
+        assert_eq!(claim_status.claim_status_payer, claimer.pubkey());
+    }
+    let merkle_root = tip_distribution_account(client, &tda).merkle_root.unwrap();
+    assert_eq!(merkle_root.num_nodes_claimed, tree.max_num_nodes);
+
+    // once expired, the claim statuses and tip distribution account can be closed
+    client.advance_to_epoch(epoch + NUM_EPOCHS_VALID + 1);
+    let claimer_balance_before = client.bank().get_balance(&claimer.pubkey());
+    let expired_funds_balance_before = client.bank().get_balance(&fixture.expired_funds_account);
+    let unclaimed_lamports = client.bank().get_balance(&tda)
+        - client
+            .bank()
+            .get_minimum_balance_for_rent_exemption(TipDistributionAccount::SIZE);
+    reclaim_rent_with_client(
+        client,
+        program_id,
+        claimer.clone(),
+        MAX_LOOP_DURATION,
+        true,
+        1,
+    )
+    .await
+    .unwrap();
+    let bank = client.bank();
+    assert!(tree
+        .tree_nodes
+        .iter()
+        .all(|node| bank.get_account(&node.claim_status_pubkey).is_none()));
+    assert!(bank.get_account(&tda).is_none());
+    assert!(bank.get_balance(&claimer.pubkey()) > claimer_balance_before);
+    assert_eq!(
+        bank.get_balance(&fixture.expired_funds_account),
+        expired_funds_balance_before + unclaimed_lamports
+    );
+}
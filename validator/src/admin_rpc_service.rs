@@ -15,9 +15,11 @@ use {
         consensus::{tower_storage::TowerStorage, Tower},
         proxy::{
             block_engine_stage::{BlockEngineConfig, BlockEngineStage},
+            relayer_packet_qos::RelayerPacketQosConfig,
             relayer_stage::{RelayerConfig, RelayerStage},
         },
         repair::repair_service,
+        tip_manager::TipDistributionAccountConfigStore,
         validator::ValidatorStartProgress,
     },
     solana_geyser_plugin_manager::GeyserPluginManagerRequest,
@@ -25,6 +27,7 @@ use {
     solana_rpc::rpc::verify_pubkey,
     solana_rpc_client_api::{config::RpcAccountIndex, custom_error::RpcCustomError},
     solana_sdk::{
+        clock::Epoch,
         exit::Exit,
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
@@ -94,6 +97,52 @@ pub struct AdminRpcRepairWhitelist {
     pub whitelist: Vec<Pubkey>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcTipDistributionAccountConfig {
+    pub vote_account: String,
+    pub merkle_root_upload_authority: String,
+    pub commission_bps: u16,
+    /// Epoch of the next tip distribution account, which will be initialized with the config above
+    pub effective_epoch: Epoch,
+    /// Config of the last tip distribution account initialized, if any
+    pub applied: Option<AdminRpcAppliedTipDistributionAccountConfig>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AdminRpcAppliedTipDistributionAccountConfig {
+    pub epoch: Epoch,
+    pub merkle_root_upload_authority: String,
+    pub commission_bps: u16,
+}
+
+impl AdminRpcTipDistributionAccountConfig {
+    fn new(config_store: &TipDistributionAccountConfigStore, current_epoch: Epoch) -> Self {
+        let config = config_store.config();
+        let applied = config_store.applied();
+        // tip distribution accounts are initialized once per epoch, so changes made after this
+        // epoch's account was initialized won't apply until the next one
+        let effective_epoch = match &applied {
+            Some((applied_epoch, _)) if *applied_epoch >= current_epoch => {
+                applied_epoch.saturating_add(1)
+            }
+            _ => current_epoch,
+        };
+        Self {
+            vote_account: config.vote_account.to_string(),
+            merkle_root_upload_authority: config.merkle_root_upload_authority.to_string(),
+            commission_bps: config.commission_bps,
+            effective_epoch,
+            applied: applied.map(
+                |(epoch, config)| AdminRpcAppliedTipDistributionAccountConfig {
+                    epoch,
+                    merkle_root_upload_authority: config.merkle_root_upload_authority.to_string(),
+                    commission_bps: config.commission_bps,
+                },
+            ),
+        }
+    }
+}
+
 impl From<ContactInfo> for AdminRpcContactInfo {
     fn from(node: ContactInfo) -> Self {
         macro_rules! unwrap_socket {
@@ -145,6 +194,31 @@ impl Display for AdminRpcRepairWhitelist {
     }
 }
 
+impl Display for AdminRpcTipDistributionAccountConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Vote Account: {}", self.vote_account)?;
+        writeln!(
+            f,
+            "Merkle Root Upload Authority: {}",
+            self.merkle_root_upload_authority
+        )?;
+        writeln!(f, "Commission: {} bps", self.commission_bps)?;
+        writeln!(f, "Effective Epoch: {}", self.effective_epoch)?;
+        match &self.applied {
+            Some(applied) => {
+                writeln!(f, "Applied Epoch: {}", applied.epoch)?;
+                writeln!(
+                    f,
+                    "Applied Merkle Root Upload Authority: {}",
+                    applied.merkle_root_upload_authority
+                )?;
+                writeln!(f, "Applied Commission: {} bps", applied.commission_bps)
+            }
+            None => writeln!(f, "Applied Epoch: none"),
+        }
+    }
+}
+
 #[rpc]
 pub trait AdminRpc {
     type Metadata;
@@ -253,8 +327,9 @@ pub trait AdminRpc {
     fn set_block_engine_config(
         &self,
         meta: Self::Metadata,
-        block_engine_url: String,
+        block_engine_urls: Vec<String>,
         trust_packets: bool,
+        multi_stream: bool,
     ) -> Result<()>;
 
     #[rpc(meta, name = "setRelayerConfig")]
@@ -269,6 +344,20 @@ pub trait AdminRpc {
 
     #[rpc(meta, name = "setShredReceiverAddress")]
     fn set_shred_receiver_address(&self, meta: Self::Metadata, addr: String) -> Result<()>;
+
+    #[rpc(meta, name = "setTipDistributionAccountConfig")]
+    fn set_tip_distribution_account_config(
+        &self,
+        meta: Self::Metadata,
+        commission_bps: Option<u16>,
+        merkle_root_upload_authority: Option<String>,
+    ) -> Result<()>;
+
+    #[rpc(meta, name = "tipDistributionAccountConfig")]
+    fn tip_distribution_account_config(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<AdminRpcTipDistributionAccountConfig>;
 }
 
 pub struct AdminRpcImpl;
@@ -470,13 +559,15 @@ impl AdminRpc for AdminRpcImpl {
     fn set_block_engine_config(
         &self,
         meta: Self::Metadata,
-        block_engine_url: String,
+        block_engine_urls: Vec<String>,
         trust_packets: bool,
+        multi_stream: bool,
     ) -> Result<()> {
         debug!("set_block_engine_config request received");
         let config = BlockEngineConfig {
-            block_engine_url,
+            block_engine_urls,
             trust_packets,
+            multi_stream,
         };
         // Detailed log messages are printed inside validate function
         if BlockEngineStage::is_valid_block_engine_config(&config) {
@@ -537,16 +628,29 @@ impl AdminRpc for AdminRpcImpl {
         let expected_heartbeat_interval = Duration::from_millis(expected_heartbeat_interval_ms);
         let oldest_allowed_heartbeat =
             Duration::from_millis(max_failed_heartbeats * expected_heartbeat_interval_ms);
+        // Packet QoS limits are only configurable at startup, so keep the current ones.
         let config = RelayerConfig {
             relayer_url,
             expected_heartbeat_interval,
             oldest_allowed_heartbeat,
             trust_packets,
+            packet_qos: RelayerPacketQosConfig::default(),
         };
         // Detailed log messages are printed inside validate function
         if RelayerStage::is_valid_relayer_config(&config) {
             meta.with_post_init(|post_init| {
-                *post_init.relayer_config.lock().unwrap() = config;
+                // untrusted packets would be sent back through sigverify and into the embedded
+                // relayer again
+                if post_init.embedded_relayer && !config.trust_packets {
+                    return Err(jsonrpc_core::error::Error::invalid_params(
+                        "relayer packets must be trusted while the embedded relayer is enabled",
+                    ));
+                }
+                let mut relayer_config = post_init.relayer_config.lock().unwrap();
+                *relayer_config = RelayerConfig {
+                    packet_qos: relayer_config.packet_qos,
+                    ..config
+                };
                 Ok(())
             })
         } else {
@@ -574,6 +678,45 @@ impl AdminRpc for AdminRpcImpl {
         })
     }
 
+    fn set_tip_distribution_account_config(
+        &self,
+        meta: Self::Metadata,
+        commission_bps: Option<u16>,
+        merkle_root_upload_authority: Option<String>,
+    ) -> Result<()> {
+        debug!("set_tip_distribution_account_config request received");
+        if commission_bps.is_none() && merkle_root_upload_authority.is_none() {
+            return Err(jsonrpc_core::error::Error::invalid_params(
+                "at least one of commission_bps or merkle_root_upload_authority must be provided",
+            ));
+        }
+        let merkle_root_upload_authority = merkle_root_upload_authority
+            .map(|authority| verify_pubkey(&authority))
+            .transpose()?;
+
+        meta.with_post_init(|post_init| {
+            post_init
+                .tip_distribution_account_config
+                .update(commission_bps, merkle_root_upload_authority)
+                .map(|_| ())
+                .map_err(|err| jsonrpc_core::error::Error::invalid_params(err.to_string()))
+        })
+    }
+
+    fn tip_distribution_account_config(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<AdminRpcTipDistributionAccountConfig> {
+        debug!("tip_distribution_account_config request received");
+        meta.with_post_init(|post_init| {
+            let current_epoch = post_init.bank_forks.read().unwrap().working_bank().epoch();
+            Ok(AdminRpcTipDistributionAccountConfig::new(
+                &post_init.tip_distribution_account_config,
+                current_epoch,
+            ))
+        })
+    }
+
     fn set_staked_nodes_overrides(&self, meta: Self::Metadata, path: String) -> Result<()> {
         let loaded_config = load_staked_nodes_overrides(&path)
             .map_err(|err| {
@@ -956,7 +1099,9 @@ mod tests {
         super::*,
         serde_json::Value,
         solana_accounts_db::{accounts_index::AccountSecondaryIndexes, inline_spl_token},
-        solana_core::consensus::tower_storage::NullTowerStorage,
+        solana_core::{
+            consensus::tower_storage::NullTowerStorage, tip_manager::TipDistributionAccountConfig,
+        },
         solana_gossip::cluster_info::ClusterInfo,
         solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
         solana_rpc::rpc::create_validator_exit,
@@ -992,7 +1137,7 @@ mod tests {
     }
 
     impl RpcHandler {
-        fn _start() -> Self {
+        fn start() -> Self {
             Self::start_with_config(TestConfig::default())
         }
 
@@ -1018,6 +1163,14 @@ mod tests {
             let block_engine_config = Arc::new(Mutex::new(BlockEngineConfig::default()));
             let relayer_config = Arc::new(Mutex::new(RelayerConfig::default()));
             let shred_receiver_address = Arc::new(RwLock::new(None));
+            let tip_distribution_account_config = TipDistributionAccountConfigStore::new(
+                TipDistributionAccountConfig {
+                    merkle_root_upload_authority: Pubkey::new_unique(),
+                    vote_account,
+                    commission_bps: 800,
+                },
+                None,
+            );
             let meta = AdminRpcRequestMetadata {
                 rpc_addr: None,
                 start_time: SystemTime::now(),
@@ -1040,7 +1193,9 @@ mod tests {
                     ),
                     block_engine_config,
                     relayer_config,
+                    embedded_relayer: false,
                     shred_receiver_address,
+                    tip_distribution_account_config,
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
                 rpc_to_plugin_manager_sender: None,
@@ -1073,6 +1228,99 @@ mod tests {
         (BankForks::new_rw_arc(bank), Arc::new(voting_keypair))
     }
 
+    #[test]
+    fn test_set_tip_distribution_account_config() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start();
+
+        let get_config = || {
+            let req =
+                r#"{"jsonrpc":"2.0","id":1,"method":"tipDistributionAccountConfig","params":[]}"#;
+            let res = io.handle_request_sync(req, meta.clone());
+            let result: Value = serde_json::from_str(&res.expect("actual response"))
+                .expect("actual response deserialization");
+            serde_json::from_value::<AdminRpcTipDistributionAccountConfig>(result["result"].clone())
+                .unwrap()
+        };
+
+        let config = get_config();
+        assert_eq!(config.commission_bps, 800);
+        assert_eq!(config.effective_epoch, 0);
+        assert!(config.applied.is_none());
+
+        let new_authority = Pubkey::new_unique();
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"setTipDistributionAccountConfig","params":[500, "{new_authority}"]}}"#,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"], Value::Null);
+
+        let config = get_config();
+        assert_eq!(config.commission_bps, 500);
+        assert_eq!(
+            config.merkle_root_upload_authority,
+            new_authority.to_string()
+        );
+
+        // only the commission
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"setTipDistributionAccountConfig","params":[1000]}"#;
+        io.handle_request_sync(req, meta.clone());
+        let config = get_config();
+        assert_eq!(config.commission_bps, 1_000);
+        assert_eq!(
+            config.merkle_root_upload_authority,
+            new_authority.to_string()
+        );
+
+        // the commission can't exceed 100%
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"setTipDistributionAccountConfig","params":[10001]}"#;
+        let res = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert!(result["error"].is_object());
+        assert_eq!(get_config().commission_bps, 1_000);
+    }
+
+    #[test]
+    fn test_set_relayer_config_with_embedded_relayer() {
+        let RpcHandler { io, meta, .. } = RpcHandler::start();
+        meta.post_init
+            .write()
+            .unwrap()
+            .as_mut()
+            .unwrap()
+            .embedded_relayer = true;
+        let relayer_config = meta
+            .post_init
+            .read()
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .relayer_config
+            .clone();
+
+        let set_relayer_config = |trust_packets: bool| {
+            let req = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"setRelayerConfig","params":["http://127.0.0.1:11226", {trust_packets}, 500, 3]}}"#,
+            );
+            let res = io.handle_request_sync(&req, meta.clone());
+            serde_json::from_str::<Value>(&res.expect("actual response"))
+                .expect("actual response deserialization")
+        };
+
+        // untrusted packets would loop back into the embedded relayer
+        let result = set_relayer_config(false);
+        assert!(result["error"].is_object());
+        assert!(relayer_config.lock().unwrap().relayer_url.is_empty());
+
+        let result = set_relayer_config(true);
+        assert_eq!(result["result"], Value::Null);
+        let relayer_config = relayer_config.lock().unwrap();
+        assert_eq!(relayer_config.relayer_url, "http://127.0.0.1:11226");
+        assert!(relayer_config.trust_packets);
+    }
+
     #[test]
     fn test_secondary_index_key_sizes() {
         for secondary_index_enabled in [true, false] {
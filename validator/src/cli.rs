@@ -21,12 +21,20 @@ use {
     },
     solana_core::{
         banking_trace::{DirByteLimit, BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT},
+        bundle_stage::{
+            bundle_auction::BundleOrderingPolicy,
+            bundle_reserved_space_manager::BundleReservedSpacePolicy,
+        },
+        proxy::relayer_packet_qos::RelayerPacketQosConfig,
         validator::{BlockProductionMethod, BlockVerificationMethod},
     },
     solana_faucet::faucet::{self, FAUCET_PORT},
     solana_ledger::use_snapshot_archives_at_startup,
     solana_net_utils::{MINIMUM_VALIDATOR_PORT_RANGE_WIDTH, VALIDATOR_PORT_RANGE},
-    solana_rpc::{rpc::MAX_REQUEST_BODY_SIZE, rpc_pubsub_service::PubSubConfig},
+    solana_rpc::{
+        rpc::MAX_REQUEST_BODY_SIZE, rpc_pubsub_service::PubSubConfig,
+        rpc_request_limiter::parse_method_weight,
+    },
     solana_rpc_client_api::request::MAX_MULTIPLE_ACCOUNTS,
     solana_runtime::{
         snapshot_bank_utils::{
@@ -48,7 +56,7 @@ use {
     },
     solana_tpu_client::tpu_client::DEFAULT_TPU_CONNECTION_POOL_SIZE,
     solana_unified_scheduler_pool::DefaultSchedulerPool,
-    std::{path::PathBuf, str::FromStr},
+    std::{net::IpAddr, path::PathBuf, str::FromStr},
 };
 
 const EXCLUDE_KEY: &str = "account-index-exclude-key";
@@ -79,8 +87,29 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
         .arg(
             Arg::with_name("block_engine_url")
                 .long("block-engine-url")
-                .help("Block engine url.  Set to empty string to disable block engine connection.")
+                .help("Block engine url. May be specified multiple times, in order of preference. \
+                       Set to empty string to disable block engine connection.")
                 .takes_value(true)
+                .multiple(true)
+        )
+        .arg(
+            Arg::with_name("block_engine_multi_stream")
+                .long("block-engine-multi-stream")
+                .takes_value(false)
+                .requires("block_engine_url")
+                .help("Stream bundles from all block engines at once instead of failing over between them. \
+                       Bundles received from more than one block engine are de-duplicated by id.")
+        )
+        .arg(
+            Arg::with_name("embedded_relayer_address")
+                .long("embedded-relayer-address")
+                .value_name("HOST:PORT")
+                .takes_value(true)
+                .validator(solana_net_utils::is_host_port)
+                .conflicts_with_all(&["relayer_url", "block_engine_url"])
+                .help("Serve the relayer and block engine gRPC services from this validator on this address \
+                       and connect to them. Packets received on the TPU are streamed through the relayer \
+                       service and bundles submitted with sendBundle through the block engine service.")
         )
         .arg(
             Arg::with_name("relayer_url")
@@ -108,6 +137,35 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .help("Maximum number of heartbeats the Relayer can miss before falling back to the normal TPU pipeline.")
                 .default_value(DEFAULT_RELAYER_MAX_FAILED_HEARTBEATS)
         )
+        .arg(
+            Arg::with_name("relayer_staked_packets_per_second")
+                .long("relayer-staked-packets-per-second")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .help("Packets per second accepted from all staked senders behind the Relayer, \
+                       shared in proportion to each sender's stake. Staked senders are never held \
+                       to less than --relayer-unstaked-packets-per-second-per-ip, so this only \
+                       applies when that is set. 0 is unlimited.")
+                .default_value(&default_args.relayer_staked_packets_per_second)
+        )
+        .arg(
+            Arg::with_name("relayer_unstaked_packets_per_second_per_ip")
+                .long("relayer-unstaked-packets-per-second-per-ip")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .help("Packets per second accepted from each unstaked source IP behind the Relayer. \
+                       0 is unlimited.")
+                .default_value(&default_args.relayer_unstaked_packets_per_second_per_ip)
+        )
+        .arg(
+            Arg::with_name("relayer_unstaked_packets_per_second")
+                .long("relayer-unstaked-packets-per-second")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .help("Packets per second accepted from all unstaked senders behind the Relayer \
+                       combined. 0 is unlimited.")
+                .default_value(&default_args.relayer_unstaked_packets_per_second)
+        )
         .arg(
             Arg::with_name("trust_block_engine_packets")
                 .long("trust-block-engine-packets")
@@ -150,6 +208,22 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(DEFAULT_PREALLOCATED_BUNDLE_COST)
                 .help("Number of CUs to allocate for bundles at beginning of slot.")
         )
+        .arg(
+            Arg::with_name("bundle_ordering_policy")
+                .long("bundle-ordering-policy")
+                .value_name("POLICY")
+                .takes_value(true)
+                .possible_values(BundleOrderingPolicy::cli_names())
+                .help(BundleOrderingPolicy::cli_message()),
+        )
+        .arg(
+            Arg::with_name("bundle_reserved_space_policy")
+                .long("bundle-reserved-space-policy")
+                .value_name("POLICY")
+                .takes_value(true)
+                .possible_values(BundleReservedSpacePolicy::cli_names())
+                .help(BundleReservedSpacePolicy::cli_message()),
+        )
         .arg(
             Arg::with_name("shred_receiver_address")
                 .long("shred-receiver-address")
@@ -1071,6 +1145,41 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `voteSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_bundle_status_subscription")
+                .long("rpc-pubsub-enable-bundle-status-subscription")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `bundleStatusSubscribe` subscription"),
+        )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_transaction_subscription")
+                .long("rpc-pubsub-enable-transaction-subscription")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `transactionSubscribe` subscription"),
+        )
+        .arg(
+            Arg::with_name("rpc_enable_send_bundle")
+                .long("rpc-enable-send-bundle")
+                .takes_value(false)
+                .requires("full_rpc_api")
+                .requires("rpc_send_bundle_allowed_signer")
+                .help(
+                    "Enable the `sendBundle` RPC method, which sends bundles directly to this \
+                     validator's bundle stage",
+                ),
+        )
+        .arg(
+            Arg::with_name("rpc_send_bundle_allowed_signer")
+                .long("rpc-send-bundle-allowed-signer")
+                .validator(is_pubkey)
+                .value_name("PUBKEY")
+                .multiple(true)
+                .takes_value(true)
+                .help(
+                    "Bundles submitted with `sendBundle` must contain a transaction signed by \
+                     this pubkey. May be specified multiple times",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_pubsub_max_connections")
                 .long("rpc-pubsub-max-connections")
@@ -1241,6 +1350,16 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_send_transaction_retry_pool_max_size)
                 .help("The maximum size of transactions retry pool."),
         )
+        .arg(
+            Arg::with_name("rpc_send_transaction_retry_journal")
+                .long("rpc-send-transaction-retry-journal")
+                .value_name("FILE")
+                .takes_value(true)
+                .help(
+                    "Journal the transactions retry pool to this file, so that transactions \
+                     still being retried are retried again after a restart",
+                ),
+        )
         .arg(
             Arg::with_name("rpc_scan_and_fix_roots")
                 .long("rpc-scan-and-fix-roots")
@@ -1257,6 +1376,64 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .default_value(&default_args.rpc_max_request_body_size)
                 .help("The maximum request body size accepted by rpc service"),
         )
+        .arg(
+            Arg::with_name("rpc_max_batch_size")
+                .long("rpc-max-batch-size")
+                .value_name("NUMBER")
+                .takes_value(true)
+                .validator(|s| is_within_range(s, 1..))
+                .help(
+                    "The maximum number of requests in a JSON RPC batch [default: unlimited]",
+                ),
+        )
+        .arg(
+            Arg::with_name("rpc_method_weight")
+                .long("rpc-method-weight")
+                .value_name("METHOD=WEIGHT")
+                .takes_value(true)
+                .multiple(true)
+                .validator(|s| parse_method_weight(&s).map(|_| ()))
+                .help(
+                    "Number of tokens a call to METHOD takes from the client's token bucket. \
+                     Methods without a weight cost 1 token. May be specified multiple times",
+                ),
+        )
+        .arg(
+            Arg::with_name("rpc_client_token_bucket_capacity")
+                .long("rpc-client-token-bucket-capacity")
+                .value_name("TOKENS")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .requires("rpc_client_token_bucket_refill_rate")
+                .help(
+                    "Rate limit JSON RPC clients with a token bucket holding at most this many \
+                     tokens. Clients are identified by their IP address, see \
+                     --rpc-trusted-proxy [default: unlimited]",
+                ),
+        )
+        .arg(
+            Arg::with_name("rpc_client_token_bucket_refill_rate")
+                .long("rpc-client-token-bucket-refill-rate")
+                .value_name("TOKENS_PER_SECOND")
+                .takes_value(true)
+                .validator(is_parsable::<u64>)
+                .requires("rpc_client_token_bucket_capacity")
+                .help("Number of tokens added to each JSON RPC client's bucket every second"),
+        )
+        .arg(
+            Arg::with_name("rpc_trusted_proxy")
+                .long("rpc-trusted-proxy")
+                .value_name("IP_ADDRESS")
+                .takes_value(true)
+                .multiple(true)
+                .validator(is_parsable::<IpAddr>)
+                .help(
+                    "Address of a reverse proxy in front of the JSON RPC service. Requests it \
+                     relays are attributed to the client in its X-Forwarded-For or X-Real-IP \
+                     header, which is ignored on requests from any other address. \
+                     May be specified multiple times",
+                ),
+        )
         .arg(
             Arg::with_name("enable_accountsdb_repl")
                 .long("enable-accountsdb-repl")
@@ -1671,10 +1848,18 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                 .arg(
                     Arg::with_name("block_engine_url")
                         .long("block-engine-url")
-                        .help("Block engine url.  Set to empty string to disable block engine connection.")
+                        .help("Block engine url. May be specified multiple times, in order of preference. \
+                               Set to empty string to disable block engine connection.")
                         .takes_value(true)
+                        .multiple(true)
                         .required(true)
                 )
+                .arg(
+                    Arg::with_name("block_engine_multi_stream")
+                        .long("block-engine-multi-stream")
+                        .takes_value(false)
+                        .help("Stream bundles from all block engines at once instead of failing over between them.")
+                )
                 .arg(
                     Arg::with_name("trust_block_engine_packets")
                         .long("trust-block-engine-packets")
@@ -1727,6 +1912,48 @@ pub fn app<'a>(version: &'a str, default_args: &'a DefaultArgs) -> App<'a, 'a> {
                         .required(true)
                 )
         )
+        .subcommand(
+            SubCommand::with_name("set-tip-distribution-config")
+                .about("Change the commission and/or merkle-root upload authority used for \
+                        future tip distribution accounts")
+                .arg(
+                    Arg::with_name("commission_bps")
+                        .long("commission-bps")
+                        .value_name("COMMISSION_BPS")
+                        .takes_value(true)
+                        .validator(is_parsable::<u16>)
+                        .help("The commission validator takes from tips expressed in basis points.")
+                )
+                .arg(
+                    Arg::with_name("merkle_root_upload_authority")
+                        .long("merkle-root-upload-authority")
+                        .value_name("MERKLE_ROOT_UPLOAD_AUTHORITY")
+                        .takes_value(true)
+                        .validator(is_pubkey)
+                        .help("The public key of the authorized merkle-root uploader.")
+                )
+                .group(
+                    ArgGroup::with_name("tip_distribution_config")
+                        .args(&["commission_bps", "merkle_root_upload_authority"])
+                        .multiple(true)
+                        .required(true)
+                )
+                .after_help("Tip distribution accounts are initialized once per epoch, so changes \
+                             apply from the first epoch whose account hasn't been initialized yet. \
+                             Changes are persisted in the ledger directory.")
+        )
+        .subcommand(
+            SubCommand::with_name("tip-distribution-config")
+                .about("Display the config used for this validator's tip distribution accounts")
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .takes_value(true)
+                        .value_name("MODE")
+                        .possible_values(&["json", "json-compact"])
+                        .help("Output display mode"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("exit")
                 .about("Send an exit request to the validator")
@@ -2383,11 +2610,16 @@ pub struct DefaultArgs {
     pub banking_trace_dir_byte_limit: String,
 
     pub wen_restart_path: String,
+
+    pub relayer_staked_packets_per_second: String,
+    pub relayer_unstaked_packets_per_second_per_ip: String,
+    pub relayer_unstaked_packets_per_second: String,
 }
 
 impl DefaultArgs {
     pub fn new() -> Self {
         let default_send_transaction_service_config = send_transaction_service::Config::default();
+        let default_relayer_packet_qos_config = RelayerPacketQosConfig::default();
 
         DefaultArgs {
             bind_address: "0.0.0.0".to_string(),
@@ -2465,6 +2697,15 @@ impl DefaultArgs {
             wait_for_restart_window_max_delinquent_stake: "5".to_string(),
             banking_trace_dir_byte_limit: BANKING_TRACE_DIR_DEFAULT_BYTE_LIMIT.to_string(),
             wen_restart_path: "wen_restart_progress.proto".to_string(),
+            relayer_staked_packets_per_second: default_relayer_packet_qos_config
+                .staked_packets_per_second
+                .to_string(),
+            relayer_unstaked_packets_per_second_per_ip: default_relayer_packet_qos_config
+                .unstaked_packets_per_second_per_ip
+                .to_string(),
+            relayer_unstaked_packets_per_second: default_relayer_packet_qos_config
+                .unstaked_packets_per_second
+                .to_string(),
         }
     }
 }
@@ -2650,6 +2891,12 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                 .takes_value(false)
                 .help("Enable the unstable RPC PubSub `blockSubscribe` subscription"),
         )
+        .arg(
+            Arg::with_name("rpc_pubsub_enable_transaction_subscription")
+                .long("rpc-pubsub-enable-transaction-subscription")
+                .takes_value(false)
+                .help("Enable the unstable RPC PubSub `transactionSubscribe` subscription"),
+        )
         .arg(
             Arg::with_name("bpf_program")
                 .long("bpf-program")
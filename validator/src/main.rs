@@ -21,8 +21,15 @@ use {
     solana_clap_utils::input_parsers::{keypair_of, keypairs_of, pubkey_of, value_of},
     solana_core::{
         banking_trace::DISABLED_BAKING_TRACE_DIR,
+        bundle_stage::{
+            bundle_auction::BundleOrderingPolicy,
+            bundle_reserved_space_manager::BundleReservedSpacePolicy,
+        },
         consensus::tower_storage,
-        proxy::{block_engine_stage::BlockEngineConfig, relayer_stage::RelayerConfig},
+        proxy::{
+            block_engine_stage::BlockEngineConfig, embedded_relayer::EmbeddedRelayerConfig,
+            relayer_packet_qos::RelayerPacketQosConfig, relayer_stage::RelayerConfig,
+        },
         system_monitor_service::SystemMonitorService,
         tip_manager::{TipDistributionAccountConfig, TipManagerConfig},
         tpu::DEFAULT_TPU_COALESCE,
@@ -45,6 +52,7 @@ use {
     solana_rpc::{
         rpc::{JsonRpcConfig, RpcBigtableConfig},
         rpc_pubsub_service::PubSubConfig,
+        rpc_request_limiter::{parse_method_weight, RpcRequestLimitsConfig, RpcTokenBucketConfig},
     },
     solana_rpc_client::rpc_client::RpcClient,
     solana_rpc_client_api::config::RpcLeaderScheduleConfig,
@@ -479,14 +487,15 @@ pub fn main() {
     let operation = match matches.subcommand() {
         ("", _) | ("run", _) => Operation::Run,
         ("set-block-engine-config", Some(subcommand_matches)) => {
-            let block_engine_url = value_t_or_exit!(subcommand_matches, "block_engine_url", String);
+            let block_engine_urls = block_engine_urls_from_matches(subcommand_matches);
             let trust_packets = subcommand_matches.is_present("trust_block_engine_packets");
+            let multi_stream = subcommand_matches.is_present("block_engine_multi_stream");
             let admin_client = admin_rpc_service::connect(&ledger_path);
             admin_rpc_service::runtime()
                 .block_on(async move {
                     admin_client
                         .await?
-                        .set_block_engine_config(block_engine_url, trust_packets)
+                        .set_block_engine_config(block_engine_urls, trust_packets, multi_stream)
                         .await
                 })
                 .unwrap_or_else(|err| {
@@ -532,6 +541,50 @@ pub fn main() {
                 });
             return;
         }
+        ("set-tip-distribution-config", Some(subcommand_matches)) => {
+            let commission_bps = value_t!(subcommand_matches, "commission_bps", u16).ok();
+            let merkle_root_upload_authority =
+                pubkey_of(subcommand_matches, "merkle_root_upload_authority")
+                    .map(|pubkey| pubkey.to_string());
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            admin_rpc_service::runtime()
+                .block_on(async move {
+                    admin_client
+                        .await?
+                        .set_tip_distribution_account_config(
+                            commission_bps,
+                            merkle_root_upload_authority,
+                        )
+                        .await
+                })
+                .unwrap_or_else(|err| {
+                    println!("set tip distribution config failed: {}", err);
+                    exit(1);
+                });
+            return;
+        }
+        ("tip-distribution-config", Some(subcommand_matches)) => {
+            let output_mode = subcommand_matches.value_of("output");
+            let admin_client = admin_rpc_service::connect(&ledger_path);
+            let config = admin_rpc_service::runtime()
+                .block_on(
+                    async move { admin_client.await?.tip_distribution_account_config().await },
+                )
+                .unwrap_or_else(|err| {
+                    eprintln!("Tip distribution config query failed: {err}");
+                    exit(1);
+                });
+            if let Some(mode) = output_mode {
+                match mode {
+                    "json" => println!("{}", serde_json::to_string_pretty(&config).unwrap()),
+                    "json-compact" => print!("{}", serde_json::to_string(&config).unwrap()),
+                    _ => unreachable!(),
+                }
+            } else {
+                print!("{config}");
+            }
+            return;
+        }
         ("authorized-voter", Some(authorized_voter_subcommand_matches)) => {
             match authorized_voter_subcommand_matches.subcommand() {
                 ("add", Some(subcommand_matches)) => {
@@ -1461,13 +1514,21 @@ pub fn main() {
     let voting_disabled = matches.is_present("no_voting") || restricted_repair_only_mode;
     let tip_manager_config = tip_manager_config_from_matches(&matches, voting_disabled);
 
+    let embedded_relayer_config =
+        matches
+            .value_of("embedded_relayer_address")
+            .map(|address| EmbeddedRelayerConfig {
+                bind_address: solana_net_utils::parse_host_port(address)
+                    .expect("failed to parse embedded relayer address"),
+            });
+
     let block_engine_config = BlockEngineConfig {
-        block_engine_url: if matches.is_present("block_engine_url") {
-            value_of(&matches, "block_engine_url").expect("couldn't parse block_engine_url")
-        } else {
-            "".to_string()
+        block_engine_urls: match &embedded_relayer_config {
+            Some(embedded_relayer_config) => vec![embedded_relayer_config.url()],
+            None => block_engine_urls_from_matches(&matches),
         },
         trust_packets: matches.is_present("trust_block_engine_packets"),
+        multi_stream: matches.is_present("block_engine_multi_stream"),
     };
 
     // Defaults are set in cli definition, safe to use unwrap() here
@@ -1484,7 +1545,9 @@ pub fn main() {
     );
 
     let relayer_config = RelayerConfig {
-        relayer_url: if matches.is_present("relayer_url") {
+        relayer_url: if let Some(embedded_relayer_config) = &embedded_relayer_config {
+            embedded_relayer_config.url()
+        } else if matches.is_present("relayer_url") {
             value_of(&matches, "relayer_url").expect("couldn't parse relayer_url")
         } else {
             "".to_string()
@@ -1493,7 +1556,26 @@ pub fn main() {
         oldest_allowed_heartbeat: Duration::from_millis(
             max_failed_heartbeats * expected_heartbeat_interval_ms,
         ),
-        trust_packets: matches.is_present("trust_relayer_packets"),
+        // the embedded relayer streams packets that already passed sigverify
+        trust_packets: matches.is_present("trust_relayer_packets")
+            || embedded_relayer_config.is_some(),
+        packet_qos: RelayerPacketQosConfig {
+            staked_packets_per_second: value_t_or_exit!(
+                matches,
+                "relayer_staked_packets_per_second",
+                u64
+            ),
+            unstaked_packets_per_second_per_ip: value_t_or_exit!(
+                matches,
+                "relayer_unstaked_packets_per_second_per_ip",
+                u64
+            ),
+            unstaked_packets_per_second: value_t_or_exit!(
+                matches,
+                "relayer_unstaked_packets_per_second",
+                u64
+            ),
+        },
     };
 
     let mut validator_config = ValidatorConfig {
@@ -1538,6 +1620,38 @@ pub fn main() {
                 "rpc_max_request_body_size",
                 usize
             )),
+            enable_send_bundle: matches.is_present("rpc_enable_send_bundle"),
+            send_bundle_allowed_signers: values_t!(
+                matches,
+                "rpc_send_bundle_allowed_signer",
+                Pubkey
+            )
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+            request_limits: RpcRequestLimitsConfig {
+                max_batch_size: value_t!(matches, "rpc_max_batch_size", usize).ok(),
+                method_weights: matches
+                    .values_of("rpc_method_weight")
+                    .into_iter()
+                    .flatten()
+                    .map(|value| parse_method_weight(value).unwrap())
+                    .collect(),
+                client_token_bucket: value_t!(matches, "rpc_client_token_bucket_capacity", u64)
+                    .ok()
+                    .map(|capacity| RpcTokenBucketConfig {
+                        capacity,
+                        refill_per_second: value_t_or_exit!(
+                            matches,
+                            "rpc_client_token_bucket_refill_rate",
+                            u64
+                        ),
+                    }),
+                trusted_proxies: values_t!(matches, "rpc_trusted_proxy", IpAddr)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect(),
+            },
         },
         on_start_geyser_plugin_config_files,
         rpc_addrs: value_t!(matches, "rpc_port", u16).ok().map(|rpc_port| {
@@ -1552,6 +1666,10 @@ pub fn main() {
         pubsub_config: PubSubConfig {
             enable_block_subscription: matches.is_present("rpc_pubsub_enable_block_subscription"),
             enable_vote_subscription: matches.is_present("rpc_pubsub_enable_vote_subscription"),
+            enable_bundle_status_subscription: matches
+                .is_present("rpc_pubsub_enable_bundle_status_subscription"),
+            enable_transaction_subscription: matches
+                .is_present("rpc_pubsub_enable_transaction_subscription"),
             max_active_subscriptions: value_t_or_exit!(
                 matches,
                 "rpc_pubsub_max_active_subscriptions",
@@ -1610,6 +1728,9 @@ pub fn main() {
                 "rpc_send_transaction_retry_pool_max_size",
                 usize
             ),
+            retry_journal_path: matches
+                .value_of("rpc_send_transaction_retry_journal")
+                .map(PathBuf::from),
         },
         no_poh_speed_test: matches.is_present("no_poh_speed_test"),
         no_os_memory_stats_reporting: matches.is_present("no_os_memory_stats_reporting"),
@@ -1635,6 +1756,7 @@ pub fn main() {
         },
         relayer_config: Arc::new(Mutex::new(relayer_config)),
         block_engine_config: Arc::new(Mutex::new(block_engine_config)),
+        embedded_relayer_config,
         tip_manager_config,
         shred_receiver_address: Arc::new(RwLock::new(
             matches
@@ -1650,6 +1772,14 @@ pub fn main() {
         ),
         preallocated_bundle_cost: value_of(&matches, "preallocated_bundle_cost")
             .expect("preallocated_bundle_cost set as default"),
+        bundle_ordering_policy: value_t!(matches, "bundle_ordering_policy", BundleOrderingPolicy)
+            .unwrap_or_default(),
+        bundle_reserved_space_policy: value_t!(
+            matches,
+            "bundle_reserved_space_policy",
+            BundleReservedSpacePolicy
+        )
+        .unwrap_or_default(),
         ..ValidatorConfig::default()
     };
 
@@ -2255,3 +2385,15 @@ fn tip_manager_config_from_matches(
         },
     }
 }
+
+/// Block engine urls in order of preference, empty strings disable the connection
+fn block_engine_urls_from_matches(matches: &ArgMatches) -> Vec<String> {
+    matches
+        .values_of("block_engine_url")
+        .map(|urls| {
+            urls.filter(|url| !url.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}